@@ -0,0 +1,167 @@
+//! What each kind of glyph block is, and the color its glyphs are marked with in FontForge's font
+//! view (their `Colour`), kept in one place, with a legend of the colors so screenshots of the font
+//! view can be read
+
+use itertools::Itertools;
+
+use crate::{glyph_blocks::VERSION, Font};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// The control characters (joiners, variation selectors, and the cartouche ticks)
+    Control,
+    /// The sitelen pona control glyphs (the cartouche and long glyph marks, and the joiners)
+    TokControl,
+    Latin,
+    /// The glyphs that don't take part in combinations or cartouches
+    NoCombination,
+    Radical,
+    Core,
+    Extended,
+    /// The glyphs of glyph packs and SVG directories
+    Pack,
+    Alternate,
+    Simplified,
+    /// The scaling containers
+    Outer,
+    /// The glyphs scaled inside them
+    Inner,
+    /// The lower halves of stacks
+    Lower,
+    /// The upper halves of stacks
+    Upper,
+    /// The latin letters spelled out in cartouches, and the names made of them
+    Names,
+    Numbers,
+    DottedCircle,
+    Fallback,
+    /// The components glyphs reference their shared contours from
+    SharedContours,
+    Vertical,
+    Padding,
+}
+
+impl BlockKind {
+    pub const ALL: [BlockKind; 21] = [
+        BlockKind::Control,
+        BlockKind::TokControl,
+        BlockKind::Latin,
+        BlockKind::NoCombination,
+        BlockKind::Radical,
+        BlockKind::Core,
+        BlockKind::Extended,
+        BlockKind::Pack,
+        BlockKind::Alternate,
+        BlockKind::Simplified,
+        BlockKind::Outer,
+        BlockKind::Inner,
+        BlockKind::Lower,
+        BlockKind::Upper,
+        BlockKind::Names,
+        BlockKind::Numbers,
+        BlockKind::DottedCircle,
+        BlockKind::Fallback,
+        BlockKind::SharedContours,
+        BlockKind::Vertical,
+        BlockKind::Padding,
+    ];
+
+    /// The color its glyphs are marked with, in hex as FontForge writes it (without leading zeros)
+    pub fn color(self) -> &'static str {
+        match self {
+            BlockKind::Control => "fa6791",
+            BlockKind::TokControl => "aaafff",
+            BlockKind::Latin => "fffaaa",
+            BlockKind::NoCombination => "cccfff",
+            BlockKind::Radical => "7777cc",
+            BlockKind::Core => "bf80ff",
+            BlockKind::Extended => "df80ff",
+            BlockKind::Pack => "ef80ff",
+            BlockKind::Alternate => "ff80e6",
+            BlockKind::Simplified => "ffb380",
+            BlockKind::Outer => "ffff",
+            BlockKind::Inner => "80ffff",
+            BlockKind::Lower => "ff00",
+            BlockKind::Upper => "80ff80",
+            BlockKind::Names => "ffccaa",
+            BlockKind::Numbers => "c0c0ff",
+            BlockKind::DottedCircle => "cccccc",
+            BlockKind::Fallback => "ff8080",
+            BlockKind::SharedContours | BlockKind::Padding => "dddddd",
+            BlockKind::Vertical => "ffc080",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            BlockKind::Control => "control characters",
+            BlockKind::TokControl => "sitelen pona control glyphs",
+            BlockKind::Latin => "latin glyphs",
+            BlockKind::NoCombination => "glyphs without combinations",
+            BlockKind::Radical => "radicals",
+            BlockKind::Core => "core words",
+            BlockKind::Extended => "extended words",
+            BlockKind::Pack => "glyph packs",
+            BlockKind::Alternate => "alternates",
+            BlockKind::Simplified => "simplified forms (ss03)",
+            BlockKind::Outer => "scaling containers",
+            BlockKind::Inner => "glyphs scaled inside containers",
+            BlockKind::Lower => "lower halves of stacks",
+            BlockKind::Upper => "upper halves of stacks",
+            BlockKind::Names => "names and their letters",
+            BlockKind::Numbers => "numbers",
+            BlockKind::DottedCircle => "dotted circle",
+            BlockKind::Fallback => "fallback glyphs",
+            BlockKind::SharedContours => "shared contours",
+            BlockKind::Vertical => "vertical forms",
+            BlockKind::Padding => "padding",
+        }
+    }
+}
+
+/// Generates the legend (an HTML page) of the colors the blocks of `font` are marked with: each
+/// color, the kinds of block it's for, and the blocks of `font` marked with it
+pub fn legend(font: &Font) -> String {
+    let rows = BlockKind::ALL
+        .iter()
+        .map(|kind| kind.color())
+        .unique()
+        .map(|color| {
+            let kinds = BlockKind::ALL
+                .iter()
+                .filter(|kind| kind.color() == color)
+                .map(|kind| kind.description())
+                .join(", ");
+            let blocks = font
+                .blocks
+                .iter()
+                .filter(|block| block.color == color && !block.name.is_empty())
+                .map(|block| block.name.as_str())
+                .join(" ");
+            format!(
+                r#"    <tr>
+      <td style="background: #{color:0>6}; width: 4em"></td>
+      <td><code>{color}</code></td>
+      <td>{kinds}</td>
+      <td><code>{blocks}</code></td>
+    </tr>
+"#
+            )
+        })
+        .collect::<String>();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>nasin nanpa {VERSION} block colors</title>
+</head>
+<body>
+  <h1>nasin nanpa {VERSION} block colors</h1>
+  <table>
+{rows}  </table>
+</body>
+</html>
+"#
+    )
+}
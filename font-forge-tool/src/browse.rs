@@ -0,0 +1,230 @@
+//! A terminal browser of the generated font: its blocks, their glyphs, and a preview of the
+//! selected glyph (drawn in braille) with the lookup entries it takes part in
+
+use std::{collections::HashMap, io};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, List, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    ffir::{EncPos, TAG_PADDING},
+    invalid_data,
+    preview::Previewer,
+    raster, rules, Font,
+};
+
+/// A glyph, with what's shown about it
+struct Entry {
+    name: String,
+    codepoint: Option<usize>,
+    width: usize,
+    /// Its lookup entries, and the other glyphs' entries that take or make it
+    rules: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Blocks,
+    Glyphs,
+}
+
+struct Browser {
+    blocks: Vec<(String, Vec<Entry>)>,
+    previewer: Previewer,
+    pane: Pane,
+    block: ListState,
+    glyph: ListState,
+}
+
+impl Browser {
+    fn new(font: &Font) -> Result<Self, String> {
+        let sections = rules::sections(font);
+        let mut rules_of = HashMap::<&str, Vec<String>>::new();
+        for rule in rules::rules(font, &sections) {
+            let line = format!(
+                "{}: {} → {}",
+                rule.lookup,
+                rule.inputs.join(" "),
+                rule.outputs.join(" ")
+            );
+            let mut names = rule.inputs.iter().chain(&rule.outputs).collect::<Vec<_>>();
+            names.sort();
+            names.dedup();
+            for name in names {
+                rules_of.entry(name).or_default().push(line.clone());
+            }
+        }
+
+        let blocks = font
+            .blocks
+            .iter()
+            .map(|block| {
+                let entries = block
+                    .full_names()
+                    .filter(|(_, glyph)| !glyph.glyph.has_tag(TAG_PADDING))
+                    .map(|(name, glyph)| Entry {
+                        codepoint: match glyph.encoding.enc_pos {
+                            EncPos::Pos(codepoint) => Some(codepoint),
+                            EncPos::None => None,
+                        },
+                        width: glyph.glyph.width,
+                        rules: rules_of.remove(name.as_str()).unwrap_or_default(),
+                        name: name.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                (block.name.clone(), entries)
+            })
+            .filter(|(_, entries)| !entries.is_empty())
+            .collect();
+        Ok(Self {
+            blocks,
+            previewer: Previewer::new(font)?,
+            pane: Pane::Blocks,
+            block: ListState::default().with_selected(Some(0)),
+            glyph: ListState::default().with_selected(Some(0)),
+        })
+    }
+
+    fn entries(&self) -> &[Entry] {
+        &self.blocks[self.block.selected().unwrap_or(0)].1
+    }
+
+    fn entry(&self) -> &Entry {
+        &self.entries()[self.glyph.selected().unwrap_or(0)]
+    }
+
+    /// Moves the selection of the focused pane by `by` (clamped to the list)
+    fn select(&mut self, by: isize) {
+        let (state, len) = match self.pane {
+            Pane::Blocks => (&mut self.block, self.blocks.len()),
+            Pane::Glyphs => {
+                let len = self.entries().len();
+                (&mut self.glyph, len)
+            }
+        };
+        let selected = state.selected().unwrap_or(0) as isize + by;
+        state.select(Some(selected.clamp(0, len as isize - 1) as usize));
+        if self.pane == Pane::Blocks {
+            self.glyph.select(Some(0));
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [blocks_area, glyphs_area, detail_area] = Layout::horizontal([
+            Constraint::Length(24),
+            Constraint::Length(40),
+            Constraint::Min(0),
+        ])
+        .areas(frame.area());
+        let pane_block = |title: &'static str, pane: Pane| {
+            let block = Block::bordered().title(title);
+            if self.pane == pane {
+                block.border_style(Style::new().yellow())
+            } else {
+                block
+            }
+        };
+
+        let blocks = List::new(
+            self.blocks
+                .iter()
+                .map(|(name, entries)| format!("{name} ({})", entries.len())),
+        )
+        .block(pane_block("blocks", Pane::Blocks))
+        .highlight_style(Style::new().reversed());
+        let entries = &self.blocks[self.block.selected().unwrap_or(0)].1;
+        let glyphs = List::new(entries.iter().map(|entry| entry.name.as_str()))
+            .block(pane_block("glyphs", Pane::Glyphs))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(blocks, blocks_area, &mut self.block);
+        frame.render_stateful_widget(glyphs, glyphs_area, &mut self.glyph);
+
+        let entry = self.entry();
+        let info_height = (entry.rules.len() as u16 + 3).min(detail_area.height / 3);
+        let [preview_area, info_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(info_height)])
+                .areas(detail_area);
+        let preview = Block::bordered().title(entry.name.as_str());
+        let inner = preview.inner(preview_area);
+        frame.render_widget(
+            Paragraph::new(self.preview(entry, inner).join("\n")).block(preview),
+            preview_area,
+        );
+
+        let codepoint = entry
+            .codepoint
+            .map_or("no codepoint".to_string(), |c| format!("U+{c:04X}"));
+        let info = std::iter::once(format!(
+            "{codepoint} · width {} · {} contours",
+            entry.width,
+            self.previewer
+                .outline(&entry.name)
+                .map_or(0, |outline| outline.contours.len())
+        ))
+        .chain(entry.rules.iter().cloned())
+        .collect::<Vec<_>>()
+        .join("\n");
+        frame.render_widget(
+            Paragraph::new(info).block(Block::bordered().title("lookups")),
+            info_area,
+        );
+    }
+
+    /// `entry` drawn to fill `area`
+    fn preview(&self, entry: &Entry, area: Rect) -> Vec<String> {
+        let outline = self
+            .previewer
+            .outline(&entry.name)
+            .cloned()
+            .unwrap_or_default();
+        let bitmap = raster::rasterize(
+            &outline,
+            self.previewer.frame(&outline, entry.width),
+            area.width as usize * 2,
+            area.height as usize * 4,
+        );
+        raster::braille(&bitmap)
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.select(1),
+                KeyCode::PageUp => self.select(-20),
+                KeyCode::PageDown => self.select(20),
+                KeyCode::Left | KeyCode::Char('h') => self.pane = Pane::Blocks,
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => self.pane = Pane::Glyphs,
+                KeyCode::Tab => {
+                    self.pane = match self.pane {
+                        Pane::Blocks => Pane::Glyphs,
+                        Pane::Glyphs => Pane::Blocks,
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Browses `font` in the terminal until `q` (or escape) is pressed
+pub fn browse(font: &Font) -> io::Result<()> {
+    let browser = Browser::new(font).map_err(invalid_data)?;
+    let mut terminal = ratatui::init();
+    let result = browser.run(&mut terminal);
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,75 @@
+//! The glyphs generated by earlier builds, so `watch` only regenerates the glyphs whose inputs
+//! changed, and patches the rest in from before
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    features::FeatureRegistry,
+    ffir::{GlyphBlock, GlyphFull, GlyphName},
+    Profile,
+};
+
+/// The hash of everything that goes into a glyph's section of the `.sfd`: the glyph itself (its
+/// outline, references, slot, lookups, and metadata), its block's prefix, suffix, and color, the
+/// lines declared for it, and the `fingerprint` of the features
+pub fn key(
+    fingerprint: u64,
+    profile: Profile,
+    block: &GlyphBlock,
+    glyph: &GlyphFull,
+    features: &FeatureRegistry,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    fingerprint.hash(&mut hasher);
+    profile.hash(&mut hasher);
+    block.prefix.hash(&mut hasher);
+    block.suffix.hash(&mut hasher);
+    block.color.hash(&mut hasher);
+    glyph.hash(&mut hasher);
+    let full_name = GlyphName::new(&block.prefix, &glyph.glyph.name, &block.suffix);
+    features.glyph_lines(full_name.as_str()).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The sections of the glyphs in the last build, by their `key`s
+#[derive(Default)]
+pub struct GlyphCache {
+    sections: HashMap<u64, String>,
+    /// The keys of the sections written since the last `finish_build`
+    used: HashSet<u64>,
+    generated: usize,
+    reused: usize,
+}
+
+impl GlyphCache {
+    /// The section generated before for `key`
+    pub fn get(&self, key: u64) -> Option<&str> {
+        self.sections.get(&key).map(String::as_str)
+    }
+
+    /// Records that the section for `key` was written, and what it is if it was just generated
+    pub fn record(&mut self, key: u64, generated: Option<String>) {
+        match generated {
+            Some(section) => {
+                self.generated += 1;
+                self.sections.insert(key, section);
+            }
+            None => self.reused += 1,
+        }
+        self.used.insert(key);
+    }
+
+    /// Forgets the sections that weren't written since the last build, returning how many glyphs
+    /// were generated and how many were reused
+    pub fn finish_build(&mut self) -> (usize, usize) {
+        let used = std::mem::take(&mut self.used);
+        self.sections.retain(|key, _| used.contains(key));
+        let counts = (self.generated, self.reused);
+        self.generated = 0;
+        self.reused = 0;
+        counts
+    }
+}
@@ -0,0 +1,76 @@
+//! Ligature caret positions (`LCarets2`), so text editors can put the caret between the glyphs a
+//! ligature (a word typed in latin letters, a combination, a sequence in a cartouche or a long
+//! glyph) is made of, instead of only before or after it.
+
+use std::collections::HashMap;
+
+use crate::{
+    features::FeatureRegistry,
+    ffir::{GlyphBlock, TAG_PADDING},
+    gdef,
+    profile::Profile,
+};
+
+/// Places the carets of every ligature in `blocks`, at the boundaries between its components: the
+/// glyphs of the first ligature that makes it out of several glyphs besides the selectors (the
+/// letters of a word, or the glyphs of a combination or a sequence; see `gdef::glyph_ligature`),
+/// laid out across its width in proportion to their own widths
+pub fn place(blocks: &mut [GlyphBlock], features: &FeatureRegistry, profile: Profile) {
+    let widths = blocks
+        .iter()
+        .flat_map(|block| block.full_names())
+        .map(|(name, glyph)| (name.to_string(), glyph.glyph.width))
+        .collect::<HashMap<_, _>>();
+    let selectors = gdef::selectors(blocks);
+
+    for block in blocks.iter_mut() {
+        let names = block
+            .full_names()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        for (glyph, name) in block.glyphs.iter_mut().zip(names) {
+            if glyph.glyph.has_tag(TAG_PADDING) || glyph.glyph.width == 0 {
+                continue;
+            }
+            let Some(components) =
+                gdef::glyph_ligature(glyph, &name, &selectors, profile, features)
+            else {
+                continue;
+            };
+            let Some(components) = components
+                .iter()
+                .map(|component| widths.get(component).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            glyph.glyph.carets = carets(glyph.glyph.width, &components);
+        }
+    }
+}
+
+/// The carets of a ligature `width` wide, between components with the widths `components`. The
+/// components without any width (the joiners) don't get carets of their own, and there are none if
+/// every caret would be at an edge of the ligature, since there's nothing between them to move to.
+fn carets(width: usize, components: &[usize]) -> Vec<usize> {
+    let components = components
+        .iter()
+        .copied()
+        .filter(|component| *component > 0)
+        .collect::<Vec<_>>();
+    let total = components.iter().sum::<usize>();
+    if total == 0 {
+        return vec![];
+    }
+    let carets = components[..components.len() - 1]
+        .iter()
+        .scan(0, |boundary, component| {
+            *boundary += component;
+            Some(((width * *boundary) as f64 / total as f64).round() as usize)
+        })
+        .collect::<Vec<_>>();
+    if carets.iter().all(|caret| *caret == 0 || *caret >= width) {
+        return vec![];
+    }
+    carets
+}
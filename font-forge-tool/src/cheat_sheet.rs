@@ -0,0 +1,251 @@
+//! A cheat sheet of the ASCII input method: what to type for each word, alternate, arrow, and
+//! cartouche or long glyph mark, as markdown and as HTML. It's read off the ligatures the font is
+//! generated with, so it can't drift from them.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{
+    ascii,
+    features::{LIGA_VARIATIONS, LIGA_WORDS},
+    glyph_blocks::VERSION,
+    rules,
+    web::escape_html,
+    BlockFilter, Font,
+};
+
+/// The sections of the cheat sheet
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Word,
+    Alternate,
+    Arrow,
+    Control,
+}
+
+impl Kind {
+    const ALL: [Kind; 4] = [Kind::Word, Kind::Alternate, Kind::Arrow, Kind::Control];
+
+    /// The section the glyph `name`, of the block `block`, goes in, if it's `typed` that way
+    fn of(name: &str, block: &str, typed: &str) -> Self {
+        if name.starts_with("arrow") || BlockFilter::matches("arrow", block) {
+            Kind::Arrow
+        } else if BlockFilter::matches("alt", block) || BlockFilter::matches("ss03", block) {
+            Kind::Alternate
+        } else if BlockFilter::matches("ctrl", block) || !typed.contains(char::is_alphabetic) {
+            Kind::Control
+        } else {
+            Kind::Word
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Kind::Word => "Words",
+            Kind::Alternate => "Alternates",
+            Kind::Arrow => "Arrows",
+            Kind::Control => "Punctuation, cartouches, long glyphs, and joiners",
+        }
+    }
+
+    fn about(self) -> &'static str {
+        match self {
+            Kind::Word => {
+                "Each word is typed as it's spelled, with a space (or nothing) between words."
+            }
+            Kind::Alternate => {
+                "An alternate is typed as its word followed by the digit that picks it, or as the \
+                 words it stands for."
+            }
+            Kind::Arrow => {
+                "The arrows are typed with ^, v, <, and > (two of them for the diagonals), and \
+                 after a word that points, turn it that way."
+            }
+            Kind::Control => {
+                "Punctuation is typed as it's written, cartouches in brackets, long glyphs in \
+                 parentheses (or braces, to go backwards), and the joiners between the words they \
+                 join."
+            }
+        }
+    }
+}
+
+/// A glyph, and the ways it's typed
+struct Entry {
+    kind: Kind,
+    name: String,
+    codepoint: Option<usize>,
+    typed: Vec<String>,
+}
+
+pub struct CheatSheet {
+    entries: Vec<Entry>,
+}
+
+/// How the glyphs `inputs` are typed, with each typed the first way it can be (`None` if one can't
+/// be). Two words are typed with a space between them, so they aren't read as one.
+fn typed(inputs: &[&str], spellings: &HashMap<String, Vec<String>>) -> Option<String> {
+    let mut typed = String::new();
+    let mut after_word = false;
+    for input in inputs {
+        let (spelling, word) = match ascii::glyph_char(input) {
+            Some(c) => (c.to_string(), false),
+            None => (
+                spellings.get(*input)?.first()?.clone(),
+                input.ends_with("Tok"),
+            ),
+        };
+        if after_word
+            && word
+            && typed.ends_with(char::is_alphabetic)
+            && spelling.starts_with(char::is_alphabetic)
+        {
+            typed.push(' ');
+        }
+        typed.push_str(&spelling);
+        after_word = word;
+    }
+    Some(typed)
+}
+
+impl CheatSheet {
+    /// Reads the cheat sheet off the word and variation ligatures of `font`
+    pub fn new(font: &Font) -> Self {
+        let sections = rules::sections(font);
+        // picking an alternate again, after another one, isn't another way to type it
+        let ligatures = rules::rules(font, &sections)
+            .into_iter()
+            .filter(|rule| rule.lookup == LIGA_WORDS || rule.lookup == LIGA_VARIATIONS)
+            .filter(|rule| !rule.inputs.iter().any(|input| input.contains("_VAR")))
+            .collect::<Vec<_>>();
+
+        // the variations are typed with the glyphs the words become, so they're spelled out until
+        // there are no more ways to type anything
+        let mut spellings: HashMap<String, Vec<String>> = HashMap::new();
+        loop {
+            let mut added = false;
+            for rule in &ligatures {
+                let Some(typed) = typed(&rule.inputs, &spellings) else {
+                    continue;
+                };
+                let known = spellings.entry(rule.outputs[0].to_string()).or_default();
+                if !known.contains(&typed) {
+                    known.push(typed);
+                    added = true;
+                }
+            }
+            if !added {
+                break;
+            }
+        }
+
+        let codepoints = font
+            .encoded_glyphs()
+            .map(|(codepoint, name)| (name.to_string(), codepoint))
+            .collect::<HashMap<_, _>>();
+        let mut entries = font
+            .blocks
+            .iter()
+            .flat_map(|block| {
+                block
+                    .full_names()
+                    .map(move |(name, _)| (name.to_string(), block.name.as_str()))
+            })
+            .filter_map(|(name, block)| {
+                let typed = spellings.remove(&name)?;
+                Some(Entry {
+                    kind: Kind::of(&name, block, &typed[0]),
+                    codepoint: codepoints.get(&name).copied(),
+                    typed,
+                    name,
+                })
+            })
+            .collect::<Vec<_>>();
+        // the characters first, then the glyphs that are only typed as sequences of them
+        entries.sort_by_key(|entry| entry.codepoint.is_none());
+        Self { entries }
+    }
+
+    fn section(&self, kind: Kind) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(move |entry| entry.kind == kind)
+    }
+
+    /// The cheat sheet as markdown, with a table for each section
+    pub fn markdown(&self) -> String {
+        // a `|` would end the table's cell, even in code
+        let code = |s: &str| format!("`{}`", s.replace('|', "\\|"));
+        let mut s = format!(
+            "# nasin nanpa {VERSION} input method\n\n\
+             Generated by `font-forge-tool cheat-sheet` from the font's ligatures; don't edit by \
+             hand.\n"
+        );
+        for kind in Kind::ALL {
+            s += &format!(
+                "\n## {}\n\n{}\n\n| Glyph | Codepoint | Typed as |\n| --- | --- | --- |\n",
+                kind.title(),
+                kind.about()
+            );
+            for entry in self.section(kind) {
+                s += &format!(
+                    "| {} | {} | {} |\n",
+                    code(&entry.name),
+                    entry
+                        .codepoint
+                        .map(|codepoint| format!("U+{codepoint:04X}"))
+                        .unwrap_or_default(),
+                    entry.typed.iter().map(|typed| code(typed)).join(", ")
+                );
+            }
+        }
+        s
+    }
+
+    /// The cheat sheet as an HTML page, with a table for each section
+    pub fn html(&self) -> String {
+        let code = |s: &str| format!("<code>{}</code>", escape_html(s));
+        let sections = Kind::ALL
+            .iter()
+            .map(|&kind| {
+                let rows = self
+                    .section(kind)
+                    .map(|entry| {
+                        format!(
+                            "    <tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                            code(&entry.name),
+                            entry
+                                .codepoint
+                                .map(|codepoint| format!("U+{codepoint:04X}"))
+                                .unwrap_or_default(),
+                            entry.typed.iter().map(|typed| code(typed)).join(", ")
+                        )
+                    })
+                    .collect::<String>();
+                format!(
+                    r#"  <h2>{}</h2>
+  <p>{}</p>
+  <table>
+    <tr><th>Glyph</th><th>Codepoint</th><th>Typed as</th></tr>
+{rows}  </table>
+"#,
+                    kind.title(),
+                    escape_html(kind.about())
+                )
+            })
+            .collect::<String>();
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>nasin nanpa {VERSION} input method</title>
+</head>
+<body>
+  <h1>nasin nanpa {VERSION} input method</h1>
+  <p>Generated by <code>font-forge-tool cheat-sheet</code> from the font's ligatures.</p>
+{sections}</body>
+</html>
+"#
+        )
+    }
+}
@@ -0,0 +1,774 @@
+use std::{io, path::PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::{
+    containers,
+    em::Metrics,
+    ffir::Padding,
+    fontforge::FontFormat,
+    gasp::{self, GaspRange},
+    invalid_data, names,
+    packs::GlyphPack,
+    plugins,
+    profile::Capability,
+    sources::{self, GlyphBlockSpec, GlyphSource},
+    spacing::{Gap, Spacing},
+    spellings,
+    spline::Direction,
+    svg::SvgDirectory,
+    widths::SideBearings,
+    BlockFilter, GenOptions, PLUGINS,
+};
+
+/// Generates the nasin nanpa `.sfd` files
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// What to do (by default, both variations are generated)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Also write a JSON manifest of the glyphs next to each `.sfd`
+    #[arg(long)]
+    pub manifest: bool,
+
+    /// Also write the color layers of each variation (containers in one color, the glyphs inside
+    /// them in another), and add them to the compiled fonts
+    #[arg(long)]
+    pub color: bool,
+
+    /// Also write an SVG document for each glyph, and add them to the compiled fonts as an `SVG `
+    /// table
+    #[arg(long)]
+    pub svg: bool,
+
+    /// How `--svg` draws the glyphs (a TOML file; black by default)
+    #[arg(long, value_name = "FILE", requires = "svg")]
+    pub svg_style: Option<PathBuf>,
+
+    /// Also generate the (experimental) right-to-left variation
+    #[arg(long)]
+    pub rtl: bool,
+
+    /// Also generate the variation with sharp corners and square terminals
+    #[arg(long)]
+    pub sharp: bool,
+
+    /// Also generate the small optical size, with thicker strokes and simpler outlines, for UI
+    /// text at 12–16px
+    #[arg(long)]
+    pub small: bool,
+
+    /// What the build is for
+    #[arg(long, global = true, value_enum, default_value_t = Target::Desktop)]
+    pub target: Target,
+
+    /// Also compile each `.sfd` with FontForge
+    #[arg(long, global = true)]
+    pub compile: bool,
+
+    /// The formats `--compile` produces
+    #[arg(
+        long = "compile-format",
+        global = true,
+        value_name = "FORMAT",
+        value_enum
+    )]
+    pub compile_formats: Vec<FontFormat>,
+
+    /// Have FontForge hint the fonts `--compile` produces (PostScript hints, and TrueType
+    /// instructions for the TrueType formats)
+    #[arg(long, global = true)]
+    pub autohint: bool,
+
+    /// The directory compiled fonts are placed in
+    #[arg(long, global = true, value_name = "DIR", default_value = "dist")]
+    pub dist: PathBuf,
+
+    /// The directory glyph packs are discovered in
+    #[arg(long, global = true, value_name = "DIR", default_value = "packs")]
+    pub packs_dir: PathBuf,
+
+    /// Include an extra glyph pack (in addition to the discovered ones)
+    #[arg(long = "pack", global = true, value_name = "FILE")]
+    pub packs: Vec<PathBuf>,
+
+    /// Include a directory of SVG glyphs (one `{word}.svg` each), as a pack named after it
+    #[arg(long = "svg-dir", global = true, value_name = "DIR")]
+    pub svg_dirs: Vec<PathBuf>,
+
+    /// Leave out a glyph pack (or SVG directory) by name
+    #[arg(long = "exclude-pack", global = true, value_name = "NAME")]
+    pub exclude_packs: Vec<String>,
+
+    /// Only include the blocks matching this (a block name like `base_cor`, or a part of one like
+    /// `alt`)
+    #[arg(long = "include-block", global = true, value_name = "BLOCK")]
+    pub include_blocks: Vec<String>,
+
+    /// Leave out the blocks matching this (a block name like `latn`, or a part of one like `alt`)
+    #[arg(long = "exclude-block", global = true, value_name = "BLOCK")]
+    pub exclude_blocks: Vec<String>,
+
+    /// The list of words that can start a long glyph (by default, the one in `data/containers.txt`)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub containers: Option<PathBuf>,
+
+    /// The names to spell out in a cartouche when they're typed as `[Name]` (one per line, like
+    /// `data/containers.txt`)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub names: Option<PathBuf>,
+
+    /// Leave a capability out of every build (like `rand`, or `latin-ligatures` to keep the main
+    /// variation's latin glyphs without turning words typed in them into sitelen pona)
+    #[arg(long = "without", global = true, value_enum, value_name = "CAPABILITY")]
+    pub without: Vec<Capability>,
+
+    /// Latin spellings to add to glyphs or replace theirs with (like `data/spellings-example.txt`)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub spellings: Option<PathBuf>,
+
+    /// Add vertical metrics and the `vert`/`vrt2` rotated forms, for top-to-bottom layout
+    #[arg(long, global = true)]
+    pub vertical: bool,
+
+    /// Turn the outlines counter-clockwise and the holes clockwise (the PostScript convention),
+    /// instead of the other way
+    #[arg(long, global = true)]
+    pub postscript_direction: bool,
+
+    /// Round every corner to this radius (in the units the glyphs are drawn in), after making the
+    /// drawn rounded corners and terminals sharp; 0 leaves them all sharp
+    #[arg(long, global = true, value_name = "UNITS")]
+    pub corner_radius: Option<f64>,
+
+    /// Move the points that are a unit or two off the guides the sitelen pona are drawn on (0, 100,
+    /// 500, 900, and 1000, across and up) onto them
+    #[arg(long, global = true)]
+    pub snap_to_grid: bool,
+
+    /// Simplify the outlines, taking out the points they don't need and merging curves, without
+    /// moving them further than this (in the units the glyphs are drawn in)
+    #[arg(long, global = true, value_name = "UNITS")]
+    pub simplify: Option<f64>,
+
+    /// How many units the em is divided into (the glyphs are drawn on 1000, and scaled to fit)
+    #[arg(
+        long,
+        global = true,
+        value_name = "UNITS",
+        default_value_t = 1000,
+        value_parser = clap::value_parser!(u16).range(16..=16384)
+    )]
+    pub units_per_em: u16,
+
+    /// How wide the half-width punctuation is (in the units the glyphs are drawn in, where the
+    /// sitelen pona are 1000 wide)
+    #[arg(
+        long,
+        global = true,
+        value_name = "UNITS",
+        default_value_t = 500,
+        value_parser = clap::value_parser!(u16).range(1..=1000)
+    )]
+    pub half_width: u16,
+
+    /// How wide the gap between sitelen pona words (the sitelen pona space, which two latin spaces
+    /// make) is
+    #[arg(long, global = true, value_enum, default_value_t = Gap::Regular)]
+    pub word_gap: Gap,
+
+    /// Make two latin spaces a gap with a dot in it, to separate the words visibly (the sitelen
+    /// pona space typed as U+3000 stays empty)
+    #[arg(long, global = true)]
+    pub word_separator: bool,
+
+    /// Run a plugin on the glyphs once they're built, as NAME=NUMBER (`letterspacing=2` adds 2% of
+    /// each glyph's width after it, `raise-descenders=20` moves the glyphs that go below the
+    /// baseline up 20 units)
+    #[arg(long = "plugin", global = true, value_name = "NAME=NUMBER")]
+    pub plugins: Vec<String>,
+
+    /// Work out the widths of a block's glyphs from their ink, as BLOCK=LEFT,RIGHT (or
+    /// BLOCK=BEARING): the ink is moved to start LEFT units in, and the glyph ends RIGHT units
+    /// after it (a block's name, or a `_`-separated part of it, like `base`)
+    #[arg(long = "side-bearings", global = true, value_name = "BLOCK=LEFT,RIGHT")]
+    pub side_bearings: Vec<String>,
+
+    /// Pad a block's glyphs with empty ones as BLOCK=POLICY: `none`, `align:N` (up to a multiple of
+    /// N slots, starting on one) or `fixed:N` (up to N slots in all). By default, the blocks drawn
+    /// by hand are aligned to FontForge's rows of 16, and the small generated ones aren't padded.
+    #[arg(long = "padding", global = true, value_name = "BLOCK=POLICY")]
+    pub padding: Vec<String>,
+
+    /// How TrueType rasterizers render the glyphs up to a size, as PPEM=BEHAVIOR+BEHAVIOR... (the
+    /// behaviors being gridfit, grayscale, symmetric-gridfit, symmetric-smoothing, or none), with
+    /// the last range going up to `max`. By default, every behavior is set at every size.
+    #[arg(long = "gasp", global = true, value_name = "PPEM=BEHAVIORS")]
+    pub gasp: Vec<String>,
+
+    /// Move the contours that glyphs share into component glyphs, and reference those instead
+    #[arg(long, global = true)]
+    pub extract_refs: bool,
+
+    /// The `ModificationTime` to write, in seconds since the UNIX epoch, for reproducible builds
+    #[arg(long, global = true, value_name = "SECONDS", env = "SOURCE_DATE_EPOCH")]
+    pub timestamp: Option<u64>,
+
+    /// List each block on stderr as it's written, with a bar for how many glyphs are done
+    #[arg(long, global = true)]
+    pub progress: bool,
+
+    /// Generate and check everything a build would, but write nothing (no `.sfd`s, manifests,
+    /// tables, or compiled fonts)
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Log each block as it's given its slots (with its glyph count, slots, and codepoints) and
+    /// each lookup as it's written, on stderr
+    #[arg(long, global = true)]
+    pub trace: bool,
+
+    /// Fail the build on glyphs that can't be reached and lookups with entries only for missing
+    /// glyphs, instead of warning about them
+    #[arg(long, global = true)]
+    pub strict: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Generates named builds from a file of profiles
+    ///
+    /// Each profile (in `profiles.toml` by default) is written with its own file names.
+    Build {
+        /// A profile to generate
+        #[arg(long = "profile", value_name = "NAME", required_unless_present = "all")]
+        profiles: Vec<String>,
+
+        /// Generate every profile
+        #[arg(long, conflicts_with = "profiles")]
+        all: bool,
+
+        /// The file the profiles are defined in
+        #[arg(long, value_name = "FILE", default_value = "profiles.toml")]
+        config: PathBuf,
+    },
+
+    /// Generates each part of the font as a font of its own
+    ///
+    /// The parts are the base glyphs, the extension, and the alternates. Each has the blocks every
+    /// part needs, and is named after it (like `nasin-nanpa-base`).
+    Split,
+
+    /// Generates both variations, and again whenever a glyph pack changes
+    Watch,
+
+    /// Writes one variation's `.sfd` to stdout instead of a file
+    Print {
+        /// Print the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+    },
+
+    /// Regenerates some glyphs and splices them into an `.sfd` written earlier
+    ///
+    /// The `.sfd` has to have the glyphs in the same slots. Only they are written, instead of the
+    /// whole font again.
+    Patch {
+        /// The `.sfd` to patch
+        sfd: PathBuf,
+
+        /// A glyph to regenerate (by its name in the `.sfd`, like `tokiTok`)
+        #[arg(
+            long = "glyph",
+            value_name = "GLYPH",
+            required_unless_present = "blocks"
+        )]
+        glyphs: Vec<String>,
+
+        /// A block whose glyphs are all regenerated (a block name like `base_cor`, or a part of one
+        /// like `alt`)
+        #[arg(long = "block", value_name = "BLOCK")]
+        blocks: Vec<String>,
+
+        /// Regenerate the glyphs of the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+
+        /// Write the patched `.sfd` here instead of over the one it's patched from
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Browses the font's blocks and glyphs in the terminal
+    ///
+    /// Each glyph is shown with a preview and the lookup entries it's in.
+    Browse {
+        /// Browse the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+    },
+
+    /// Draws PNG previews of glyphs and of sample sentences
+    ///
+    /// They're drawn straight from the generated outlines.
+    Png {
+        /// A glyph to draw (written to `<GLYPH>.png`)
+        #[arg(long = "glyph", value_name = "GLYPH")]
+        glyphs: Vec<String>,
+
+        /// A sample sentence (toki pona in ASCII or UCSUR) to draw a character at a time, without
+        /// the font's lookups (written to `sample-<N>.png`)
+        #[arg(long = "text", value_name = "TEXT")]
+        texts: Vec<String>,
+
+        /// A sequence of glyph names separated by spaces (like `test-shaping` prints) to draw as a
+        /// line (written to `sample-<N>.png`)
+        #[arg(long = "sequence", value_name = "GLYPHS")]
+        sequences: Vec<String>,
+
+        /// How many pixels tall the em is drawn
+        #[arg(long, default_value_t = 128)]
+        size: usize,
+
+        /// Draw the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+
+        /// The directory the previews are written to
+        #[arg(long, value_name = "DIR", default_value = "previews")]
+        out: PathBuf,
+    },
+
+    /// Shapes a corpus of strings with the font and checks the resulting glyph sequences
+    TestShaping {
+        /// A compiled font to test (by default, the main variation is generated and compiled with
+        /// FontForge, so without one `--dry-run` is an error)
+        #[arg(long, value_name = "FILE")]
+        font: Option<PathBuf>,
+
+        /// The corpus of strings and their expected glyph sequences
+        #[arg(long, value_name = "FILE", default_value = "shaping-corpus.txt")]
+        corpus: PathBuf,
+    },
+
+    /// Reports which codepoints of the UCSUR sitelen pona block are implemented, missing, or
+    /// misnamed
+    Coverage {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports glyphs whose ink goes past where it should
+    ///
+    /// That's past the em box, into the descent unexpectedly, or past the glyph's width.
+    AuditMetrics {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports the OS/2 Unicode ranges and code pages the font's codepoints set
+    ///
+    /// The codepoints in no range are reported too.
+    AuditOs2 {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports the stem widths measured from the outlines
+    ///
+    /// The private dictionary's hinting values are picked from them.
+    AuditHinting {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports contours that cross themselves or go the wrong way
+    ///
+    /// The outlines should go counter-clockwise, and the holes clockwise.
+    AuditOutlines {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports the points that are a unit or two off the guides
+    ///
+    /// The guides are the lines the sitelen pona are drawn on (0, 100, 500, 900, and 1000, across
+    /// and up), which `--snap-to-grid` moves the points onto.
+    AuditGrid {
+        /// Only check the glyphs in blocks matching this (a block's name, or a `_`-separated part
+        /// of it, like `ext`)
+        #[arg(long = "block", value_name = "BLOCK")]
+        blocks: Vec<String>,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports the glyphs whose widths differ from the ones worked out from their ink
+    ///
+    /// The widths are worked out with the `--side-bearings` (or 50 units on each side, if there are
+    /// none).
+    AuditWidths {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports how many points simplifying the outlines takes out of each glyph
+    ///
+    /// The outlines are simplified as `--simplify` does.
+    AuditSimplify {
+        /// Only simplify the glyphs in blocks matching this (a block's name, or a `_`-separated
+        /// part of it, like `ext`)
+        #[arg(long = "block", value_name = "BLOCK")]
+        blocks: Vec<String>,
+
+        /// How far the outlines can be moved (in the units the glyphs are drawn in)
+        #[arg(long, value_name = "UNITS", default_value_t = 1.0)]
+        max_error: f64,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports glyphs drawn with the same (or nearly the same) outlines
+    ///
+    /// They could be references instead.
+    Similar {
+        /// Only compare the glyphs in blocks matching this (a block's name, or a `_`-separated part
+        /// of it, like `alt`)
+        #[arg(long = "block", value_name = "BLOCK")]
+        blocks: Vec<String>,
+
+        /// How much of two glyphs' ink can differ (from 0 to 1) for them to be nearly the same
+        #[arg(long, value_name = "FRACTION", default_value_t = 0.02)]
+        tolerance: f64,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Compares every glyph against an earlier build
+    ///
+    /// Both are drawn the same way, and side-by-side images are written of the glyphs that changed.
+    VisualDiff {
+        /// The earlier build's `.sfd` (like a release's, in `ffversions`)
+        #[arg(long, value_name = "FILE")]
+        old: PathBuf,
+
+        /// The build to compare (by default, the generated font)
+        #[arg(long, value_name = "FILE")]
+        new: Option<PathBuf>,
+
+        /// Generate the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+
+        /// How many pixels tall the em is drawn
+        #[arg(long, default_value_t = 64)]
+        size: usize,
+
+        /// How much (in percent of their ink) a glyph can change before an image is written for it
+        #[arg(long, value_name = "PERCENT", default_value_t = 1.0)]
+        threshold: f64,
+
+        /// The directory the images are written to
+        #[arg(long, value_name = "DIR", default_value = "visual-diff")]
+        out: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Reports where the UCSUR control characters deviate from the proposal
+    ///
+    /// The control characters are the delimiters, extensions, and joiners.
+    Conformance {
+        /// Check the main variation instead of the UCSUR one
+        #[arg(long)]
+        main: bool,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Prints a glyph's width, codepoint, and the lookups that take or make it
+    Glyph {
+        /// The glyph's full name, like `tokiTok`
+        name: String,
+
+        /// Look in the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+    },
+
+    /// Lists the glyphs (name, codepoint, width, block, and the lookups they take part in)
+    ListGlyphs {
+        /// Only list the glyphs in blocks matching this (a block's name, or a `_`-separated part of
+        /// it, like `base`)
+        #[arg(long = "block", value_name = "BLOCK")]
+        blocks: Vec<String>,
+
+        /// List the glyphs of the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+    },
+
+    /// Reports the differences between the glyphs and the words in Linku
+    ///
+    /// Linku is the toki pona dictionary. The words without a glyph are reported, and the glyphs
+    /// whose words aren't in it.
+    Linku {
+        /// A copy of Linku's words, as served at `https://api.linku.la/v1/words` (downloaded if not
+        /// given)
+        #[arg(long, value_name = "FILE")]
+        data: Option<PathBuf>,
+
+        /// A usage category (like `common`) whose words should have glyphs (by default, `core`,
+        /// `common`, and `uncommon`)
+        #[arg(long = "category", value_name = "CATEGORY")]
+        categories: Vec<String>,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Writes the credits, and every glyph's provenance as JSON
+    ///
+    /// The credits list who drew the glyphs and what they're based on.
+    Credits {
+        #[arg(long, value_name = "FILE", default_value = "CREDITS.md")]
+        output: PathBuf,
+
+        #[arg(long, value_name = "FILE", default_value = "provenance.json")]
+        provenance: PathBuf,
+    },
+
+    /// Writes the table of ASCII spellings that `translit::ascii_to_ucsur` is built on
+    Transliteration {
+        #[arg(long, value_name = "FILE", default_value = "data/transliteration.txt")]
+        output: PathBuf,
+    },
+
+    /// Writes a cheat sheet of what to type, as markdown and as HTML
+    ///
+    /// It lists each word, alternate, arrow, and cartouche, read off the font's ligatures.
+    CheatSheet {
+        #[arg(long, value_name = "FILE", default_value = "input-method.md")]
+        markdown: PathBuf,
+
+        #[arg(long, value_name = "FILE", default_value = "input-method.html")]
+        html: PathBuf,
+    },
+
+    /// Writes the `nasin-nanpa-data` crate
+    ///
+    /// It has the codepoints, glyph names and ASCII spellings as Rust tables.
+    DataCrate {
+        #[arg(long, value_name = "DIR", default_value = "../nasin-nanpa-data")]
+        output: PathBuf,
+    },
+
+    /// Writes a FontForge Python script that builds a variation
+    ///
+    /// The script builds it through FontForge's API, for pipelines that change the font before
+    /// saving it.
+    Script {
+        /// Write the UCSUR variation's script instead of the main one's
+        #[arg(long)]
+        ucsur: bool,
+
+        /// Where to write it (by default, the name of the `.sfd` with `.py` instead)
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Writes a legend (`legend.html`) of the block colors
+    ///
+    /// The glyphs of each kind of block are marked with its color in FontForge.
+    Legend,
+
+    /// Writes a proof sheet (`proof.html`) of every glyph and combination
+    ///
+    /// It's for the fonts built with `--target web`.
+    Proof,
+
+    /// Generates a font with only the sitelen pona that some text needs
+    ///
+    /// Their alternates and combinations are included too.
+    Subset {
+        /// A word to include
+        #[arg(long = "word", value_name = "WORD")]
+        words: Vec<String>,
+
+        /// A text (toki pona in ASCII or UCSUR) whose words are included
+        #[arg(long, value_name = "FILE")]
+        corpus: Option<PathBuf>,
+
+        /// A tag (like `animal`) whose words are included
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Subset the UCSUR variation instead of the main one
+        #[arg(long)]
+        ucsur: bool,
+
+        #[arg(long, value_name = "FILE", default_value = "nasin-nanpa-subset.sfd")]
+        output: PathBuf,
+    },
+
+    /// Compares both generated variations against the golden `.sfd` files
+    Snapshot {
+        /// The directory the golden files are kept in
+        #[arg(long, value_name = "DIR", default_value = "snapshots")]
+        dir: PathBuf,
+
+        /// Overwrite the golden files with the current output instead of comparing
+        #[arg(long)]
+        bless: bool,
+    },
+}
+
+impl Cli {
+    /// Loads every discovered and explicitly included pack and SVG directory, minus the excluded
+    /// ones, and merges their blocks
+    pub fn load_sources(&self) -> io::Result<Vec<GlyphBlockSpec>> {
+        let mut sources: Vec<Box<dyn GlyphSource>> = vec![];
+        for pack in GlyphPack::discover(&self.packs_dir)? {
+            sources.push(Box::new(pack));
+        }
+        for path in &self.packs {
+            sources.push(Box::new(GlyphPack::load(path)?));
+        }
+        for dir in &self.svg_dirs {
+            sources.push(Box::new(SvgDirectory::load(dir)?));
+        }
+        sources.retain(|source| {
+            !source
+                .blocks()
+                .iter()
+                .any(|block| self.exclude_packs.contains(&block.name))
+        });
+        sources::merge(&sources).map_err(invalid_data)
+    }
+
+    /// Whether to compile each `.sfd` (always, for the web)
+    pub fn compiles(&self) -> bool {
+        self.compile || self.target == Target::Web
+    }
+
+    /// The formats to compile to (all of them, unless some were picked, or just WOFF2 for the web)
+    pub fn compile_formats(&self) -> &[FontFormat] {
+        if self.target == Target::Web {
+            &[FontFormat::Woff2]
+        } else if self.compile_formats.is_empty() {
+            &FontFormat::ALL
+        } else {
+            &self.compile_formats
+        }
+    }
+
+    pub fn gen_options(&self) -> io::Result<GenOptions> {
+        Ok(GenOptions {
+            sources: self.load_sources()?,
+            blocks: BlockFilter {
+                include: self.include_blocks.clone(),
+                exclude: self.exclude_blocks.clone(),
+            },
+            words: None,
+            containers: self.containers.as_ref().map(containers::load).transpose()?,
+            names: self
+                .names
+                .as_ref()
+                .map(names::load)
+                .transpose()?
+                .unwrap_or_default(),
+            without: self.without.clone(),
+            spellings: self
+                .spellings
+                .as_ref()
+                .map(spellings::load)
+                .transpose()?
+                .unwrap_or_default(),
+            vertical: self.vertical,
+            timestamp: self.timestamp,
+            progress: self.progress,
+            dry_run: self.dry_run,
+            extract_refs: self.extract_refs,
+            strict: self.strict,
+            corner_radius: self.corner_radius,
+            snap_to_grid: self.snap_to_grid,
+            simplify: self.simplify,
+            metrics: Metrics::with_units_per_em(self.units_per_em.into())
+                .with_half_width(self.half_width.into()),
+            plugins: self
+                .plugins
+                .iter()
+                .map(|spec| plugins::parse(spec, &PLUGINS))
+                .collect::<Result<_, _>>()
+                .map_err(invalid_data)?,
+            side_bearings: self
+                .side_bearings
+                .iter()
+                .map(|spec| SideBearings::parse(spec))
+                .collect::<Result<_, _>>()
+                .map_err(invalid_data)?,
+            padding: self
+                .padding
+                .iter()
+                .map(|spec| {
+                    let (block, policy) = spec.split_once('=').ok_or_else(|| {
+                        format!("the padding `{spec}` isn't written as BLOCK=POLICY")
+                    })?;
+                    Ok((block.to_string(), Padding::parse(policy)?))
+                })
+                .collect::<Result<_, String>>()
+                .map_err(invalid_data)?,
+            gasp: self
+                .gasp
+                .iter()
+                .map(|spec| GaspRange::parse(spec))
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|ranges| gasp::check(&ranges).map(|()| ranges))
+                .map_err(invalid_data)?,
+            spacing: Spacing {
+                gap: self.word_gap,
+                separator: self.word_separator,
+            },
+            direction: if self.postscript_direction {
+                Direction::CounterClockwise
+            } else {
+                Direction::Clockwise
+            },
+            name_suffix: "",
+        })
+    }
+}
+
+/// What a build is for
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Target {
+    /// `.sfd` files (and, with `--compile`, font binaries)
+    Desktop,
+    /// WOFF2 fonts, an `@font-face` stylesheet, and a specimen page
+    Web,
+}
+
+/// How a report is printed
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Json,
+}
+
+/// How a list of glyphs is printed
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ListFormat {
+    Table,
+    Json,
+    Csv,
+}
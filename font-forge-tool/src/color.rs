@@ -0,0 +1,117 @@
+//! A color build for learners, where containers (the outer glyphs, cartouches and long glyphs)
+//! are drawn in one color and the glyphs inside them in another, as COLRv0 layers. Everything
+//! else is drawn in the text's color. FontForge can't write the `COLR` and `CPAL` tables, so
+//! they're written to a `.colr.json` next to the `.sfd`, and added to compiled fonts with
+//! fontTools.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process,
+};
+
+use serde::Serialize;
+
+use crate::{ffir::ColorLayer, metrics::FRAME_PARTS, Font};
+
+/// An entry of the palette, by what it colors
+#[derive(Clone, Copy)]
+pub enum PaletteEntry {
+    Container,
+    Inner,
+}
+
+/// The colors of the palette (as `#rrggbbaa`), in the order of the entries
+const PALETTE: [&str; 2] = ["#1f5fbfff", "#d0402aff"];
+
+/// The palette entry the glyph `name` of the block `block` is drawn in, if it isn't drawn in the
+/// text's color
+pub fn palette_entry(block: &str, name: &str) -> Option<PaletteEntry> {
+    if block.starts_with("outer") || FRAME_PARTS.iter().any(|part| name.contains(part)) {
+        Some(PaletteEntry::Container)
+    } else if block.starts_with("inner") {
+        Some(PaletteEntry::Inner)
+    } else {
+        None
+    }
+}
+
+/// The data of the `COLR` and `CPAL` tables
+#[derive(Serialize)]
+pub struct ColorTable {
+    palette: Vec<&'static str>,
+    /// The layers of each color glyph
+    glyphs: BTreeMap<String, Vec<ColorLayer>>,
+}
+
+/// Colors the glyphs of `font` that are drawn in an entry of the palette (each one is a single
+/// layer of itself)
+pub fn table(font: &Font) -> ColorTable {
+    let mut glyphs = BTreeMap::new();
+    for block in &font.blocks {
+        for (name, glyph) in block.full_names() {
+            if glyph.glyph.rep.is_empty() {
+                continue;
+            }
+            let name = name.to_string();
+            if let Some(entry) = palette_entry(&block.name, &name) {
+                let layer = ColorLayer {
+                    glyph: name.clone(),
+                    palette_index: entry as usize,
+                };
+                glyphs.insert(name, vec![layer]);
+            }
+        }
+    }
+    ColorTable {
+        palette: PALETTE.to_vec(),
+        glyphs,
+    }
+}
+
+impl ColorTable {
+    /// Writes the table next to the `.sfd` at `sfd`, returning where it went
+    pub fn write(&self, sfd: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let path = sfd.as_ref().with_extension("colr.json");
+        serde_json::to_writer_pretty(BufWriter::new(File::create(&path)?), self)?;
+        Ok(path)
+    }
+}
+
+/// The fontTools script that adds the tables (from the JSON in its first argument) to the fonts
+/// in the rest
+const APPLY: &str = r#"
+import json, sys
+from fontTools.ttLib import TTFont
+from fontTools.colorLib.builder import buildCOLR, buildCPAL
+table = json.load(open(sys.argv[1]))
+palette = [tuple(int(c[i:i + 2], 16) / 255 for i in (1, 3, 5, 7)) for c in table["palette"]]
+layers = {
+    glyph: [(layer["glyph"], layer["palette_index"]) for layer in glyph_layers]
+    for glyph, glyph_layers in table["glyphs"].items()
+}
+for path in sys.argv[2:]:
+    font = TTFont(path)
+    font["COLR"] = buildCOLR(layers, version=0)
+    font["CPAL"] = buildCPAL([palette])
+    font.save(path)
+"#;
+
+/// Adds the `COLR` and `CPAL` tables in `table` (as written by `ColorTable::write`) to each of
+/// the compiled `fonts`
+pub fn apply(table: impl AsRef<Path>, fonts: &[PathBuf]) -> io::Result<()> {
+    let status = process::Command::new("python3")
+        .args(["-c", APPLY])
+        .arg(table.as_ref())
+        .args(fonts)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("couldn't run python3: {e}")))?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "couldn't add the color tables (is fontTools installed?) ({status})"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! The stacking and scaling forms of glyphs, as transformations of other forms. Glyphs without a
+//! hand-drawn lower or inner form get one made from their base glyph, so new base glyphs (and the
+//! glyphs in packs) can be combined right away. Outer forms stay hand-drawn, since they have to
+//! leave room for the inner glyph.
+
+use std::collections::HashSet;
+
+use crate::ffir::*;
+
+/// A base glyph squashed into the bottom half of the em (from -100 to 400)
+pub const LOWER_FROM_BASE: Transform = Transform::scale_xy(1.0, 0.5).translate(0.0, -50.0);
+
+/// A lower form moved into the top half of the em, over the glyph before it
+pub const UPPER_FROM_LOWER: Transform = Transform::translation(-1000.0, 500.0);
+
+/// A base glyph at half size, centered on the middle of the glyph before it (-500, 400)
+pub const INNER_FROM_BASE: Transform = Transform::scale(0.5).translate(-750.0, 200.0);
+
+/// Adds a form of each glyph in `sources` that `block` doesn't have yet, made by `transform`
+pub fn fill_missing(
+    block: &mut GlyphBlock,
+    sources: &[&GlyphBlock],
+    transform: &Transform,
+    lookups: LookupsMode,
+    width: Option<usize>,
+    anchor: Anchor,
+) {
+    let mut names = block
+        .glyphs
+        .iter()
+        .map(|glyph| glyph.glyph.name.clone())
+        .collect::<HashSet<_>>();
+    for source in sources {
+        let mut derived = source.derive(
+            transform,
+            lookups.clone(),
+            Cc::Full,
+            false,
+            block.prefix.clone(),
+            block.suffix.clone(),
+            block.color.clone(),
+            width,
+            Some(anchor.clone()),
+        );
+        derived
+            .glyphs
+            .retain(|glyph| !names.contains(&glyph.glyph.name));
+        names.extend(derived.glyphs.iter().map(|glyph| glyph.glyph.name.clone()));
+        block.glyphs.append(&mut derived.glyphs);
+    }
+}
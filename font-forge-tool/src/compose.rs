@@ -0,0 +1,87 @@
+//! Glyphs composed of other glyphs: each component is a reference to a glyph, moved by a
+//! transformation, so a glyph made of copies of another (like three of `ijo`) doesn't repeat its
+//! outlines, and follows it when it's redrawn.
+//!
+//! The built-in descriptors list their components with `Component::new`. In a glyph pack, they're
+//! written as a list of strings like `ref(ijo, scale(0.5) translate(250, 0))`: the word the
+//! referenced glyph is for, then the transformations applied to it, in order (`translate(x, y)`,
+//! `scale(s)`, `scale(sx, sy)`, and `rotate(degrees)`, counter-clockwise around the origin).
+
+use std::borrow::Cow;
+
+use crate::ffir::{GlyphName, Ref, Transform};
+
+/// A reference to another glyph (by its full name, like `ijoTok`), moved by `transform`
+#[derive(Clone, PartialEq, Debug)]
+pub struct Component {
+    pub glyph: Cow<'static, str>,
+    pub transform: Transform,
+}
+
+impl Component {
+    pub const fn new(glyph: &'static str, transform: Transform) -> Self {
+        Self {
+            glyph: Cow::Borrowed(glyph),
+            transform,
+        }
+    }
+
+    /// The reference the component is written as
+    pub fn to_ref(&self) -> Ref {
+        Ref::new(GlyphName::new("", &self.glyph, ""), self.transform)
+    }
+
+    /// Parses a component as a glyph pack writes it, where the glyph is named by its word (so
+    /// `ijo` is `ijoTok`)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let error = |why: &str| format!("couldn't parse the component `{s}`: {why}");
+        let inner = s
+            .trim()
+            .strip_prefix("ref(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .ok_or_else(|| error("it isn't written as ref(...)"))?;
+        let (word, transforms) = inner.split_once(',').unwrap_or((inner, ""));
+        let word = word.trim();
+        if word.is_empty() {
+            return Err(error("it doesn't name a glyph"));
+        }
+
+        let mut transform = Transform::scale(1.0);
+        let mut rest = transforms.trim();
+        while !rest.is_empty() {
+            let (call, after) = rest
+                .split_once(')')
+                .ok_or_else(|| error("a transformation isn't closed"))?;
+            let (op, args) = call
+                .split_once('(')
+                .ok_or_else(|| error("a transformation has no arguments"))?;
+            let args = args
+                .split(',')
+                .map(|arg| arg.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| error(&e.to_string()))?;
+            let next = match (op.trim(), args.as_slice()) {
+                ("translate", &[x, y]) => Transform::translation(x, y),
+                ("scale", &[s]) => Transform::scale(s),
+                ("scale", &[sx, sy]) => Transform::scale_xy(sx, sy),
+                ("rotate", &[degrees]) => {
+                    let radians = degrees.to_radians();
+                    Transform::rotation(radians.cos(), radians.sin())
+                }
+                (op, _) => {
+                    return Err(error(&format!(
+                        "there's no transformation {op} with {} arguments",
+                        args.len()
+                    )))
+                }
+            };
+            transform = transform.then(next);
+            rest = after.trim();
+        }
+
+        Ok(Self {
+            glyph: Cow::Owned(format!("{word}Tok")),
+            transform,
+        })
+    }
+}
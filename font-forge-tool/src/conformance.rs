@@ -0,0 +1,186 @@
+//! Conformance of the UCSUR control characters (the cartouche and long glyph delimiters, their
+//! extensions, and the joiners) with the proposal, checked against the table in
+//! `data/ucsur-controls.txt`: each must be encoded under its name from `data/ucsur.txt`, and
+//! render on its own the way the proposal says it does
+
+use std::{collections::HashMap, io};
+
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, coverage::ucsur_table, ffir::*, invalid_data, Font};
+
+const UCSUR_CONTROLS: &str = include_str!("../data/ucsur-controls.txt");
+
+/// How a control character renders on its own
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Rendering {
+    Spacing,
+    ZeroWidth,
+    Invisible,
+}
+
+impl Rendering {
+    fn parse(rendering: &str) -> Option<Self> {
+        match rendering {
+            "spacing" => Some(Self::Spacing),
+            "zero-width" => Some(Self::ZeroWidth),
+            "invisible" => Some(Self::Invisible),
+            _ => None,
+        }
+    }
+
+    fn advances(&self) -> bool {
+        *self == Self::Spacing
+    }
+
+    fn draws(&self) -> bool {
+        *self != Self::Invisible
+    }
+}
+
+/// The codepoints, renderings, and names of the control characters, as listed in the table
+fn controls_table() -> Vec<(usize, Rendering, &'static str)> {
+    UCSUR_CONTROLS
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let (Some(codepoint), Some(rendering), Some(name)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                panic!("malformed UCSUR controls table line {line:?}");
+            };
+            let codepoint = usize::from_str_radix(codepoint, 16).unwrap_or_else(|_| {
+                panic!("malformed codepoint in UCSUR controls table line {line:?}")
+            });
+            let rendering = Rendering::parse(rendering).unwrap_or_else(|| {
+                panic!("malformed rendering in UCSUR controls table line {line:?}")
+            });
+            (codepoint, rendering, name.trim())
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct Deviation {
+    codepoint: String,
+    /// The proposal's name for the character
+    character: String,
+    glyph: Option<String>,
+    problem: String,
+}
+
+#[derive(Serialize)]
+pub struct ConformanceReport {
+    checked: usize,
+    deviations: Vec<Deviation>,
+}
+
+/// The ways `glyph` (named `name`) deviates from the proposal
+fn problems(
+    glyph: &GlyphFull,
+    name: &str,
+    expected_name: Option<&str>,
+    rendering: Rendering,
+) -> Vec<String> {
+    let mut problems = vec![];
+    if let Some(expected) = expected_name {
+        if name.strip_suffix("Tok").unwrap_or(name) != expected {
+            problems.push(format!("is named {name}, not {expected}Tok"));
+        }
+    }
+
+    let advances = glyph.glyph.width > 0;
+    if advances != rendering.advances() {
+        problems.push(if advances {
+            format!(
+                "advances by {}, but should be zero-width",
+                glyph.glyph.width
+            )
+        } else {
+            "doesn't advance, but should be spacing".to_string()
+        });
+    }
+
+    let rep = &glyph.glyph.rep;
+    let draws = !rep.spline_set().trim().is_empty() || !rep.references().is_empty();
+    if draws != rendering.draws() {
+        problems.push(if draws {
+            "draws something, but should be invisible".to_string()
+        } else {
+            "draws nothing on its own".to_string()
+        });
+    }
+    problems
+}
+
+/// Checks the control characters of `font` against the proposal
+pub fn report(font: &Font) -> ConformanceReport {
+    let names = ucsur_table().into_iter().collect::<HashMap<_, _>>();
+    let mut report = ConformanceReport {
+        checked: 0,
+        deviations: vec![],
+    };
+    for (codepoint, rendering, character) in controls_table() {
+        report.checked += 1;
+        let deviation = |glyph: Option<String>, problem: String| Deviation {
+            codepoint: format!("U+{codepoint:04X}"),
+            character: character.to_string(),
+            glyph,
+            problem,
+        };
+        let Some((name, glyph)) = font.codepoint(codepoint) else {
+            report
+                .deviations
+                .push(deviation(None, "isn't encoded".to_string()));
+            continue;
+        };
+        let expected_name = names.get(&codepoint).copied();
+        for problem in problems(glyph, name.as_str(), expected_name, rendering) {
+            report
+                .deviations
+                .push(deviation(Some(name.to_string()), problem));
+        }
+    }
+    report
+}
+
+impl ConformanceReport {
+    /// Fails if anything deviates from the proposal, so releases aren't built from a font that does
+    pub fn gate(&self) -> io::Result<()> {
+        if self.deviations.is_empty() {
+            return Ok(());
+        }
+        self.print(ReportFormat::Table)?;
+        Err(invalid_data(format!(
+            "{} deviations from the UCSUR proposal",
+            self.deviations.len()
+        )))
+    }
+
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} deviations in {} control characters",
+                    self.deviations.len(),
+                    self.checked
+                );
+                for deviation in &self.deviations {
+                    println!(
+                        "{}  {:<40} {}  {}",
+                        deviation.codepoint,
+                        deviation.character,
+                        deviation.glyph.as_deref().unwrap_or("-"),
+                        deviation.problem
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
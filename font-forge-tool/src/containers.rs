@@ -0,0 +1,105 @@
+//! The words that can start a long glyph ("container"), like `pi` and `la`. The list lives in
+//! `data/containers.txt` (or any file passed with `--containers`). Words with a hand-drawn form in
+//! `START_LONG_GLYPH` use it; the rest get one generated from their base glyph and the start of
+//! the extension line.
+
+use std::{fs, io, path::Path};
+
+use crate::{
+    compose::Component,
+    ffir::*,
+    glyph_blocks::{base::*, ctrl::START_LONG_GLYPH, synth::long_glyph_start_bar},
+};
+
+const CONTAINERS: &str = include_str!("../data/containers.txt");
+
+fn parse(list: &str) -> Vec<String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The built-in list of container words
+pub fn default_containers() -> Vec<String> {
+    parse(CONTAINERS)
+}
+
+/// Reads a list of container words, in the same format as `data/containers.txt`
+pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+/// The name of the base glyph an entry refers to (e.g. `wileTok_VAR02` for `wile_VAR02`)
+pub fn glyph_name(entry: &str) -> String {
+    match entry.split_once('_') {
+        Some((word, sel)) => format!("{word}Tok_{sel}"),
+        None => format!("{entry}Tok"),
+    }
+}
+
+/// Parses the optional direction after an entry's word (`forward`, `reverse`, or `both`)
+fn parse_direction(direction: &str) -> Result<ContainerDirection, String> {
+    match direction {
+        "forward" => Ok(ContainerDirection::Forward),
+        "reverse" => Ok(ContainerDirection::Reverse),
+        "both" => Ok(ContainerDirection::Both),
+        _ => Err(format!(
+            "{direction} isn't a long glyph direction (forward, reverse, or both)"
+        )),
+    }
+}
+
+/// The glyphs of the start long glyph block (named like the base glyphs they start from),
+/// each with the direction its long glyph extends
+pub fn start_long_glyphs(
+    entries: &[String],
+) -> Result<Vec<(GlyphBasic, ContainerDirection)>, String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let mut parts = entry.split_whitespace();
+            let word = parts.next().unwrap_or_default();
+            let direction = parts.next().map(parse_direction).transpose()?;
+            let name = glyph_name(word);
+
+            if let Some(drawn) = START_LONG_GLYPH.iter().find(|glyph| glyph.name == name) {
+                let glyph = GlyphBasic::new(
+                    name,
+                    drawn.width.unwrap_or(1000),
+                    Rep::new(drawn.outlines()?, vec![]),
+                    drawn.anchor.clone(),
+                );
+                return Ok((glyph, direction.unwrap_or(drawn.container)));
+            }
+
+            let direction = direction.unwrap_or_default();
+            let base = BASE_COR
+                .iter()
+                .chain(BASE_EXT.iter())
+                .find(|glyph| glyph.name == word)
+                .or_else(|| BASE_ALT.iter().find(|glyph| glyph.name == name))
+                .ok_or_else(|| {
+                    format!(
+                        "{word} can't start a long glyph, since there's no {name} to build it from"
+                    )
+                })?;
+            let spline_set = format!(
+                "{}{}",
+                base.outlines()?,
+                long_glyph_start_bar(direction).gen()
+            );
+            let glyph = GlyphBasic::new(
+                name,
+                base.width.unwrap_or(1000),
+                Rep::new(
+                    spline_set,
+                    base.components.iter().map(Component::to_ref).collect(),
+                ),
+                None,
+            );
+            Ok((glyph, direction))
+        })
+        .collect()
+}
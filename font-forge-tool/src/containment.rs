@@ -0,0 +1,322 @@
+//! The containment context model behind `'calt' CART AND CONT`: which container (cartouche or long
+//! glyph) each glyph is in, including one nested in the other, and the extension added after it.
+//!
+//! The state is carried by the last glyph's class: every glyph in a container is followed by an
+//! extension whose class says which container(s) it's in, so the next glyph can look back one
+//! glyph to know what to add. Glyphs that open or close a nested container switch the state, and
+//! since they draw their own part of the container, they're followed by an empty marker instead.
+
+use itertools::Itertools;
+
+use crate::{block_kind::BlockKind, contextual::*, features::*, ffir::*, glyph_blocks::synth::*};
+
+/// The extensions for glyphs in a container nested in another one, and the empty markers
+pub const NESTED_EXTS: [&str; 6] = [
+    "combCartInContExtTok",
+    "combCartInContExtHalfTok",
+    "combCartInContExtNoneTok",
+    "combContInCartExtTok",
+    "combContInCartExtHalfTok",
+    "combContInCartExtNoneTok",
+];
+
+/// A class of glyphs in the `'calt' CART AND CONT` rules
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ContextClass {
+    Other,
+    /// Glyphs that get an extension when they're in a container
+    Base,
+    /// In a cartouche
+    Cart,
+    /// In a long glyph
+    Cont,
+    /// In a cartouche, in a long glyph
+    CartInCont,
+    /// In a long glyph, in a cartouche
+    ContInCart,
+    /// Closes a cartouche
+    EndCart,
+    /// Closes a long glyph
+    EndCont,
+}
+
+impl ContextClass {
+    const ALL: [Self; 8] = [
+        Self::Other,
+        Self::Base,
+        Self::Cart,
+        Self::Cont,
+        Self::CartInCont,
+        Self::ContInCart,
+        Self::EndCart,
+        Self::EndCont,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Other => "other",
+            Self::Base => "base",
+            Self::Cart => "cart",
+            Self::Cont => "cont",
+            Self::CartInCont => "cartInCont",
+            Self::ContInCart => "contInCart",
+            Self::EndCart => "endCart",
+            Self::EndCont => "endCont",
+        }
+    }
+
+    fn idx(&self) -> usize {
+        Self::ALL.iter().position(|class| class == self).unwrap()
+    }
+}
+
+/// A glyph of class `input` right after a glyph of class `after` is substituted by `lookup`
+struct Rule {
+    input: ContextClass,
+    after: ContextClass,
+    lookup: &'static str,
+}
+
+const RULES: [Rule; 8] = [
+    Rule {
+        input: ContextClass::Base,
+        after: ContextClass::Cart,
+        lookup: CC01_CART,
+    },
+    Rule {
+        input: ContextClass::Base,
+        after: ContextClass::Cont,
+        lookup: CC02_CONT,
+    },
+    Rule {
+        input: ContextClass::Base,
+        after: ContextClass::ContInCart,
+        lookup: CC03_CONT_IN_CART,
+    },
+    Rule {
+        input: ContextClass::Base,
+        after: ContextClass::CartInCont,
+        lookup: CC04_CART_IN_CONT,
+    },
+    // opening a container inside the other kind
+    Rule {
+        input: ContextClass::Cart,
+        after: ContextClass::Cont,
+        lookup: CC04_CART_IN_CONT,
+    },
+    Rule {
+        input: ContextClass::Cont,
+        after: ContextClass::Cart,
+        lookup: CC03_CONT_IN_CART,
+    },
+    // closing the inner container goes back to the outer one
+    Rule {
+        input: ContextClass::EndCart,
+        after: ContextClass::CartInCont,
+        lookup: CC02_CONT,
+    },
+    Rule {
+        input: ContextClass::EndCont,
+        after: ContextClass::ContInCart,
+        lookup: CC01_CART,
+    },
+];
+
+/// The glyphs that open a cartouche
+const CART_STARTS: [&str; 2] = ["startCartTok", "startCartAltTok"];
+/// The combining glyphs that open a long glyph
+const CONT_STARTS: [&str; 2] = ["startLongGlyphTok", "startRevLongGlyphTok"];
+const CART_ENDS: [&str; 2] = ["endCartTok", "endCartAltTok"];
+const CONT_ENDS: [&str; 2] = ["endLongGlyphTok", "endRevLongGlyphTok"];
+/// The glyphs in a cartouche before the next glyph in it, besides the ticks (which go after the
+/// first two)
+const CART_MEMBERS: [&str; 5] = [
+    "combCartExtHalfTok",
+    "combCartExtNoneTok",
+    "startCartTok",
+    "combCartExtTok",
+    "startCartAltTok",
+];
+/// The glyphs in a long glyph (besides the start long glyphs), before the next glyph in it
+const CONT_MEMBERS: [&str; 6] = [
+    "combLongGlyphExtHalfTok",
+    "startLongPiTok",
+    "combLongPiExtTok",
+    "startLongGlyphTok",
+    "combLongGlyphExtTok",
+    "startRevLongGlyphTok",
+];
+
+/// The block of nested extensions, each drawn as both of the extensions it combines
+pub fn nested_ext_block() -> GlyphBlock {
+    let glyphs = NESTED_EXTS
+        .iter()
+        .map(|name| {
+            let refs = match name {
+                _ if name.ends_with("NoneTok") => vec![],
+                _ => {
+                    let half = if name.contains("Half") { "Half" } else { "" };
+                    [
+                        format!("combCartExt{half}Tok"),
+                        format!("combLongGlyphExt{half}Tok"),
+                    ]
+                    .iter()
+                    .map(|target| Ref::new(GlyphName::new("", target, ""), Transform::identity()))
+                    .collect()
+                }
+            };
+            GlyphBasic::new(*name, 0, Rep::new("", refs), None)
+        })
+        .collect();
+    GlyphBlock::new_from_basic_glyphs(
+        glyphs,
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "",
+        BlockKind::Control.color(),
+        EncPos::None,
+    )
+    .named("ctrl_nested")
+    .padded(Padding::None)
+}
+
+/// Declares the substitutions of the glyphs that open and close containers, for when they're in
+/// the other kind of container. `longs` are the start long glyphs that open a long glyph forward.
+pub fn declare_transitions(features: &mut FeatureRegistry, longs: &[String]) {
+    for start in CART_STARTS {
+        features.declare(
+            start,
+            CC04_CART_IN_CONT,
+            format!("{start} combLongGlyphExtHalfTok combCartInContExtNoneTok"),
+        );
+    }
+    for start in CONT_STARTS {
+        features.declare(
+            start,
+            CC03_CONT_IN_CART,
+            format!("{start} combContInCartExtNoneTok"),
+        );
+    }
+    for long in longs.iter().map(String::as_str).chain(["startLongPiTok"]) {
+        features.declare(
+            long,
+            CC03_CONT_IN_CART,
+            format!("{long} combCartExtTok combContInCartExtNoneTok"),
+        );
+    }
+    for end in CART_ENDS {
+        features.declare(end, CC02_CONT, format!("{end} combLongGlyphExtHalfTok"));
+    }
+    for end in CONT_ENDS {
+        features.declare(end, CC01_CART, format!("{end} combCartExtNoneTok"));
+    }
+}
+
+/// Generates the `'calt' CART AND CONT` subtable. `base` are the names of the glyphs that get
+/// extensions, `longs` are as in `declare_transitions`, and `put_in_class` makes a class line.
+pub fn gen_fpst(
+    features: &FeatureRegistry,
+    base: String,
+    longs: &[String],
+    put_in_class: impl Fn(String) -> String,
+) -> String {
+    let members = |class: ContextClass| match class {
+        ContextClass::Other => String::new(),
+        ContextClass::Base => base.clone(),
+        ContextClass::Cart => format!(
+            "{} {} {}",
+            CART_MEMBERS[..2].join(" "),
+            (1..=2 * CART_TICK_COUNT).map(cart_tick_name).join(" "),
+            CART_MEMBERS[2..].join(" "),
+        ),
+        // a subset may have no long glyphs, and the class can't end in a space
+        ContextClass::Cont => CONT_MEMBERS
+            .iter()
+            .copied()
+            .chain(longs.iter().map(String::as_str))
+            .join(" "),
+        ContextClass::CartInCont => NESTED_EXTS[..3].join(" "),
+        ContextClass::ContInCart => NESTED_EXTS[3..].join(" "),
+        ContextClass::EndCart => CART_ENDS.join(" "),
+        ContextClass::EndCont => CONT_ENDS.join(" "),
+    };
+    let mut builder =
+        ContextualLookupBuilder::new(features, CALT_CART_AND_CONT, ContextClass::Other.name());
+    let mut ids = vec![builder.others()];
+    for class in &ContextClass::ALL[1..] {
+        ids.push(builder.class(class.name(), members(*class)));
+    }
+    for rule in &RULES {
+        builder.rule(
+            ContextRule::new([ids[rule.input.idx()]])
+                .backtrack([ids[rule.after.idx()]])
+                .apply(0, rule.lookup),
+        );
+    }
+    builder.build(put_in_class)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ContextClass::*;
+
+    /// The lookup each of `glyphs` gets, following the state from the glyph before it (the
+    /// extension it's followed by, or the glyph itself if it gets none)
+    fn walk(glyphs: &[ContextClass]) -> Vec<Option<&'static str>> {
+        let mut last = Other;
+        glyphs
+            .iter()
+            .map(|&glyph| {
+                let lookup = RULES
+                    .iter()
+                    .find(|rule| rule.input == glyph && rule.after == last)
+                    .map(|rule| rule.lookup);
+                last = match lookup {
+                    Some(CC01_CART) => Cart,
+                    Some(CC02_CONT) => Cont,
+                    Some(CC03_CONT_IN_CART) => ContInCart,
+                    Some(CC04_CART_IN_CONT) => CartInCont,
+                    _ => glyph,
+                };
+                lookup
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cartouche_in_long_glyph() {
+        assert_eq!(
+            walk(&[Cont, Base, Cart, Base, EndCart, Base, EndCont, Base]),
+            [
+                None,
+                Some(CC02_CONT),
+                Some(CC04_CART_IN_CONT),
+                Some(CC04_CART_IN_CONT),
+                Some(CC02_CONT),
+                Some(CC02_CONT),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn long_glyph_in_cartouche() {
+        assert_eq!(
+            walk(&[Cart, Base, Cont, Base, EndCont, Base, EndCart, Base]),
+            [
+                None,
+                Some(CC01_CART),
+                Some(CC03_CONT_IN_CART),
+                Some(CC03_CONT_IN_CART),
+                Some(CC01_CART),
+                Some(CC01_CART),
+                None,
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! A builder for class-based contextual subtables (FontForge's `ContextSub2` and `ChainSub2`
+//! FPSTs), which matches sequences of glyph classes and applies other lookups within them.
+
+use itertools::Itertools;
+
+use crate::features::FeatureRegistry;
+
+/// A class of a contextual subtable
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ClassId(usize);
+
+/// A rule of a contextual subtable: the classes it matches (its input, and the ones before it), and
+/// the lookups it applies to the glyphs of its input (by their position in the input)
+pub struct ContextRule {
+    backtrack: Vec<ClassId>,
+    input: Vec<ClassId>,
+    lookups: Vec<(usize, &'static str)>,
+}
+
+impl ContextRule {
+    pub fn new(input: impl Into<Vec<ClassId>>) -> Self {
+        Self {
+            backtrack: vec![],
+            input: input.into(),
+            lookups: vec![],
+        }
+    }
+
+    /// The classes that have to come before the input
+    pub fn backtrack(mut self, classes: impl Into<Vec<ClassId>>) -> Self {
+        self.backtrack = classes.into();
+        self
+    }
+
+    /// Applies the lookup `lookup` to the glyph at `pos` in the input, which has to be in it
+    pub fn apply(mut self, pos: usize, lookup: &'static str) -> Self {
+        assert!(
+            pos < self.input.len(),
+            "{lookup} is applied at {pos}, past the input of {} glyphs",
+            self.input.len()
+        );
+        self.lookups.push((pos, lookup));
+        self
+    }
+
+    fn gen(&self, features: &FeatureRegistry) -> String {
+        let list =
+            |classes: &[ClassId]| classes.iter().map(|class| format!(" {}", class.0)).join("");
+        let lookups = self
+            .lookups
+            .iter()
+            .map(|(pos, lookup)| features.seq_lookup(*pos, lookup))
+            .join("");
+        // no rule looks past its input, so the lookahead is always empty
+        format!(
+            " {} {} 0\n  ClsList:{}\n  BClsList:{}\n  FClsList:\n {}\n{lookups}",
+            self.input.len(),
+            self.backtrack.len(),
+            list(&self.input),
+            list(&self.backtrack),
+            self.lookups.len(),
+        )
+    }
+}
+
+/// A contextual subtable, whose classes are shared by its input, backtrack and lookahead
+pub struct ContextualLookupBuilder<'a> {
+    features: &'a FeatureRegistry,
+    subtable: &'static str,
+    /// The name of each class, and its members (the first class, of every other glyph, has none)
+    classes: Vec<(String, String)>,
+    rules: Vec<ContextRule>,
+}
+
+impl<'a> ContextualLookupBuilder<'a> {
+    /// A builder for `subtable`, whose class of every glyph that isn't in another class is called
+    /// `others`
+    pub fn new(features: &'a FeatureRegistry, subtable: &'static str, others: &str) -> Self {
+        Self {
+            features,
+            subtable,
+            classes: vec![(others.to_string(), String::new())],
+            rules: vec![],
+        }
+    }
+
+    /// The class of every glyph that isn't in another class
+    pub fn others(&self) -> ClassId {
+        ClassId(0)
+    }
+
+    /// Adds a class of `members` (glyph names, separated by spaces)
+    pub fn class(&mut self, name: &str, members: impl Into<String>) -> ClassId {
+        self.classes.push((name.to_string(), members.into()));
+        ClassId(self.classes.len() - 1)
+    }
+
+    /// Adds a rule, after the ones before it (the first one that matches is applied)
+    pub fn rule(&mut self, rule: ContextRule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Generates the subtable, with `put_in_class` making each class line
+    pub fn build(&self, put_in_class: impl Fn(String) -> String) -> String {
+        let count = self.classes.len();
+        let classes = self.classes[1..]
+            .iter()
+            .map(|(_, members)| put_in_class(members.clone()))
+            .collect_vec();
+        let put_in_sub = |c: &str| {
+            classes
+                .iter()
+                .map(|class| format!("  {c}{class}\n"))
+                .join("")
+        };
+        let subs = format!("{}{}{}", put_in_sub(""), put_in_sub("B"), put_in_sub("F"));
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| rule.gen(self.features))
+            .join("");
+        let names = self
+            .classes
+            .iter()
+            .map(|(name, _)| format!("\"{name}\""))
+            .join(" ");
+        format!(
+            "{fpst} {count} {count} {count} {rule_count}\n{subs}{rules}  ClassNames: {names}\n  BClassNames: {names}\n  FClassNames: {names}\nEndFPST\n",
+            fpst = self.features.fpst(self.subtable),
+            rule_count = self.rules.len(),
+        )
+    }
+}
@@ -0,0 +1,278 @@
+//! Rounding the corners of outlines at generation time, to the same radius in every glyph: the
+//! rounded corners and terminals that were drawn (curves tighter than `MAX_DRAWN_RADIUS`) are made
+//! sharp first, and then every sharp corner is rounded to the radius asked for (or left sharp, for
+//! a radius of 0). Rounding a square terminal by half its width makes it round again.
+
+use std::f64::consts::PI;
+
+use crate::{
+    spline::{Point, SplineSet},
+    stroke::{
+        add, contour, cross, dot, intersection, length, pieces, scale, sub, unit, Piece, EPSILON,
+    },
+};
+
+/// The widest curve that's taken to round off a corner or terminal, rather than to be part of the
+/// shape, in the units the glyphs are drawn in
+const MAX_DRAWN_RADIUS: f64 = 60.0;
+
+/// How far (in radians) a contour can turn where two segments meet and still be treated as
+/// smooth (the drawn points are whole units, so their tangents are a little off)
+const SMOOTH_ANGLE: f64 = 0.05;
+
+/// How far (in radians) a corner can turn and still come to a point: one that turns further is cut
+/// off where its curves reach, so it doesn't stick out past them
+const MAX_POINTED_ANGLE: f64 = 2.0 * PI / 3.0;
+
+/// How far (in radians) a run of tight curves can turn past a half turn (as a terminal does) and
+/// still be made sharp
+const TERMINAL_SLACK: f64 = 0.3;
+
+/// How far the direction `to` is turned from `from` (counter-clockwise if it's positive)
+fn turn(from: Point, to: Point) -> f64 {
+    cross(from, to).atan2(dot(from, to))
+}
+
+/// Which way `piece` turns (1 for counter-clockwise and -1 for clockwise), if it's a curve
+/// tighter than `MAX_DRAWN_RADIUS`
+fn tight_turn(piece: &Piece) -> Option<f64> {
+    let Piece::Curve(..) = piece else {
+        return None;
+    };
+    let turn = turn(piece.start_tangent(), piece.end_tangent());
+    let chord = length(sub(piece.end(), piece.start()));
+    let radius = chord / (2.0 * (turn.abs() / 2.0).sin());
+    (turn.abs() > SMOOTH_ANGLE && radius <= MAX_DRAWN_RADIUS).then_some(turn.signum())
+}
+
+/// The sharp corner that a run of tight curves rounds off (cut off where the curves reach if it
+/// turns too far to come to a point, which makes terminals square), unless it turns too far to be
+/// a corner at all
+fn sharp(run: &[Piece]) -> Option<Vec<Piece>> {
+    let (first, last) = (run[0], run[run.len() - 1]);
+    let (a, b) = (first.start(), last.end());
+    let (t_in, t_out) = (first.start_tangent(), last.end_tangent());
+    let total = run
+        .iter()
+        .map(|piece| turn(piece.start_tangent(), piece.end_tangent()))
+        .chain(
+            run.windows(2)
+                .map(|pair| turn(pair[0].end_tangent(), pair[1].start_tangent())),
+        )
+        .sum::<f64>()
+        .abs();
+    if total > PI + TERMINAL_SLACK {
+        return None;
+    }
+
+    if total <= MAX_POINTED_ANGLE {
+        let p = intersection(a, t_in, b, t_out)?;
+        return (dot(sub(p, a), t_in) > 0.0 && dot(sub(b, p), t_out) > 0.0)
+            .then(|| vec![Piece::Line(a, p), Piece::Line(p, b)]);
+    }
+    // the direction the corner points in, and how far the curves reach that way
+    let out = unit(sub(t_in, t_out));
+    let depth = run
+        .iter()
+        .flat_map(|piece| (1..=8).map(|i| piece.split(i as f64 / 8.0).0.end()))
+        .map(|p| dot(sub(p, a), out))
+        .fold(0.0, f64::max);
+    let c1 = add(a, scale(t_in, depth / dot(t_in, out)));
+    let c2 = sub(
+        b,
+        scale(t_out, (depth - dot(sub(b, a), out)) / -dot(t_out, out)),
+    );
+    Some(vec![
+        Piece::Line(a, c1),
+        Piece::Line(c1, c2),
+        Piece::Line(c2, b),
+    ])
+}
+
+/// `pieces` with the lines that carry straight on from a line merged into it
+fn merged(pieces: Vec<Piece>) -> Vec<Piece> {
+    let straight = |a: &Piece, b: &Piece| {
+        matches!((a, b), (Piece::Line(..), Piece::Line(..)))
+            && turn(a.end_tangent(), b.start_tangent()).abs() < SMOOTH_ANGLE
+    };
+    let mut merged: Vec<Piece> = vec![];
+    for piece in pieces {
+        match merged.last_mut() {
+            Some(last) if straight(last, &piece) => *last = Piece::Line(last.start(), piece.end()),
+            _ => merged.push(piece),
+        }
+    }
+    if merged.len() > 2 && straight(&merged[merged.len() - 1], &merged[0]) {
+        let last = merged.pop().unwrap();
+        merged[0] = Piece::Line(last.start(), merged[0].end());
+    }
+    merged
+}
+
+/// `pieces` with their runs of tight curves made sharp (see `sharp`)
+fn sharpened(pieces: Vec<Piece>) -> Vec<Piece> {
+    // starting from a piece that isn't tight, so no run goes past the end (a contour that's all
+    // tight curves, like a dot, is left round)
+    let Some(first) = pieces.iter().position(|piece| tight_turn(piece).is_none()) else {
+        return pieces;
+    };
+    let n = pieces.len();
+    let mut sharpened = vec![];
+    let mut i = 0;
+    while i < n {
+        // a run that turns one way and then the other (like a wave) isn't a corner, so it's ended
+        // where it changes
+        let way = tight_turn(&pieces[(first + i) % n]);
+        let run = (i..n)
+            .map(|i| pieces[(first + i) % n])
+            .take_while(|piece| way.is_some() && tight_turn(piece) == way)
+            .collect::<Vec<_>>();
+        if run.is_empty() {
+            sharpened.push(pieces[(first + i) % n]);
+            i += 1;
+            continue;
+        }
+        i += run.len();
+        sharpened.extend(sharp(&run).unwrap_or(run));
+    }
+    merged(sharpened)
+}
+
+/// Where along `piece` (from 0 at its start to 1 at its end) it's first `distance` from its start
+fn parameter_at(piece: &Piece, distance: f64) -> f64 {
+    if distance <= 0.0 {
+        return 0.0;
+    }
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..40 {
+        let middle = (low + high) / 2.0;
+        if length(sub(piece.split(middle).0.end(), piece.start())) < distance {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    high
+}
+
+/// `piece` with `start` cut off its start and `end` off its end (measured straight from them)
+fn trimmed(piece: Piece, start: f64, end: f64) -> Piece {
+    let s = parameter_at(&piece, start);
+    let e = (1.0 - parameter_at(&piece.reversed(), end)).max(s + 1e-9);
+    piece.split(e).0.split(s / e).1
+}
+
+/// `pieces` with every outside corner (one that turns the way `outside` is signed) rounded to
+/// `radius`, or as close to it as fits in half of each of the pieces on either side. Corners that
+/// turn back on themselves are left sharp, as are the inside corners, which are drawn that way.
+fn rounded(pieces: Vec<Piece>, radius: f64, outside: f64) -> Vec<Piece> {
+    let n = pieces.len();
+    // how far each piece is cut back from its start and its end, and whether it's followed by an
+    // arc
+    let mut cuts = vec![(0.0, 0.0); n];
+    let mut arcs = vec![false; n];
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let turn = turn(pieces[i].end_tangent(), pieces[j].start_tangent());
+        let angle = turn.abs();
+        if !(SMOOTH_ANGLE..=PI - SMOOTH_ANGLE).contains(&angle) || turn.signum() != outside {
+            continue;
+        }
+        let chord = |piece: &Piece| length(sub(piece.end(), piece.start()));
+        let reach = (radius * (angle / 2.0).tan())
+            .min(chord(&pieces[i]) / 2.0)
+            .min(chord(&pieces[j]) / 2.0);
+        if reach < EPSILON {
+            continue;
+        }
+        cuts[i].1 = reach;
+        cuts[j].0 = reach;
+        arcs[i] = true;
+    }
+
+    let trimmed = (0..n)
+        .map(|i| trimmed(pieces[i], cuts[i].0, cuts[i].1))
+        .collect::<Vec<_>>();
+    let mut rounded = vec![];
+    for i in 0..n {
+        rounded.push(trimmed[i]);
+        if !arcs[i] {
+            continue;
+        }
+        // the arc between the cut pieces, drawn as one curve (a line can be cut down to nothing,
+        // between the arcs at its ends, but it still goes the same way)
+        let j = (i + 1) % n;
+        let (a, b) = (trimmed[i].end(), trimmed[j].start());
+        let tangent = |cut: Point, whole: Point| if length(cut) > 0.0 { cut } else { whole };
+        let t_in = tangent(trimmed[i].end_tangent(), pieces[i].end_tangent());
+        let t_out = tangent(trimmed[j].start_tangent(), pieces[j].start_tangent());
+        let angle = turn(t_in, t_out).abs();
+        let r = length(sub(b, a)) / (2.0 * (angle / 2.0).sin());
+        let handle = 4.0 / 3.0 * (angle / 4.0).tan() * r;
+        rounded.push(Piece::Curve(
+            a,
+            add(a, scale(unit(t_in), handle)),
+            sub(b, scale(unit(t_out), handle)),
+            b,
+        ));
+    }
+    rounded.retain(|piece| {
+        matches!(piece, Piece::Curve(..)) || length(sub(piece.end(), piece.start())) > EPSILON
+    });
+    rounded
+}
+
+/// `spline_set` with the rounded corners and terminals it was drawn with made sharp, and then
+/// every outside corner rounded to `radius` (so none are, if it's 0), with the points rounded to
+/// whole units
+pub fn round_corners(spline_set: &SplineSet, radius: f64) -> SplineSet {
+    let depths = spline_set.depths();
+    let changed = SplineSet::new(
+        spline_set
+            .contours
+            .iter()
+            .zip(&depths)
+            .map(|(original, depth)| {
+                let mut pieces = sharpened(pieces(original));
+                if radius > 0.0 {
+                    // an outline's outside corners turn the way it goes around, and a hole's the
+                    // other way
+                    let outside = original.signed_area().signum();
+                    let outside = if depth % 2 == 0 { outside } else { -outside };
+                    pieces = rounded(pieces, radius, outside);
+                }
+                if pieces.is_empty() {
+                    original.clone()
+                } else {
+                    contour(&pieces)
+                }
+            })
+            .collect(),
+    )
+    .mapped(|p| Point::new(p.x.round(), p.y.round()));
+
+    // a contour that's made to cross itself or go the other way is left as it was drawn, and so
+    // is the whole spline set if its contours end up inside different ones
+    let kept = SplineSet::new(
+        spline_set
+            .contours
+            .iter()
+            .zip(changed.contours)
+            .map(|(original, changed)| {
+                let crosses =
+                    changed.self_intersections().len() > original.self_intersections().len();
+                let turned = changed.signed_area().signum() != original.signed_area().signum();
+                if crosses || turned {
+                    original.clone()
+                } else {
+                    changed
+                }
+            })
+            .collect(),
+    );
+    if kept.depths() == depths {
+        kept
+    } else {
+        spline_set.clone()
+    }
+}
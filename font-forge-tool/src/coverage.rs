@@ -0,0 +1,134 @@
+//! Coverage of the UCSUR sitelen pona block, checked against the table in `data/ucsur.txt`
+
+use std::{collections::HashMap, io, ops::RangeInclusive};
+
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, Font};
+
+const UCSUR: &str = include_str!("../data/ucsur.txt");
+pub const SITELEN_PONA_BLOCK: RangeInclusive<usize> = 0xF1900..=0xF19FF;
+
+/// The codepoints and names of the block, as listed in the table
+pub fn ucsur_table() -> Vec<(usize, &'static str)> {
+    UCSUR
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (codepoint, name) = line
+                .split_once(' ')
+                .unwrap_or_else(|| panic!("malformed UCSUR table line {line:?}"));
+            let codepoint = usize::from_str_radix(codepoint, 16)
+                .unwrap_or_else(|_| panic!("malformed codepoint in UCSUR table line {line:?}"));
+            (codepoint, name.trim())
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct Entry {
+    codepoint: String,
+    name: String,
+}
+
+#[derive(Serialize)]
+pub struct Mismatch {
+    codepoint: String,
+    expected: String,
+    actual: String,
+}
+
+#[derive(Serialize)]
+pub struct CoverageReport {
+    implemented: Vec<Entry>,
+    missing: Vec<Entry>,
+    mismatched: Vec<Mismatch>,
+    /// Codepoints in the block that the font uses, but UCSUR doesn't assign
+    unassigned: Vec<Entry>,
+}
+
+fn fmt_codepoint(codepoint: usize) -> String {
+    format!("U+{codepoint:04X}")
+}
+
+/// Compares the codepoints of `font` against the UCSUR table
+pub fn report(font: &Font) -> CoverageReport {
+    let table = ucsur_table();
+    let glyphs: HashMap<usize, String> = font
+        .encoded_glyphs()
+        .filter(|(codepoint, _)| SITELEN_PONA_BLOCK.contains(codepoint))
+        .map(|(codepoint, name)| (codepoint, name.to_string()))
+        .collect();
+
+    let mut report = CoverageReport {
+        implemented: vec![],
+        missing: vec![],
+        mismatched: vec![],
+        unassigned: vec![],
+    };
+    for &(codepoint, expected) in &table {
+        match glyphs.get(&codepoint) {
+            Some(actual) if actual.strip_suffix("Tok").unwrap_or(actual) == expected => {
+                report.implemented.push(Entry {
+                    codepoint: fmt_codepoint(codepoint),
+                    name: actual.clone(),
+                })
+            }
+            Some(actual) => report.mismatched.push(Mismatch {
+                codepoint: fmt_codepoint(codepoint),
+                expected: expected.to_string(),
+                actual: actual.clone(),
+            }),
+            None => report.missing.push(Entry {
+                codepoint: fmt_codepoint(codepoint),
+                name: expected.to_string(),
+            }),
+        }
+    }
+
+    let mut unassigned = glyphs
+        .iter()
+        .filter(|(codepoint, _)| !table.iter().any(|(c, _)| c == *codepoint))
+        .collect::<Vec<_>>();
+    unassigned.sort();
+    report.unassigned = unassigned
+        .into_iter()
+        .map(|(&codepoint, name)| Entry {
+            codepoint: fmt_codepoint(codepoint),
+            name: name.clone(),
+        })
+        .collect();
+
+    report
+}
+
+impl CoverageReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                let assigned = self.implemented.len() + self.missing.len() + self.mismatched.len();
+                println!(
+                    "{} of {assigned} UCSUR codepoints implemented",
+                    self.implemented.len()
+                );
+                for entry in &self.missing {
+                    println!("missing     {}  {}", entry.codepoint, entry.name);
+                }
+                for mismatch in &self.mismatched {
+                    println!(
+                        "mismatched  {}  {} (expected {})",
+                        mismatch.codepoint, mismatch.actual, mismatch.expected
+                    );
+                }
+                for entry in &self.unassigned {
+                    println!("unassigned  {}  {}", entry.codepoint, entry.name);
+                }
+            }
+        }
+        Ok(())
+    }
+}
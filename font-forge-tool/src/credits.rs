@@ -0,0 +1,89 @@
+//! Who drew the font's glyphs and what they're based on: a CREDITS list of the contributors, and
+//! the provenance of every glyph as JSON
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{ffir::TAG_PADDING, glyph_blocks::VERSION, Font};
+
+/// Who drew the glyphs that don't name their designer
+pub const FONT_DESIGNER: &str = "jan Itan";
+
+#[derive(Serialize)]
+pub struct GlyphProvenance {
+    pub name: String,
+    pub block: String,
+    pub designer: String,
+    pub source: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Provenances {
+    pub version: &'static str,
+    pub glyphs: Vec<GlyphProvenance>,
+}
+
+impl Provenances {
+    /// Collects the provenance of every glyph in `font` that's drawn (rather than composed of
+    /// references, or empty) or says where it's from
+    pub fn new(font: &Font) -> Self {
+        let glyphs = font
+            .blocks
+            .iter()
+            .flat_map(|block| {
+                block.full_names().filter_map(|(name, glyph)| {
+                    let glyph = &glyph.glyph;
+                    let provenance = &glyph.provenance;
+                    let drawn = !glyph.rep.spline_set().trim().is_empty();
+                    let credited = provenance.designer.is_some() || provenance.source.is_some();
+                    if glyph.has_tag(TAG_PADDING) || !(drawn || credited) {
+                        return None;
+                    }
+                    Some(GlyphProvenance {
+                        name: name.to_string(),
+                        block: block.name.to_string(),
+                        designer: provenance
+                            .designer
+                            .as_deref()
+                            .unwrap_or(FONT_DESIGNER)
+                            .to_string(),
+                        source: provenance.source.as_deref().map(String::from),
+                    })
+                })
+            })
+            .collect();
+        Self {
+            version: VERSION,
+            glyphs,
+        }
+    }
+
+    /// The CREDITS list: each designer with how many glyphs they drew, then each source with the
+    /// glyphs based on it
+    pub fn credits(&self) -> String {
+        let mut designers: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut sources: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for glyph in &self.glyphs {
+            *designers.entry(&glyph.designer).or_default() += 1;
+            if let Some(source) = &glyph.source {
+                sources.entry(source).or_default().push(&glyph.name);
+            }
+        }
+
+        let mut s = format!("# nasin nanpa {VERSION} credits\n\n## Designers\n\n");
+        let mut designers = designers.into_iter().collect::<Vec<_>>();
+        designers.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        for (designer, count) in designers {
+            let plural = if count == 1 { "" } else { "s" };
+            s += &format!("- {designer} ({count} glyph{plural})\n");
+        }
+        if !sources.is_empty() {
+            s += "\n## Sources\n\n";
+            for (source, glyphs) in sources {
+                s += &format!("- {source}: {}\n", glyphs.join(", "));
+            }
+        }
+        s
+    }
+}
@@ -0,0 +1,120 @@
+//! The `nasin-nanpa-data` crate: the font's codepoints, glyph names and ASCII spellings as Rust
+//! tables, generated from the same manifest as everything else, so applications (keyboards,
+//! converters, bots) can look them up without parsing a font file
+
+use std::{fs, io, path::Path};
+
+use itertools::Itertools;
+
+use crate::manifest::Manifest;
+
+/// The crate's manifest, at `version` (the font's)
+fn cargo_toml(version: &str) -> String {
+    format!(
+        "[package]\n\
+         name = \"nasin-nanpa-data\"\n\
+         version = \"{version}\"\n\
+         edition = \"2021\"\n\
+         description = \"The codepoints, glyph names and ASCII spellings of the nasin nanpa font\"\n\
+         license = \"MIT\"\n"
+    )
+}
+
+/// The crate's code: a `Glyph` for each glyph of `manifest` with a codepoint past ASCII or an
+/// ASCII spelling, and the functions that look them up
+fn lib_rs(manifest: &Manifest) -> String {
+    let glyphs = manifest
+        .glyphs
+        .iter()
+        .filter_map(|glyph| {
+            let codepoint = glyph
+                .codepoint
+                .as_deref()
+                .and_then(|codepoint| codepoint.strip_prefix("U+"))
+                .and_then(|codepoint| u32::from_str_radix(codepoint, 16).ok())
+                .filter(|codepoint| *codepoint >= 0x80);
+            if codepoint.is_none() && glyph.spellings.is_empty() {
+                return None;
+            }
+            let codepoint = match codepoint {
+                Some(codepoint) => format!("Some('\\u{{{codepoint:X}}}')"),
+                None => "None".to_string(),
+            };
+            let spellings = glyph
+                .spellings
+                .iter()
+                .map(|spelling| format!("{spelling:?}"))
+                .join(", ");
+            Some(format!(
+                "    Glyph {{ name: {:?}, codepoint: {codepoint}, spellings: &[{spellings}] }},\n",
+                glyph.name
+            ))
+        })
+        .collect_vec();
+    format!(
+        r#"//! The codepoints, glyph names and ASCII spellings of nasin nanpa {version}.
+//! Generated by `font-forge-tool data-crate`; don't edit by hand.
+
+/// A glyph of the font
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Glyph {{
+    /// The glyph's name in the font (like `tokiTok`)
+    pub name: &'static str,
+    /// The character the glyph is mapped from, if it's mapped from one past ASCII
+    pub codepoint: Option<char>,
+    /// The ASCII text the font turns into the glyph (like `toki`)
+    pub spellings: &'static [&'static str],
+}}
+
+/// The version of the font the tables are from
+pub const VERSION: &str = "{version}";
+
+/// Every glyph with a codepoint past ASCII or an ASCII spelling, in the font's order
+pub static GLYPHS: [Glyph; {count}] = [
+{glyphs}];
+
+/// The glyph called `name`
+pub fn by_name(name: &str) -> Option<&'static Glyph> {{
+    GLYPHS.iter().find(|glyph| glyph.name == name)
+}}
+
+/// The glyph mapped from `c`
+pub fn by_codepoint(c: char) -> Option<&'static Glyph> {{
+    GLYPHS.iter().find(|glyph| glyph.codepoint == Some(c))
+}}
+
+/// The glyph the font turns `spelling` into
+pub fn by_spelling(spelling: &str) -> Option<&'static Glyph> {{
+    GLYPHS.iter().find(|glyph| glyph.spellings.contains(&spelling))
+}}
+"#,
+        version = manifest.version,
+        count = glyphs.len(),
+        glyphs = glyphs.concat(),
+    )
+}
+
+/// Writes the crate to `dir`
+pub fn write(manifest: &Manifest, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), cargo_toml(manifest.version))?;
+    fs::write(dir.join("src").join("lib.rs"), lib_rs(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gen_font, snapshot, Profile};
+
+    #[test]
+    fn committed_crate_is_up_to_date() {
+        let font = gen_font(Profile::MAIN, &snapshot::options()).unwrap();
+        let manifest = Manifest::new(&font);
+        assert!(
+            lib_rs(&manifest) == include_str!("../../nasin-nanpa-data/src/lib.rs")
+                && cargo_toml(manifest.version)
+                    == include_str!("../../nasin-nanpa-data/Cargo.toml"),
+            "nasin-nanpa-data is out of date; run `font-forge-tool data-crate`"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Arrow alternates for the glyphs that point somewhere (like `tawa` and `kama`), picked by typing
+//! an arrow after the glyph (or joining it to one with a ZWJ). The descriptors say which way their
+//! glyph points as it's drawn, and an alternate is generated for each arrow that doesn't have a
+//! hand-drawn one (like `ni`'s): a reference to the glyph, turned to point the arrow's way.
+
+use std::collections::HashSet;
+
+use crate::{
+    block_kind::BlockKind,
+    ffir::*,
+    selectors::{SelectorKind, SELECTORS},
+};
+
+/// The point the alternates are turned around (the middle of `ni`'s hand-drawn arrows)
+const CENTER: (f64, f64) = (500.0, 400.0);
+
+/// A way a glyph can point, as an arrow can
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Heading {
+    W,
+    N,
+    E,
+    S,
+    NW,
+    NE,
+    SE,
+    SW,
+}
+
+impl Heading {
+    /// Every heading, in the order of the arrow selectors
+    pub const ALL: [Self; 8] = [
+        Self::W,
+        Self::N,
+        Self::E,
+        Self::S,
+        Self::NW,
+        Self::NE,
+        Self::SE,
+        Self::SW,
+    ];
+
+    /// The arrow selector that picks the alternate pointing this way (like `arrowNE`)
+    pub fn arrow(self) -> &'static str {
+        let index = Self::ALL
+            .iter()
+            .position(|heading| *heading == self)
+            .unwrap();
+        SELECTORS
+            .of_kind(SelectorKind::Arrow)
+            .nth(index)
+            .map(|selector| selector.name)
+            .unwrap()
+    }
+
+    /// How many eighths of a turn counter-clockwise from east this points
+    fn eighths(self) -> i32 {
+        match self {
+            Self::E => 0,
+            Self::NE => 1,
+            Self::N => 2,
+            Self::NW => 3,
+            Self::W => 4,
+            Self::SW => 5,
+            Self::S => 6,
+            Self::SE => 7,
+        }
+    }
+
+    /// Which side this points to: 1 for east, -1 for west, and 0 for straight up or down
+    fn side(self) -> i32 {
+        match self {
+            Self::E | Self::NE | Self::SE => 1,
+            Self::W | Self::NW | Self::SW => -1,
+            Self::N | Self::S => 0,
+        }
+    }
+
+    /// The heading across a vertical mirror
+    fn mirrored(self) -> Self {
+        match self {
+            Self::W => Self::E,
+            Self::E => Self::W,
+            Self::NW => Self::NE,
+            Self::NE => Self::NW,
+            Self::SE => Self::SW,
+            Self::SW => Self::SE,
+            Self::N | Self::S => self,
+        }
+    }
+}
+
+/// The transformation that turns a glyph pointing `from` (in an em 1000 wide) to point `to`. It's
+/// mirrored first if it has to turn to the other side, so a glyph that walks (like `tawa`) isn't
+/// turned upside down.
+fn turn(from: Heading, to: Heading) -> Transform {
+    let (from, mirror) = if from.side() * to.side() < 0 {
+        (from.mirrored(), Transform::mirror(1000.0))
+    } else {
+        (from, Transform::identity())
+    };
+    let diagonal = std::f64::consts::FRAC_1_SQRT_2;
+    let (cos, sin) = match (to.eighths() - from.eighths()).rem_euclid(8) {
+        0 => (1.0, 0.0),
+        1 => (diagonal, diagonal),
+        2 => (0.0, 1.0),
+        3 => (-diagonal, diagonal),
+        4 => (-1.0, 0.0),
+        5 => (-diagonal, -diagonal),
+        6 => (0.0, -1.0),
+        _ => (diagonal, -diagonal),
+    };
+    mirror
+        .translate(-CENTER.0, -CENTER.1)
+        .then(Transform::rotation(cos, sin))
+        .translate(CENTER.0, CENTER.1)
+}
+
+/// The block of the arrow alternates of every glyph in `descriptors` that points somewhere, except
+/// the ones already drawn in `drawn` (which are listed by their full names, like `niTok_arrowW`)
+pub fn block(descriptors: &[&[GlyphDescriptor]], drawn: &[GlyphDescriptor]) -> GlyphBlock {
+    let drawn = drawn
+        .iter()
+        .map(|descriptor| descriptor.name)
+        .collect::<HashSet<_>>();
+    let glyphs = descriptors
+        .iter()
+        .flat_map(|descriptors| descriptors.iter())
+        .filter_map(|descriptor| Some((descriptor, descriptor.heading?)))
+        .flat_map(|(descriptor, from)| Heading::ALL.iter().map(move |to| (descriptor, from, *to)))
+        .filter(|(descriptor, _, to)| {
+            !drawn.contains(format!("{}Tok_{}", descriptor.name, to.arrow()).as_str())
+        })
+        .map(|(descriptor, from, to)| {
+            let reference = Ref::new(GlyphName::new("", descriptor.name, "Tok"), turn(from, to));
+            let mut glyph = GlyphBasic::new(
+                format!("{}Tok_{}", descriptor.name, to.arrow()),
+                descriptor.width.unwrap_or(1000),
+                Rep::new("", vec![reference]),
+                None,
+            );
+            glyph.tags = vec![TAG_DIRECTIONAL];
+            glyph
+        })
+        .collect();
+    GlyphBlock::new_from_basic_glyphs(
+        glyphs,
+        LookupsMode::Alt,
+        Cc::Full,
+        "",
+        "",
+        BlockKind::Alternate.color(),
+        EncPos::None,
+    )
+    .named("base_arrow")
+}
@@ -0,0 +1,93 @@
+//! The dotted circle (U+25CC), which the combining characters (the extensions of cartouches and
+//! long glyphs) are shown on when they're typed after it, so they can be previewed (as in a
+//! character picker, or a chart of the block) instead of being drawn over nothing
+
+use std::{collections::HashSet, f64::consts::PI};
+
+use crate::{block_kind::BlockKind, features::*, ffir::*};
+
+pub const DOTTED_CIRCLE: &str = "dottedCircle";
+
+/// The combining characters that get a glyph on the dotted circle
+const MARKS: [&str; 3] = ["combCartExtTok", "combLongPiExtTok", "combLongGlyphExtTok"];
+
+/// How many dots go around the circle
+const DOTS: usize = 12;
+
+/// The name of the glyph of `mark` on the dotted circle
+fn composition_name(mark: &str) -> String {
+    format!("{DOTTED_CIRCLE}_{mark}")
+}
+
+/// The block of the dotted circle, as wide as a sitelen pona glyph (so a mark shown on it covers
+/// it like it would a glyph), and of each of `MARKS` on it
+pub fn block() -> Result<GlyphBlock, String> {
+    let centerlines = (0..DOTS)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f64 / DOTS as f64;
+            let (x, y) = (500.0 + 350.0 * angle.sin(), 350.0 + 350.0 * angle.cos());
+            format!("{} {} m 1\n", x.round(), y.round())
+        })
+        .collect::<String>();
+    let dots = Stroke {
+        centerlines: &centerlines,
+        width: 70.0,
+    };
+    let circle = GlyphBasic::new(DOTTED_CIRCLE, 1000, Rep::new(dots.expand()?, vec![]), None);
+    let compositions = MARKS.iter().map(|mark| {
+        let references = vec![
+            Ref::new(GlyphName::new("", DOTTED_CIRCLE, ""), Transform::identity()),
+            Ref::new(
+                GlyphName::new("", mark, ""),
+                Transform::translation(1000.0, 0.0),
+            ),
+        ];
+        GlyphBasic::new(composition_name(mark), 1000, Rep::new("", references), None)
+    });
+
+    let mut block = GlyphBlock::new_from_basic_glyphs(
+        std::iter::once(circle).chain(compositions).collect(),
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "",
+        BlockKind::DottedCircle.color(),
+        EncPos::None,
+    )
+    .named("dotted_circle")
+    .padded(Padding::None);
+    block.glyph_mut(DOTTED_CIRCLE)?.encoding.enc_pos = EncPos::Pos(0x25CC);
+    Ok(block)
+}
+
+/// Leaves out the glyphs of the marks in `excluded` on the dotted circle (since they'd reference
+/// glyphs that aren't there), returning their names
+pub fn leave_out(blocks: &mut [GlyphBlock], excluded: &HashSet<String>) -> Vec<String> {
+    blocks
+        .iter_mut()
+        .filter(|block| block.name == "dotted_circle")
+        .flat_map(|block| {
+            block.retain_glyphs(|name| {
+                MARKS
+                    .iter()
+                    .all(|mark| name != composition_name(mark) || !excluded.contains(*mark))
+            })
+        })
+        .collect()
+}
+
+/// Declares the ligatures of the dotted circle and each combining character after it, if the
+/// dotted circle is in `blocks`
+pub fn declare(blocks: &[GlyphBlock], features: &mut FeatureRegistry) {
+    if !blocks.iter().any(|block| block.name == "dotted_circle") {
+        return;
+    }
+    features.add_after(LIGA_SPACE, LIGA_DOTTED_CIRCLE, LookupType::Ligature, 0);
+    for mark in MARKS {
+        features.declare(
+            &composition_name(mark),
+            LIGA_DOTTED_CIRCLE,
+            format!("{DOTTED_CIRCLE} {mark}"),
+        );
+    }
+}
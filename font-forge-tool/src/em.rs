@@ -0,0 +1,166 @@
+//! The em: how many units it's divided into, and where the baseline splits it into the ascent
+//! and descent. The glyphs are drawn on a 1000-unit em, then scaled to the font's once they're
+//! generated (e.g. to 2048 units, which hints better as a TTF).
+
+use itertools::Itertools;
+
+use crate::{ffir::*, spline::gen_coord};
+
+/// The lines of the header (`Key: value`) in font units
+const SCALED_KEYS: [&str; 25] = [
+    "UnderlinePosition",
+    "UnderlineWidth",
+    "LineGap",
+    "VLineGap",
+    "OS2TypoAscent",
+    "OS2TypoDescent",
+    "OS2TypoLinegap",
+    "OS2WinAscent",
+    "OS2WinDescent",
+    "HheadAscent",
+    "HheadDescent",
+    "OS2SubXSize",
+    "OS2SubYSize",
+    "OS2SubXOff",
+    "OS2SubYOff",
+    "OS2SupXSize",
+    "OS2SupYSize",
+    "OS2SupXOff",
+    "OS2SupYOff",
+    "OS2StrikeYSize",
+    "OS2StrikeYPos",
+    "OS2CapHeight",
+    "OS2XHeight",
+    "OS2TypoAOffset",
+    "OS2TypoDOffset",
+];
+
+/// The entries of the private dictionary (`Key length value`) in font units (besides the stems,
+/// which are measured on the scaled outlines)
+const SCALED_PRIVATE_KEYS: [&str; 3] = ["BlueValues", "OtherBlues", "BlueShift"];
+
+/// The size of the em and how it's split
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Metrics {
+    pub units_per_em: usize,
+    /// How far the em goes above the baseline
+    pub ascent: usize,
+    /// How far the em goes below the baseline
+    pub descent: usize,
+    /// How wide the half-width punctuation is (see `half_width`), on the em the glyphs are drawn on
+    pub half_width: usize,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::DRAWN
+    }
+}
+
+impl Metrics {
+    /// The em the glyphs are drawn on
+    pub const DRAWN: Self = Self {
+        units_per_em: 1000,
+        ascent: 900,
+        descent: 100,
+        half_width: 500,
+    };
+
+    /// An em of `units_per_em` units, split like the one the glyphs are drawn on
+    pub fn with_units_per_em(units_per_em: usize) -> Self {
+        let ascent = (Self::DRAWN.ascent as f64 * units_per_em as f64
+            / Self::DRAWN.units_per_em as f64)
+            .round() as usize;
+        Self {
+            units_per_em,
+            ascent,
+            descent: units_per_em - ascent,
+            half_width: Self::DRAWN.half_width,
+        }
+    }
+
+    /// This em, with half-width punctuation `half_width` wide
+    pub fn with_half_width(self, half_width: usize) -> Self {
+        Self { half_width, ..self }
+    }
+
+    /// How much bigger this em is than the one the glyphs are drawn on
+    pub fn scale(&self) -> f64 {
+        self.units_per_em as f64 / Self::DRAWN.units_per_em as f64
+    }
+
+    /// Scales `units` on the drawn em to this em, rounding to a whole unit
+    fn scale_units(&self, units: f64) -> f64 {
+        (units * self.scale()).round()
+    }
+
+    /// Scales the glyphs in `blocks` (their outlines, references, anchors, and advances) from the
+    /// drawn em to this one
+    pub fn scale_blocks(&self, blocks: &mut [GlyphBlock]) -> Result<(), String> {
+        let s = self.scale();
+        let scale_advance = |advance: usize| self.scale_units(advance as f64) as usize;
+        for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+            let glyph = &mut glyph.glyph;
+            glyph
+                .rep
+                .scale(s)
+                .map_err(|e| format!("{}: {e}", glyph.name))?;
+            if let Some(anchor) = &mut glyph.anchor {
+                anchor.scale(s);
+            }
+            glyph.width = scale_advance(glyph.width);
+            glyph.vwidth = glyph.vwidth.map(scale_advance);
+        }
+        Ok(())
+    }
+
+    /// Scales the values in font units in a part of the header, and sets the ascent and descent
+    pub fn scale_header(&self, header: &str) -> String {
+        header
+            .split_inclusive('\n')
+            .map(|line| {
+                let (content, newline) = match line.strip_suffix('\n') {
+                    Some(content) => (content, "\n"),
+                    None => (line, ""),
+                };
+                format!("{}{newline}", self.scale_header_line(content))
+            })
+            .join("")
+    }
+
+    fn scale_header_line(&self, line: &str) -> String {
+        if let Some((key, value)) = line.split_once(": ") {
+            return match key {
+                "Ascent" => format!("{key}: {}", self.ascent),
+                "Descent" => format!("{key}: {}", self.descent),
+                _ if SCALED_KEYS.contains(&key) => match value.parse::<f64>() {
+                    Ok(units) => format!("{key}: {}", gen_coord(self.scale_units(units))),
+                    Err(_) => line.to_string(),
+                },
+                _ => line.to_string(),
+            };
+        }
+        let mut parts = line.splitn(3, ' ');
+        let (Some(key), Some(_), Some(value)) = (parts.next(), parts.next(), parts.next()) else {
+            return line.to_string();
+        };
+        if !SCALED_PRIVATE_KEYS.contains(&key) {
+            return line.to_string();
+        }
+        // the value is either a number or an array of them, after its length
+        let numbers = value
+            .trim_matches(['[', ']'])
+            .split_whitespace()
+            .map(|n| match n.parse::<f64>() {
+                Ok(units) => gen_coord(self.scale_units(units)),
+                Err(_) => n.to_string(),
+            })
+            .join(" ");
+        let value = if value.starts_with('[') {
+            format!("[{numbers}]")
+        } else {
+            numbers
+        };
+        format!("{key} {} {value}", value.len())
+    }
+}
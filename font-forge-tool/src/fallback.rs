@@ -0,0 +1,83 @@
+//! The glyphs drawn where the font has nothing better: `.notdef`, for the characters it doesn't
+//! have at all, and `unknownSitelenTok`, which every codepoint of the UCSUR sitelen pona block
+//! without a glyph of its own is mapped to (as a last resort), so a word the font doesn't have yet
+//! shows up as a sitelen pona-sized question mark rather than as the system's tofu
+
+use std::collections::HashSet;
+
+use crate::{block_kind::BlockKind, coverage::SITELEN_PONA_BLOCK, ffir::*};
+
+pub const NOTDEF: &str = ".notdef";
+
+/// A box a little narrower than the Latin letters are
+const NOTDEF_STROKE: Stroke = Stroke {
+    centerlines: "100 0 m 1\n 100 700 l 1\n 500 700 l 1\n 500 0 l 1\n 100 0 l 1\n",
+    width: 50.0,
+};
+
+/// A thin box around the whole glyph
+const FRAME_STROKE: Stroke = Stroke {
+    centerlines: "50 -50 m 1\n 50 850 l 1\n 950 850 l 1\n 950 -50 l 1\n 50 -50 l 1\n",
+    width: 30.0,
+};
+
+/// A question mark in the middle of the glyph, as thick as the sitelen pona are drawn
+const QUESTION_STROKE: Stroke = Stroke {
+    centerlines: "350 550 m 0\n 350 633 417 700 500 700 c 0\n 583 700 650 633 650 550 c 0\n \
+                  650 467 583 400 500 400 c 0\n 500 280 l 1\n500 120 m 1\n",
+    width: 100.0,
+};
+
+pub const UNKNOWN: &str = "unknownSitelenTok";
+
+/// The block of `.notdef` and `unknownSitelenTok` (which nothing is mapped to until
+/// `map_unmapped`)
+pub fn block() -> Result<GlyphBlock, String> {
+    let notdef = GlyphBasic::new(NOTDEF, 600, Rep::new(NOTDEF_STROKE.expand()?, vec![]), None);
+    let unknown = GlyphBasic::new(
+        UNKNOWN,
+        1000,
+        Rep::new(
+            format!("{}{}", FRAME_STROKE.expand()?, QUESTION_STROKE.expand()?),
+            vec![],
+        ),
+        None,
+    );
+    let mut block = GlyphBlock::new_from_basic_glyphs(
+        vec![notdef, unknown],
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "",
+        BlockKind::Fallback.color(),
+        EncPos::None,
+    )
+    .named("fallback")
+    .padded(Padding::None);
+    // the unknown word stands in for a word, so it's extended inside cartouches and long glyphs
+    block.glyph_mut(UNKNOWN)?.cc_subs = Cc::Full;
+    Ok(block)
+}
+
+/// Maps every codepoint of the sitelen pona block that no glyph in `blocks` has to
+/// `unknownSitelenTok`, if it's there
+pub fn map_unmapped(blocks: &mut [GlyphBlock]) {
+    let mapped = blocks
+        .iter()
+        .flat_map(|block| &block.glyphs)
+        .filter_map(|glyph| match glyph.encoding.enc_pos {
+            EncPos::Pos(codepoint) => Some(codepoint),
+            EncPos::None => None,
+        })
+        .collect::<HashSet<_>>();
+    let unmapped = SITELEN_PONA_BLOCK
+        .filter(|codepoint| !mapped.contains(codepoint))
+        .collect();
+    if let Some(unknown) = blocks
+        .iter_mut()
+        .filter(|block| block.name == "fallback")
+        .find_map(|block| block.glyph_mut(UNKNOWN).ok())
+    {
+        unknown.encoding.alt_unis = unmapped;
+    }
+}
@@ -0,0 +1,543 @@
+use itertools::Itertools;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use crate::ffir::AnchorClass;
+
+//MARK: LOOKUP NAMES
+pub const LIGA_SPACE: &str = "'liga' SPACE";
+/// Only declared when the gap between words is wide (see `spacing`)
+pub const LIGA_WIDE_SPACE: &str = "'liga' WIDE SPACE";
+pub const LIGA_WORDS: &str = "'liga' WORDS";
+pub const RAND_VARIATIONS: &str = "'rand' RAND VARIATIONS";
+pub const LIGA_VARIATIONS: &str = "'liga' VARIATIONS";
+pub const SS03_SIMPLIFIED: &str = "'ss03' SIMPLIFIED";
+pub const CALT_REMOVE_SPACE: &str = "'calt' REMOVE SPACE";
+pub const SS00_SP_TO_ZWSP: &str = "'ss00' SP TO ZWSP";
+/// Only declared when the half-width punctuation is generated (see `half_width`)
+pub const CALT_HALF_WIDTH: &str = "'calt' HALF WIDTH";
+pub const SS00_PUNCT_TO_HALF: &str = "'ss00' PUNCT TO HALF";
+pub const SS05_NUMBERS: &str = "'ss05' NUMBERS";
+pub const SS05_ORDINALS: &str = "'ss05' ORDINALS";
+pub const LIGA_START_CONTAINER: &str = "'liga' START CONTAINER";
+pub const CALT_CHANGE_ZWJ: &str = "'calt' CHANGE ZWJ";
+pub const SS01_ZWJ_TO_SCALE: &str = "'ss01' ZWJ TO SCALE";
+pub const SS02_ZWJ_TO_STACK: &str = "'ss02' ZWJ TO STACK";
+pub const SS02_ZWJ_TO_STACK3: &str = "'ss02' ZWJ TO STACK3";
+pub const LIGA_GLYPH_THEN_JOINER: &str = "'liga' GLYPH THEN JOINER";
+pub const CCMP_RESPAWN_JOINER: &str = "'ccmp' RESPAWN JOINER";
+pub const LIGA_JOINER_THEN_GLYPH: &str = "'liga' JOINER THEN GLYPH";
+pub const CALT_CART_AND_CONT: &str = "'calt' CART AND CONT";
+pub const CC01_CART: &str = "'cc01' CART";
+pub const CC02_CONT: &str = "'cc02' CONT";
+pub const CC03_CONT_IN_CART: &str = "'cc03' CONT IN CART";
+pub const CC04_CART_IN_CONT: &str = "'cc04' CART IN CONT";
+pub const LIGA_CC_CLEANUP: &str = "'liga' CC CLEANUP";
+/// Only declared when the underline is generated (see `underline`)
+pub const SS04_PI_UNDERLINE: &str = "'ss04' PI UNDERLINE";
+pub const SS00_TO_UNDERLINE: &str = "'ss00' TO UNDERLINE";
+pub const MARK_POSITION_COMBO: &str = "'mark' POSITION COMBO";
+pub const VERT_VERTICAL: &str = "'vert' VERTICAL";
+pub const VRT2_VERTICAL: &str = "'vrt2' VERTICAL";
+/// Only declared when there are names to spell out (see `names`)
+pub const LIGA_NAMES: &str = "'liga' NAMES";
+pub const LIGA_SPELL_NAMES: &str = "'liga' SPELL NAMES";
+/// Only declared when the dotted circle is generated (see `dotted_circle`)
+pub const LIGA_DOTTED_CIRCLE: &str = "'liga' DOTTED CIRCLE";
+
+//MARK: SUBTABLE NAMES
+/// Subtable names are the same as their lookup's name, except for these
+pub const LIGA_WORD: &str = "'liga' WORD";
+pub const LIGA_VAR: &str = "'liga' VAR";
+pub const MARK_STACK: &str = "'mark' STACK";
+pub const MARK_SCALE: &str = "'mark' SCALE";
+
+/// The script/language list every nasin nanpa feature is registered under
+const SCRIPTS: &str = "('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > )";
+
+/// A lookup type, numbered the way FontForge numbers them in `Lookup:` lines
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LookupType {
+    Single,
+    Multiple,
+    Alternate,
+    Ligature,
+    Context,
+    ChainContext,
+    MarkToBase,
+}
+
+impl LookupType {
+    fn id(&self) -> usize {
+        match self {
+            LookupType::Single => 1,
+            LookupType::Multiple => 2,
+            LookupType::Alternate => 3,
+            LookupType::Ligature => 4,
+            LookupType::Context => 5,
+            LookupType::ChainContext => 6,
+            LookupType::MarkToBase => 260,
+        }
+    }
+
+    /// The keyword used in a glyph's body to add an entry to a subtable of this type
+    fn glyph_keyword(&self) -> Option<&'static str> {
+        match self {
+            LookupType::Single => Some("Substitution2"),
+            LookupType::Multiple => Some("MultipleSubs2"),
+            LookupType::Alternate => Some("AlternateSubs2"),
+            LookupType::Ligature => Some("Ligature2"),
+            _ => None,
+        }
+    }
+
+    /// The keyword used to start a contextual subtable of this type
+    fn fpst_keyword(&self) -> Option<&'static str> {
+        match self {
+            LookupType::Context => Some("ContextSub2"),
+            LookupType::ChainContext => Some("ChainSub2"),
+            _ => None,
+        }
+    }
+}
+
+/// A lookup, consisting of its feature tag, type, flags, and subtables
+pub struct Lookup {
+    pub name: String,
+    pub tag: String,
+    pub ty: LookupType,
+    pub flags: usize,
+    pub subtables: Vec<String>,
+    /// The name applications show for this lookup's feature (e.g. in a character variant picker)
+    pub ui_name: Option<String>,
+}
+
+impl Lookup {
+    fn gen_ui_name(&self) -> Option<String> {
+        self.ui_name
+            .as_ref()
+            .map(|ui_name| format!("OtfFeatName: '{}' 1033 \"{ui_name}\"\n", self.tag))
+    }
+
+    fn gen(&self) -> String {
+        let subtables = self
+            .subtables
+            .iter()
+            .map(|subtable| format!("\"{subtable}\"  "))
+            .join("");
+        format!(
+            "Lookup: {ty} {flags} 0 \"{name}\" {{ {subtables}}} ['{tag}' {SCRIPTS} ]\n",
+            ty = self.ty.id(),
+            flags = self.flags,
+            name = self.name,
+            tag = self.tag,
+        )
+    }
+}
+
+/// The single place where every lookup (and its subtables) is declared.
+/// Both the `Lookup:` header lines and the per-glyph lookup lines are generated from this.
+#[derive(Default)]
+pub struct FeatureRegistry {
+    lookups: Vec<Lookup>,
+    /// Entries declared for specific glyphs up front, keyed by full glyph name
+    glyph_lines: HashMap<String, Vec<String>>,
+    /// Glyphs that were filtered out of the font; entries that mention them are left out
+    excluded: HashSet<String>,
+    /// Glyphs whose generated latin spellings are left out, for the ones declared for them (see
+    /// `respell`)
+    respelled: HashSet<String>,
+    /// The anchor classes positioned by the mark-to-base lookups
+    anchor_classes: Vec<AnchorClass>,
+    /// Pairs of a lookup and a lookup that has to be applied before it
+    dependencies: Vec<(String, String)>,
+}
+
+/// A glyph's generated entry in a lookup, by the glyphs it takes and the glyphs it makes
+pub struct Rule<'a> {
+    pub lookup: &'a str,
+    /// Where the lookup is applied, among the others
+    pub position: usize,
+    pub inputs: Vec<&'a str>,
+    pub outputs: Vec<&'a str>,
+}
+
+impl FeatureRegistry {
+    /// Declares a lookup whose only subtable shares its name
+    pub fn add(&mut self, name: &str, ty: LookupType, flags: usize) -> &mut Self {
+        self.add_with_subtables(name, ty, flags, &[name])
+    }
+
+    /// Declares a lookup with the given subtables (the feature tag is taken from the name)
+    pub fn add_with_subtables(
+        &mut self,
+        name: &str,
+        ty: LookupType,
+        flags: usize,
+        subtables: &[&str],
+    ) -> &mut Self {
+        let lookup = Self::new_lookup(name, ty, flags, subtables);
+        self.insert(self.lookups.len(), lookup)
+    }
+
+    /// Declares a mark-to-base lookup with a subtable for each of `classes`
+    pub fn add_anchor_classes(
+        &mut self,
+        name: &str,
+        flags: usize,
+        classes: &[AnchorClass],
+    ) -> &mut Self {
+        let subtables = classes.iter().map(|class| class.subtable()).collect_vec();
+        self.anchor_classes.extend(classes);
+        self.add_with_subtables(name, LookupType::MarkToBase, flags, &subtables)
+    }
+
+    /// Declares a lookup whose only subtable shares its name, right after the lookup `after`
+    pub fn add_after(
+        &mut self,
+        after: &str,
+        name: &str,
+        ty: LookupType,
+        flags: usize,
+    ) -> &mut Self {
+        let idx = self.position(after) + 1;
+        let lookup = Self::new_lookup(name, ty, flags, &[name]);
+        self.insert(idx, lookup)
+    }
+
+    /// Declares a lookup whose only subtable shares its name, right before the lookup `before`
+    pub fn add_before(
+        &mut self,
+        before: &str,
+        name: &str,
+        ty: LookupType,
+        flags: usize,
+    ) -> &mut Self {
+        let idx = self.position(before);
+        let lookup = Self::new_lookup(name, ty, flags, &[name]);
+        self.insert(idx, lookup)
+    }
+
+    /// Declares that the lookup `name` has to be applied after each of `dependencies` (see
+    /// `check_order`)
+    pub fn depends_on(&mut self, name: &str, dependencies: &[&str]) -> &mut Self {
+        for dependency in dependencies {
+            self.dependencies
+                .push((name.to_string(), dependency.to_string()));
+        }
+        self
+    }
+
+    /// Fails if a lookup is applied before a lookup it depends on
+    pub fn check_order(&self) -> Result<(), String> {
+        for (name, dependency) in &self.dependencies {
+            let (Some(idx), Some(dependency_idx)) =
+                (self.lookup_position(name), self.lookup_position(dependency))
+            else {
+                return Err(format!(
+                    "lookup {name} depends on {dependency}, but one of them isn't declared"
+                ));
+            };
+            if dependency_idx > idx {
+                return Err(format!(
+                    "lookup {name} depends on {dependency}, but is applied before it"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the lookup `name` is applied, among the others
+    pub fn lookup_position(&self, name: &str) -> Option<usize> {
+        self.lookups.iter().position(|l| l.name.eq(name))
+    }
+
+    fn position(&self, name: &str) -> usize {
+        self.lookup_position(name)
+            .unwrap_or_else(|| panic!("lookup {name} is not declared in the feature registry"))
+    }
+
+    fn new_lookup(name: &str, ty: LookupType, flags: usize, subtables: &[&str]) -> Lookup {
+        let tag = name
+            .strip_prefix('\'')
+            .and_then(|rest| rest.split_once('\''))
+            .map(|(tag, _)| tag.to_string())
+            .unwrap_or_else(|| panic!("lookup {name} doesn't start with a quoted feature tag"));
+        Lookup {
+            name: name.to_string(),
+            tag,
+            ty,
+            flags,
+            subtables: subtables.iter().map(|s| s.to_string()).collect(),
+            ui_name: None,
+        }
+    }
+
+    fn insert(&mut self, idx: usize, lookup: Lookup) -> &mut Self {
+        if self.lookups.iter().any(|l| l.name.eq(&lookup.name)) {
+            panic!("lookup {} was declared twice", lookup.name);
+        }
+        self.lookups.insert(idx, lookup);
+        self
+    }
+
+    /// Declares a `cvXX` feature (placed right after the lookup `after`) for every base glyph with alternates.
+    /// `alternates` are full alternate names like `jakiTok_VAR01`, grouped by the base glyph before the `_`.
+    /// Fails if more glyphs have alternates than there are `cvXX` features.
+    pub fn add_character_variants<'a>(
+        &mut self,
+        after: &str,
+        alternates: impl IntoIterator<Item = &'a str>,
+    ) -> Result<&mut Self, String> {
+        let mut bases: Vec<(&str, Vec<&str>)> = vec![];
+        for alt in alternates {
+            let Some((base, _)) = alt.split_once('_') else {
+                continue;
+            };
+            match bases.iter_mut().find(|(b, _)| base.eq(*b)) {
+                Some((_, alts)) => alts.push(alt),
+                None => bases.push((base, vec![alt])),
+            }
+        }
+        if bases.len() > 99 {
+            return Err(format!(
+                "{} glyphs have alternates, but there are only 99 cvXX features",
+                bases.len()
+            ));
+        }
+
+        let start = self.position(after) + 1;
+        for (i, (base, alts)) in bases.into_iter().enumerate() {
+            let name = format!("'cv{:02}' {base}", i + 1);
+            let mut lookup = Self::new_lookup(&name, LookupType::Alternate, 0, &[&name]);
+            lookup.ui_name = Some(base.strip_suffix("Tok").unwrap_or(base).to_string());
+            self.insert(start + i, lookup);
+
+            self.declare(base, &name, alts.join(" "));
+        }
+        Ok(self)
+    }
+
+    /// The registry for nasin nanpa, in the order the lookups are applied
+    pub fn nasin_nanpa() -> Self {
+        let mut features = Self::default();
+        features
+            .add(LIGA_SPACE, LookupType::Ligature, 0)
+            .add_with_subtables(LIGA_WORDS, LookupType::Ligature, 0, &[LIGA_WORD])
+            .add(RAND_VARIATIONS, LookupType::Alternate, 0)
+            .add_with_subtables(LIGA_VARIATIONS, LookupType::Ligature, 0, &[LIGA_VAR])
+            .add(SS03_SIMPLIFIED, LookupType::Single, 0)
+            .add(CALT_REMOVE_SPACE, LookupType::Context, 0)
+            .add(SS00_SP_TO_ZWSP, LookupType::Single, 0)
+            .add(SS05_NUMBERS, LookupType::Ligature, 0)
+            .add(SS05_ORDINALS, LookupType::Multiple, 0)
+            .add(LIGA_START_CONTAINER, LookupType::Ligature, 0)
+            .add(CALT_CHANGE_ZWJ, LookupType::Context, 0)
+            .add(SS01_ZWJ_TO_SCALE, LookupType::Single, 0)
+            .add(SS02_ZWJ_TO_STACK, LookupType::Single, 0)
+            .add(SS02_ZWJ_TO_STACK3, LookupType::Single, 0)
+            .add(LIGA_GLYPH_THEN_JOINER, LookupType::Ligature, 0)
+            .add(CCMP_RESPAWN_JOINER, LookupType::Multiple, 0)
+            .add(LIGA_JOINER_THEN_GLYPH, LookupType::Ligature, 0)
+            .add(CALT_CART_AND_CONT, LookupType::ChainContext, 0)
+            .add(CC01_CART, LookupType::Multiple, 0)
+            .add(CC02_CONT, LookupType::Multiple, 0)
+            .add(CC03_CONT_IN_CART, LookupType::Multiple, 0)
+            .add(CC04_CART_IN_CONT, LookupType::Multiple, 0)
+            .add(LIGA_CC_CLEANUP, LookupType::Ligature, 0)
+            .add_anchor_classes(MARK_POSITION_COMBO, 0, &AnchorClass::ALL)
+            .depends_on(LIGA_VARIATIONS, &[LIGA_WORDS])
+            .depends_on(SS03_SIMPLIFIED, &[LIGA_WORDS])
+            .depends_on(LIGA_START_CONTAINER, &[LIGA_WORDS])
+            .depends_on(CALT_CHANGE_ZWJ, &[LIGA_WORDS, LIGA_VARIATIONS])
+            .depends_on(LIGA_GLYPH_THEN_JOINER, &[CALT_CHANGE_ZWJ])
+            .depends_on(CCMP_RESPAWN_JOINER, &[LIGA_GLYPH_THEN_JOINER])
+            .depends_on(LIGA_JOINER_THEN_GLYPH, &[CCMP_RESPAWN_JOINER])
+            .depends_on(
+                CALT_CART_AND_CONT,
+                &[LIGA_START_CONTAINER, LIGA_JOINER_THEN_GLYPH],
+            )
+            .depends_on(
+                LIGA_CC_CLEANUP,
+                &[CC01_CART, CC02_CONT, CC03_CONT_IN_CART, CC04_CART_IN_CONT],
+            );
+        features
+    }
+
+    fn lookup(&self, name: &str) -> &Lookup {
+        self.lookups
+            .iter()
+            .find(|lookup| lookup.name.eq(name))
+            .unwrap_or_else(|| panic!("lookup {name} is not declared in the feature registry"))
+    }
+
+    fn lookup_of_subtable(&self, subtable: &str) -> &Lookup {
+        self.lookups
+            .iter()
+            .find(|lookup| lookup.subtables.iter().any(|s| s.eq(subtable)))
+            .unwrap_or_else(|| {
+                panic!("subtable {subtable} is not declared in the feature registry")
+            })
+    }
+
+    /// A hash of everything besides the per-glyph declarations that goes into the glyphs' lookup
+    /// lines (the lookups, which glyphs are left out, and which are respelled)
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.gen_header().hash(&mut hasher);
+        self.excluded
+            .iter()
+            .sorted()
+            .for_each(|name| name.hash(&mut hasher));
+        self.respelled
+            .iter()
+            .sorted()
+            .for_each(|name| name.hash(&mut hasher));
+        hasher.finish()
+    }
+
+    /// Generates the `Lookup:` lines of the header, plus the UI names of named features
+    pub fn gen_header(&self) -> String {
+        let lookups = self
+            .lookups
+            .iter()
+            .map(|lookup| {
+                tracing::debug!(lookup = lookup.name, "writing lookup");
+                lookup.gen()
+            })
+            .join("");
+        let ui_names = self
+            .lookups
+            .iter()
+            .filter_map(|lookup| lookup.gen_ui_name())
+            .join("");
+        format!("{lookups}MarkAttachClasses: 1\n{ui_names}")
+    }
+
+    /// Generates the `AnchorClass2:` line of the header, which gives each anchor class its subtable
+    pub fn gen_anchor_classes(&self) -> String {
+        let classes = self
+            .anchor_classes
+            .iter()
+            .map(|class| format!("\"{}\" \"{}\"", class.name(), class.subtable()))
+            .join(" ");
+        format!("AnchorClass2: {classes}\n")
+    }
+
+    /// Declares an entry in `subtable` for the glyph `full_name`
+    pub fn declare(&mut self, full_name: &str, subtable: &str, args: impl AsRef<str>) -> &mut Self {
+        let line = self.line(subtable, args);
+        self.glyph_lines
+            .entry(full_name.to_string())
+            .or_default()
+            .push(line);
+        self
+    }
+
+    /// Leaves out every entry that mentions one of `names` from now on
+    pub fn leave_out(&mut self, names: HashSet<String>) {
+        self.excluded = names;
+    }
+
+    /// Leaves out the latin spellings (the `LIGA_WORD` entries) that the glyph `full_name` is
+    /// generated with, so only the ones declared for it are left
+    pub fn respell(&mut self, full_name: &str) {
+        self.respelled.insert(full_name.to_string());
+    }
+
+    /// The entries generated for the glyph `full_name`, without its latin spellings if it's
+    /// respelled
+    pub fn unless_respelled(&self, full_name: &str, lines: String) -> String {
+        if !self.respelled.contains(full_name) {
+            return lines;
+        }
+        let spelling = format!(": \"{LIGA_WORD}\" ");
+        lines
+            .lines()
+            .filter(|line| !line.contains(&spelling))
+            .map(|line| format!("{line}\n"))
+            .collect()
+    }
+
+    /// Generates the entries that were declared up front for the glyph `full_name`
+    pub fn glyph_lines(&self, full_name: &str) -> String {
+        self.glyph_lines
+            .get(full_name)
+            .map(|lines| lines.join(""))
+            .unwrap_or_default()
+    }
+
+    /// The names of every lookup, in the order they're applied
+    pub fn lookup_names(&self) -> impl Iterator<Item = &str> {
+        self.lookups.iter().map(|lookup| lookup.name.as_str())
+    }
+
+    /// Splits a generated glyph entry into the name of its lookup and its arguments
+    pub fn parse_line<'a>(&self, line: &'a str) -> Option<(&str, &'a str)> {
+        let (_, rest) = line.split_once(": \"")?;
+        let (subtable, args) = rest.split_once('"')?;
+        let lookup = self
+            .lookups
+            .iter()
+            .find(|lookup| lookup.subtables.iter().any(|s| s.eq(subtable)))?;
+        Some((lookup.name.as_str(), args.trim()))
+    }
+
+    /// Parses an entry generated for the glyph `full_name`: a ligature takes its arguments and makes
+    /// the glyph, and the other types take the glyph and make their arguments
+    pub fn parse_rule<'a>(&'a self, full_name: &'a str, line: &'a str) -> Option<Rule<'a>> {
+        let (_, rest) = line.split_once(": \"")?;
+        let (subtable, args) = rest.split_once('"')?;
+        let position = self
+            .lookups
+            .iter()
+            .position(|lookup| lookup.subtables.iter().any(|s| s.eq(subtable)))?;
+        let lookup = &self.lookups[position];
+        let args = args.split_whitespace().collect_vec();
+        let (inputs, outputs) = match lookup.ty {
+            LookupType::Ligature => (args, vec![full_name]),
+            _ => (vec![full_name], args),
+        };
+        Some(Rule {
+            lookup: &lookup.name,
+            position,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Generates a glyph's entry in `subtable` (e.g. `Ligature2: "'liga' WORD" t o k i`)
+    pub fn line(&self, subtable: &str, args: impl AsRef<str>) -> String {
+        let lookup = self.lookup_of_subtable(subtable);
+        let keyword = lookup
+            .ty
+            .glyph_keyword()
+            .unwrap_or_else(|| panic!("subtable {subtable} can't have per-glyph entries"));
+        let args = args.as_ref();
+        if args
+            .split_whitespace()
+            .any(|name| self.excluded.contains(name))
+        {
+            return String::new();
+        }
+        format!("{keyword}: \"{subtable}\" {args}\n")
+    }
+
+    /// Generates the first line of a contextual subtable (e.g. `ContextSub2: class "'calt' REMOVE SPACE"`)
+    pub fn fpst(&self, subtable: &str) -> String {
+        let lookup = self.lookup_of_subtable(subtable);
+        let keyword = lookup
+            .ty
+            .fpst_keyword()
+            .unwrap_or_else(|| panic!("subtable {subtable} isn't contextual"));
+        format!("{keyword}: class \"{subtable}\"")
+    }
+
+    /// Generates a reference from a contextual rule to another lookup
+    pub fn seq_lookup(&self, pos: usize, name: &str) -> String {
+        let lookup = self.lookup(name);
+        format!("  SeqLookup: {pos} \"{name}\"\n", name = lookup.name)
+    }
+}
@@ -0,0 +1,750 @@
+//! A FontForge Python script that builds the font through FontForge's API instead of loading an
+//! `.sfd`, converted from a generated one, for pipelines that change the font before saving it
+//! (like `import` the script, call `build()`, and edit what it returns). It sets the metadata and
+//! metrics, the lookups (contextual ones included), the anchor classes, and every glyph with its
+//! outlines, references, anchors, codepoints, and lookup entries. The names of the stylistic sets
+//! and character variants are left out.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::spline::{gen_coord, Point, Segment, SplineSet};
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Number,
+    Text,
+    /// A list of numbers, like `Panose`
+    Numbers,
+}
+
+/// The header keys that are set as attributes of the font
+const ATTRIBUTES: [(&str, &str, Kind); 42] = [
+    ("FontName", "fontname", Kind::Text),
+    ("FullName", "fullname", Kind::Text),
+    ("FamilyName", "familyname", Kind::Text),
+    ("Weight", "weight", Kind::Text),
+    ("Copyright", "copyright", Kind::Text),
+    ("Version", "version", Kind::Text),
+    ("ItalicAngle", "italicangle", Kind::Number),
+    ("UnderlinePosition", "upos", Kind::Number),
+    ("UnderlineWidth", "uwidth", Kind::Number),
+    ("Ascent", "ascent", Kind::Number),
+    ("Descent", "descent", Kind::Number),
+    ("FSType", "os2_fstype", Kind::Number),
+    ("OS2Version", "os2_version", Kind::Number),
+    ("OS2_UseTypoMetrics", "os2_use_typo_metrics", Kind::Number),
+    ("TTFWeight", "os2_weight", Kind::Number),
+    ("TTFWidth", "os2_width", Kind::Number),
+    ("Panose", "os2_panose", Kind::Numbers),
+    ("LineGap", "hhea_linegap", Kind::Number),
+    ("VLineGap", "vhea_linegap", Kind::Number),
+    ("OS2TypoAscent", "os2_typoascent", Kind::Number),
+    ("OS2TypoAOffset", "os2_typoascent_add", Kind::Number),
+    ("OS2TypoDescent", "os2_typodescent", Kind::Number),
+    ("OS2TypoDOffset", "os2_typodescent_add", Kind::Number),
+    ("OS2TypoLinegap", "os2_typolinegap", Kind::Number),
+    ("OS2WinAscent", "os2_winascent", Kind::Number),
+    ("OS2WinAOffset", "os2_winascent_add", Kind::Number),
+    ("OS2WinDescent", "os2_windescent", Kind::Number),
+    ("OS2WinDOffset", "os2_windescent_add", Kind::Number),
+    ("HheadAscent", "hhea_ascent", Kind::Number),
+    ("HheadAOffset", "hhea_ascent_add", Kind::Number),
+    ("HheadDescent", "hhea_descent", Kind::Number),
+    ("HheadDOffset", "hhea_descent_add", Kind::Number),
+    ("OS2SubXSize", "os2_subxsize", Kind::Number),
+    ("OS2SubYSize", "os2_subysize", Kind::Number),
+    ("OS2SubXOff", "os2_subxoff", Kind::Number),
+    ("OS2SubYOff", "os2_subyoff", Kind::Number),
+    ("OS2SupXSize", "os2_supxsize", Kind::Number),
+    ("OS2SupYSize", "os2_supysize", Kind::Number),
+    ("OS2SupXOff", "os2_supxoff", Kind::Number),
+    ("OS2SupYOff", "os2_supyoff", Kind::Number),
+    ("OS2StrikeYSize", "os2_strikeysize", Kind::Number),
+    ("OS2StrikeYPos", "os2_strikeypos", Kind::Number),
+];
+
+/// What goes before the font is built: drawing a glyph's contours with a pen
+const PREAMBLE: &str = r#"import sys
+
+import fontforge
+
+
+def draw(glyph, contours):
+    pen = glyph.glyphPen()
+    for start, segments in contours:
+        pen.moveTo(start)
+        for segment in segments:
+            if len(segment) == 2:
+                pen.lineTo(segment)
+            else:
+                pen.curveTo(*segment)
+        pen.closePath()
+    pen = None
+
+
+def build():
+    font = fontforge.font()
+    font.encoding = "UnicodeFull"
+"#;
+
+const POSTSCRIPT: &str = r#"    return font
+
+
+if __name__ == "__main__":
+    build().save(sys.argv[1] if len(sys.argv) > 1 else "nasin-nanpa.sfd")
+"#;
+
+/// A string as a Python literal
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+fn tuple(items: impl IntoIterator<Item = String>) -> String {
+    let items = items.into_iter().collect_vec();
+    match items.len() {
+        1 => format!("({},)", items[0]),
+        _ => format!("({})", items.join(", ")),
+    }
+}
+
+fn point(p: Point) -> String {
+    format!("({}, {})", gen_coord(p.x), gen_coord(p.y))
+}
+
+/// A token of a `Lookup:` line
+#[derive(PartialEq, Debug)]
+enum Token<'a> {
+    Word(&'a str),
+    /// A `"quoted"` name
+    Name(&'a str),
+    /// A `'tag'`
+    Tag(&'a str),
+    Punct(char),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = vec![];
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let (token, len) = match c {
+            '"' | '\'' => {
+                let end = rest[1..]
+                    .find(c)
+                    .ok_or_else(|| format!("unclosed {c} in `{s}`"))?;
+                let inner = &rest[1..end + 1];
+                let token = if c == '"' {
+                    Token::Name(inner)
+                } else {
+                    Token::Tag(inner)
+                };
+                (token, end + 2)
+            }
+            '{' | '}' | '[' | ']' | '(' | ')' | '<' | '>' => (Token::Punct(c), 1),
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                (Token::Word(&rest[..end]), end)
+            }
+        };
+        tokens.push(token);
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A feature's tag, with the scripts it's for and the languages of each
+type Feature = (String, Vec<(String, Vec<String>)>);
+
+/// A lookup, as its `Lookup:` line declares it
+struct Lookup {
+    kind: &'static str,
+    flags: Vec<&'static str>,
+    name: String,
+    subtables: Vec<String>,
+    /// The features it's in, with the scripts and languages of each
+    features: Vec<Feature>,
+}
+
+impl Lookup {
+    fn parse(line: &str) -> Result<Self, String> {
+        let err = || format!("couldn't parse the lookup `{line}`");
+        let tokens = tokenize(line)?;
+        let mut tokens = tokens.iter().peekable();
+        let mut number = || match tokens.next() {
+            Some(Token::Word(word)) => word.parse::<usize>().map_err(|_| err()),
+            _ => Err(err()),
+        };
+        let (kind, flags, _) = (number()?, number()?, number()?);
+        let kind = match kind {
+            1 => "gsub_single",
+            2 => "gsub_multiple",
+            3 => "gsub_alternate",
+            4 => "gsub_ligature",
+            5 => "gsub_context",
+            6 => "gsub_contextchain",
+            0x104 => "gpos_mark2base",
+            _ => return Err(err()),
+        };
+        if flags & !0xf != 0 {
+            return Err(err());
+        }
+        let flags = [
+            "right_to_left",
+            "ignore_bases",
+            "ignore_ligatures",
+            "ignore_marks",
+        ]
+        .into_iter()
+        .enumerate()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, flag)| flag)
+        .collect();
+
+        let Some(Token::Name(name)) = tokens.next() else {
+            return Err(err());
+        };
+        let mut subtables = vec![];
+        if tokens.next() != Some(&Token::Punct('{')) {
+            return Err(err());
+        }
+        while let Some(Token::Name(subtable)) = tokens.peek() {
+            subtables.push(subtable.to_string());
+            tokens.next();
+        }
+        if tokens.next() != Some(&Token::Punct('}')) || tokens.next() != Some(&Token::Punct('[')) {
+            return Err(err());
+        }
+        let mut features = vec![];
+        while let Some(Token::Tag(feature)) = tokens.next() {
+            if tokens.next() != Some(&Token::Punct('(')) {
+                return Err(err());
+            }
+            let mut scripts = vec![];
+            while let Some(Token::Tag(script)) = tokens.next() {
+                if tokens.next() != Some(&Token::Punct('<')) {
+                    return Err(err());
+                }
+                let mut languages = vec![];
+                while let Some(Token::Tag(language)) = tokens.next() {
+                    languages.push(language.to_string());
+                }
+                scripts.push((script.to_string(), languages));
+            }
+            features.push((feature.to_string(), scripts));
+        }
+        Ok(Self {
+            kind,
+            flags,
+            name: name.to_string(),
+            subtables,
+            features,
+        })
+    }
+
+    fn is_contextual(&self) -> bool {
+        matches!(self.kind, "gsub_context" | "gsub_contextchain")
+    }
+
+    fn gen(&self, after: Option<&str>) -> String {
+        let features = tuple(self.features.iter().map(|(feature, scripts)| {
+            let scripts = tuple(scripts.iter().map(|(script, languages)| {
+                format!(
+                    "({}, {})",
+                    quote(script),
+                    tuple(languages.iter().map(|l| quote(l)))
+                )
+            }));
+            format!("({}, {scripts})", quote(feature))
+        }));
+        let after = after
+            .map(|after| format!(", {}", quote(after)))
+            .unwrap_or_default();
+        format!(
+            "    font.addLookup({}, {}, {}, {features}{after})\n",
+            quote(&self.name),
+            quote(self.kind),
+            tuple(self.flags.iter().map(|flag| quote(flag))),
+        )
+    }
+}
+
+/// A class-based contextual subtable (a `ContextSub2` or `ChainSub2`)
+#[derive(Default)]
+struct Fpst {
+    subtable: String,
+    chain: bool,
+    /// The members of each class (after the first, which is every other glyph), for the
+    /// backtrack, the input, and the lookahead
+    classes: [Vec<Vec<String>>; 3],
+    class_names: [Vec<String>; 3],
+    rules: Vec<String>,
+}
+
+impl Fpst {
+    /// A name for class `i` of the backtrack (0), input (1), or lookahead (2)
+    fn class_name(&self, part: usize, i: usize) -> Result<String, String> {
+        match self.class_names[part].get(i) {
+            Some(name) => Ok(name.clone()),
+            None if self.class_names[part].is_empty() => Ok(format!("class{i}")),
+            None => Err(format!("{} has no class {i}", self.subtable)),
+        }
+    }
+
+    fn gen(&self, lookup: &str) -> Result<String, String> {
+        let classes = |part: usize| {
+            tuple(
+                std::iter::once("None".to_string()).chain(
+                    self.classes[part]
+                        .iter()
+                        .map(|members| tuple(members.iter().map(|m| quote(m)))),
+                ),
+            )
+        };
+        let names = |part: usize| {
+            (0..=self.classes[part].len())
+                .map(|i| self.class_name(part, i).map(|name| quote(&name)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(tuple)
+        };
+        let mut args = format!("mclasses={}, mclassnames={}", classes(1), names(1)?);
+        if self.chain {
+            args = format!(
+                "bclasses={}, bclassnames={}, {args}, fclasses={}, fclassnames={}",
+                classes(0),
+                names(0)?,
+                classes(2),
+                names(2)?,
+            );
+        }
+        Ok(format!(
+            "    font.addContextualSubtable({}, {}, \"class\", {}, {args})\n",
+            quote(lookup),
+            quote(&self.subtable),
+            quote(&self.rules.join("\n")),
+        ))
+    }
+}
+
+/// The names in a `ClassNames:` line
+fn class_names(rest: &str) -> Result<Vec<String>, String> {
+    Ok(tokenize(rest)?
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Name(name) => Some(name.to_string()),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Parses the FPST starting at `lines` (after its first line), up to its `EndFPST`
+fn parse_fpst<'a>(
+    subtable: &str,
+    chain: bool,
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<Fpst, String> {
+    let mut fpst = Fpst {
+        subtable: subtable.to_string(),
+        chain,
+        ..Default::default()
+    };
+    // the classes each rule is made of, by part, and the lookups it applies
+    let mut rule: [Vec<usize>; 3] = Default::default();
+    let mut seq_lookups: Vec<(usize, String)> = vec![];
+    let mut rules = vec![];
+    for line in lines.by_ref() {
+        let line = line.trim();
+        let (key, rest) = line.split_once(':').unwrap_or((line, ""));
+        let list = |rest: &str| {
+            rest.split_whitespace()
+                .map(|n| {
+                    n.parse::<usize>()
+                        .map_err(|_| format!("couldn't parse `{line}`"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        match key {
+            "Class" | "BClass" | "FClass" => {
+                let part = ["BClass", "Class", "FClass"]
+                    .iter()
+                    .position(|k| *k == key)
+                    .unwrap();
+                let members = rest
+                    .split_whitespace()
+                    .skip(1)
+                    .map(str::to_string)
+                    .collect();
+                fpst.classes[part].push(members);
+            }
+            "ClassNames" => fpst.class_names[1] = class_names(rest)?,
+            "BClassNames" => fpst.class_names[0] = class_names(rest)?,
+            "FClassNames" => fpst.class_names[2] = class_names(rest)?,
+            "ClsList" => rule[1] = list(rest)?,
+            "BClsList" => rule[0] = list(rest)?,
+            "FClsList" => rule[2] = list(rest)?,
+            "SeqLookup" => {
+                let (pos, lookup) = rest
+                    .trim()
+                    .split_once(' ')
+                    .ok_or(format!("couldn't parse `{line}`"))?;
+                let pos = pos
+                    .parse()
+                    .map_err(|_| format!("couldn't parse `{line}`"))?;
+                seq_lookups.push((pos, lookup.trim().trim_matches('"').to_string()));
+            }
+            "EndFPST" => break,
+            // the counts that start each rule: the rule before it is done
+            _ if !rule[1].is_empty() && line.split_whitespace().count() == 3 => {
+                rules.push((std::mem::take(&mut rule), std::mem::take(&mut seq_lookups)));
+            }
+            _ => {}
+        }
+    }
+    rules.push((rule, seq_lookups));
+
+    for ([backtrack, input, lookahead], lookups) in rules {
+        let names = |part: usize, classes: &[usize]| {
+            classes
+                .iter()
+                .map(|i| fpst.class_name(part, *i))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let mut input = names(1, &input)?;
+        for (pos, lookup) in lookups {
+            let class = input
+                .get_mut(pos)
+                .ok_or(format!("{subtable} applies {lookup} past its input"))?;
+            class.push_str(&format!(" @<{lookup}>"));
+        }
+        let rule = format!(
+            "{} | {} | {}",
+            names(0, &backtrack)?.join(" "),
+            input.join(" "),
+            names(2, &lookahead)?.join(" ")
+        );
+        fpst.rules.push(rule.trim().to_string());
+    }
+    Ok(fpst)
+}
+
+/// A glyph, as its section declares it
+#[derive(Default)]
+struct Glyph {
+    name: String,
+    slot: usize,
+    codepoint: Option<usize>,
+    /// The `(codepoint, variation selector)` pairs it's also mapped to
+    alt_unis: Vec<(usize, Option<usize>)>,
+    width: usize,
+    vwidth: Option<usize>,
+    /// Its class in the `GDEF` table, as FontForge's scripts name it
+    class: Option<&'static str>,
+    color: Option<String>,
+    anchors: Vec<String>,
+    /// The slots it references, with their transformations
+    references: Vec<(usize, String)>,
+    spline_set: String,
+    /// Where the caret goes between the components of a ligature
+    carets: Vec<usize>,
+    entries: Vec<String>,
+    /// Whether it has anything besides its slot, so it isn't padding
+    shown: bool,
+}
+
+fn parse_glyph<'a>(name: &str, lines: &mut impl Iterator<Item = &'a str>) -> Result<Glyph, String> {
+    let mut glyph = Glyph {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let mut in_spline_set = false;
+    for line in lines.by_ref() {
+        if in_spline_set {
+            if line == "EndSplineSet" {
+                in_spline_set = false;
+            } else {
+                glyph.spline_set.push_str(line);
+                glyph.spline_set.push('\n');
+            }
+            continue;
+        }
+        let err = || format!("couldn't parse `{line}` of {name}");
+        let (key, rest) = line.split_once(": ").unwrap_or((line, ""));
+        let number = |s: &str| s.parse::<usize>().map_err(|_| err());
+        match key {
+            "EndChar" => break,
+            "Encoding" => {
+                let parts = rest.split_whitespace().collect_vec();
+                let [slot, codepoint, _] = parts.as_slice() else {
+                    return Err(err());
+                };
+                glyph.slot = number(slot)?;
+                glyph.codepoint = codepoint.parse().ok();
+                glyph.shown |= glyph.codepoint.is_some();
+            }
+            "AltUni2" => {
+                for alt in rest.split_whitespace() {
+                    let parts = alt.split('.').collect_vec();
+                    let [codepoint, selector, _] = parts.as_slice() else {
+                        return Err(err());
+                    };
+                    let hex = |s: &str| usize::from_str_radix(s, 16).map_err(|_| err());
+                    let selector = if *selector == "ffffffff" {
+                        None
+                    } else {
+                        Some(hex(selector)?)
+                    };
+                    glyph.alt_unis.push((hex(codepoint)?, selector));
+                }
+            }
+            "Width" => glyph.width = number(rest)?,
+            "VWidth" => glyph.vwidth = Some(number(rest)?),
+            "GlyphClass" => {
+                glyph.class = Some(match number(rest)? {
+                    1 => "noclass",
+                    2 => "baseglyph",
+                    3 => "baseligature",
+                    4 => "mark",
+                    5 => "component",
+                    _ => return Err(err()),
+                })
+            }
+            "Colour" => glyph.color = Some(rest.to_string()),
+            "AnchorPoint" => {
+                let tokens = tokenize(rest)?;
+                let [Token::Name(class), Token::Word(x), Token::Word(y), Token::Word(ty), ..] =
+                    tokens.as_slice()
+                else {
+                    return Err(err());
+                };
+                let ty = if *ty == "basechar" { "base" } else { ty };
+                glyph
+                    .anchors
+                    .push(format!("{}, {}, {x}, {y}", quote(class), quote(ty)));
+            }
+            "Refer" => {
+                let parts = rest.split_whitespace().collect_vec();
+                let [slot, _, _, matrix @ .., _] = parts.as_slice() else {
+                    return Err(err());
+                };
+                glyph
+                    .references
+                    .push((number(slot)?, format!("({})", matrix.join(", "))));
+            }
+            "LCarets2" => {
+                let carets = rest.split_whitespace().skip(1).map(number);
+                glyph.carets = carets.collect::<Result<_, _>>()?;
+            }
+            "Fore" | "LayerCount" | "Flags" => {}
+            "SplineSet" => in_spline_set = true,
+            "Ligature2" | "Substitution2" | "MultipleSubs2" | "AlternateSubs2" => {
+                let tokens = tokenize(rest)?;
+                let [Token::Name(subtable), args @ ..] = tokens.as_slice() else {
+                    return Err(err());
+                };
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        Token::Word(name) => Ok(quote(name)),
+                        _ => Err(err()),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let args = if key == "Substitution2" {
+                    args.join(", ")
+                } else {
+                    tuple(args)
+                };
+                glyph.entries.push(format!("{}, {args}", quote(subtable)));
+            }
+            _ => {
+                return Err(format!(
+                    "{name} has `{line}`, which can't be written as a script"
+                ))
+            }
+        }
+        glyph.shown |= !matches!(
+            (key, rest),
+            ("Encoding" | "LayerCount" | "Colour", _) | ("Width", "0")
+        );
+    }
+    Ok(glyph)
+}
+
+impl Glyph {
+    /// Creates the glyph, and draws it (but not its references)
+    fn gen(&self) -> Result<String, String> {
+        let mut out = format!(
+            "    g = font.createChar({}, {})\n    g.width = {}\n",
+            self.codepoint
+                .map_or("-1".to_string(), |c| format!("{c:#x}")),
+            quote(&self.name),
+            self.width,
+        );
+        if let Some(vwidth) = self.vwidth {
+            out += &format!("    g.vwidth = {vwidth}\n");
+        }
+        if let Some(class) = self.class {
+            out += &format!("    g.glyphclass = {}\n", quote(class));
+        }
+        if let Some(color) = &self.color {
+            out += &format!("    g.color = 0x{color:0>6}\n");
+        }
+        if !self.alt_unis.is_empty() {
+            let alt_unis = tuple(self.alt_unis.iter().map(|(codepoint, selector)| {
+                let selector = selector.map_or("-1".to_string(), |s| format!("{s:#x}"));
+                format!("({codepoint:#x}, {selector}, 0)")
+            }));
+            out += &format!("    g.altuni = {alt_unis}\n");
+        }
+        if !self.carets.is_empty() {
+            out += &format!(
+                "    g.lcarets = {}\n",
+                tuple(self.carets.iter().map(usize::to_string))
+            );
+        }
+        for anchor in &self.anchors {
+            out += &format!("    g.addAnchorPoint({anchor})\n");
+        }
+        let spline_set =
+            SplineSet::parse(&self.spline_set).map_err(|e| format!("{}: {e}", self.name))?;
+        if !spline_set.contours.is_empty() {
+            let mut contours = spline_set.contours.iter().map(|contour| {
+                let mut segments = contour
+                    .segments
+                    .iter()
+                    .map(|(segment, _)| *segment)
+                    .collect_vec();
+                // closing the path draws the line back to the start
+                if segments.last() == Some(&Segment::Line { to: contour.start }) {
+                    segments.pop();
+                }
+                let mut segments = segments.iter().map(|segment| match segment {
+                    Segment::Line { to } => point(*to),
+                    Segment::Curve { c1, c2, to } => {
+                        format!("({}, {}, {})", point(*c1), point(*c2), point(*to))
+                    }
+                });
+                format!("({}, [{}])", point(contour.start), segments.join(", "))
+            });
+            out += &format!("    draw(g, [{}])\n", contours.join(", "));
+        }
+        Ok(out)
+    }
+
+    /// Adds the glyph's references (to the glyphs in `names`, by slot) and its lookup entries
+    fn gen_links(&self, names: &HashMap<usize, &str>) -> Result<String, String> {
+        if self.references.is_empty() && self.entries.is_empty() {
+            return Ok(String::new());
+        }
+        let mut out = format!("    g = font[{}]\n", quote(&self.name));
+        for (slot, matrix) in &self.references {
+            let target = names
+                .get(slot)
+                .ok_or(format!("{} references the empty slot {slot}", self.name))?;
+            out += &format!("    g.addReference({}, {matrix})\n", quote(target));
+        }
+        for entry in &self.entries {
+            out += &format!("    g.addPosSub({entry})\n");
+        }
+        Ok(out)
+    }
+}
+
+/// Converts the generated `.sfd` `sfd` into a script that builds the same font
+pub fn script(sfd: &str) -> Result<String, String> {
+    let mut out = PREAMBLE.to_string();
+    let mut lookups = vec![];
+    let mut anchor_classes = vec![];
+    let mut fpsts = vec![];
+    let mut glyphs = vec![];
+
+    let mut lines = sfd.lines();
+    while let Some(line) = lines.next() {
+        let (key, rest) = line.split_once(": ").unwrap_or((line, ""));
+        if let Some((_, attribute, kind)) = ATTRIBUTES.iter().find(|(k, _, _)| *k == key) {
+            let value = match kind {
+                Kind::Number => rest.to_string(),
+                Kind::Text => quote(rest),
+                Kind::Numbers => tuple(rest.split_whitespace().map(str::to_string)),
+            };
+            out += &format!("    font.{attribute} = {value}\n");
+            continue;
+        }
+        match key {
+            "OS2Vendor" => {
+                out += &format!("    font.os2_vendor = {}\n", quote(rest.trim_matches('\'')))
+            }
+            "Lookup" => lookups.push(Lookup::parse(rest)?),
+            "AnchorClass2" => {
+                for pair in tokenize(rest)?.chunks(2) {
+                    let [Token::Name(class), Token::Name(subtable)] = pair else {
+                        return Err(format!("couldn't parse `{line}`"));
+                    };
+                    anchor_classes.push((class.to_string(), subtable.to_string()));
+                }
+            }
+            "ContextSub2" | "ChainSub2" => {
+                let tokens = tokenize(rest)?;
+                let [Token::Word("class"), Token::Name(subtable), ..] = tokens.as_slice() else {
+                    return Err(format!("`{line}` isn't a class-based subtable"));
+                };
+                fpsts.push(parse_fpst(subtable, key == "ChainSub2", &mut lines)?);
+            }
+            "StartChar" => {
+                let glyph = parse_glyph(rest, &mut lines)?;
+                if glyph.shown {
+                    glyphs.push(glyph);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // each table's lookups in order, after the one before
+    let mut last: HashMap<bool, &str> = HashMap::new();
+    for lookup in &lookups {
+        let gpos = lookup.kind.starts_with("gpos");
+        out += &lookup.gen(last.get(&gpos).copied());
+        last.insert(gpos, &lookup.name);
+        if lookup.is_contextual() {
+            continue;
+        }
+        let mut after: Option<&str> = None;
+        for subtable in &lookup.subtables {
+            let after_arg = after.map(|a| format!(", {}", quote(a))).unwrap_or_default();
+            out += &format!(
+                "    font.addLookupSubtable({}, {}{after_arg})\n",
+                quote(&lookup.name),
+                quote(subtable)
+            );
+            for (class, _) in anchor_classes.iter().filter(|(_, s)| s == subtable) {
+                out += &format!(
+                    "    font.addAnchorClass({}, {})\n",
+                    quote(subtable),
+                    quote(class)
+                );
+            }
+            after = Some(subtable);
+        }
+    }
+
+    for glyph in &glyphs {
+        out += &glyph.gen()?;
+    }
+    let names = glyphs
+        .iter()
+        .map(|glyph| (glyph.slot, glyph.name.as_str()))
+        .collect();
+    for glyph in &glyphs {
+        out += &glyph.gen_links(&names)?;
+    }
+
+    for fpst in &fpsts {
+        let lookup = lookups
+            .iter()
+            .find(|lookup| lookup.subtables.contains(&fpst.subtable))
+            .ok_or(format!("no lookup has the subtable {}", fpst.subtable))?;
+        out += &fpst.gen(&lookup.name)?;
+    }
+    out += POSTSCRIPT;
+    Ok(out)
+}
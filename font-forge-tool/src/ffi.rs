@@ -0,0 +1,98 @@
+//! C bindings (the `ffi` feature), the same as the JavaScript ones in `wasm`. Strings go in and
+//! out as NUL-terminated UTF-8, and every string returned is owned by the caller, who frees it with
+//! `nasin_nanpa_free_string`.
+
+use std::ffi::{c_char, CStr, CString};
+
+use crate::translit;
+
+/// The string at `text`, if it's UTF-8
+///
+/// # Safety
+/// `text` must be null or point to a NUL-terminated string.
+unsafe fn borrow<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+/// Hands `text` to the caller (or null, for none)
+fn give(text: Option<String>) -> *mut c_char {
+    text.and_then(|text| CString::new(text).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Converts ASCII text to UCSUR the way the font's ligatures do. Null if `text` isn't UTF-8.
+///
+/// # Safety
+/// `text` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nasin_nanpa_ascii_to_ucsur(text: *const c_char) -> *mut c_char {
+    give(borrow(text).map(translit::ascii_to_ucsur))
+}
+
+/// The name of the glyph mapped from `codepoint`, or null
+#[no_mangle]
+pub extern "C" fn nasin_nanpa_glyph_name(codepoint: u32) -> *mut c_char {
+    give(
+        char::from_u32(codepoint)
+            .and_then(nasin_nanpa_data::by_codepoint)
+            .map(|glyph| glyph.name.to_string()),
+    )
+}
+
+/// The codepoint the glyph called `name` is mapped from, or 0
+///
+/// # Safety
+/// `name` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nasin_nanpa_glyph_codepoint(name: *const c_char) -> u32 {
+    borrow(name)
+        .and_then(nasin_nanpa_data::by_name)
+        .and_then(|glyph| glyph.codepoint)
+        .map_or(0, u32::from)
+}
+
+/// The ASCII text the font turns into the glyph called `name`, one spelling per line, or null
+///
+/// # Safety
+/// `name` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nasin_nanpa_glyph_spellings(name: *const c_char) -> *mut c_char {
+    give(
+        borrow(name)
+            .and_then(nasin_nanpa_data::by_name)
+            .map(|glyph| glyph.spellings.join("\n")),
+    )
+}
+
+/// The name of the glyph the font turns `spelling` into, or null
+///
+/// # Safety
+/// `spelling` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nasin_nanpa_spelling_glyph(spelling: *const c_char) -> *mut c_char {
+    give(
+        borrow(spelling)
+            .and_then(nasin_nanpa_data::by_spelling)
+            .map(|glyph| glyph.name.to_string()),
+    )
+}
+
+/// The version of the font the glyphs are from
+#[no_mangle]
+pub extern "C" fn nasin_nanpa_version() -> *mut c_char {
+    give(Some(nasin_nanpa_data::VERSION.to_string()))
+}
+
+/// Frees a string returned by one of these functions
+///
+/// # Safety
+/// `text` must be null or a string returned by one of these functions, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn nasin_nanpa_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
@@ -1,9 +1,31 @@
 use itertools::Itertools;
-
-use crate::NasinNanpaVariation;
+use serde::Serialize;
+use std::{
+    borrow::{Borrow, Cow},
+    collections::HashMap,
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{
+    block_kind::BlockKind,
+    compose::Component,
+    containment::NESTED_EXTS,
+    corners,
+    directional::Heading,
+    features::*,
+    glyph_blocks::synth::*,
+    grid,
+    profile::{Capability, Profile},
+    selectors::{SelectorKind, SELECTORS},
+    simplify,
+    spline::{gen_coord, Direction, Point, SplineSet},
+    stack3, stroke,
+};
 
 /// An encoding position (either a number, or `None` which prints `-1`)
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub enum EncPos {
     Pos(usize),
     None,
@@ -25,117 +47,493 @@ impl EncPos {
     }
 }
 
+/// A glyph's full name (prefix, name, and suffix), as it appears in the `.sfd`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GlyphName(Arc<str>);
+
+impl GlyphName {
+    pub fn new(prefix: &str, name: &str, suffix: &str) -> Self {
+        Self(format!("{prefix}{name}{suffix}").into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for GlyphName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for GlyphName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A Unicode variation sequence: a base character followed by a variation selector, which the
+/// cmap (format 14) maps straight to a glyph
+#[derive(Clone, Copy, Hash)]
+pub struct VariationSequence {
+    pub base: usize,
+    pub selector: usize,
+}
+
 /// An encoding, consisting of a fontforge position and an encoding position
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub struct Encoding {
     pub ff_pos: usize,
     pub enc_pos: EncPos,
+    /// More codepoints that are mapped to the glyph (as `AltUni2`s)
+    pub alt_unis: Vec<usize>,
+    /// The variation sequences that are mapped to the glyph (also as `AltUni2`s)
+    pub variation_sequences: Vec<VariationSequence>,
 }
 
 impl Encoding {
-    pub fn new(ff_pos: usize, enc_pos: EncPos) -> Self {
-        Self { ff_pos, enc_pos }
+    /// An encoding at `enc_pos`, whose slot is given out later by `slots::allocate`
+    pub fn new(enc_pos: EncPos) -> Self {
+        Self {
+            ff_pos: 0,
+            enc_pos,
+            alt_unis: vec![],
+            variation_sequences: vec![],
+        }
+    }
+
+    /// Whether any codepoint (or variation sequence) is mapped to the glyph
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self.enc_pos, EncPos::None)
+            || !self.alt_unis.is_empty()
+            || !self.variation_sequences.is_empty()
     }
 
     pub fn gen(&self) -> String {
+        // `AltUni2: unicode.selector.0`, with a selector of `ffffffff` for a plain codepoint
+        let alt_unis = self
+            .alt_unis
+            .iter()
+            .map(|c| format!(" {c:06x}.ffffffff.0"))
+            .chain(
+                self.variation_sequences
+                    .iter()
+                    .map(|sequence| format!(" {:06x}.{:06x}.0", sequence.base, sequence.selector)),
+            )
+            .collect::<String>();
+        let alt_unis = if alt_unis.is_empty() {
+            String::new()
+        } else {
+            format!("\nAltUni2:{alt_unis}")
+        };
         format!(
-            "Encoding: {ff_pos} {enc_pos} {ff_pos}",
+            "Encoding: {ff_pos} {enc_pos} {ff_pos}{alt_unis}",
             ff_pos = self.ff_pos,
             enc_pos = self.enc_pos.gen(),
         )
     }
 
-    pub fn gen_ref(&self, position: String) -> String {
-        let Encoding { ff_pos, enc_pos } = self;
+    pub fn gen_ref(&self, transform: &Transform) -> String {
+        let Encoding {
+            ff_pos, enc_pos, ..
+        } = self;
         format!(
             "Refer: {ff_pos} {enc_pos} {position}",
             enc_pos = enc_pos.gen(),
-            position = position,
+            position = transform.gen(),
         )
     }
 }
 
-/// A glyph reference (with positional data)
-#[derive(Clone)]
+/// A glyph reference (with how its target is transformed), which names its target glyph until
+/// it's resolved to a slot
+#[derive(Clone, Hash)]
 pub struct Ref {
-    ref_glyph: Encoding,
-    position: String,
+    target: GlyphName,
+    transform: Transform,
+    resolved: Option<Encoding>,
 }
 
 impl Ref {
-    pub fn new(ref_glyph: Encoding, position: impl Into<String>) -> Self {
+    pub fn new(target: GlyphName, transform: Transform) -> Self {
         Self {
-            ref_glyph,
-            position: position.into(),
+            target,
+            transform,
+            resolved: None,
         }
     }
 
+    /// Resolves the target's slot. Targets must exist and come before the referencing glyph (in slot `from`)
+    pub fn resolve(&mut self, names: &NameRegistry, from: usize) -> Result<(), String> {
+        let Some((_, encoding)) = names.get(self.target.as_str()) else {
+            return Err(format!(
+                "the glyph in slot {from} references {}, which doesn't exist",
+                self.target
+            ));
+        };
+        if encoding.ff_pos >= from {
+            return Err(format!(
+                "the glyph in slot {from} references {}, which comes later (slot {})",
+                self.target, encoding.ff_pos
+            ));
+        }
+        self.resolved = Some(encoding.clone());
+        Ok(())
+    }
+
+    pub fn target(&self) -> &str {
+        self.target.as_str()
+    }
+
+    /// How the target is transformed to where it's shown
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Mirrors this reference within a glyph `width` wide, given the target's width if the target
+    /// itself is mirrored (in which case the reference shows it mirrored back, then mirrors that)
+    pub fn mirror(&mut self, width: f64, target_width: Option<f64>) {
+        let transform = match target_width {
+            Some(w) => Transform::mirror(w).then(self.transform),
+            None => self.transform,
+        };
+        self.transform = transform.then(Transform::mirror(width));
+    }
+
+    /// Scales where the target is shown by `s` (the target itself is scaled too, so the matrix
+    /// stays the same)
+    pub fn scale(&mut self, s: f64) {
+        self.transform.e *= s;
+        self.transform.f *= s;
+    }
+
+    /// Moves where the target is shown by `dx` across and `dy` up
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        self.transform = self.transform.translate(dx, dy);
+    }
+
     pub fn gen(&self) -> String {
-        self.ref_glyph.gen_ref(self.position.clone())
+        self.resolved
+            .as_ref()
+            .unwrap_or_else(|| panic!("reference to {} was never resolved", self.target))
+            .gen_ref(&self.transform)
     }
 }
 
-/// A glyph representation, consisting of a spline set and references
-#[derive(Default, Clone)]
+/// A glyph representation, consisting of a spline set (borrowed from the constants until it's
+/// changed) and references
+#[derive(Default, Clone, Hash)]
 pub struct Rep {
-    spline_set: String,
+    spline_set: Cow<'static, str>,
     references: Vec<Ref>,
 }
 
 impl Rep {
-    pub fn new(spline_set: impl Into<String>, references: Vec<Ref>) -> Self {
+    pub fn new(spline_set: impl Into<Cow<'static, str>>, references: Vec<Ref>) -> Self {
         Self {
             spline_set: spline_set.into(),
             references,
         }
     }
 
-    pub fn gen(&self) -> String {
-        let f = if !self.spline_set.is_empty() || !self.references.is_empty() {
-            "Fore\n"
-        } else {
-            ""
-        };
+    /// Whether this draws nothing (no contours and no references)
+    pub fn is_empty(&self) -> bool {
+        self.spline_set.trim().is_empty() && self.references.is_empty()
+    }
 
-        let r = self
-            .references
-            .clone()
-            .into_iter()
-            .map(|r| r.gen())
-            .join("\n");
+    /// The spline set, in the same form as the hand-written constants
+    pub fn spline_set(&self) -> &str {
+        &self.spline_set
+    }
 
-        let nl = if !self.references.is_empty() {
-            "\n"
-        } else {
-            ""
-        };
+    pub fn references(&self) -> &[Ref] {
+        &self.references
+    }
 
-        let s = if !self.spline_set.is_empty() {
-            format!("SplineSet{s}\nEndSplineSet\n", s = self.spline_set)
-        } else {
-            String::new()
-        };
+    /// Mirrors this representation horizontally within `width`. `mirrored_width` gives the width
+    /// of each referenced glyph that's mirrored too.
+    pub fn mirror(
+        &mut self,
+        width: usize,
+        mirrored_width: impl Fn(&str) -> Option<usize>,
+    ) -> Result<(), String> {
+        let width = width as f64;
+        if !self.spline_set.is_empty() {
+            self.spline_set = SplineSet::parse(&self.spline_set)?
+                .mirrored(width)
+                .gen()
+                .into();
+        }
+        for reference in &mut self.references {
+            let target_width = mirrored_width(reference.target.as_str()).map(|w| w as f64);
+            reference.mirror(width, target_width);
+        }
+        Ok(())
+    }
+
+    /// Makes the outlines of the spline set go `direction`, and its holes the other way
+    pub fn direct(&mut self, direction: Direction) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            self.spline_set = SplineSet::parse(&self.spline_set)?
+                .directed(direction)
+                .gen()
+                .into();
+        }
+        Ok(())
+    }
+
+    /// Rounds every corner of the spline set to `radius` (see `corners::round_corners`)
+    pub fn round_corners(&mut self, radius: f64) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            let spline_set = SplineSet::parse(&self.spline_set)?;
+            self.spline_set = corners::round_corners(&spline_set, radius).gen().into();
+        }
+        Ok(())
+    }
+
+    /// Moves the points of the spline set that are off the guides onto them (see `grid::snap`)
+    pub fn snap_to_grid(&mut self) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            self.spline_set = grid::snap(&SplineSet::parse(&self.spline_set)?)
+                .gen()
+                .into();
+        }
+        Ok(())
+    }
+
+    /// Simplifies the spline set to within `max_error` (see `simplify::simplify`)
+    pub fn simplify(&mut self, max_error: f64) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            let spline_set = SplineSet::parse(&self.spline_set)?;
+            self.spline_set = simplify::simplify(&spline_set, max_error).gen().into();
+        }
+        Ok(())
+    }
+
+    /// Grows the outlines of the spline set outwards by `d` (see `stroke::grow`)
+    pub fn grow(&mut self, d: f64) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            let spline_set = SplineSet::parse(&self.spline_set)?;
+            self.spline_set = stroke::grow(&spline_set, d).gen().into();
+        }
+        Ok(())
+    }
 
-        format!("{f}{r}{nl}{s}")
+    /// Scales this representation by `s`, around the origin
+    pub fn scale(&mut self, s: f64) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            self.spline_set = SplineSet::parse(&self.spline_set)?.scaled(s).gen().into();
+        }
+        for reference in &mut self.references {
+            reference.scale(s);
+        }
+        Ok(())
+    }
+
+    /// Moves this representation by `dx` across and `dy` up
+    pub fn translate(&mut self, dx: f64, dy: f64) -> Result<(), String> {
+        if !self.spline_set.is_empty() {
+            self.spline_set = SplineSet::parse(&self.spline_set)?
+                .translated(dx, dy)
+                .gen()
+                .into();
+        }
+        for reference in &mut self.references {
+            reference.translate(dx, dy);
+        }
+        Ok(())
+    }
+
+    /// Resolves every reference of a glyph in slot `from`
+    pub fn resolve(&mut self, names: &NameRegistry, from: usize) -> Result<(), String> {
+        for reference in &mut self.references {
+            reference.resolve(names, from)?;
+        }
+        Ok(())
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> fmt::Result {
+        if !self.spline_set.is_empty() || !self.references.is_empty() {
+            out.write_str("Fore\n")?;
+        }
+        for reference in &self.references {
+            writeln!(out, "{}", reference.gen())?;
+        }
+        if !self.spline_set.is_empty() {
+            write!(out, "SplineSet{}\nEndSplineSet\n", self.spline_set)?;
+        }
+        Ok(())
     }
 }
 
 /// An anchor class, either stack or scale
-#[derive(Clone)]
+#[derive(Clone, Copy, Hash)]
 pub enum AnchorClass {
     Stack,
     Scale,
 }
 
+impl AnchorClass {
+    /// Every anchor class, in the order their subtables are applied
+    pub const ALL: [AnchorClass; 2] = [AnchorClass::Stack, AnchorClass::Scale];
+
+    /// The name of the class, as in `AnchorPoint:` lines
+    pub fn name(self) -> &'static str {
+        match self {
+            AnchorClass::Stack => "stack",
+            AnchorClass::Scale => "scale",
+        }
+    }
+
+    /// The subtable that attaches marks to bases with this class
+    pub fn subtable(self) -> &'static str {
+        match self {
+            AnchorClass::Stack => MARK_STACK,
+            AnchorClass::Scale => MARK_SCALE,
+        }
+    }
+}
+
 /// An anchor type, either base (for lower/outer) or mark (for upper/inner)
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Hash)]
 pub enum AnchorType {
     Base,
     Mark,
 }
 
+/// Centerlines (written like a spline set) drawn with a round pen `width` wide, which are expanded
+/// into outlines when the glyph is generated
+#[derive(Clone, Copy)]
+pub struct Stroke<'a> {
+    pub centerlines: &'a str,
+    pub width: f64,
+}
+
+impl Stroke<'_> {
+    /// The outlines of the stroke, as a spline set
+    pub fn expand(&self) -> Result<String, String> {
+        let centerlines = SplineSet::parse(self.centerlines)?;
+        Ok(stroke::expand(&centerlines, self.width)
+            .directed(Direction::default())
+            .gen())
+    }
+}
+
+/// An affine transformation of a reference: `x' = a x + c y + e` and `y' = b x + d y + f`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Transform {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+    f: f64,
+}
+
+/// Compared by the bits of the matrix, so equal transformations hash the same
+impl Hash for Transform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for n in [self.a, self.b, self.c, self.d, self.e, self.f] {
+            n.to_bits().hash(state);
+        }
+    }
+}
+
+impl Transform {
+    /// Leaves the target where it is
+    pub const fn identity() -> Self {
+        Self::scale(1.0)
+    }
+
+    /// Flips across the vertical line through the middle of a glyph `width` wide
+    pub const fn mirror(width: f64) -> Self {
+        Self::scale_xy(-1.0, 1.0).translate(width, 0.0)
+    }
+
+    /// Scales by `s` in both directions, around the origin
+    pub const fn scale(s: f64) -> Self {
+        Self::scale_xy(s, s)
+    }
+
+    /// Scales by `sx` across and `sy` up, around the origin
+    pub const fn scale_xy(sx: f64, sy: f64) -> Self {
+        Self {
+            a: sx,
+            b: 0.0,
+            c: 0.0,
+            d: sy,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// Turns counter-clockwise around the origin, by the angle with this cosine and sine
+    pub const fn rotation(cos: f64, sin: f64) -> Self {
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// Moves by `x` across and `y` up
+    pub const fn translation(x: f64, y: f64) -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: x,
+            f: y,
+        }
+    }
+
+    /// This transformation, then `next`
+    pub const fn then(self, next: Self) -> Self {
+        Self {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// This transformation, then moving by `x` across and `y` up
+    pub const fn translate(self, x: f64, y: f64) -> Self {
+        self.then(Self::translation(x, y))
+    }
+
+    /// Where this transformation takes `p`
+    pub fn apply(&self, p: Point) -> Point {
+        Point::new(
+            self.a * p.x + self.c * p.y + self.e,
+            self.b * p.x + self.d * p.y + self.f,
+        )
+    }
+
+    /// Generates the position of a reference (as in `Refer:` lines): selected, the matrix, and
+    /// the flag that rounds it to the grid
+    pub fn gen(&self) -> String {
+        format!(
+            "S {} {} {} {} {} {} 2",
+            gen_coord(self.a),
+            gen_coord(self.b),
+            gen_coord(self.c),
+            gen_coord(self.d),
+            gen_coord(self.e),
+            gen_coord(self.f),
+        )
+    }
+}
+
 /// An anchor, consisting of a class, type, and position
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub struct Anchor {
     class: AnchorClass,
     ty: AnchorType,
@@ -165,11 +563,31 @@ impl Anchor {
         }
     }
 
+    #[cfg(test)]
+    pub fn class(&self) -> AnchorClass {
+        self.class
+    }
+
+    /// Whether the glyph with this anchor is attached to another (rather than another to it)
+    pub fn is_mark(&self) -> bool {
+        matches!(self.ty, AnchorType::Mark)
+    }
+
+    /// Mirrors this anchor horizontally within a glyph `width` wide
+    pub fn mirror(&mut self, width: usize) {
+        self.pos.0 = width as isize - self.pos.0;
+    }
+
+    /// Scales this anchor's position by `s`, around the origin
+    pub fn scale(&mut self, s: f64) {
+        self.pos = (
+            (self.pos.0 as f64 * s).round() as isize,
+            (self.pos.1 as f64 * s).round() as isize,
+        );
+    }
+
     fn gen(&self) -> String {
-        let class = match self.class {
-            AnchorClass::Stack => "stack",
-            AnchorClass::Scale => "scale",
-        };
+        let class = self.class.name();
         let x = self.pos.0;
         let y = self.pos.1;
         let ty = match self.ty {
@@ -180,25 +598,92 @@ impl Anchor {
     }
 }
 
-
 /// This is the smallest building block of a glyph, containing the name, width, representation, and optional anchor
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub struct GlyphBasic {
-    pub name: String,
+    pub name: Cow<'static, str>,
     pub width: usize,
     pub rep: Rep,
     pub anchor: Option<Anchor>,
+    /// The vertical advance, for fonts with vertical metrics
+    pub vwidth: Option<usize>,
+    /// The full names of the alternates that `'rand'` picks from in place of this glyph
+    pub alternates: Vec<GlyphName>,
+    /// What the glyph is, for selecting it (see `TAG_PADDING`)
+    pub tags: Vec<&'static str>,
+    /// Who drew the glyph and what it's based on, if that's known
+    pub provenance: Provenance,
+    /// Where the caret goes between the components of a ligature (see `carets`)
+    pub carets: Vec<usize>,
+    /// Its class in the `GDEF` table, if it's been assigned one (see `gdef`)
+    pub class: Option<GlyphClass>,
+}
+
+/// What a glyph is to the shaper (its class in the `GDEF` table)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GlyphClass {
+    Base,
+    /// Made of several characters
+    Ligature,
+    /// Attached to the glyph before it
+    Mark,
+    /// Only used as a part of other glyphs
+    Component,
+}
+
+impl GlyphClass {
+    /// The number FontForge writes it as in a `GlyphClass:` line
+    pub fn id(self) -> usize {
+        match self {
+            GlyphClass::Base => 2,
+            GlyphClass::Ligature => 3,
+            GlyphClass::Mark => 4,
+            GlyphClass::Component => 5,
+        }
+    }
+}
+
+/// Who drew a glyph (if it wasn't the font's designer), and what it's based on
+#[derive(Clone, Default, Hash)]
+pub struct Provenance {
+    pub designer: Option<Cow<'static, str>>,
+    pub source: Option<Cow<'static, str>>,
+}
+
+impl Provenance {
+    /// A glyph the font's designer drew after `source`
+    pub const fn based_on(source: &'static str) -> Self {
+        Self {
+            designer: None,
+            source: Some(Cow::Borrowed(source)),
+        }
+    }
 }
 
 impl GlyphBasic {
-    pub fn new(name: impl Into<String>, width: usize, rep: Rep, anchor: Option<Anchor>) -> Self {
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        width: usize,
+        rep: Rep,
+        anchor: Option<Anchor>,
+    ) -> Self {
         Self {
             name: name.into(),
             width,
             rep,
             anchor,
+            vwidth: None,
+            alternates: vec![],
+            tags: vec![],
+            provenance: Provenance::default(),
+            carets: vec![],
+            class: None,
         }
     }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
 }
 
 /// This is a `GlyphBasic` that has been assigned an `EncPos`
@@ -213,7 +698,12 @@ impl GlyphEnc {
         Self { glyph, enc }
     }
 
-    pub fn new_from_parts(enc: EncPos, name: impl Into<String>, width: usize, rep: Rep) -> Self {
+    pub fn new_from_parts(
+        enc: EncPos,
+        name: impl Into<Cow<'static, str>>,
+        width: usize,
+        rep: Rep,
+    ) -> Self {
         Self {
             glyph: GlyphBasic::new(name, width, rep, None),
             enc,
@@ -221,26 +711,60 @@ impl GlyphEnc {
     }
 }
 
-/// 
+/// Which way a long glyph ("container") extends from the glyph that starts it
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ContainerDirection {
+    /// Over the glyphs after it (like `pi`)
+    #[default]
+    Forward,
+    /// Over the glyphs before it (like `la`)
+    Reverse,
+    Both,
+}
+
+/// How a block gives each of its glyphs its `Lookups`
+#[derive(Clone)]
 pub enum LookupsMode {
     WordLigFromLetters,
     WordLigManual(Vec<String>),
-    StartLongGlyph,
+    StartLongGlyph(Vec<ContainerDirection>),
     Alt,
-    ComboFirst,
-    ComboLast,
+    ComboFirst(Joiner),
+    ComboLast(Joiner),
     None,
 }
 
-#[derive(Clone)]
+/// A joiner, which combines the glyph before it with the glyph after it
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Joiner {
+    /// Stacks the glyphs, the first over the second
+    Stack,
+    /// Scales the second glyph down into the first
+    Scale,
+    /// Stacks three glyphs (see `stack3`)
+    Stack3,
+}
+
+impl Joiner {
+    pub const fn glyph(&self) -> &'static str {
+        match self {
+            Joiner::Stack => "joinStackTok",
+            Joiner::Scale => "joinScaleTok",
+            Joiner::Stack3 => stack3::JOINER,
+        }
+    }
+}
+
+#[derive(Clone, Hash)]
 pub enum Lookups {
     WordLigFromLetters,
     WordLigManual(String),
-    StartLongGlyph,
-    EndLongGlyph,
+    StartLongGlyph(ContainerDirection),
     Alt,
-    ComboFirst,
-    ComboLast,
+    /// A glyph combined with the joiner after it (like `tokiTok_joinStackTok`)
+    ComboFirst(Joiner),
+    /// A joiner combined with the glyph after it (like `joinStackTok_ponaTok`)
+    ComboLast(Joiner),
     None,
 }
 
@@ -250,60 +774,91 @@ impl Lookups {
             LookupsMode::WordLigFromLetters => Lookups::WordLigFromLetters,
             LookupsMode::WordLigManual(vec) => {
                 let s = &vec[idx];
-                if s.len() > 0 {
+                if !s.is_empty() {
                     Lookups::WordLigManual(vec[idx].clone())
                 } else {
                     Lookups::None
                 }
             }
-            LookupsMode::StartLongGlyph => Lookups::StartLongGlyph,
+            LookupsMode::StartLongGlyph(vec) => Lookups::StartLongGlyph(vec[idx]),
             LookupsMode::Alt => Lookups::Alt,
-            LookupsMode::ComboFirst => Lookups::ComboFirst,
-            LookupsMode::ComboLast => Lookups::ComboLast,
+            LookupsMode::ComboFirst(joiner) => Lookups::ComboFirst(*joiner),
+            LookupsMode::ComboLast(joiner) => Lookups::ComboLast(*joiner),
             LookupsMode::None => Lookups::None,
         }
     }
 
-    fn gen(&self, name: String, full_name: String, variation: NasinNanpaVariation) -> String {
+    /// The glyphs that the combination glyph `full_name` is made of, in the order they're typed
+    /// (the joiner as its glyph), or `None` if these aren't a combination glyph's lookups
+    pub fn decomposition<'a>(&self, full_name: &'a str) -> Option<[&'a str; 2]> {
+        match self {
+            Lookups::ComboFirst(joiner) => {
+                let glyph = full_name.strip_suffix(joiner.glyph())?.strip_suffix('_')?;
+                Some([glyph, joiner.glyph()])
+            }
+            Lookups::ComboLast(joiner) => {
+                let glyph = full_name.strip_prefix(joiner.glyph())?.strip_prefix('_')?;
+                Some([joiner.glyph(), glyph])
+            }
+            _ => None,
+        }
+    }
 
+    fn gen(
+        &self,
+        name: String,
+        full_name: String,
+        profile: Profile,
+        features: &FeatureRegistry,
+    ) -> String {
         let latin_ligs = match &self {
-
-            // Used in tok_block and tok_ext_block when NasinNanpaVariation == Main
+            // Used in tok_block and tok_ext_block when Profile == Main
             Lookups::WordLigFromLetters => {
                 let lig = name.chars().join(" ");
                 let special = if full_name.eq("aleTok") {
-                    "Ligature2: \"'liga' WORD\" a l i\n"
+                    features.line(LIGA_WORD, "a l i")
                 } else {
-                    ""
+                    String::new()
                 };
-                format!("Ligature2: \"'liga' WORD\" {lig}\n{special}")
+                format!("{}{special}", features.line(LIGA_WORD, lig))
             }
 
             // Used in ctrl_block, tok_ctrl_block, and tok_no_combo_block
             Lookups::WordLigManual(word) => {
-
                 let mut do_it = true;
                 let always = if word.contains("middleDotTok") {
                     do_it = false;
-                    format!("Ligature2: \"'liga' VAR\" {word}\n")
+                    features.line(LIGA_VAR, word)
                 } else if word.contains("CartAlt") {
+                    do_it = false;
+                    let which = if word.contains("start") {
+                        "startCart"
+                    } else {
+                        "endCart"
+                    };
+                    SELECTORS
+                        .pickers("VAR01", profile)
+                        .into_iter()
+                        .map(|picker| features.line(LIGA_VAR, format!("{which}Tok {picker}")))
+                        .collect()
+                } else if name.eq("ZWJ") {
                     format!(
-                        "Ligature2: \"'liga' VAR\" {which}Tok VAR01\n",
-                        which = if word.contains("start") { "startCart" } else { "endCart" }
+                        "{}{}",
+                        features.line(SS02_ZWJ_TO_STACK, "joinStackTok"),
+                        features.line(SS01_ZWJ_TO_SCALE, "joinScaleTok"),
                     )
-                } else if name.eq("ZWJ") {
-                    "Substitution2: \"'ss02' ZWJ TO STACK\" joinStackTok\nSubstitution2: \"'ss01' ZWJ TO SCALE\" joinScaleTok\n".to_string()
                 } else if word.eq("i t a n") {
-                    "Ligature2: \"'liga' VAR\" ijoTok ZWJ tanTok ZWJ anpaTok ZWJ nanpaTok\n".to_string()
+                    features.line(LIGA_VAR, "ijoTok ZWJ tanTok ZWJ anpaTok ZWJ nanpaTok")
                 } else if word.eq("l e p e k a") {
-                    "Ligature2: \"'liga' VAR\" meliTok ZWJ kuleTok ZWJ kuleTok\n".to_string()
+                    features.line(LIGA_VAR, "meliTok ZWJ kuleTok ZWJ kuleTok")
                 } else {
                     String::new()
                 };
 
-                let latin = if variation == NasinNanpaVariation::Main && do_it {
+                let latin = if profile.has(Capability::LatinLigatures) && do_it {
                     if word.eq("space space") {
-                        format!("Ligature2: \"'liga' SPACE\" {word}\nLigature2: \"'liga' SPACE\" z z space\nLigature2: \"'liga' SPACE\" z z\n")
+                        // declared with the spacing settings (see `spacing`)
+                        String::new()
                     } else if word.eq("arrow") {
                         let convert = |c: char| match c {
                             'W' => "less",
@@ -316,19 +871,18 @@ impl Lookups {
                         let dir1 = convert(name.chars().nth(5).unwrap());
                         if let Some(dir2) = name.chars().nth(6) {
                             let dir2 = convert(dir2);
-                            format!("Ligature2: \"'liga' WORD\" {dir1} {dir2}\nLigature2: \"'liga' WORD\" {dir2} {dir1}\n")
+                            format!(
+                                "{}{}",
+                                features.line(LIGA_WORD, format!("{dir1} {dir2}")),
+                                features.line(LIGA_WORD, format!("{dir2} {dir1}")),
+                            )
                         } else {
-                            format!("Ligature2: \"'liga' WORD\" {dir1}\n")
+                            features.line(LIGA_WORD, dir1)
                         }
                     } else if word.eq("bar") {
-                        format!("Ligature2: \"'liga' WORD\" bar\n")
-                    } else if word.contains("CartAlt") {
-                            format!(
-                                "Ligature2: \"'liga' VAR\" {which}Tok VAR01\nLigature2: \"'liga' VAR\" {which}Tok one\n",
-                                which = if word.contains("start") { "startCart" } else { "endCart" }
-                            )
+                        features.line(LIGA_WORD, "bar")
                     } else {
-                        format!("Ligature2: \"'liga' WORD\" {word}\n")
+                        features.line(LIGA_WORD, word)
                     }
                 } else {
                     String::new()
@@ -338,15 +892,16 @@ impl Lookups {
             } // Lookups::WordLigManual
 
             // Used in start_long_glyph_block
-            Lookups::StartLongGlyph => {
+            Lookups::StartLongGlyph(direction) => {
                 let (glyph, joiner) = full_name.rsplit_once("_").unwrap();
-                format!("Ligature2: \"'liga' START CONTAINER\" {glyph} {joiner}\n")
-            }
-
-            // Used in start_long_glyph_block for laTok
-            Lookups::EndLongGlyph => {
-                let (glyph, _) = full_name.split_once("_").unwrap();
-                format!("Ligature2: \"'liga' START CONTAINER\" endRevLongGlyphTok {glyph}\n")
+                let forward = || features.line(LIGA_START_CONTAINER, format!("{glyph} {joiner}"));
+                let reverse =
+                    || features.line(LIGA_START_CONTAINER, format!("endRevLongGlyphTok {glyph}"));
+                match direction {
+                    ContainerDirection::Forward => forward(),
+                    ContainerDirection::Reverse => reverse(),
+                    ContainerDirection::Both => format!("{}{}", forward(), reverse()),
+                }
             }
 
             // Used in tok_alt_block
@@ -356,127 +911,100 @@ impl Lookups {
                 let sel = parts[1];
 
                 let a = if full_name.eq("aTok_VAR02") {
-                    "Ligature2: \"'liga' VAR\" aTok aTok\n"
+                    features.line(LIGA_VAR, "aTok aTok")
                 } else if full_name.eq("aTok_VAR03") {
-                    "Ligature2: \"'liga' VAR\" aTok aTok aTok\n"
+                    features.line(LIGA_VAR, "aTok aTok aTok")
                 } else if full_name.eq("aTok_VAR04") {
-                    "Ligature2: \"'liga' VAR\" semeTok ZWJ aTok\nLigature2: \"'liga' VAR\" aTok ZWJ semeTok\n"
-                } else if full_name.eq("aTok_VAR05") && variation == NasinNanpaVariation::Main {
-r#" Ligature2: "'liga' VAR" aTok exclam question
-Ligature2: "'liga' VAR" aTok question exclam
-"#              } else { "" };
-
-                let arrow_lig = if full_name.contains("niTok_arrow") {
-                    format!("Ligature2: \"'liga' VAR\" {glyph} ZWJ {sel}\n")
-                } else {
-                    String::new()
-                };
-
-                let num_lig = if variation == NasinNanpaVariation::Main && full_name.contains("VAR0") {
                     format!(
-                        "Ligature2: \"'liga' VAR\" {glyph} {sel}\n",
-                        sel = match sel {
-                            "VAR01" | "arrowW" => "one",
-                            "VAR02" | "arrowN" => "two",
-                            "VAR03" | "arrowE" => "three",
-                            "VAR04" | "arrowS" => "four",
-                            "VAR05" | "arrowNW" => "five",
-                            "VAR06" | "arrowNE" => "six",
-                            "VAR07" | "arrowSE" => "seven",
-                            "VAR08" | "arrowSW" => "eight",
-                            _ => panic!(),
-                        }
+                        "{}{}",
+                        features.line(LIGA_VAR, "semeTok ZWJ aTok"),
+                        features.line(LIGA_VAR, "aTok ZWJ semeTok"),
+                    )
+                } else if full_name.eq("aTok_VAR05") && profile.has(Capability::LatinLigatures) {
+                    format!(
+                        "{}{}",
+                        features.line(LIGA_VAR, "aTok exclam question"),
+                        features.line(LIGA_VAR, "aTok question exclam"),
                     )
                 } else {
                     String::new()
                 };
 
-                let rerand = if full_name.contains("VAR0") {
-                    let sel_word = match sel {
-                        "VAR01" | "arrowW" => "one",
-                        "VAR02" | "arrowN" => "two",
-                        "VAR03" | "arrowE" => "three",
-                        "VAR04" | "arrowS" => "four",
-                        "VAR05" | "arrowNW" => "five",
-                        "VAR06" | "arrowNE" => "six",
-                        "VAR07" | "arrowSE" => "seven",
-                        "VAR08" | "arrowSW" => "eight",
-                        _ => panic!(),
-                    };
-                    let sel = sel.chars().last().unwrap().to_string();
-                    if full_name.starts_with("jakiTok") {
-                        if variation == NasinNanpaVariation::Main {
-                            (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" jakiTok_VAR0{n} VAR0{sel}\nLigature2: \"'liga' VAR\" jakiTok_VAR0{n} {sel_word}\n")).collect::<String>()
-                        } else {
-                            (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" jakiTok_VAR0{n} VAR0{sel}\n")).collect::<String>()
-                        }
-                    } else if full_name.starts_with("koTok") {
-                        if variation == NasinNanpaVariation::Main {
-                            (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" koTok_VAR0{n} VAR0{sel}\nLigature2: \"'liga' VAR\" koTok_VAR0{n} {sel_word}\n")).collect::<String>()
-                        } else {
-                            (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" koTok_VAR0{n} VAR0{sel}\n")).collect::<String>()
-                        }
-                    } else {
-                        String::new()
-                    }
+                let arrow_lig = if SELECTORS
+                    .get(sel)
+                    .is_some_and(|selector| selector.kind == SelectorKind::Arrow)
+                {
+                    features.line(LIGA_VAR, format!("{glyph} ZWJ {sel}"))
                 } else {
                     String::new()
                 };
 
-                format!("{a}Ligature2: \"'liga' VAR\" {glyph} {sel}\n{arrow_lig}{num_lig}{rerand}")
+                let picks = SELECTORS
+                    .pickers(sel, profile)
+                    .into_iter()
+                    .map(|picker| features.line(LIGA_VAR, format!("{glyph} {picker}")))
+                    .collect::<String>();
+
+                format!("{a}{picks}{arrow_lig}")
             }
 
             // Used in tok_outer_block, tok_ext_outer_block, tok_alt_outer_block,
             // tok_lower_block, tok_ext_lower_block, and tok_alt_lower_block.
-            Lookups::ComboFirst => {
-                let (glyph, joiner) = full_name.rsplit_once('_').unwrap();
-                format!("Ligature2: \"'liga' GLYPH THEN JOINER\" {glyph} {joiner}\nMultipleSubs2: \"'ccmp' RESPAWN JOINER\" {full_name} {joiner}\n")
+            Lookups::ComboFirst(_) => {
+                let [glyph, joiner] = self.decomposition(&full_name).unwrap();
+                // the joiner comes back after the combination glyph, so the glyph after it can
+                // combine with it too
+                format!(
+                    "{}{}",
+                    features.line(LIGA_GLYPH_THEN_JOINER, format!("{glyph} {joiner}")),
+                    features.line(CCMP_RESPAWN_JOINER, format!("{full_name} {joiner}")),
+                )
             }
 
             // Used in tok_inner_block, tok_ext_inner_block, tok_alt_inner_block,
             // tok_upper_block, tok_ext_upper_block, and tok_alt_upper_block.
-            Lookups::ComboLast => {
-                let (joiner, glyph) = full_name.split_once("_").unwrap();
-                format!("Ligature2: \"'liga' JOINER THEN GLYPH\" {joiner} {glyph}\nLigature2: \"'liga' CC CLEANUP\" combCartExtHalfTok {full_name}\nLigature2: \"'liga' CC CLEANUP\" combLongGlyphExtHalfTok {full_name}\nLigature2: \"'liga' CC CLEANUP\" combCartExtTok {full_name}\nLigature2: \"'liga' CC CLEANUP\" combLongGlyphExtTok {full_name}\n")
+            Lookups::ComboLast(_) => {
+                let [joiner, glyph] = self.decomposition(&full_name).unwrap();
+                let cleanup = [
+                    "combCartExtHalfTok",
+                    "combLongGlyphExtHalfTok",
+                    "combCartExtTok",
+                    "combLongGlyphExtTok",
+                ]
+                .iter()
+                .chain(NESTED_EXTS.iter())
+                .map(|ext| features.line(LIGA_CC_CLEANUP, format!("{ext} {full_name}")))
+                .collect::<String>();
+                format!(
+                    "{}{cleanup}",
+                    features.line(LIGA_JOINER_THEN_GLYPH, format!("{joiner} {glyph}"))
+                )
             }
             Lookups::None => String::new(),
         };
 
-        let rand = if full_name.eq("jakiTok") {
-            format!(
-                "{rerand}AlternateSubs2: \"'rand' RAND VARIATIONS\" jakiTok_VAR01 jakiTok_VAR02 jakiTok_VAR03 jakiTok_VAR04 jakiTok_VAR05 jakiTok_VAR06 jakiTok_VAR07 jakiTok_VAR08\n",
-                rerand = if variation == NasinNanpaVariation::Main {
-                    (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" jakiTok_VAR0{n} VAR09\nLigature2: \"'liga' VAR\" jakiTok_VAR0{n} nine\n")).collect::<String>()
-                } else { 
-                    (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" jakiTok_VAR0{n} VAR09\n")).collect::<String>()
-                }
-            )
-        } else if full_name.eq("koTok") {
-            format!(
-                "{rerand}AlternateSubs2: \"'rand' RAND VARIATIONS\" koTok_VAR01 koTok_VAR02 koTok_VAR03 koTok_VAR04 koTok_VAR05 koTok_VAR06 koTok_VAR07 koTok_VAR08\n",
-                rerand = if variation == NasinNanpaVariation::Main { 
-                    (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" koTok_VAR0{n} VAR09\nLigature2: \"'liga' VAR\" koTok_VAR0{n} nine\n")).collect::<String>()
-                } else {
-                    (1..9).map(|n| format!("Ligature2: \"'liga' VAR\" koTok_VAR0{n} VAR09\n")).collect::<String>()
-                }
-            )
-        } else {
-            String::new()
-        };
-
-        format!("{latin_ligs}{rand}")
+        latin_ligs
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub enum Cc {
     Full,
     Half,
+    /// A latin letter, which turns into the letter glyph named here inside a cartouche
+    Letter(String),
     Participant,
     None,
 }
 
-#[derive(Clone)]
+/// A layer of a color glyph (as in COLRv0): a glyph, drawn in an entry of the palette
+#[derive(Serialize)]
+pub struct ColorLayer {
+    pub glyph: String,
+    pub palette_index: usize,
+}
+
+#[derive(Clone, Hash)]
 pub struct GlyphFull {
     pub glyph: GlyphBasic,
     pub encoding: Encoding,
@@ -499,17 +1027,17 @@ impl GlyphFull {
         }
     }
 
-    pub fn new_from_enc(glyph: GlyphEnc, ff_pos: usize, lookups: Lookups, cc_subs: Cc) -> Self {
+    pub fn new_from_enc(glyph: GlyphEnc, lookups: Lookups, cc_subs: Cc) -> Self {
         Self {
             glyph: glyph.glyph,
-            encoding: Encoding::new(ff_pos, glyph.enc),
+            encoding: Encoding::new(glyph.enc),
             lookups,
             cc_subs,
         }
     }
 
     pub fn new_from_parts(
-        name: impl Into<String>,
+        name: impl Into<Cow<'static, str>>,
         width: usize,
         rep: Rep,
         anchor: Option<Anchor>,
@@ -527,45 +1055,139 @@ impl GlyphFull {
 
     pub fn gen(
         &self,
-        prefix: String,
-        suffix: String,
-        color: String,
-        variation: NasinNanpaVariation,
+        prefix: &str,
+        suffix: &str,
+        color: &str,
+        profile: Profile,
+        features: &FeatureRegistry,
     ) -> String {
+        let mut s = String::new();
+        self.write(&mut s, prefix, suffix, color, profile, features)
+            .expect("writing to a string doesn't fail");
+        s
+    }
+
+    /// Writes this glyph's section of the `.sfd` into `out`
+    pub fn write(
+        &self,
+        out: &mut impl Write,
+        prefix: &str,
+        suffix: &str,
+        color: &str,
+        profile: Profile,
+        features: &FeatureRegistry,
+    ) -> fmt::Result {
         let name = &self.glyph.name;
         let encoding = self.encoding.gen();
-        let color = format!("Colour: {color}");
-        if name.contains("empty") {
-            return format!(
-                "\nStartChar: {name}\n{encoding}\nWidth: 0\nLayerCount: 2\n{color}\nEndChar\n"
+        if self.glyph.has_tag(TAG_PADDING) {
+            return write!(
+                out,
+                "\nStartChar: {name}\n{encoding}\nWidth: 0\nLayerCount: 2\nColour: {color}\nEndChar\n"
             );
         }
-        let full_name = format!("{}{}{}", prefix, name, suffix);
+        let full_name = GlyphName::new(prefix, name, suffix).to_string();
         let width = self.glyph.width;
-        let representation = self.glyph.rep.gen();
-        let lookups = self
-            .lookups
-            .gen(name.to_string(), full_name.clone(), variation);
-        let cc_subs = match self.cc_subs {
-            Cc::Full => format!("MultipleSubs2: \"'cc01' CART\" {full_name} combCartExtTok\nMultipleSubs2: \"'cc02' CONT\" {full_name} combLongGlyphExtTok\n"),
-            Cc::Half => if full_name.eq("comma") {
-                "MultipleSubs2: \"'cc01' CART\" combCartExt1TickTok\nMultipleSubs2: \"'cc02' CONT\" combLongGlyphExtHalfTok\n".to_string()
-            } else if full_name.eq("quotesingle") {
-                "MultipleSubs2: \"'cc01' CART\" combCartExt5TickTok\nMultipleSubs2: \"'cc02' CONT\" combLongGlyphExtHalfTok\n".to_string()
-            } else {
-                let ss00 = if full_name.eq("space") {
-                    "Substitution2: \"'ss00' SP TO ZWSP\" ZWSP\n"
+        let vwidth = self
+            .glyph
+            .vwidth
+            .map(|vwidth| format!("VWidth: {vwidth}\n"))
+            .unwrap_or_default();
+        let class = self
+            .glyph
+            .class
+            .map(|class| format!("GlyphClass: {}\n", class.id()))
+            .unwrap_or_default();
+        let lookups = self.lookup_lines(&full_name, profile, features);
+        let carets = if self.glyph.carets.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "LCarets2: {} {}\n",
+                self.glyph.carets.len(),
+                self.glyph.carets.iter().join(" ")
+            )
+        };
+        let cc_subs = match &self.cc_subs {
+            Cc::Full => format!(
+                "{}{}{}{}",
+                features.line(CC01_CART, format!("{full_name} combCartExtTok")),
+                features.line(CC02_CONT, format!("{full_name} combLongGlyphExtTok")),
+                features.line(
+                    CC03_CONT_IN_CART,
+                    format!("{full_name} combContInCartExtTok")
+                ),
+                features.line(
+                    CC04_CART_IN_CONT,
+                    format!("{full_name} combCartInContExtTok")
+                ),
+            ),
+            Cc::Half => {
+                if full_name.eq("comma") {
+                    format!(
+                        "{}{}",
+                        features.line(CC01_CART, cart_tick_name(1)),
+                        features.line(CC02_CONT, "combLongGlyphExtHalfTok"),
+                    )
+                } else if full_name.eq("quotesingle") {
+                    format!(
+                        "{}{}",
+                        features.line(CC01_CART, cart_tick_name(CART_TICK_COUNT + 1)),
+                        features.line(CC02_CONT, "combLongGlyphExtHalfTok"),
+                    )
                 } else {
-                    ""
-                };
+                    let ss00 = if full_name.eq("space") {
+                        features.line(SS00_SP_TO_ZWSP, "ZWSP")
+                    } else {
+                        String::new()
+                    };
 
-                format!("{ss00}MultipleSubs2: \"'cc01' CART\" {full_name} combCartExtHalfTok\nMultipleSubs2: \"'cc02' CONT\" {full_name} combLongGlyphExtHalfTok\n")
-            },
-            Cc::Participant => if full_name.contains("Tick") {
-                format!("MultipleSubs2: \"'cc01' CART\" {full_name} combCartExtNoneTok\n")
-            } else {
-                format!("MultipleSubs2: \"'cc01' CART\" {full_name} combCartExtNoneTok\nMultipleSubs2: \"'cc02' CONT\" {full_name} combCartExtNoneTok\n")
-            },
+                    format!(
+                        "{ss00}{}{}{}{}",
+                        features.line(CC01_CART, format!("{full_name} combCartExtHalfTok")),
+                        features.line(CC02_CONT, format!("{full_name} combLongGlyphExtHalfTok")),
+                        features.line(
+                            CC03_CONT_IN_CART,
+                            format!("{full_name} combContInCartExtHalfTok")
+                        ),
+                        features.line(
+                            CC04_CART_IN_CONT,
+                            format!("{full_name} combCartInContExtHalfTok")
+                        ),
+                    )
+                }
+            }
+            Cc::Letter(letter) => format!(
+                "{}{}{}{}",
+                features.line(CC01_CART, format!("{letter} combCartExtHalfTok")),
+                features.line(CC02_CONT, format!("{full_name} combLongGlyphExtHalfTok")),
+                features.line(
+                    CC03_CONT_IN_CART,
+                    format!("{full_name} combContInCartExtHalfTok")
+                ),
+                features.line(
+                    CC04_CART_IN_CONT,
+                    format!("{letter} combCartInContExtHalfTok")
+                ),
+            ),
+            Cc::Participant => {
+                if full_name.contains("Tick") {
+                    features.line(CC01_CART, format!("{full_name} combCartExtNoneTok"))
+                } else {
+                    format!(
+                        "{}{}{}{}",
+                        features.line(CC01_CART, format!("{full_name} combCartExtNoneTok")),
+                        features.line(CC02_CONT, format!("{full_name} combCartExtNoneTok")),
+                        features.line(
+                            CC03_CONT_IN_CART,
+                            format!("{full_name} combContInCartExtNoneTok")
+                        ),
+                        features.line(
+                            CC04_CART_IN_CONT,
+                            format!("{full_name} combCartInContExtNoneTok")
+                        ),
+                    )
+                }
+            }
             Cc::None => String::new(),
         };
         let flags = if full_name.eq("ZWSP")
@@ -574,9 +1196,10 @@ impl GlyphFull {
             || full_name.starts_with("VAR")
             || full_name.starts_with("arrow")
             || full_name.eq("joinStackTok")
+            || full_name.eq("joinStack3Tok")
             || full_name.eq("joinScaleTok")
             || full_name.contains("space")
-            || full_name.eq("combCartExtNoneTok")
+            || full_name.ends_with("ExtNoneTok")
             || full_name.ends_with("Rad")
         {
             "Flags: W\n"
@@ -588,15 +1211,60 @@ impl GlyphFull {
         } else {
             String::new()
         };
-        format!("\nStartChar: {full_name}\n{encoding}\nWidth: {width}\n{flags}{anchor}LayerCount: 2\n{representation}{lookups}{cc_subs}{color}\nEndChar\n")
+        write!(out, "\nStartChar: {full_name}\n{encoding}\nWidth: {width}\n{vwidth}{class}{flags}{anchor}LayerCount: 2\n")?;
+        self.glyph.rep.write(out)?;
+        write!(out, "{carets}{lookups}{cc_subs}Colour: {color}\nEndChar\n")
+    }
+
+    /// The entries this glyph (called `full_name`) has in the lookups, besides its cartouche
+    /// substitutions: its generated ones, then the ones declared for it
+    pub fn lookup_lines(
+        &self,
+        full_name: &str,
+        profile: Profile,
+        features: &FeatureRegistry,
+    ) -> String {
+        let lookups = features.unless_respelled(
+            full_name,
+            self.lookups.gen(
+                self.glyph.name.to_string(),
+                full_name.to_string(),
+                profile,
+                features,
+            ),
+        );
+        format!("{lookups}{}", features.glyph_lines(full_name))
     }
 }
 
+/// The tag of the glyphs that pad a block, which are left out wherever glyphs are listed
+pub const TAG_PADDING: &str = "padding";
+/// The tag of glyphs that point in a direction (like `niTok_arrowN`), which don't stack
+pub const TAG_DIRECTIONAL: &str = "directional";
+/// The tag of the words for animals
+pub const TAG_ANIMAL: &str = "animal";
+/// The tag of the glyphs that extend a container over the glyph before them
+pub const TAG_EXTENSION: &str = "extension";
+
 pub struct GlyphDescriptor {
     pub name: &'static str,
     pub spline_set: &'static str,
     pub width: Option<usize>,
     pub anchor: Option<Anchor>,
+    /// Which way the long glyph extends, for glyphs that start one
+    pub container: ContainerDirection,
+    /// Which way the glyph points, for glyphs that get an alternate for each arrow (see
+    /// `directional`)
+    pub heading: Option<Heading>,
+    /// The full names of the alternates that `'rand'` picks from
+    pub alternates: &'static [&'static str],
+    pub tags: &'static [&'static str],
+    /// Who drew the glyph and what it's based on, if that's known
+    pub provenance: Option<&'static Provenance>,
+    /// If set, `spline_set` holds centerlines, drawn with a pen this wide (see `Stroke`)
+    pub stroke_width: Option<f64>,
+    /// The glyphs the glyph references, besides its own outlines (see `compose`)
+    pub components: &'static [Component],
 }
 
 impl GlyphDescriptor {
@@ -606,6 +1274,13 @@ impl GlyphDescriptor {
             spline_set,
             width: None,
             anchor: None,
+            container: ContainerDirection::Forward,
+            heading: None,
+            alternates: &[],
+            tags: &[],
+            provenance: None,
+            stroke_width: None,
+            components: &[],
         }
     }
 
@@ -619,6 +1294,13 @@ impl GlyphDescriptor {
             spline_set,
             width: Some(width),
             anchor: None,
+            container: ContainerDirection::Forward,
+            heading: None,
+            alternates: &[],
+            tags: &[],
+            provenance: None,
+            stroke_width: None,
+            components: &[],
         }
     }
 
@@ -632,20 +1314,133 @@ impl GlyphDescriptor {
             spline_set,
             width: None,
             anchor: Some(anchor),
+            container: ContainerDirection::Forward,
+            heading: None,
+            alternates: &[],
+            tags: &[],
+            provenance: None,
+            stroke_width: None,
+            components: &[],
+        }
+    }
+
+    /// Sets which way the long glyph that this glyph starts extends
+    pub const fn with_container(mut self, container: ContainerDirection) -> Self {
+        self.container = container;
+        self
+    }
+
+    /// Sets which way the glyph points, so it gets an alternate for each arrow
+    pub const fn pointing(mut self, heading: Heading) -> Self {
+        self.heading = Some(heading);
+        self
+    }
+
+    /// Sets the alternates that `'rand'` picks from in place of this glyph
+    pub const fn with_alternates(mut self, alternates: &'static [&'static str]) -> Self {
+        self.alternates = alternates;
+        self
+    }
+
+    /// Sets what the glyph is, for selecting it
+    pub const fn with_tags(mut self, tags: &'static [&'static str]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Sets who drew the glyph and what it's based on
+    pub const fn with_provenance(mut self, provenance: &'static Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Makes the spline set centerlines, drawn with a pen `width` wide
+    pub const fn stroked(mut self, width: f64) -> Self {
+        self.stroke_width = Some(width);
+        self
+    }
+
+    /// The glyph's outlines: its spline set, with the strokes expanded if it's stroked
+    pub fn outlines(&self) -> Result<Cow<'static, str>, String> {
+        match self.stroke_width {
+            Some(width) => Stroke {
+                centerlines: self.spline_set,
+                width,
+            }
+            .expand()
+            .map(Cow::Owned)
+            .map_err(|e| format!("{}: {e}", self.name)),
+            None => Ok(Cow::Borrowed(self.spline_set)),
+        }
+    }
+
+    /// Sets the glyphs the glyph is composed of, besides its own outlines
+    pub const fn composed_of(mut self, components: &'static [Component]) -> Self {
+        self.components = components;
+        self
+    }
+}
+
+/// How many empty glyphs a block is padded with, after its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    None,
+    /// Up to the next multiple of this many slots, with the block starting on one
+    AlignTo(usize),
+    /// Up to this many slots in all
+    FixedSize(usize),
+}
+
+/// Blocks line up with the rows of FontForge's font view
+impl Default for Padding {
+    fn default() -> Self {
+        Padding::AlignTo(16)
+    }
+}
+
+impl Padding {
+    /// Parses `none`, `align:N` or `fixed:N`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let size = |n: &str| {
+            n.parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("the padding `{spec}` isn't a positive number of slots"))
+        };
+        match spec.split_once(':') {
+            None if spec == "none" => Ok(Padding::None),
+            Some(("align", n)) => Ok(Padding::AlignTo(size(n)?)),
+            Some(("fixed", n)) => Ok(Padding::FixedSize(size(n)?)),
+            _ => Err(format!(
+                "the padding `{spec}` isn't `none`, `align:N` or `fixed:N`"
+            )),
+        }
+    }
+
+    /// How many empty glyphs to pad `len` glyphs with, when the last of them is before the slot
+    /// `end`
+    pub fn count(self, end: usize, len: usize) -> usize {
+        match self {
+            Padding::None => 0,
+            Padding::AlignTo(n) => (n - end % n) % n,
+            Padding::FixedSize(n) => n.saturating_sub(len),
         }
     }
 }
 
 pub struct GlyphBlock {
+    /// What this block is called in exports and on the command line (e.g. `base_cor`)
+    pub name: String,
     pub glyphs: Vec<GlyphFull>,
     pub prefix: String,
     pub suffix: String,
     pub color: String,
+    /// The empty glyphs `renumber` adds after this block's
+    pub padding: Padding,
 }
 
 impl GlyphBlock {
     pub fn new_from_enc_glyphs(
-        ff_pos: &mut usize,
         glyphs: Vec<GlyphEnc>,
         lookups: LookupsMode,
         cc_subs: Cc,
@@ -653,34 +1448,29 @@ impl GlyphBlock {
         suffix: impl Into<String>,
         color: impl Into<String>,
     ) -> Self {
-        let mut glyphs: Vec<GlyphFull> = glyphs
+        let glyphs: Vec<GlyphFull> = glyphs
             .into_iter()
             .enumerate()
             .map(|(idx, glyph)| {
-                let g = GlyphFull::new_from_enc(
+                GlyphFull::new_from_enc(
                     glyph,
-                    *ff_pos,
                     Lookups::new_from_mode(&lookups, idx),
                     cc_subs.clone(),
-                );
-                *ff_pos += 1;
-                g
+                )
             })
             .collect();
 
-        let mut padding = Self::new_empty(ff_pos, 15 - ((glyphs.len() + 15) % 16), 0).glyphs;
-        glyphs.append(&mut padding);
-
         Self {
+            name: String::new(),
             glyphs,
             prefix: prefix.into(),
             suffix: suffix.into(),
             color: color.into(),
+            padding: Padding::default(),
         }
     }
 
     pub fn new_from_basic_glyphs(
-        ff_pos: &mut usize,
         glyphs: Vec<GlyphBasic>,
         lookups: LookupsMode,
         cc_subs: Cc,
@@ -689,35 +1479,33 @@ impl GlyphBlock {
         color: impl Into<String>,
         mut enc_pos: EncPos,
     ) -> Self {
-        let mut glyphs: Vec<GlyphFull> = glyphs
+        let glyphs: Vec<GlyphFull> = glyphs
             .into_iter()
             .enumerate()
             .map(|(idx, glyph)| {
                 let g = GlyphFull::new_from_basic(
                     glyph,
-                    Encoding::new(*ff_pos, enc_pos.clone()),
+                    Encoding::new(enc_pos.clone()),
                     Lookups::new_from_mode(&lookups, idx),
                     cc_subs.clone(),
                 );
-                *ff_pos += 1;
                 enc_pos.inc();
                 g
             })
             .collect();
 
-        let mut padding = Self::new_empty(ff_pos, 15 - ((glyphs.len() + 15) % 16), 0).glyphs;
-        glyphs.append(&mut padding);
-
         Self {
+            name: String::new(),
             glyphs,
             prefix: prefix.into(),
             suffix: suffix.into(),
             color: color.into(),
+            padding: Padding::default(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_from_constants(
-        ff_pos: &mut usize,
         glyphs: &'static [GlyphDescriptor],
         lookups: LookupsMode,
         cc_subs: Cc,
@@ -726,36 +1514,49 @@ impl GlyphBlock {
         color: impl Into<String>,
         enc_pos: EncPos,
         fallback_width: usize,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let glyphs: Vec<GlyphBasic> = glyphs
-            .into_iter()
+            .iter()
             .map(
-                |GlyphDescriptor {
+                |descriptor @ GlyphDescriptor {
                      name,
-                     spline_set,
                      width,
                      anchor,
+                     alternates,
+                     tags,
+                     provenance,
+                     components,
+                     ..
                  }| {
-                    GlyphBasic::new(
+                    let references = components.iter().map(Component::to_ref).collect();
+                    let rep = Rep::new(descriptor.outlines()?, references);
+                    let mut glyph = GlyphBasic::new(
                         name.to_string(),
                         width.unwrap_or(fallback_width),
-                        Rep::new(spline_set.to_string(), vec![]),
+                        rep,
                         anchor.clone(),
-                    )
+                    );
+                    glyph.alternates = alternates
+                        .iter()
+                        .map(|alternate| GlyphName::new("", alternate, ""))
+                        .collect();
+                    glyph.tags = tags.to_vec();
+                    glyph.provenance = provenance.cloned().unwrap_or_default();
+                    Ok(glyph)
                 },
             )
-            .collect();
+            .collect::<Result<_, String>>()?;
 
-        Self::new_from_basic_glyphs(
-            ff_pos, glyphs, lookups, cc_subs, prefix, suffix, color, enc_pos,
-        )
+        Ok(Self::new_from_basic_glyphs(
+            glyphs, lookups, cc_subs, prefix, suffix, color, enc_pos,
+        ))
     }
 
-    /// Generates a `GlyphBlock` whose glyphs are all references this block's glyphs, all with the same `rel_pos`
-    pub fn new_from_refs(
+    /// Generates a `GlyphBlock` whose glyphs are all references to this block's glyphs, all moved by `transform`
+    #[allow(clippy::too_many_arguments)]
+    pub fn derive(
         &self,
-        ff_pos: &mut usize,
-        rel_pos: String,
+        transform: &Transform,
         lookups: LookupsMode,
         cc_subs: Cc,
         use_full_names: bool,
@@ -769,46 +1570,42 @@ impl GlyphBlock {
             .glyphs
             .clone()
             .into_iter()
-            .map(
-                |GlyphFull {
-                     glyph, encoding, ..
-                 }| {
-                    let refs = vec![
-                        Some(Ref::new(encoding.clone(), rel_pos.clone())),
-                        None,
-                    ]
+            .filter(|GlyphFull { glyph, .. }| !glyph.has_tag(TAG_PADDING))
+            .map(|GlyphFull { glyph, .. }| {
+                let target = GlyphName::new(&self.prefix, &glyph.name, &self.suffix);
+                let refs = vec![Some(Ref::new(target, *transform)), None]
                     .into_iter()
                     .flatten()
                     .collect();
-                    let name = if use_full_names {
-                        format!(
-                            "{pre}{name}{post}",
-                            pre = self.prefix,
-                            name = glyph.name,
-                            post = self.suffix
-                        )
-                    } else {
-                        glyph.name
-                    };
-                    let g = GlyphBasic::new(
-                        name,
-                        match width {
-                            Some(width) => width,
-                            None => glyph.width,
-                        },
-                        Rep::new(String::default(), refs),
-                        match &anchor {
-                            Some(anchor) => Some(anchor.clone()),
-                            None => glyph.anchor,
-                        },
-                    );
-                    g
-                },
-            )
+                let name = if use_full_names {
+                    format!(
+                        "{pre}{name}{post}",
+                        pre = self.prefix,
+                        name = glyph.name,
+                        post = self.suffix
+                    )
+                    .into()
+                } else {
+                    glyph.name
+                };
+                let mut g = GlyphBasic::new(
+                    name,
+                    match width {
+                        Some(width) => width,
+                        None => glyph.width,
+                    },
+                    Rep::new(String::default(), refs),
+                    match &anchor {
+                        Some(anchor) => Some(anchor.clone()),
+                        None => glyph.anchor,
+                    },
+                );
+                g.tags = glyph.tags;
+                g
+            })
             .collect();
 
         Self::new_from_basic_glyphs(
-            ff_pos,
             glyphs,
             lookups,
             cc_subs,
@@ -819,43 +1616,315 @@ impl GlyphBlock {
         )
     }
 
-    /// Generates a `GlyphBlock` with a given `count` of empty glyphs
-    pub fn new_empty(ff_pos: &mut usize, count: usize, width: usize) -> Self {
-        let end = *ff_pos + count;
-        let mut glyphs = vec![];
+    /// Names this block
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
 
-        while *ff_pos < end {
-            glyphs.push(GlyphFull::new_from_parts(
-                format!("empty{i:04}", i = *ff_pos),
-                width,
-                Rep::default(),
-                None,
-                Encoding::new(*ff_pos, EncPos::None),
-                Lookups::None,
-                Cc::None,
-            ));
-            *ff_pos += 1;
-        }
+    /// Pads this block with `padding` (instead of up to a multiple of 16 slots)
+    pub fn padded(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// The glyph called `name` (without the block's prefix and suffix)
+    pub fn glyph_mut(&mut self, name: &str) -> Result<&mut GlyphFull, String> {
+        let block = &self.name;
+        self.glyphs
+            .iter_mut()
+            .find(|glyph| glyph.glyph.name == name)
+            .ok_or_else(|| format!("there's no {name} in {block}"))
+    }
+
+    /// Overrides the glyph called `name` (without the block's prefix and suffix) with `f`
+    pub fn with_glyph(
+        mut self,
+        name: &str,
+        f: impl FnOnce(&mut GlyphFull),
+    ) -> Result<Self, String> {
+        f(self.glyph_mut(name)?);
+        Ok(self)
+    }
 
+    /// Generates a `GlyphBlock` without any glyphs, for one that's left out
+    pub fn new_empty() -> Self {
         Self {
-            glyphs,
+            name: String::new(),
+            glyphs: vec![],
             prefix: String::default(),
             suffix: String::default(),
-            color: "dddddd".to_string(),
+            color: BlockKind::Padding.color().to_string(),
+            padding: Padding::default(),
         }
     }
 
-    /// Generates a `GlyphBlock`
-    pub fn gen(&self, variation: NasinNanpaVariation) -> String {
-        let mut s = String::new();
-        for g in &self.glyphs {
-            s += &g.gen(
-                self.prefix.clone(),
-                self.suffix.clone(),
-                self.color.clone(),
-                variation,
-            )
+    /// Declares `'rand'` (if `profile` has it) for each glyph in this block with alternates, along
+    /// with the ligatures that pick an alternate (or, with `VAR09`, go back to the glyph) after one
+    /// was picked at random
+    pub fn declare_alternates(&self, features: &mut FeatureRegistry, profile: Profile) {
+        for (name, glyph) in self.full_names() {
+            let alternates = &glyph.glyph.alternates;
+            if alternates.is_empty() {
+                continue;
+            }
+            for alternate in alternates {
+                let Some((_, sel)) = alternate.as_str().rsplit_once('_') else {
+                    continue;
+                };
+                for from in alternates {
+                    for sel in SELECTORS.pickers(sel, profile) {
+                        features.declare(alternate.as_str(), LIGA_VAR, format!("{from} {sel}"));
+                    }
+                }
+            }
+            for from in alternates {
+                for sel in SELECTORS.pickers("VAR09", profile) {
+                    features.declare(name.as_str(), LIGA_VAR, format!("{from} {sel}"));
+                }
+            }
+            if profile.has(Capability::Rand) {
+                features.declare(name.as_str(), RAND_VARIATIONS, alternates.iter().join(" "));
+            }
         }
-        s
+    }
+
+    /// The full name of each glyph in this block (padding included)
+    pub fn full_names(&self) -> impl Iterator<Item = (GlyphName, &GlyphFull)> {
+        self.glyphs.iter().map(|glyph| {
+            let name = if glyph.glyph.has_tag(TAG_PADDING) {
+                GlyphName::new("", &glyph.glyph.name, "")
+            } else {
+                GlyphName::new(&self.prefix, &glyph.glyph.name, &self.suffix)
+            };
+            (name, glyph)
+        })
+    }
+
+    /// Removes the glyphs whose full names `keep` rejects, returning the names of the removed ones
+    pub fn retain_glyphs(&mut self, mut keep: impl FnMut(&str) -> bool) -> Vec<String> {
+        let names = self
+            .full_names()
+            .map(|(name, _)| name.to_string())
+            .collect_vec();
+        let mut removed = vec![];
+        let mut names = names.into_iter();
+        self.glyphs.retain(|_| {
+            let name = names.next().unwrap();
+            if keep(&name) {
+                true
+            } else {
+                removed.push(name);
+                false
+            }
+        });
+        removed
+    }
+
+    /// Resolves every reference in this block to a slot
+    pub fn resolve_refs(&mut self, names: &NameRegistry) -> Result<(), String> {
+        for glyph in &mut self.glyphs {
+            glyph.glyph.rep.resolve(names, glyph.encoding.ff_pos)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every glyph name in the font, interned and mapped to its encoding
+#[derive(Default)]
+pub struct NameRegistry {
+    names: HashMap<GlyphName, Encoding>,
+}
+
+/// A full glyph name given to two glyphs
+#[derive(Debug)]
+pub struct DuplicateName {
+    pub name: GlyphName,
+    /// The slots of the glyph that had it first, and of the one that took it again
+    pub slots: (usize, usize),
+}
+
+impl fmt::Display for DuplicateName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "glyph name {} is used by both slot {} and slot {}",
+            self.name, self.slots.0, self.slots.1
+        )
+    }
+}
+
+impl std::error::Error for DuplicateName {}
+
+impl NameRegistry {
+    /// Registers all of a block's glyphs, failing on the first full name that's already taken
+    pub fn register_block(&mut self, block: &GlyphBlock) -> Result<(), DuplicateName> {
+        for (name, glyph) in block.full_names() {
+            if let Some(existing) = self.names.get(&name) {
+                return Err(DuplicateName {
+                    slots: (existing.ff_pos, glyph.encoding.ff_pos),
+                    name,
+                });
+            }
+            self.names.insert(name, glyph.encoding.clone());
+        }
+        Ok(())
+    }
+
+    /// Looks up the interned name and encoding of a glyph by its full name
+    pub fn get(&self, name: &str) -> Option<(&GlyphName, &Encoding)> {
+        self.names.get_key_value(name)
+    }
+}
+
+/// Gives every glyph across `blocks` consecutive slots, in order (needed after blocks are filtered out),
+/// padding each block as it asks, and returning the number of slots used. A block aligned to `n`
+/// slots starts on a multiple of them, so the block before it is padded up to one too.
+pub fn renumber(blocks: &mut [GlyphBlock]) -> usize {
+    let mut ff_pos = 0;
+    for b in 0..blocks.len() {
+        blocks[b]
+            .glyphs
+            .retain(|glyph| !glyph.glyph.has_tag(TAG_PADDING));
+        if let (Padding::AlignTo(n), Some(before)) = (blocks[b].padding, b.checked_sub(1)) {
+            pad(&mut blocks[before], (n - ff_pos % n) % n, &mut ff_pos);
+        }
+        let block = &mut blocks[b];
+        for glyph in &mut block.glyphs {
+            glyph.encoding.ff_pos = ff_pos;
+            ff_pos += 1;
+        }
+        let count = block.padding.count(ff_pos, block.glyphs.len());
+        pad(block, count, &mut ff_pos);
+    }
+    ff_pos
+}
+
+/// Adds `count` empty glyphs to the end of `block`, from the slot `ff_pos` on
+fn pad(block: &mut GlyphBlock, count: usize, ff_pos: &mut usize) {
+    for _ in 0..count {
+        block.glyphs.push(padding_glyph(*ff_pos));
+        *ff_pos += 1;
+    }
+}
+
+/// An empty glyph, taking up the slot `ff_pos` to pad a block
+fn padding_glyph(ff_pos: usize) -> GlyphFull {
+    let mut glyph = GlyphFull::new_from_parts(
+        format!("empty{ff_pos:04}"),
+        0,
+        Rep::default(),
+        None,
+        Encoding::new(EncPos::None),
+        Lookups::None,
+        Cc::None,
+    );
+    glyph.glyph.tags = vec![TAG_PADDING];
+    glyph.encoding.ff_pos = ff_pos;
+    glyph
+}
+
+/// Checks that no two glyphs across `blocks` are encoded at the same codepoint
+pub fn check_unique_codepoints(blocks: &[GlyphBlock]) -> Result<(), String> {
+    let mut seen: HashMap<usize, String> = HashMap::new();
+    for block in blocks {
+        for (name, glyph) in block.full_names() {
+            if let EncPos::Pos(p) = glyph.encoding.enc_pos {
+                if let Some(other) = seen.insert(p, name.to_string()) {
+                    return Err(format!("{other} and {name} are both encoded at U+{p:04X}"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combinations_decompose_into_what_they_are_typed_as() {
+        assert_eq!(
+            Lookups::ComboFirst(Joiner::Stack).decomposition("tokiTok_joinStackTok"),
+            Some(["tokiTok", "joinStackTok"])
+        );
+        assert_eq!(
+            Lookups::ComboLast(Joiner::Scale).decomposition("joinScaleTok_jamiTok"),
+            Some(["joinScaleTok", "jamiTok"])
+        );
+        assert_eq!(
+            Lookups::ComboFirst(Joiner::Stack).decomposition("kalaTok_VAR01_joinStackTok"),
+            Some(["kalaTok_VAR01", "joinStackTok"])
+        );
+    }
+
+    #[test]
+    fn other_glyphs_dont_decompose() {
+        assert_eq!(
+            Lookups::ComboFirst(Joiner::Stack).decomposition("tokiTok_joinScaleTok"),
+            None
+        );
+        assert_eq!(Lookups::Alt.decomposition("tokiTok_joinStackTok"), None);
+    }
+
+    /// A block of `len` empty glyphs, padded with `padding`
+    fn block(name: &str, len: usize, padding: Padding) -> GlyphBlock {
+        let glyphs = (0..len)
+            .map(|i| GlyphBasic::new(format!("{name}{i}"), 0, Rep::default(), None))
+            .collect();
+        GlyphBlock::new_from_basic_glyphs(
+            glyphs,
+            LookupsMode::None,
+            Cc::None,
+            "",
+            "",
+            "dddddd",
+            EncPos::None,
+        )
+        .named(name)
+        .padded(padding)
+    }
+
+    /// The slot of the first glyph of each block
+    fn starts(blocks: &[GlyphBlock]) -> Vec<usize> {
+        blocks
+            .iter()
+            .map(|block| block.glyphs[0].encoding.ff_pos)
+            .collect()
+    }
+
+    #[test]
+    fn aligned_blocks_start_on_a_multiple() {
+        let mut blocks = vec![
+            block("a", 2, Padding::None),
+            block("b", 5, Padding::AlignTo(16)),
+            block("c", 3, Padding::None),
+            block("d", 1, Padding::None),
+            block("e", 20, Padding::AlignTo(8)),
+            block("f", 1, Padding::AlignTo(16)),
+        ];
+        assert_eq!(renumber(&mut blocks), 80);
+        assert_eq!(starts(&blocks), [0, 16, 32, 35, 40, 64]);
+    }
+
+    #[test]
+    fn fixed_size_blocks_take_up_their_size() {
+        let mut blocks = vec![
+            block("a", 3, Padding::FixedSize(10)),
+            block("b", 12, Padding::FixedSize(10)),
+            block("c", 1, Padding::None),
+        ];
+        assert_eq!(renumber(&mut blocks), 23);
+        assert_eq!(starts(&blocks), [0, 10, 22]);
+    }
+
+    #[test]
+    fn padding_policies_parse() {
+        assert_eq!(Padding::parse("none"), Ok(Padding::None));
+        assert_eq!(Padding::parse("align:16"), Ok(Padding::AlignTo(16)));
+        assert_eq!(Padding::parse("fixed:32"), Ok(Padding::FixedSize(32)));
+        assert!(Padding::parse("align:0").is_err());
+        assert!(Padding::parse("16").is_err());
     }
 }
@@ -0,0 +1,92 @@
+//! Running the `fontforge` executable to turn generated `.sfd` files into font binaries
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::ValueEnum;
+
+/// A font format FontForge can generate (picked by FontForge from the file extension)
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FontFormat {
+    Otf,
+    Ttf,
+    Woff2,
+}
+
+impl FontFormat {
+    pub const ALL: [FontFormat; 3] = [FontFormat::Otf, FontFormat::Ttf, FontFormat::Woff2];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FontFormat::Otf => "otf",
+            FontFormat::Ttf => "ttf",
+            FontFormat::Woff2 => "woff2",
+        }
+    }
+}
+
+/// The script that generates each of `formats` (the path of each is the argument after the
+/// `.sfd`'s). With `autohint`, the glyphs are given PostScript hints first, and the TrueType
+/// formats are generated last, from quadratic outlines with instructions.
+fn script(formats: &[FontFormat], autohint: bool) -> String {
+    let generate = |truetype: bool| {
+        formats
+            .iter()
+            .enumerate()
+            .filter(|(_, format)| !autohint || (**format == FontFormat::Ttf) == truetype)
+            .map(|(i, _)| format!(" Generate(${});", i + 2))
+            .collect::<String>()
+    };
+    if !autohint {
+        return format!("Open($1);{}", generate(false));
+    }
+    let mut script = format!("Open($1); SelectAll(); AutoHint();{}", generate(false));
+    if formats.contains(&FontFormat::Ttf) {
+        script += &format!(" SetFontOrder(2); AutoInstr();{}", generate(true));
+    }
+    script
+}
+
+/// Compiles an `.sfd` file into each of `formats`, placing the results in `dist` (hinted by
+/// FontForge, with `autohint`)
+pub fn compile(
+    sfd: impl AsRef<Path>,
+    dist: impl AsRef<Path>,
+    formats: &[FontFormat],
+    autohint: bool,
+) -> io::Result<Vec<PathBuf>> {
+    let sfd = sfd.as_ref();
+    let dist = dist.as_ref();
+    fs::create_dir_all(dist)?;
+
+    // not `with_extension`, since the version in the file name has dots in it
+    let stem = sfd.file_stem().unwrap_or_default().to_string_lossy();
+    let outputs = formats
+        .iter()
+        .map(|format| dist.join(format!("{stem}.{}", format.extension())))
+        .collect::<Vec<_>>();
+
+    let status = process::Command::new("fontforge")
+        .args(["-quiet", "-lang=ff", "-c", &script(formats, autohint)])
+        .arg(sfd)
+        .args(&outputs)
+        .status()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => io::Error::new(
+                e.kind(),
+                "couldn't find the `fontforge` executable; install FontForge (https://fontforge.org) \
+                 and make sure it's on your PATH, or build without compiling",
+            ),
+            _ => io::Error::new(e.kind(), format!("couldn't run fontforge: {e}")),
+        })?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "fontforge failed to compile {} ({status})",
+            sfd.display()
+        )));
+    }
+    Ok(outputs)
+}
@@ -0,0 +1,95 @@
+//! The `gasp` table, which tells TrueType rasterizers how to render the glyphs at each size:
+//! whether to grid-fit them to the pixels and whether to smooth them. Without one, Windows renders
+//! small sizes in black and white, and the detailed sitelen pona glyphs come out jagged, so every
+//! build has one (anti-aliased at every size, unless `--gasp` says otherwise). FontForge only
+//! writes it into TrueType fonts.
+
+/// The largest size a range can go up to, which the last range has to
+const MAX_PPEM: u16 = 0xFFFF;
+
+/// How the glyphs are rendered, as the bits of the table's flags
+const BEHAVIORS: [(&str, u16); 4] = [
+    ("gridfit", 1),
+    ("grayscale", 2),
+    ("symmetric-gridfit", 4),
+    ("symmetric-smoothing", 8),
+];
+
+/// The behaviors set when there's no `--gasp`: every one, at every size
+const RECOMMENDED: [GaspRange; 1] = [GaspRange {
+    max_ppem: MAX_PPEM,
+    flags: 15,
+}];
+
+/// How the glyphs are rendered up to a size (after the range before it)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GaspRange {
+    /// The largest size in the range, in pixels per em
+    pub max_ppem: u16,
+    pub flags: u16,
+}
+
+impl GaspRange {
+    /// Parses `PPEM=BEHAVIOR+BEHAVIOR...` (with `max` for the largest size, and `none` for no
+    /// behaviors), like `8=grayscale` or `max=gridfit+grayscale`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (ppem, behaviors) = spec.split_once('=').ok_or_else(|| {
+            format!("the gasp range `{spec}` isn't written as PPEM=BEHAVIOR+BEHAVIOR...")
+        })?;
+        let max_ppem = match ppem.trim() {
+            "max" => MAX_PPEM,
+            ppem => ppem
+                .parse()
+                .map_err(|e| format!("the gasp range `{spec}` has {e}"))?,
+        };
+        let mut flags = 0;
+        for behavior in behaviors.split('+').map(str::trim) {
+            if behavior == "none" {
+                continue;
+            }
+            let (_, flag) = BEHAVIORS
+                .iter()
+                .find(|(name, _)| *name == behavior)
+                .ok_or_else(|| {
+                    format!(
+                        "the gasp range `{spec}` has `{behavior}`, which isn't one of none, {}",
+                        BEHAVIORS.map(|(name, _)| name).join(", ")
+                    )
+                })?;
+            flags |= flag;
+        }
+        Ok(Self { max_ppem, flags })
+    }
+}
+
+/// Checks that `ranges` go up in size, to the largest
+pub fn check(ranges: &[GaspRange]) -> Result<(), String> {
+    if ranges
+        .windows(2)
+        .any(|pair| pair[0].max_ppem >= pair[1].max_ppem)
+    {
+        return Err("the gasp ranges don't go up in size".to_string());
+    }
+    match ranges.last() {
+        Some(last) if last.max_ppem != MAX_PPEM => {
+            Err("the last gasp range doesn't go up to `max`".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The `GaspTable` line of the `.sfd` header, for `ranges` (or the recommended ones, if there are
+/// none)
+pub fn gen(ranges: &[GaspRange]) -> String {
+    let ranges = match ranges {
+        [] => &RECOMMENDED[..],
+        ranges => ranges,
+    };
+    // the symmetric behaviors are only in version 1 of the table
+    let version = u16::from(ranges.iter().any(|range| range.flags & !3 != 0));
+    let entries = ranges
+        .iter()
+        .map(|range| format!(" {} {}", range.max_ppem, range.flags))
+        .collect::<String>();
+    format!("GaspTable: {}{entries} {version}\n", ranges.len())
+}
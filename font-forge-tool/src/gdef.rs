@@ -0,0 +1,128 @@
+//! The class every glyph has in the `GDEF` table, assigned from what the glyph is for, rather than
+//! left for FontForge to guess (which it does from the codepoint, so the variation selectors past
+//! the BMP don't come out as marks like the ones in it)
+
+use std::collections::HashSet;
+
+use crate::{
+    features::FeatureRegistry,
+    ffir::{EncPos, GlyphBlock, GlyphClass, GlyphFull, TAG_PADDING},
+    profile::Profile,
+    selectors::SELECTORS,
+};
+
+/// The block of the contours shared between glyphs (see `shared_contours`)
+const COMPONENTS_BLOCK: &str = "shared_contours";
+
+/// The block of the latin characters the words are typed with
+const LATIN_BLOCK: &str = "latn";
+
+/// Whether `glyph` is a variation selector
+fn is_variation_selector(glyph: &GlyphFull) -> bool {
+    matches!(
+        glyph.encoding.enc_pos,
+        EncPos::Pos(0xFE00..=0xFE0F | 0xE0100..=0xE01EF)
+    )
+}
+
+/// Whether `glyph` (called `name`) combines with the glyph before it without taking up any room:
+/// the extensions of cartouches and long glyphs, and the zero-width parts drawn over the glyph
+/// before them (like the top of a 3-stack)
+fn is_combiner(glyph: &GlyphFull, name: &str) -> bool {
+    glyph.glyph.width == 0 && (name.starts_with("comb") || !glyph.glyph.rep.is_empty())
+}
+
+/// The components of the first ligature that makes `glyph` (called `name`) out of more than one
+/// glyph besides the `modifiers` (see `modifiers` and `selectors`)
+pub fn glyph_ligature(
+    glyph: &GlyphFull,
+    name: &str,
+    modifiers: &HashSet<String>,
+    profile: Profile,
+    features: &FeatureRegistry,
+) -> Option<Vec<String>> {
+    glyph
+        .lookup_lines(name, profile, features)
+        .lines()
+        .filter(|line| line.starts_with("Ligature2:"))
+        .filter_map(|line| features.parse_line(line))
+        .map(|(_, components)| {
+            components
+                .split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .find(|components| {
+            components
+                .iter()
+                .filter(|component| !modifiers.contains(*component))
+                .count()
+                > 1
+        })
+}
+
+/// The names of the glyphs in `blocks` that only change how the glyphs they're typed with are
+/// written: the latin characters the words are typed with, and the selectors
+pub fn modifiers(blocks: &[GlyphBlock]) -> HashSet<String> {
+    let mut modifiers = selectors(blocks);
+    modifiers.extend(
+        blocks
+            .iter()
+            .filter(|block| block.name == LATIN_BLOCK)
+            .flat_map(|block| block.full_names().map(|(name, _)| name.to_string())),
+    );
+    modifiers
+}
+
+/// The names of the selectors in `blocks`, which pick an alternate of the glyph before them
+pub fn selectors(blocks: &[GlyphBlock]) -> HashSet<String> {
+    blocks
+        .iter()
+        .flat_map(|block| block.full_names())
+        .filter(|(name, glyph)| {
+            is_variation_selector(glyph) || SELECTORS.get(name.as_str()).is_some()
+        })
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Assigns every glyph in `blocks` (but padding) its class:
+/// - the glyphs attached to the glyph before them by a mark anchor (the upper halves of stacks and
+///   the glyphs scaled into containers), the zero-width glyphs that combine with the glyph before
+///   them, and the variation selectors, are marks
+/// - the shared contours are components
+/// - the glyphs a ligature makes out of several glyphs (like a glyph and the joiner after it, and
+///   numbers) are ligatures
+/// - the rest (the words, even those typed in latin letters, and their alternates) are bases
+pub fn assign(blocks: &mut [GlyphBlock], features: &FeatureRegistry, profile: Profile) {
+    let modifiers = modifiers(blocks);
+    for block in blocks.iter_mut() {
+        let components = block.name == COMPONENTS_BLOCK;
+        let names = block
+            .full_names()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        for (glyph, name) in block.glyphs.iter_mut().zip(names) {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            let mark = is_variation_selector(glyph)
+                || is_combiner(glyph, &name)
+                || glyph
+                    .glyph
+                    .anchor
+                    .as_ref()
+                    .is_some_and(|anchor| anchor.is_mark());
+            let class = if mark {
+                GlyphClass::Mark
+            } else if components {
+                GlyphClass::Component
+            } else if glyph_ligature(glyph, &name, &modifiers, profile, features).is_some() {
+                GlyphClass::Ligature
+            } else {
+                GlyphClass::Base
+            };
+            glyph.glyph.class = Some(class);
+        }
+    }
+}
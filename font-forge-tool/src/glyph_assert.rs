@@ -0,0 +1,315 @@
+//! Checking structural facts about the glyphs of a generated font (their widths, contours,
+//! references and what they refer to, anchors, codepoints, tags, lookups, classes, and carets), written like
+//! `assert_glyph!(font, "tokiTok", width = 1000, contours = 3)`
+
+use crate::{
+    ffir::{EncPos, GlyphClass, GlyphFull, Transform},
+    spline::SplineSet,
+    Font,
+};
+
+/// A fact a glyph is expected to have
+pub enum Expectation {
+    Width(usize),
+    Contours(usize),
+    References(usize),
+    /// A reference to the glyph with this full name, moved by the transform (as its `Refer:` line
+    /// is written)
+    Refers(&'static str, Transform),
+    HasAnchor(&'static str),
+    Codepoint(usize),
+    HasTag(&'static str),
+    /// A lookup that takes or makes it
+    InLookup(&'static str),
+    /// Its class in the `GDEF` table
+    Class(GlyphClass),
+    /// Its ligature carets
+    Carets(&'static [usize]),
+}
+
+impl Expectation {
+    /// What the fact is, as it's written in `assert_glyph!`
+    fn describe(&self) -> String {
+        match self {
+            Expectation::Width(width) => format!("width = {width}"),
+            Expectation::Contours(contours) => format!("contours = {contours}"),
+            Expectation::References(references) => format!("references = {references}"),
+            Expectation::Refers(target, transform) => {
+                format!("refers = ({target:?}, {transform:?})")
+            }
+            Expectation::HasAnchor(class) => format!("has_anchor = {class:?}"),
+            Expectation::Codepoint(codepoint) => format!("codepoint = {codepoint:#X}"),
+            Expectation::HasTag(tag) => format!("has_tag = {tag:?}"),
+            Expectation::InLookup(lookup) => format!("in_lookup = {lookup:?}"),
+            Expectation::Class(class) => format!("class = {class:?}"),
+            Expectation::Carets(carets) => format!("carets = {carets:?}"),
+        }
+    }
+
+    /// What `glyph` (called `name` in `font`) has instead, if it doesn't have this fact
+    fn check(&self, font: &Font, name: &str, glyph: &GlyphFull) -> Result<(), String> {
+        let actual = match self {
+            Expectation::Width(width) => {
+                let actual = glyph.glyph.width;
+                (actual != *width).then(|| format!("its width is {actual}"))
+            }
+            Expectation::Contours(contours) => {
+                let actual = SplineSet::parse(glyph.glyph.rep.spline_set())?
+                    .contours
+                    .len();
+                (actual != *contours).then(|| format!("it has {actual} contours"))
+            }
+            Expectation::References(references) => {
+                let actual = glyph.glyph.rep.references().len();
+                (actual != *references).then(|| format!("it has {actual} references"))
+            }
+            Expectation::Refers(target, transform) => {
+                let expected = font
+                    .glyph(target)
+                    .ok_or_else(|| format!("there's no {target} to refer to"))?
+                    .encoding
+                    .gen_ref(transform);
+                let actual = glyph
+                    .glyph
+                    .rep
+                    .references()
+                    .iter()
+                    .map(|reference| reference.gen())
+                    .collect::<Vec<_>>();
+                (!actual.contains(&expected)).then(|| format!("its references are {actual:?}"))
+            }
+            Expectation::HasAnchor(class) => match &glyph.glyph.anchor {
+                Some(anchor) if anchor.class().name() == *class => None,
+                Some(anchor) => Some(format!("its anchor is {:?}", anchor.class().name())),
+                None => Some("it has no anchor".to_string()),
+            },
+            Expectation::Codepoint(codepoint) => match glyph.encoding.enc_pos {
+                EncPos::Pos(actual) if actual == *codepoint => None,
+                EncPos::Pos(actual) => Some(format!("its codepoint is {actual:#X}")),
+                EncPos::None => Some("it has no codepoint".to_string()),
+            },
+            Expectation::HasTag(tag) => {
+                (!glyph.glyph.has_tag(tag)).then(|| format!("its tags are {:?}", glyph.glyph.tags))
+            }
+            Expectation::InLookup(lookup) => {
+                let actual = font.lookups_for(name);
+                (!actual.iter().any(|l| l == lookup)).then(|| format!("its lookups are {actual:?}"))
+            }
+            Expectation::Class(class) => match glyph.glyph.class {
+                Some(actual) if actual == *class => None,
+                Some(actual) => Some(format!("its class is {actual:?}")),
+                None => Some("it has no class".to_string()),
+            },
+            Expectation::Carets(carets) => {
+                let actual = &glyph.glyph.carets;
+                (actual != carets).then(|| format!("its carets are {actual:?}"))
+            }
+        };
+        match actual {
+            Some(actual) => Err(format!("expected {}, but {actual}", self.describe())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks that the glyph of `font` with the full name `name` has every one of `expectations`,
+/// naming the first it doesn't have
+pub fn check(font: &Font, name: &str, expectations: &[Expectation]) -> Result<(), String> {
+    let glyph = font
+        .glyph(name)
+        .ok_or_else(|| format!("{name}: there's no such glyph"))?;
+    for expectation in expectations {
+        expectation
+            .check(font, name, glyph)
+            .map_err(|e| format!("{name}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Asserts facts about a glyph of a generated font, panicking with the first it doesn't have, like
+/// `assert_glyph!(font, "tokiTok", width = 1000, contours = 3, has_anchor = "stack")`. The facts
+/// are `width`, `contours`, `references`, `refers` (a full name and a `Transform`), `has_anchor` (a
+/// class, like `"stack"`), `codepoint`, `has_tag`, `in_lookup`, `class` (a `GlyphClass`), and
+/// `carets`.
+macro_rules! assert_glyph {
+    ($font:expr, $name:expr $(, $fact:ident = $value:expr)* $(,)?) => {
+        if let Err(e) = $crate::glyph_assert::check(
+            &$font,
+            $name,
+            &[$(assert_glyph!(@fact $fact $value)),*],
+        ) {
+            panic!("{e}");
+        }
+    };
+    (@fact width $value:expr) => { $crate::glyph_assert::Expectation::Width($value) };
+    (@fact contours $value:expr) => { $crate::glyph_assert::Expectation::Contours($value) };
+    (@fact references $value:expr) => { $crate::glyph_assert::Expectation::References($value) };
+    (@fact refers $value:expr) => {{
+        let (target, transform) = $value;
+        $crate::glyph_assert::Expectation::Refers(target, transform)
+    }};
+    (@fact has_anchor $value:expr) => { $crate::glyph_assert::Expectation::HasAnchor($value) };
+    (@fact codepoint $value:expr) => { $crate::glyph_assert::Expectation::Codepoint($value) };
+    (@fact has_tag $value:expr) => { $crate::glyph_assert::Expectation::HasTag($value) };
+    (@fact in_lookup $value:expr) => { $crate::glyph_assert::Expectation::InLookup($value) };
+    (@fact class $value:expr) => { $crate::glyph_assert::Expectation::Class($value) };
+    (@fact carets $value:expr) => { $crate::glyph_assert::Expectation::Carets($value) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use crate::{
+        ffir::{GlyphClass, Padding, Transform},
+        gen_font,
+        profile::Capability,
+        snapshot, Font,
+    };
+
+    /// The snapshotted variations, generated once for every test
+    fn fonts() -> &'static [Font] {
+        static FONTS: OnceLock<Vec<Font>> = OnceLock::new();
+        FONTS.get_or_init(|| {
+            snapshot::PROFILES
+                .iter()
+                .map(|profile| gen_font(*profile, &snapshot::options()).unwrap())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn toki() {
+        for font in fonts() {
+            assert_glyph!(
+                font,
+                "tokiTok",
+                width = 1000,
+                contours = 5,
+                codepoint = 0xF196C,
+                in_lookup = "'ss03' SIMPLIFIED"
+            );
+        }
+    }
+
+    #[test]
+    fn stacked_toki() {
+        for font in fonts() {
+            assert_glyph!(
+                font,
+                "tokiTok_joinStackTok",
+                width = 1000,
+                has_anchor = "stack"
+            );
+        }
+    }
+
+    #[test]
+    fn combinations_respawn_their_joiner() {
+        for font in fonts() {
+            assert_glyph!(
+                font,
+                "tokiTok_joinStackTok",
+                in_lookup = "'ccmp' RESPAWN JOINER"
+            );
+            assert_glyph!(
+                font,
+                "pokiTok_joinScaleTok",
+                in_lookup = "'ccmp' RESPAWN JOINER"
+            );
+        }
+    }
+
+    #[test]
+    fn start_of_cartouche() {
+        for font in fonts() {
+            assert_glyph!(
+                font,
+                "startCartTok",
+                width = 500,
+                contours = 1,
+                codepoint = 0xF1990
+            );
+        }
+    }
+
+    #[test]
+    fn notdef_is_glyph_zero() {
+        for font in fonts() {
+            assert_eq!(font.glyph(".notdef").unwrap().encoding.ff_pos, 0);
+        }
+    }
+
+    #[test]
+    fn aligned_blocks_start_on_a_row() {
+        for font in fonts() {
+            for block in &font.blocks {
+                let (Padding::AlignTo(n), Some(first)) = (block.padding, block.glyphs.first())
+                else {
+                    continue;
+                };
+                assert_eq!(
+                    first.encoding.ff_pos % n,
+                    0,
+                    "{} starts off a row",
+                    block.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn akesi_is_an_animal() {
+        for font in fonts() {
+            assert_glyph!(font, "akesiTok", has_tag = "animal");
+        }
+    }
+
+    #[test]
+    fn scaled_jami_is_a_reference() {
+        for font in fonts() {
+            assert_glyph!(font, "joinScaleTok_jamiTok", references = 1);
+        }
+    }
+
+    #[test]
+    fn sike_and_tenpo_are_drawn_inside_ijo() {
+        for font in fonts() {
+            for name in ["sikeTok", "tenpoTok"] {
+                assert_glyph!(
+                    font,
+                    name,
+                    references = 1,
+                    refers = ("ijoTok", Transform::identity())
+                );
+            }
+            assert_glyph!(font, "sikeTok", contours = 2);
+            assert_glyph!(font, "tenpoTok", contours = 1);
+        }
+    }
+
+    #[test]
+    fn classes_by_role() {
+        for font in fonts() {
+            assert_glyph!(font, "tokiTok", class = GlyphClass::Base);
+            assert_glyph!(font, "tokiTok_joinStackTok", class = GlyphClass::Ligature);
+            assert_glyph!(font, "combCartExtTok", class = GlyphClass::Mark);
+            assert_glyph!(font, "combCartExtHalfTok", class = GlyphClass::Mark);
+        }
+    }
+
+    #[test]
+    fn carets_between_letters_and_glyphs() {
+        for font in fonts() {
+            // a word gets a caret between each of its letters, where it's typed in them
+            let kulupu: &[usize] = if font.profile.has(Capability::LatinLigatures) {
+                &[167, 333, 500, 667, 833]
+            } else {
+                &[]
+            };
+            assert_glyph!(font, "kulupuTok", carets = kulupu);
+            assert_glyph!(font, "aTok", carets = &[]);
+            assert_glyph!(font, "itanTok", carets = &[250, 500, 750]);
+        }
+    }
+}
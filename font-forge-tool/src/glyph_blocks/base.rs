@@ -1,7 +1,7 @@
-use crate::GlyphDescriptor;
+use crate::{compose::Component, directional::Heading, ffir::Transform, GlyphDescriptor, TAG_ANIMAL, TAG_DIRECTIONAL, UCSUR_CHART};
 
 //MARK: BASE
-pub const BASE_COR: [GlyphDescriptor; 137] = [
+pub static BASE_COR: [GlyphDescriptor; 137] = [
 GlyphDescriptor::new("a",
 r#"
 500 50 m 0
@@ -83,7 +83,7 @@ r#"
  284 690 250 724 250 765 c 0
  250 806 284 840 325 840 c 0
  366 840 400 806 400 765 c 0"#
-),
+).with_tags(&[TAG_ANIMAL]),
 GlyphDescriptor::new("ala",
 r#"
 100 750 m 0
@@ -526,7 +526,7 @@ r#"
  215 724 240 750 264 770 c 0
  284 787 311 804 344 823 c 0
  352 828 360 830 369 830 c 0"#
-),
+).with_alternates(&["jakiTok_VAR01", "jakiTok_VAR02", "jakiTok_VAR03", "jakiTok_VAR04", "jakiTok_VAR05", "jakiTok_VAR06", "jakiTok_VAR07", "jakiTok_VAR08"]),
 GlyphDescriptor::new("jan",
 r#"
 200 450 m 0
@@ -657,7 +657,7 @@ r#"
  85 550 74 568 67 581 c 0
  61 590 61 596 56 601 c 0
  52 608 50 616 50 624 c 0"#
-),
+).with_tags(&[TAG_ANIMAL]),
 GlyphDescriptor::new("kalama",
 r#"
 500 797 m 0
@@ -721,7 +721,7 @@ r#"
  390 13 371 0 350 0 c 0
  239 0 150 31 68 99 c 0
  56 109 50 124 50 138 c 0"#
-),
+).pointing(Heading::W),
 GlyphDescriptor::new("kasi",
 r#"
 545 525 m 0
@@ -946,7 +946,7 @@ r#"
  168 47 136 114 136 183 c 0
  136 210 141 238 151 264 c 1
  88 305 50 376 50 451 c 0"#
-),
+).with_alternates(&["koTok_VAR01", "koTok_VAR02", "koTok_VAR03", "koTok_VAR04", "koTok_VAR05", "koTok_VAR06", "koTok_VAR07", "koTok_VAR08"]),
 GlyphDescriptor::new("kon",
 r#"
 376 851 m 0
@@ -1988,7 +1988,7 @@ r#"
  484 -50 474 -44 465 -35 c 2
  215 215 l 2
  205 225 200 238 200 250 c 0"#
-),
+).pointing(Heading::S),
 GlyphDescriptor::new("nimi",
 r#"
 150 361 m 2
@@ -2349,17 +2349,10 @@ r#"
 ),
 GlyphDescriptor::new("pi",
 r#"
-150 800 m 0
- 178 800 200 778 200 750 c 2
- 200 100 l 1
- 850 100 l 2
- 878 100 900 78 900 50 c 0
- 900 22 878 0 850 0 c 2
- 150 0 l 2
- 122 0 100 22 100 50 c 2
- 100 750 l 2
- 100 778 122 800 150 800 c 0"#
-),
+150 750 m 1
+ 150 50 l 1
+ 850 50 l 1"#
+).stroked(100.0),
 GlyphDescriptor::new("pilin",
 r#"
 300 700 m 0
@@ -2494,7 +2487,7 @@ r#"
  250 816 284 850 325 850 c 0
  366 850 400 816 400 775 c 0
  400 734 366 700 325 700 c 0"#
-),
+).with_tags(&[TAG_ANIMAL]),
 GlyphDescriptor::new("poka",
 r#"
 100 600 m 0
@@ -2797,18 +2790,8 @@ r#"
  650 483 583 550 500 550 c 0
  417 550 350 483 350 400 c 0
  350 317 417 250 500 250 c 0
- 583 250 650 317 650 400 c 0
-500 -50 m 0
- 251 -50 50 151 50 400 c 0
- 50 649 251 850 500 850 c 0
- 749 850 950 649 950 400 c 0
- 950 151 749 -50 500 -50 c 0
-500 50 m 0
- 693 50 850 207 850 400 c 0
- 850 593 693 750 500 750 c 0
- 307 750 150 593 150 400 c 0
- 150 207 307 50 500 50 c 0"#
-),
+ 583 250 650 317 650 400 c 0"#
+).composed_of(&IJO),
 GlyphDescriptor::new("sin",
 r#"
 50 200 m 0
@@ -3002,7 +2985,7 @@ r#"
  402 -50 380 -28 380 0 c 2
  380 200 l 2
  380 228 402 250 430 250 c 0"#
-),
+).with_tags(&[TAG_ANIMAL]),
 GlyphDescriptor::new("suli",
 r#"
 849 850 m 0
@@ -3170,7 +3153,7 @@ r#"
  629 0 610 13 603 33 c 2
  397 610 l 1
  210 151 l 1"#
-),
+).pointing(Heading::E),
 GlyphDescriptor::new("telo",
 r#"
 899 726 m 0
@@ -3233,18 +3216,8 @@ r#"
  485 325 l 2
  457 325 435 347 435 375 c 2
  435 650 l 2
- 435 678 457 700 485 700 c 0
-500 -50 m 0
- 251 -50 50 151 50 400 c 0
- 50 649 251 850 500 850 c 0
- 749 850 950 649 950 400 c 0
- 950 151 749 -50 500 -50 c 0
-500 50 m 0
- 693 50 850 207 850 400 c 0
- 850 593 693 750 500 750 c 0
- 307 750 150 593 150 400 c 0
- 150 207 307 50 500 50 c 0"#
-),
+ 435 678 457 700 485 700 c 0"#
+).composed_of(&IJO),
 GlyphDescriptor::new("toki",
 r#"
 750 783 m 0
@@ -3306,21 +3279,11 @@ r#"
 ),
 GlyphDescriptor::new("tu",
 r#"
-320 -50 m 0
- 292 -50 270 -28 270 0 c 2
- 270 800 l 2
- 270 828 292 850 320 850 c 0
- 348 850 370 828 370 800 c 2
- 370 0 l 2
- 370 -28 348 -50 320 -50 c 0
-680 -50 m 0
- 652 -50 630 -28 630 0 c 2
- 630 800 l 2
- 630 828 652 850 680 850 c 0
- 708 850 730 828 730 800 c 2
- 730 0 l 2
- 730 -28 708 -50 680 -50 c 0"#
-),
+320 0 m 1
+ 320 800 l 1
+680 0 m 1
+ 680 800 l 1"#
+).stroked(100.0),
 GlyphDescriptor::new("unpa",
 r#"
 635 284 m 1
@@ -3499,7 +3462,7 @@ r#"
  682 300 l 1
  381 773 l 2
  376 781 373 791 373 800 c 0"#
-),
+).with_tags(&[TAG_ANIMAL]),
 GlyphDescriptor::new("wawa",
 r#"
 92 754 m 0
@@ -3987,7 +3950,7 @@ r#"
  576 142 l 2
  576 170 598 192 626 192 c 0
  654 192 676 170 676 142 c 2"#
-),
+).with_tags(&[TAG_ANIMAL]),
 GlyphDescriptor::new("soko",
 r#"
 937 430 m 0
@@ -4265,7 +4228,7 @@ r#"
 ),
 ];
 
-pub const BASE_EXT: [GlyphDescriptor; 43] = [
+pub static BASE_EXT: [GlyphDescriptor; 43] = [
 GlyphDescriptor::new("pake",
 r#"
 150 800 m 2
@@ -4280,7 +4243,7 @@ r#"
  150 700 l 2
  122 700 100 722 100 750 c 0
  100 778 122 800 150 800 c 2"#
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new("apeja",
 r#"
 170 190 m 0
@@ -4326,7 +4289,7 @@ r#"
  415 57 453 20 500 20 c 0
  547 20 584 57 584 104 c 0
  584 151 547 189 500 189 c 0"#
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new("majuna",
 r#"
 650 300 m 0
@@ -4368,7 +4331,7 @@ r#"
  950 172 928 150 900 150 c 2
  700 150 l 2
  672 150 650 172 650 200 c 0"#
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new("powe",
 r#"
 50 200 m 0
@@ -4399,7 +4362,7 @@ r#"
  571 500 l 1
  635 435 l 2
  645 425 650 412 650 400 c 0"#
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new("linluwi",
 r#"
 303 164 m 0
@@ -6165,7 +6128,7 @@ r#"
 ),
 ];
 
-pub const BASE_ALT: [GlyphDescriptor; 57] = [
+pub static BASE_ALT: [GlyphDescriptor; 57] = [
 GlyphDescriptor::new("jakiTok_VAR01",
 r#"
 825 817 m 0
@@ -7245,7 +7208,7 @@ r#"
  50 413 55 425 65 435 c 2
  315 685 l 2
  325 695 337 700 350 700 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowN",
 r#"
 800 550 m 0
@@ -7265,7 +7228,7 @@ r#"
  513 850 525 845 535 835 c 2
  785 585 l 2
  795 575 800 563 800 550 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowE",
 r#"
 650 100 m 0
@@ -7285,7 +7248,7 @@ r#"
  950 387 945 375 935 365 c 2
  685 115 l 2
  675 105 663 100 650 100 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowS",
 r#"
 200 250 m 0
@@ -7305,7 +7268,7 @@ r#"
  484 -50 474 -44 465 -35 c 2
  215 215 l 2
  205 225 200 238 200 250 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowNW",
 r#"
 833 117 m 0
@@ -7323,7 +7286,7 @@ r#"
  338 633 l 1
  818 153 l 2
  828 143 833 130 833 117 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowNE",
 r#"
 217 67 m 0
@@ -7341,7 +7304,7 @@ r#"
  733 562 l 1
  253 82 l 2
  243 72 230 67 217 67 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowSE",
 r#"
 167 683 m 0
@@ -7359,7 +7322,7 @@ r#"
  662 167 l 1
  182 647 l 2
  172 657 167 670 167 683 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("niTok_arrowSW",
 r#"
 783 733 m 0
@@ -7377,7 +7340,7 @@ r#"
  267 238 l 1
  747 718 l 2
  757 728 770 733 783 733 c 0"#
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new("aTok_VAR01",
 r#"
 634 773 m 2
@@ -8668,3 +8631,56 @@ r#"
  888 754 897 752 905 747 c 0"#
 ),
 ];
+
+//MARK: SS03
+/// Simplified forms, which `'ss03'` substitutes for the base glyphs they're named after
+pub static BASE_SS03: [GlyphDescriptor; 2] = [
+GlyphDescriptor::new("tokiTok_SS03",
+r#"
+500 850 m 0
+ 528 850 550 828 550 800 c 2
+ 550 700 l 2
+ 550 672 528 650 500 650 c 0
+ 472 650 450 672 450 700 c 2
+ 450 800 l 2
+ 450 828 472 850 500 850 c 0
+175 275 m 0
+ 175 454 321 600 500 600 c 0
+ 679 600 825 454 825 275 c 0
+ 825 96 679 -50 500 -50 c 0
+ 321 -50 175 96 175 275 c 0
+500 500 m 0
+ 376 500 275 399 275 275 c 0
+ 275 151 376 50 500 50 c 0
+ 624 50 725 151 725 275 c 0
+ 725 399 624 500 500 500 c 0"#
+),
+GlyphDescriptor::new("namakoTok_SS03",
+r#"
+567 800 m 0
+ 595 800 617 778 617 750 c 2
+ 617 650 l 2
+ 617 622 595 600 567 600 c 0
+ 539 600 517 622 517 650 c 2
+ 517 750 l 2
+ 517 778 539 800 567 800 c 0
+213 0 m 0
+ 187 0 163 20 163 50 c 0
+ 163 65 169 80 181 89 c 0
+ 273 164 316 207 339 253 c 0
+ 361 298 367 350 367 450 c 0
+ 367 478 389 500 417 500 c 0
+ 445 500 467 478 467 450 c 0
+ 467 350 463 277 429 209 c 0
+ 411 173 386 141 353 107 c 1
+ 476 121 549 153 592 195 c 0
+ 649 250 667 331 667 450 c 0
+ 667 478 689 500 717 500 c 0
+ 745 500 767 478 767 450 c 0
+ 767 326 749 208 661 123 c 0
+ 574 39 432 0 213 0 c 0"#
+),
+];
+
+/// The ring of `ijo`, which `sike` and `tenpo` are drawn inside
+static IJO: [Component; 1] = [Component::new("ijoTok", Transform::identity())];
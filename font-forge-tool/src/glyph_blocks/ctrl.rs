@@ -1,7 +1,7 @@
-use crate::GlyphDescriptor;
+use crate::{ContainerDirection, GlyphDescriptor, TAG_EXTENSION, ITAN_GLYPH, LEPEKA_GLYPH, LIPAMANKA_GLYPH, LINKU_GLYPH};
 
 //MARK: NON COMBO
-pub const TOK_CTRL: [GlyphDescriptor; 16] = [
+pub static TOK_CTRL: [GlyphDescriptor; 16] = [
 GlyphDescriptor::new_with_width("startCart", 500,
 r#"
 110 250 m 2
@@ -30,23 +30,7 @@ r#"
  -28 -200 -50 -178 -50 -150 c 0
  -50 -122 -28 -100 0 -100 c 0"#
 ),
-GlyphDescriptor::new("combCartExt",
-r#"
--1050 -150 m 0
- -1050 -122 -1028 -100 -1000 -100 c 2
- 0 -100 l 2
- 28 -100 50 -122 50 -150 c 0
- 50 -178 28 -200 0 -200 c 2
- -1000 -200 l 2
- -1028 -200 -1050 -178 -1050 -150 c 0
--1050 950 m 0
- -1050 978 -1028 1000 -1000 1000 c 2
- 0 1000 l 2
- 28 1000 50 978 50 950 c 0
- 50 922 28 900 0 900 c 2
- -1000 900 l 2
- -1028 900 -1050 922 -1050 950 c 0"#
-),
+GlyphDescriptor::new("combCartExt", "").with_tags(&[TAG_EXTENSION]),
 GlyphDescriptor::new_with_width("startLongPi", 1000,
 r#"
 500 900 m 0
@@ -60,16 +44,7 @@ r#"
  450 850 l 2
  450 878 472 900 500 900 c 0"#
 ),
-GlyphDescriptor::new("combLongPiExt",
-r#"
--1050 -150 m 0
- -1050 -122 -1028 -100 -1000 -100 c 2
- 0 -100 l 2
- 28 -100 50 -122 50 -150 c 0
- 50 -178 28 -200 0 -200 c 2
- -1000 -200 l 2
- -1028 -200 -1050 -178 -1050 -150 c 0"#
-),
+GlyphDescriptor::new("combLongPiExt", "").with_tags(&[TAG_EXTENSION]),
 GlyphDescriptor::new("joinStack", ""),
 GlyphDescriptor::new("joinScale", ""),
 GlyphDescriptor::new("startLongGlyph",
@@ -88,16 +63,7 @@ r#"
  28 -100 50 -122 50 -150 c 0
  50 -178 28 -200 0 -200 c 0"#
 ),
-GlyphDescriptor::new("combLongGlyphExt",
-r#"
--1050 -150 m 0
- -1050 -122 -1028 -100 -1000 -100 c 2
- 0 -100 l 2
- 28 -100 50 -122 50 -150 c 0
- 50 -178 28 -200 0 -200 c 2
- -1000 -200 l 2
- -1028 -200 -1050 -178 -1050 -150 c 0"#
-),
+GlyphDescriptor::new("combLongGlyphExt", "").with_tags(&[TAG_EXTENSION]),
 GlyphDescriptor::new("startRevLongGlyph",
 r#"
 0 -200 m 0
@@ -186,7 +152,7 @@ r#"
 ),
 ];
 
-pub const START_LONG_GLYPH: [GlyphDescriptor; 19] = [
+pub static START_LONG_GLYPH: [GlyphDescriptor; 19] = [
 GlyphDescriptor::new("aTok",
 r#"
 550 750 m 2
@@ -418,7 +384,7 @@ r#"
  520 -2 563 158 563 300 c 4
  563 478 488 657 369 704 c 4
  349 712 337 730 337 750 c 4"#,
-),
+).with_container(ContainerDirection::Reverse),
 GlyphDescriptor::new("lonTok",
 r#"
 50 -150 m 0
@@ -764,7 +730,7 @@ r#"
 ),
 ];
 
-pub const LATN: [GlyphDescriptor; 95] = [
+pub static LATN: [GlyphDescriptor; 95] = [
 GlyphDescriptor::new("space",
 r#""#
 ),
@@ -2881,7 +2847,7 @@ r#"
 ),
 ];
 
-pub const TOK_NO_COMB: [GlyphDescriptor; 10] = [
+pub static TOK_NO_COMB: [GlyphDescriptor; 10] = [
 GlyphDescriptor::new("middleDot",
 r#"
 600 400 m 0
@@ -3004,7 +2970,7 @@ r#"
  320 260 342 238 342 210 c 2
  342 32 l 1
  390 11 444 0 500 0 c 0"#,
-),
+).with_provenance(&ITAN_GLYPH),
 GlyphDescriptor::new("lipamanka",
 r#"
 709 371 m 0
@@ -3134,7 +3100,7 @@ r#"
  166 61 171 51 177 46 c 0
  182 41 195 36 221 33 c 1
  221 139 l 1"#
-),
+).with_provenance(&LIPAMANKA_GLYPH),
 GlyphDescriptor::new("lepeka",
 r#"
 685 688 m 0
@@ -3275,7 +3241,7 @@ r#"
  238 225 355 108 500 108 c 0
  645 108 762 225 762 370 c 0
  762 515 645 632 500 632 c 0"#
-),
+).with_provenance(&LEPEKA_GLYPH),
 GlyphDescriptor::new("Seka",
 r#"
 789 752 m 6
@@ -3366,7 +3332,7 @@ r#"
  750 700 l 5
  250 700 l 5
  250 386 l 5"#
-),
+).with_provenance(&LINKU_GLYPH),
 ];
 
 pub const RADICALS: &[GlyphDescriptor; 29] = &[
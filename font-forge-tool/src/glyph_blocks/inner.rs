@@ -1,7 +1,7 @@
-use crate::{GlyphDescriptor, Anchor, AnchorType};
+use crate::{GlyphDescriptor, Anchor, AnchorType, UCSUR_CHART};
 
 //MARK: INNER
-pub const INNER_COR: [GlyphDescriptor; 136] = [
+pub static INNER_COR: [GlyphDescriptor; 136] = [
 GlyphDescriptor::new_with_anchor("a", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -507 305 m 0
@@ -3697,7 +3697,7 @@ r#"
 ),
 ];
 
-pub const INNER_EXT: [GlyphDescriptor; 10] = [
+pub static INNER_EXT: [GlyphDescriptor; 10] = [
 GlyphDescriptor::new_with_anchor("pake", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -652 564 m 2
@@ -3712,7 +3712,7 @@ r#"
  -652 464 l 2
  -680 464 -702 486 -702 514 c 0
  -702 542 -680 564 -652 564 c 2"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("apeja", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -674 282 m 0
@@ -3758,7 +3758,7 @@ r#"
  -509 239 -505 235 -499 235 c 0
  -493 235 -489 239 -489 245 c 0
  -489 251 -493 255 -499 255 c 0"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("majuna", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -420 340 m 0
@@ -3800,7 +3800,7 @@ r#"
  -580 312 -602 290 -630 290 c 2
  -680 290 l 2
  -708 290 -730 312 -730 340 c 0"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("powe", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -733 350 m 0
@@ -3823,7 +3823,7 @@ r#"
  -418 482 -410 469 -410 453 c 0
  -410 426 -433 403 -460 403 c 0
  -476 403 -489 411 -500 422 c 1"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("linluwi", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -585 335 m 0
@@ -4048,7 +4048,7 @@ r#"
 ),
 ];
 
-pub const INNER_ALT: [GlyphDescriptor; 38] = [
+pub static INNER_ALT: [GlyphDescriptor; 38] = [
 GlyphDescriptor::new_with_anchor("jakiTok_VAR01", Anchor::new_scale(AnchorType::Mark, (-500, 400)),
 r#"
 -263 399 m 0
@@ -1,7 +1,7 @@
-use crate::{GlyphDescriptor, Anchor, AnchorType};
+use crate::{GlyphDescriptor, Anchor, AnchorType, TAG_DIRECTIONAL, UCSUR_CHART};
 
 //MARK: LOWER
-pub const LOWER_COR: [GlyphDescriptor; 137] = [
+pub static LOWER_COR: [GlyphDescriptor; 137] = [
 GlyphDescriptor::new_with_anchor("a", Anchor::new_stack(AnchorType::Base),
 r#"
 550 340 m 2
@@ -3902,7 +3902,7 @@ r#"
 ),
 ];
 
-pub const LOWER_EXT: [GlyphDescriptor; 10] = [
+pub static LOWER_EXT: [GlyphDescriptor; 10] = [
 GlyphDescriptor::new_with_anchor("pake", Anchor::new_stack(AnchorType::Base),
 r#"
 310 390 m 2
@@ -3917,7 +3917,7 @@ r#"
  310 290 l 2
  282 290 260 312 260 340 c 0
  260 368 282 390 310 390 c 2"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("apeja", Anchor::new_stack(AnchorType::Base),
 r#"
 500 30 m 0
@@ -3963,7 +3963,7 @@ r#"
  500 155 l 1
  353 57 l 2
  344 51 336 49 326 49 c 0"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("majuna", Anchor::new_stack(AnchorType::Base),
 r#"
 640 -40 m 0
@@ -4005,7 +4005,7 @@ r#"
  835 232 830 220 820 210 c 2
  678 67 l 2
  668 57 656 52 643 52 c 0"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("powe", Anchor::new_stack(AnchorType::Base),
 r#"
 645 150 m 0
@@ -4036,7 +4036,7 @@ r#"
  950 -68 928 -90 900 -90 c 2
  100 -90 l 2
  72 -90 50 -68 50 -40 c 0"#,
-),
+).with_provenance(&UCSUR_CHART),
 GlyphDescriptor::new_with_anchor("linluwi", Anchor::new_stack(AnchorType::Base),
 r#"
 470 -31 m 1
@@ -4273,7 +4273,7 @@ r#"
 ),
 ];
 
-pub const LOWER_ALT: [GlyphDescriptor; 38] = [
+pub static LOWER_ALT: [GlyphDescriptor; 38] = [
 GlyphDescriptor::new_with_anchor("jakiTok_VAR01", Anchor::new_stack(AnchorType::Base),
 r#"
 554 136 m 1
@@ -5079,7 +5079,7 @@ r#"
  260 165 266 177 274 185 c 2
  393 305 l 2
  403 315 416 320 429 320 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowN", Anchor::new_stack(AnchorType::Base),
 r#"
 670 221 m 0
@@ -5099,7 +5099,7 @@ r#"
  515 390 527 384 535 376 c 2
  655 257 l 2
  665 247 670 234 670 221 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowE", Anchor::new_stack(AnchorType::Base),
 r#"
 571 -20 m 0
@@ -5119,7 +5119,7 @@ r#"
  740 135 734 123 726 115 c 2
  607 -5 l 2
  597 -15 584 -20 571 -20 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowS", Anchor::new_stack(AnchorType::Base),
 r#"
 330 79 m 0
@@ -5139,7 +5139,7 @@ r#"
  485 -90 473 -84 465 -76 c 2
  345 43 l 2
  335 53 330 66 330 79 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowNW", Anchor::new_stack(AnchorType::Base),
 r#"
 684 16 m 0
@@ -5157,7 +5157,7 @@ r#"
  486 235 l 1
  670 51 l 2
  680 41 684 29 684 16 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowNE", Anchor::new_stack(AnchorType::Base),
 r#"
 366 -34 m 0
@@ -5175,7 +5175,7 @@ r#"
  585 164 l 1
  401 -20 l 2
  391 -30 379 -34 366 -34 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowSE", Anchor::new_stack(AnchorType::Base),
 r#"
 316 284 m 0
@@ -5193,7 +5193,7 @@ r#"
  514 65 l 1
  330 249 l 2
  320 259 316 271 316 284 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 GlyphDescriptor::new_with_anchor("niTok_arrowSW", Anchor::new_stack(AnchorType::Base),
 r#"
 634 334 m 0
@@ -5211,7 +5211,7 @@ r#"
  415 136 l 1
  599 320 l 2
  609 330 621 334 634 334 c 0"#,
-),
+).with_tags(&[TAG_DIRECTIONAL]),
 // GlyphDescriptor::new("aTok_VAR01", ""),
 // GlyphDescriptor::new("aTok_VAR02", ""),
 // GlyphDescriptor::new("aTok_VAR03", ""),
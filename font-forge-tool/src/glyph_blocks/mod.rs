@@ -1,17 +1,30 @@
 #![cfg_attr(rustfmt, rustfmt_skip)]
 
+use crate::Provenance;
+
 pub mod ctrl;
 pub mod base;
 pub mod outer;
 pub mod inner;
 pub mod lower;
+pub mod synth;
+
+//MARK: PROVENANCE
+/// The nimi sin drawn after their forms in the UCSUR chart the font follows
+pub static UCSUR_CHART: Provenance = Provenance::based_on("the UCSUR sitelen pona chart");
+pub static ITAN_GLYPH: Provenance = Provenance::based_on("jan Itan's personal glyph");
+pub static LEPEKA_GLYPH: Provenance = Provenance::based_on("jan Lepeka's personal glyph");
+pub static LIPAMANKA_GLYPH: Provenance = Provenance::based_on("lipamanka's personal glyph");
+pub static LINKU_GLYPH: Provenance = Provenance::based_on("the Linku name glyph");
 
 //MARK: HEADERS
 pub const HEADER: &str = r#"SplineFontDB: 3.2
-FontName: nasin-nanpa
-FullName: nasin-nanpa
-FamilyName: nasin-nanpa
-Weight: Regular
+"#;
+
+/// The font's name, which builds of a part of it add to
+pub const FAMILY: &str = "nasin-nanpa";
+
+pub const STYLE: &str = r#"Weight: Regular
 Copyright: jan Itan li mama. jan mute a li pona e pali ona.
 "#;
 
@@ -69,101 +82,14 @@ OS2StrikeYPos: 258
 OS2CapHeight: 1000
 OS2XHeight: 500
 OS2Vendor: 'XXXX'
-OS2CodePages: 00000001.00000000
-OS2UnicodeRanges: 0000000f.00000000.00000000.00000000
-"#;
-
-pub const LOOKUPS: &str = r#"Lookup: 4 0 0 "'liga' SPACE" { "'liga' SPACE"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 4 0 0 "'liga' WORDS" { "'liga' WORD"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 3 0 0 "'rand' RAND VARIATIONS" { "'rand' RAND VARIATIONS"  } ['rand' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 4 0 0 "'liga' VARIATIONS" { "'liga' VAR"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 5 0 0 "'calt' REMOVE SPACE" { "'calt' REMOVE SPACE"  } ['calt' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 1 0 0 "'ss00' SP TO ZWSP" { "'ss00' SP TO ZWSP"  } ['ss00' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 4 0 0 "'liga' START CONTAINER" { "'liga' START CONTAINER"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 5 0 0 "'calt' CHANGE ZWJ" { "'calt' CHANGE ZWJ"  } ['calt' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 1 0 0 "'ss01' ZWJ TO SCALE" { "'ss01' ZWJ TO SCALE"  } ['ss01' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 1 0 0 "'ss02' ZWJ TO STACK" { "'ss02' ZWJ TO STACK"  } ['ss02' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 4 0 0 "'liga' GLYPH THEN JOINER" { "'liga' GLYPH THEN JOINER"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 2 0 0 "'ccmp' RESPAWN JOINER" { "'ccmp' RESPAWN JOINER"  } ['ccmp' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 4 0 0 "'liga' JOINER THEN GLYPH" { "'liga' JOINER THEN GLYPH"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 6 0 0 "'calt' CART AND CONT" { "'calt' CART AND CONT"  } ['calt' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 2 2 0 "'cc01' CART" { "'cc01' CART"  } ['cc01' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 2 2 0 "'cc02' CONT" { "'cc02' CONT"  } ['cc02' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 4 0 0 "'liga' CC CLEANUP" { "'liga' CC CLEANUP"  } ['liga' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-Lookup: 260 0 0 "'mark' POSITION COMBO" { "'mark' STACK"  "'mark' SCALE"  } ['mark' ('DFLT' <'dflt' 'latn' > 'latn' <'dflt' > ) ]
-MarkAttachClasses: 1
-"#;
-
-pub const AFTER_SPACE_CALT: &str = r#" 2 0 0
-  ClsList: 2 1
-  BClsList:
-  FClsList:
- 1
-  SeqLookup: 1 "'ss00' SP TO ZWSP"
-  ClassNames: "All_Others" "sp" "tok"
-  BClassNames: "All_Others" "sp" "tok"
-  FClassNames: "All_Others" "sp" "tok"
-EndFPST
 "#;
 
-pub const AFTER_ZWJ_CALT: &str = r#" 2 0 0
-  ClsList: 2 1
-  BClsList:
-  FClsList:
- 1
-  SeqLookup: 1 "'ss01' ZWJ TO SCALE"
- 2 0 0
-  ClsList: 3 1
-  BClsList:
-  FClsList:
- 1
-  SeqLookup: 1 "'ss02' ZWJ TO STACK"
-  ClassNames: "other" "zwj" "scale" "stack"
-  BClassNames: "other" "zwj" "scale" "stack"
-  FClassNames: "other" "zwj" "scale" "stack"
-EndFPST
-"#;
-
-pub const AFTER_CHAIN_CALT: &str = r#" 1 1 0
-  ClsList: 1
-  BClsList: 2
-  FClsList:
- 1
-  SeqLookup: 0 "'cc01' CART"
- 1 1 0
-  ClsList: 1
-  BClsList: 3
-  FClsList:
- 1
-  SeqLookup: 0 "'cc02' CONT"
-  ClassNames: "other" "base" "cart" "cont"
-  BClassNames: "other" "base" "cart" "cont"
-  FClassNames: "other" "base" "cart" "cont"
-EndFPST
-LangName: 1033 "" "" "" "" "" ""#;
-
-pub const OTHER: &str = r#"" "" "+ACIA-jan Itan 2023+ACIA" "+ACIAIgAA" "+ACIA-jan Itan+ACIA" "+ACIAIgAA" "+ACIAIgAA" "+ACIA-https://etbcor.com/+ACIA" "+ACIA-MIT License+ACIA" "+ACIA-https://opensource.org/licenses/MIT+ACIA" "" "nasin-nanpa" "Regular"
-Encoding: Custom
+pub const OTHER: &str = r#"Encoding: Custom
 UnicodeInterp: none
 NameList: AGL For New Fonts
 DisplaySize: -48
 AntiAlias: 1
 FitToEm: 1
 WinInfo: 32 16 8
-BeginPrivate: 12
-BlueValues 22 [-2 1 414 417 796 797]
-OtherBlues 11 [-385 -384]
-BlueFuzz 1 1
-BlueScale 8 0.039625
-BlueShift 1 7
-StdHW 5 [100]
-StdVW 5 [100]
-StemSnapH 5 [100]
-StemSnapV 5 [100]
-ForceBold 5 false
-LanguageGroup 1 0
-ExpansionFactor 4 0.06
-EndPrivate
-AnchorClass2: "stack" "'mark' STACK" "scale" "'mark' SCALE"
 "#;
 
@@ -1,7 +1,8 @@
 use crate::{GlyphDescriptor, Anchor, AnchorType};
 
 //MARK: OUTER
-pub const OUTER_COR: [GlyphDescriptor; 63] = [
+// a glyph added without a scale anchor gets one in the middle of its empty space (see `interior`)
+pub static OUTER_COR: [GlyphDescriptor; 63] = [
 GlyphDescriptor::new_with_anchor("akesi", Anchor::new_scale(AnchorType::Base, (500, 310)),
 r#"
 675 740 m 0
@@ -1783,10 +1784,10 @@ r#"
 ),
 ];
 
-pub const OUTER_EXT: [GlyphDescriptor; 0] = [
+pub static OUTER_EXT: [GlyphDescriptor; 0] = [
 ];
 
-pub const OUTER_ALT: [GlyphDescriptor; 16] = [
+pub static OUTER_ALT: [GlyphDescriptor; 16] = [
 GlyphDescriptor::new_with_anchor("koTok_VAR01", Anchor::new_scale(AnchorType::Base, (500, 430)),
 r#"
 71 693 m 0
@@ -0,0 +1,229 @@
+use crate::{spline::*, ContainerDirection, EncPos, GlyphEnc, Rep};
+
+/// How many tick glyphs there are on each side of a cartouche
+/// (ticks `1..=CART_TICK_COUNT` go below it, the rest go above it)
+pub const CART_TICK_COUNT: usize = 4;
+
+/// The distance between the centers of neighboring ticks
+pub const CART_TICK_SPACING: f64 = 200.0;
+
+/// Which side of the cartouche a tick mark goes on
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TickSide {
+    Bottom,
+    Top,
+}
+
+impl TickSide {
+    /// The top and bottom edges of ticks on this side
+    fn extent(&self) -> (f64, f64) {
+        match self {
+            TickSide::Bottom => (-100.0, -300.0),
+            TickSide::Top => (1100.0, 900.0),
+        }
+    }
+
+    /// The number of the first tick glyph on this side
+    fn first(&self) -> usize {
+        match self {
+            TickSide::Bottom => 1,
+            TickSide::Top => CART_TICK_COUNT + 1,
+        }
+    }
+
+    /// The latin character that's typed to get a tick on this side
+    fn latin(&self) -> &'static str {
+        match self {
+            TickSide::Bottom => "comma",
+            TickSide::Top => "quotesingle",
+        }
+    }
+}
+
+/// The name of the `n`th tick glyph
+pub fn cart_tick_name(n: usize) -> String {
+    format!("combCartExt{n}TickTok")
+}
+
+/// A single rounded tick, 100 units wide, centered on `x`
+fn cart_tick(x: f64, top: f64, bottom: f64) -> Contour {
+    Contour::new(Point::new(x, top), 0)
+        .curve_to(
+            Point::new(x + 28.0, top),
+            Point::new(x + 50.0, top - 22.0),
+            Point::new(x + 50.0, top - 50.0),
+            2,
+        )
+        .line_to(Point::new(x + 50.0, bottom + 50.0), 2)
+        .curve_to(
+            Point::new(x + 50.0, bottom + 22.0),
+            Point::new(x + 28.0, bottom),
+            Point::new(x, bottom),
+            0,
+        )
+        .curve_to(
+            Point::new(x - 28.0, bottom),
+            Point::new(x - 50.0, bottom + 22.0),
+            Point::new(x - 50.0, bottom + 50.0),
+            2,
+        )
+        .line_to(Point::new(x - 50.0, top - 50.0), 2)
+        .curve_to(
+            Point::new(x - 50.0, top - 22.0),
+            Point::new(x - 28.0, top),
+            Point::new(x, top),
+            0,
+        )
+}
+
+/// Generates `count` ticks, `spacing` apart, centered on the glyph before them (which ends at x = 0)
+pub fn cart_ticks(count: usize, spacing: f64, side: TickSide) -> SplineSet {
+    let (top, bottom) = side.extent();
+    let middle = (count as f64 - 1.0) / 2.0;
+    SplineSet::new(
+        (0..count)
+            .map(|i| cart_tick(-500.0 + spacing * (middle - i as f64), top, bottom))
+            .collect(),
+    )
+}
+
+/// Generates every tick glyph for one side of the cartouche, along with the latin ligature for each
+pub fn cart_tick_glyphs(side: TickSide) -> Vec<(GlyphEnc, String)> {
+    (1..=CART_TICK_COUNT)
+        .map(|count| {
+            let glyph = GlyphEnc::new_from_parts(
+                EncPos::None,
+                cart_tick_name(side.first() + count - 1),
+                0,
+                Rep::new(cart_ticks(count, CART_TICK_SPACING, side).gen(), vec![]),
+            );
+            let lig = if count == 1 {
+                format!("combCartExtTok {}", side.latin())
+            } else {
+                vec![side.latin(); count].join(" ")
+            };
+            (glyph, lig)
+        })
+        .collect()
+}
+
+/// The advance of a sitelen pona, which a full extension runs under
+pub const EXT_LENGTH: f64 = 1000.0;
+
+/// The advance of a half-width glyph (like a space), which a half extension runs under
+pub const EXT_HALF_LENGTH: f64 = EXT_LENGTH / 2.0;
+
+/// The bars that extend a container under (and over) the glyphs in it
+pub struct ExtBars {
+    /// How thick each bar is (its ends are rounded, so they stick out by half of this)
+    pub thickness: f64,
+    /// The top edge of the bar below the glyphs
+    pub bottom: f64,
+    /// The bottom edge of the bar above the glyphs, if there is one
+    pub top: Option<f64>,
+}
+
+/// The bars of a cartouche, below and above the glyphs
+pub const CART_BARS: ExtBars = ExtBars {
+    thickness: 100.0,
+    bottom: -100.0,
+    top: Some(900.0),
+};
+
+/// The bar of a long glyph, below the glyphs
+pub const LONG_GLYPH_BARS: ExtBars = ExtBars {
+    thickness: 100.0,
+    bottom: -100.0,
+    top: None,
+};
+
+/// The bar of a `pi` phrase underlined with `'ss04'`, thinner than a long glyph's (see `underline`)
+pub const UNDERLINE_BARS: ExtBars = ExtBars {
+    thickness: 60.0,
+    bottom: -120.0,
+    top: None,
+};
+
+/// How far above the underline its hooks reach
+const UNDERLINE_HOOK_TOP: f64 = -40.0;
+
+/// A horizontal bar from `left` to `right` (including its rounded ends), between `top` and `bottom`
+fn rounded_bar(left: f64, right: f64, top: f64, bottom: f64) -> Contour {
+    let radius = (top - bottom) / 2.0;
+    let middle = bottom + radius;
+    // the same handle length as the hand-drawn rounded ends (28 for a radius of 50)
+    let handle = radius * 28.0 / 50.0;
+    Contour::new(Point::new(left, middle), 0)
+        .curve_to(
+            Point::new(left, middle + handle),
+            Point::new(left + radius - handle, top),
+            Point::new(left + radius, top),
+            2,
+        )
+        .line_to(Point::new(right - radius, top), 2)
+        .curve_to(
+            Point::new(right - radius + handle, top),
+            Point::new(right, middle + handle),
+            Point::new(right, middle),
+            0,
+        )
+        .curve_to(
+            Point::new(right, middle - handle),
+            Point::new(right - radius + handle, bottom),
+            Point::new(right - radius, bottom),
+            2,
+        )
+        .line_to(Point::new(left + radius, bottom), 2)
+        .curve_to(
+            Point::new(left + radius - handle, bottom),
+            Point::new(left, middle - handle),
+            Point::new(left, middle),
+            0,
+        )
+}
+
+/// An extension glyph: `bars` running under (and over) the `length` units before it
+pub fn ext_bars(bars: &ExtBars, length: f64) -> SplineSet {
+    let radius = bars.thickness / 2.0;
+    let bar = |top: f64, bottom: f64| rounded_bar(-length - radius, radius, top, bottom);
+    let mut contours = vec![bar(bars.bottom, bars.bottom - bars.thickness)];
+    if let Some(top) = bars.top {
+        contours.push(bar(top + bars.thickness, top));
+    }
+    SplineSet::new(contours)
+}
+
+/// The start of a long glyph's extension line: a rounded bar under the glyph, from its middle to
+/// where `combLongGlyphExtTok` continues it, or the other way for reverse long glyphs
+pub fn long_glyph_start_bar(direction: ContainerDirection) -> SplineSet {
+    let bars = LONG_GLYPH_BARS;
+    let radius = bars.thickness / 2.0;
+    let middle = EXT_LENGTH / 2.0;
+    let (left, right) = match direction {
+        ContainerDirection::Forward => (middle - radius, EXT_LENGTH + radius),
+        ContainerDirection::Reverse => (-radius, middle + radius),
+        ContainerDirection::Both => (-radius, EXT_LENGTH + radius),
+    };
+    SplineSet::new(vec![rounded_bar(
+        left,
+        right,
+        bars.bottom,
+        bars.bottom - bars.thickness,
+    )])
+}
+
+/// A hook at one end of an underline: a vertical bar centered on `x`, from the bottom of the
+/// underline up towards the glyphs
+pub fn underline_hook(x: f64) -> SplineSet {
+    let bars = UNDERLINE_BARS;
+    let radius = bars.thickness / 2.0;
+    let bottom = bars.bottom - bars.thickness;
+    // drawn lying down, then turned a quarter turn counter-clockwise (which keeps its direction)
+    SplineSet::new(vec![rounded_bar(
+        bottom,
+        UNDERLINE_HOOK_TOP,
+        -x + radius,
+        -x - radius,
+    )])
+    .mapped(|p| Point::new(-p.y, p.x))
+}
@@ -0,0 +1,68 @@
+//! A list of the glyphs in a generated font, for docs and for checking against other word lists
+
+use std::io;
+
+use crate::{
+    cli::ListFormat,
+    manifest::{Manifest, ManifestGlyph},
+    BlockFilter,
+};
+
+/// The glyphs of `manifest` in blocks matching any of `blocks` (or every glyph, if there are none)
+pub fn select<'a>(manifest: &'a Manifest, blocks: &[String]) -> Vec<&'a ManifestGlyph> {
+    manifest
+        .glyphs
+        .iter()
+        .filter(|glyph| {
+            blocks.is_empty()
+                || blocks
+                    .iter()
+                    .any(|block| BlockFilter::matches(block, &glyph.block))
+        })
+        .collect()
+}
+
+/// Quotes a CSV field if it needs to be
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn print(glyphs: &[&ManifestGlyph], format: ListFormat) -> io::Result<()> {
+    match format {
+        ListFormat::Json => {
+            serde_json::to_writer_pretty(io::stdout().lock(), glyphs)?;
+            println!();
+        }
+        ListFormat::Csv => {
+            println!("name,codepoint,width,block,lookups");
+            for glyph in glyphs {
+                println!(
+                    "{},{},{},{},{}",
+                    csv_field(&glyph.name),
+                    glyph.codepoint.as_deref().unwrap_or_default(),
+                    glyph.width,
+                    csv_field(&glyph.block),
+                    csv_field(&glyph.lookups.join("; ")),
+                );
+            }
+        }
+        ListFormat::Table => {
+            for glyph in glyphs {
+                println!(
+                    "{:<40} {:<8} {:>5}  {:<16} {}",
+                    glyph.name,
+                    glyph.codepoint.as_deref().unwrap_or("-"),
+                    glyph.width,
+                    glyph.block,
+                    glyph.lookups.join(", "),
+                );
+            }
+            println!("{} glyphs", glyphs.len());
+        }
+    }
+    Ok(())
+}
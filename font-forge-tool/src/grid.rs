@@ -0,0 +1,190 @@
+//! The grid the glyphs are drawn on: their points line up with a few guides, across and up, and a
+//! point a unit or two off one (as outlines drawn by hand, or traced, often are) is almost always
+//! meant to be on it. They're reported, and moved onto the guides with `--snap-to-grid`. Only the
+//! glyphs drawn on the whole em are on the grid: the others (the numbers, the stacked and scaled
+//! forms, and so on) are built from them, or drawn to guides of their own.
+
+use std::io;
+
+use serde::Serialize;
+
+use crate::{
+    cli::ReportFormat,
+    ffir::TAG_PADDING,
+    spline::{gen_coord, Contour, Point, Segment, SplineSet},
+    BlockFilter, Font,
+};
+
+/// The guides, which are the same across and up, on the drawn em
+const GUIDES: [f64; 5] = [0.0, 100.0, 500.0, 900.0, 1000.0];
+
+/// The blocks drawn on the grid (block names, or `_`-separated parts of them)
+const DRAWN_ON_GRID: [&str; 3] = ["base", "no_comb", "pack"];
+
+/// The furthest a point can be from a guide and still be taken to be meant to be on it
+const MAX_OFF: f64 = 2.0;
+
+/// Whether the glyphs of the block `name` are drawn on the grid
+pub fn is_drawn_on_grid(name: &str) -> bool {
+    DRAWN_ON_GRID
+        .iter()
+        .any(|filter| BlockFilter::matches(filter, name))
+}
+
+/// The guide `c` is off (but not on), at `scale` times the drawn em
+fn guide_near(c: f64, scale: f64) -> Option<f64> {
+    GUIDES.iter().map(|guide| guide * scale).find(|guide| {
+        let off = (c - guide).abs();
+        off > 1e-6 && off <= MAX_OFF * scale
+    })
+}
+
+/// How far `p` moves to snap to the guides, at `scale` times the drawn em
+fn snap_offset(p: Point, scale: f64) -> Point {
+    Point::new(
+        guide_near(p.x, scale).map_or(0.0, |guide| guide - p.x),
+        guide_near(p.y, scale).map_or(0.0, |guide| guide - p.y),
+    )
+}
+
+/// The points of `contour` (not its control points)
+fn points(contour: &Contour) -> impl Iterator<Item = Point> + '_ {
+    std::iter::once(contour.start).chain(contour.segments.iter().map(
+        |(segment, _)| match segment {
+            Segment::Line { to } | Segment::Curve { to, .. } => *to,
+        },
+    ))
+}
+
+/// `contour` with its points that are off the guides snapped to them, and the control points next
+/// to them moved along, so the curves leave them the same way
+fn snap_contour(contour: &Contour) -> Contour {
+    let offsets = points(contour)
+        .map(|p| snap_offset(p, 1.0))
+        .collect::<Vec<_>>();
+    let moved = |p: Point, offset: Point| Point::new(p.x + offset.x, p.y + offset.y);
+    let mut snapped = Contour::new(moved(contour.start, offsets[0]), contour.start_flags);
+    for (i, &(segment, flags)) in contour.segments.iter().enumerate() {
+        let (before, after) = (offsets[i], offsets[i + 1]);
+        snapped = match segment {
+            Segment::Line { to } => snapped.line_to(moved(to, after), flags),
+            Segment::Curve { c1, c2, to } => {
+                snapped.curve_to(moved(c1, before), moved(c2, after), moved(to, after), flags)
+            }
+        };
+    }
+    snapped
+}
+
+/// `spline_set` (on the drawn em) with its points that are off the guides snapped to them
+pub fn snap(spline_set: &SplineSet) -> SplineSet {
+    SplineSet::new(spline_set.contours.iter().map(snap_contour).collect())
+}
+
+#[derive(Serialize)]
+pub struct Finding {
+    name: String,
+    block: String,
+    /// The point that's off a guide
+    x: f64,
+    y: f64,
+    /// Whether it's off across (`x`) or up (`y`)
+    axis: &'static str,
+    guide: f64,
+}
+
+#[derive(Serialize)]
+pub struct GridReport {
+    /// How many glyphs' outlines were checked
+    glyphs: usize,
+    findings: Vec<Finding>,
+}
+
+/// Checks the points of the outlines drawn in `font` on the grid (in blocks matching any of
+/// `blocks`, or in every block if there are none) against the guides
+pub fn report(font: &Font, blocks: &[String]) -> Result<GridReport, String> {
+    let scale = font.metrics.scale();
+    let mut report = GridReport {
+        glyphs: 0,
+        findings: vec![],
+    };
+    for block in &font.blocks {
+        if !is_drawn_on_grid(&block.name)
+            || !blocks.is_empty() && !blocks.iter().any(|b| BlockFilter::matches(b, &block.name))
+        {
+            continue;
+        }
+        for (name, glyph) in block.full_names() {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())
+                .map_err(|e| format!("{name}: {e}"))?;
+            if spline_set.contours.is_empty() {
+                continue;
+            }
+            report.glyphs += 1;
+            for contour in &spline_set.contours {
+                // the last point of a contour is usually where it starts, so it's skipped if it's
+                // been checked
+                let mut seen = vec![];
+                for p in points(contour) {
+                    if seen.contains(&p) {
+                        continue;
+                    }
+                    seen.push(p);
+                    let off = [("x", p.x), ("y", p.y)]
+                        .into_iter()
+                        .filter_map(|(axis, c)| Some((axis, guide_near(c, scale)?)));
+                    for (axis, guide) in off {
+                        report.findings.push(Finding {
+                            name: name.to_string(),
+                            block: block.name.clone(),
+                            x: p.x,
+                            y: p.y,
+                            axis,
+                            guide,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+impl GridReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} points off the guides, in the outlines of {} glyphs",
+                    self.findings.len(),
+                    self.glyphs
+                );
+                for finding in &self.findings {
+                    let at = if finding.axis == "x" {
+                        finding.x
+                    } else {
+                        finding.y
+                    };
+                    println!(
+                        "{:<12} {} ({} {})  {} = {}, not {}",
+                        finding.block,
+                        finding.name,
+                        gen_coord(finding.x),
+                        gen_coord(finding.y),
+                        finding.axis,
+                        gen_coord(at),
+                        gen_coord(finding.guide)
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+//! Half-width forms of the sitelen pona punctuation (the middle dot and the colon), which take their
+//! place after a sitelen pona glyph, so running text isn't spaced out by a full em after every
+//! sentence
+
+use crate::{
+    block_kind::BlockKind,
+    contextual::{ContextRule, ContextualLookupBuilder},
+    em::Metrics,
+    features::*,
+    ffir::*,
+    glyph_blocks::ctrl::TOK_NO_COMB,
+    query::GlyphQuery,
+    spline::SplineSet,
+};
+
+/// The punctuation (in `TOK_NO_COMB`) that has a half-width form, and the name of that form
+const PUNCTUATION: [(&str, &str); 2] = [("middleDot", "halfMiddleDot"), ("colon", "halfColon")];
+
+/// The blocks of the glyphs the punctuation is half-width after
+const AFTER_BLOCKS: [&str; 6] = ["base_", "pack_", "outer_", "inner_", "lower_", "upper_"];
+
+/// The glyphs (besides the ones in `AFTER_BLOCKS`) the punctuation is half-width after
+const AFTER_GLYPHS: &str = "endCartTok endLongGlyphTok endRevLongGlyphTok endCartAltTok";
+
+/// The block of the half-width punctuation, `metrics.half_width` wide, with each glyph's ink
+/// centered in it like the full-width glyph's is in its em
+pub fn block(metrics: &Metrics) -> Result<GlyphBlock, String> {
+    let shift = (metrics.half_width as f64 - 1000.0) / 2.0;
+    let glyphs = PUNCTUATION
+        .iter()
+        .map(|(full, half)| {
+            let descriptor = TOK_NO_COMB
+                .iter()
+                .find(|descriptor| descriptor.name == *full)
+                .ok_or_else(|| format!("there's no {full} to make a half-width form of"))?;
+            let spline_set = SplineSet::parse(&descriptor.outlines()?)?.translated(shift, 0.0);
+            Ok(GlyphBasic::new(
+                *half,
+                metrics.half_width,
+                Rep::new(spline_set.gen(), vec![]),
+                None,
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(GlyphBlock::new_from_basic_glyphs(
+        glyphs,
+        LookupsMode::None,
+        Cc::Half,
+        "",
+        "Tok",
+        BlockKind::NoCombination.color(),
+        EncPos::None,
+    )
+    .named("half_width")
+    .padded(Padding::None))
+}
+
+/// Whether the half-width punctuation is in `blocks`
+fn generated(blocks: &[GlyphBlock]) -> bool {
+    blocks.iter().any(|block| block.name == "half_width")
+}
+
+/// Declares the lookup that turns the punctuation into its half-width form, if it's in `blocks`
+pub fn declare(blocks: &[GlyphBlock], features: &mut FeatureRegistry) {
+    if !generated(blocks) {
+        return;
+    }
+    features
+        .add_after(SS00_SP_TO_ZWSP, CALT_HALF_WIDTH, LookupType::Context, 0)
+        .add_after(CALT_HALF_WIDTH, SS00_PUNCT_TO_HALF, LookupType::Single, 0);
+    for (full, half) in PUNCTUATION {
+        features.declare(
+            &format!("{full}Tok"),
+            SS00_PUNCT_TO_HALF,
+            format!("{half}Tok"),
+        );
+    }
+}
+
+/// Generates the contextual subtable that picks the half-width punctuation after a sitelen pona
+/// glyph (or the end of a cartouche or long glyph), if it's in `blocks`
+pub fn gen_fpst(
+    features: &FeatureRegistry,
+    blocks: &[GlyphBlock],
+    put_in_class: impl Fn(String) -> String,
+) -> String {
+    if !generated(blocks) {
+        return String::new();
+    }
+    let after = GlyphQuery::blocks_starting_with(&AFTER_BLOCKS)
+        .names(blocks)
+        .join(" ");
+    let punctuation = PUNCTUATION
+        .iter()
+        .map(|(full, _)| format!("{full}Tok"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut builder = ContextualLookupBuilder::new(features, CALT_HALF_WIDTH, "All_Others");
+    let tok = builder.class("tok", format!("{AFTER_GLYPHS} {after}"));
+    let punct = builder.class("punct", punctuation);
+    builder.rule(ContextRule::new([tok, punct]).apply(1, SS00_PUNCT_TO_HALF));
+    builder.build(put_in_class)
+}
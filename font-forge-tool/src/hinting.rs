@@ -0,0 +1,204 @@
+//! The hinting values of the PostScript private dictionary: the standard stem widths and the
+//! widths stems snap to are measured from the outlines each build (rather than written down once
+//! and left to go stale when the strokes change), and the alignment zones are scaled to the em
+
+use std::{collections::BTreeMap, io};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    cli::ReportFormat,
+    em::Metrics,
+    spline::{spans_at, Point, SplineSet},
+    Font,
+};
+
+/// The entries of the private dictionary before the stems (the alignment zones), on the drawn em
+const ZONES: &str = r#"BlueValues 22 [-2 1 414 417 796 797]
+OtherBlues 11 [-385 -384]
+BlueFuzz 1 1
+BlueScale 8 0.039625
+BlueShift 1 7
+"#;
+
+/// The entries of the private dictionary after the stems
+const REST: &str = r#"ForceBold 5 false
+LanguageGroup 1 0
+ExpansionFactor 4 0.06
+"#;
+
+/// The stroke the glyphs are drawn with (on the drawn em), for a font with no stems to measure
+const DEFAULT_STEM: f64 = 100.0;
+
+/// How far apart the lines the outlines are measured along are, on the drawn em
+const SCAN_STEP: f64 = 10.0;
+
+/// The most widths a `StemSnap` entry can have
+const MAX_SNAPS: usize = 12;
+
+/// How common a width has to be (compared to the most common one) to be snapped to
+const SNAP_SHARE: f64 = 0.25;
+
+/// How many of the most common widths the report lists
+const REPORTED_WIDTHS: usize = 8;
+
+/// The stems that go one way
+#[derive(Serialize)]
+pub struct Stems {
+    /// The most common width (`StdHW` or `StdVW`)
+    standard: usize,
+    /// The widths stems snap to (`StemSnapH` or `StemSnapV`), smallest first
+    snap: Vec<usize>,
+    /// The most common widths, with how many times each was measured
+    common: Vec<(usize, usize)>,
+}
+
+impl Stems {
+    /// Picks the widths from a histogram of them (`default` if it's empty)
+    fn from_histogram(histogram: BTreeMap<usize, usize>, default: usize) -> Self {
+        let mut common = histogram.into_iter().collect::<Vec<_>>();
+        // the most common first, and the narrowest of those that are as common
+        common.sort_by_key(|&(width, count)| (usize::MAX - count, width));
+        let standard = common.first().map_or(default, |&(width, _)| width);
+        let most = common.first().map_or(0, |&(_, count)| count);
+        let mut snap = common
+            .iter()
+            .filter(|&&(_, count)| count as f64 >= most as f64 * SNAP_SHARE)
+            .take(MAX_SNAPS)
+            .map(|&(width, _)| width)
+            .collect::<Vec<_>>();
+        if snap.is_empty() {
+            snap.push(standard);
+        }
+        snap.sort();
+        common.truncate(REPORTED_WIDTHS);
+        Self {
+            standard,
+            snap,
+            common,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct HintingReport {
+    /// The horizontal stems (measured up and down)
+    horizontal: Stems,
+    /// The vertical stems (measured across)
+    vertical: Stems,
+    /// How many glyphs' outlines were measured
+    glyphs: usize,
+}
+
+/// Adds the widths of the ink along the horizontal lines `step` apart across `polygons` to
+/// `histogram`
+fn measure(polygons: &[Vec<Point>], step: f64, histogram: &mut BTreeMap<usize, usize>) {
+    let (Some(min), Some(max)) = (
+        polygons
+            .iter()
+            .flatten()
+            .map(|p| p.y)
+            .min_by(f64::total_cmp),
+        polygons
+            .iter()
+            .flatten()
+            .map(|p| p.y)
+            .max_by(f64::total_cmp),
+    ) else {
+        return;
+    };
+    let mut y = min + step / 2.0;
+    while y < max {
+        for (start, end) in spans_at(polygons, y) {
+            let width = (end - start).round() as usize;
+            if width > 0 {
+                *histogram.entry(width).or_default() += 1;
+            }
+        }
+        y += step;
+    }
+}
+
+/// Measures the stems of the outlines drawn in `font` (not those of the glyphs they're
+/// referenced in, so each is measured once)
+pub fn report(font: &Font) -> Result<HintingReport, String> {
+    let step = SCAN_STEP * font.metrics.scale();
+    let mut horizontal = BTreeMap::new();
+    let mut vertical = BTreeMap::new();
+    let mut glyphs = 0;
+    for block in &font.blocks {
+        for (name, glyph) in block.full_names() {
+            let spline_set = glyph.glyph.rep.spline_set();
+            if spline_set.is_empty() {
+                continue;
+            }
+            let polygons = SplineSet::parse(spline_set)
+                .map_err(|e| format!("{name}: {e}"))?
+                .polygons();
+            let transposed = polygons
+                .iter()
+                .map(|polygon| polygon.iter().map(|p| Point::new(p.y, p.x)).collect())
+                .collect::<Vec<_>>();
+            measure(&polygons, step, &mut vertical);
+            measure(&transposed, step, &mut horizontal);
+            glyphs += 1;
+        }
+    }
+    let default = (DEFAULT_STEM * font.metrics.scale()).round() as usize;
+    Ok(HintingReport {
+        horizontal: Stems::from_histogram(horizontal, default),
+        vertical: Stems::from_histogram(vertical, default),
+        glyphs,
+    })
+}
+
+/// A private dictionary entry, as `Key length value`
+fn gen_entry(key: &str, widths: &[usize]) -> String {
+    let value = format!("[{}]", widths.iter().join(" "));
+    format!("{key} {} {value}\n", value.len())
+}
+
+impl HintingReport {
+    /// The private dictionary of the `.sfd` header, with the alignment zones scaled to `metrics`
+    pub fn gen(&self, metrics: &Metrics) -> String {
+        let entries = [
+            metrics.scale_header(ZONES),
+            gen_entry("StdHW", &[self.horizontal.standard]),
+            gen_entry("StdVW", &[self.vertical.standard]),
+            gen_entry("StemSnapH", &self.horizontal.snap),
+            gen_entry("StemSnapV", &self.vertical.snap),
+            REST.to_string(),
+        ]
+        .concat();
+        format!(
+            "BeginPrivate: {}\n{entries}EndPrivate\n",
+            entries.lines().count()
+        )
+    }
+
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!("measured the outlines of {} glyphs", self.glyphs);
+                for (name, stems) in [
+                    ("horizontal", &self.horizontal),
+                    ("vertical", &self.vertical),
+                ] {
+                    println!(
+                        "{name} stems: {} (snapping to {:?})",
+                        stems.standard, stems.snap
+                    );
+                    for (width, count) in &stems.common {
+                        println!("  {width:>5}  measured {count} times");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//! Where the inner glyph of a scaling combination goes: the middle of the biggest empty box that the
+//! outer glyph's outline encloses. Outer glyphs with a hand-placed scale anchor keep it.
+
+use crate::{ffir::*, spline::*};
+
+/// The size of the cells the em is split into when looking for empty space
+const CELL: f64 = 10.0;
+/// The bottom of the em (the descent)
+const BOTTOM: f64 = -100.0;
+/// How many cells the em is across and up
+const CELLS: usize = 100;
+
+/// The biggest box of empty space inside `spline_set`, as its bottom left and top right corners.
+/// A cell is inside if there's ink to its left and right, and above or below it.
+pub fn interior(spline_set: &SplineSet) -> Option<(Point, Point)> {
+    let inked = (0..CELLS)
+        .map(|row| {
+            let spans = spline_set.spans_at(BOTTOM + (row as f64 + 0.5) * CELL);
+            (0..CELLS)
+                .map(|col| {
+                    let x = (col as f64 + 0.5) * CELL;
+                    spans.iter().any(|&(start, end)| start <= x && x <= end)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let inside = |row: usize, col: usize| {
+        !inked[row][col]
+            && inked[row][..col].contains(&true)
+            && inked[row][col + 1..].contains(&true)
+            && (inked[..row].iter().any(|r| r[col]) || inked[row + 1..].iter().any(|r| r[col]))
+    };
+
+    // the largest rectangle under a histogram, for each row's column of inside cells
+    let mut heights = vec![0; CELLS];
+    let mut best: Option<(usize, usize, usize, usize)> = None;
+    let area = |(_, _, w, h): (usize, usize, usize, usize)| w * h;
+    for row in 0..CELLS {
+        for (col, height) in heights.iter_mut().enumerate() {
+            *height = if inside(row, col) { *height + 1 } else { 0 };
+        }
+        let mut stack: Vec<usize> = vec![];
+        for col in 0..=CELLS {
+            let height = heights.get(col).copied().unwrap_or(0);
+            while let Some(&top) = stack.last() {
+                if heights[top] < height {
+                    break;
+                }
+                stack.pop();
+                let left = stack.last().map_or(0, |&l| l + 1);
+                let rect = (left, row + 1 - heights[top], col - left, heights[top]);
+                if best.is_none_or(|best| area(rect) > area(best)) {
+                    best = Some(rect);
+                }
+            }
+            stack.push(col);
+        }
+    }
+
+    let (col, row, width, height) = best.filter(|&rect| area(rect) > 0)?;
+    let corner = |col: usize, row: usize| Point::new(col as f64 * CELL, BOTTOM + row as f64 * CELL);
+    Some((corner(col, row), corner(col + width, row + height)))
+}
+
+/// A scale anchor in the middle of the empty space inside `spline_set`
+pub fn scale_anchor(spline_set: &SplineSet) -> Option<Anchor> {
+    let (bottom_left, top_right) = interior(spline_set)?;
+    let middle = |a: f64, b: f64| (((a + b) / 2.0 / CELL).round() * CELL) as isize;
+    Some(Anchor::new_scale(
+        AnchorType::Base,
+        (
+            middle(bottom_left.x, top_right.x),
+            middle(bottom_left.y, top_right.y),
+        ),
+    ))
+}
+
+/// Gives each glyph in `block` without a scale anchor one in the middle of its empty space
+pub fn derive_scale_anchors(block: &mut GlyphBlock) -> Result<(), String> {
+    for glyph in &mut block.glyphs {
+        if glyph.glyph.anchor.is_some() || glyph.glyph.has_tag(TAG_PADDING) {
+            continue;
+        }
+        let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())
+            .map_err(|e| format!("{}: {e}", glyph.glyph.name))?;
+        glyph.glyph.anchor = scale_anchor(&spline_set);
+    }
+    Ok(())
+}
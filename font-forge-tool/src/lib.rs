@@ -0,0 +1,8 @@
+//! The parts of nasin nanpa's rules that other tools can use without generating the font
+
+pub mod translit;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,138 @@
+//! A check of the font's words against Linku, the toki pona dictionary: which of its words (in
+//! some usage categories) don't have a glyph, and which glyphs aren't in it
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::Path,
+    process,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cli::ReportFormat, invalid_data, query::GlyphQuery, Font};
+
+/// Where the words are downloaded from, when there's no copy to read
+pub const WORDS_URL: &str = "https://api.linku.la/v1/words";
+
+/// The usage categories checked by default
+pub const DEFAULT_CATEGORIES: [&str; 3] = ["core", "common", "uncommon"];
+
+/// A word, as Linku lists it (everything else about it is ignored)
+#[derive(Deserialize)]
+struct LinkuWord {
+    word: String,
+    usage_category: String,
+}
+
+/// Linku's words, by their IDs
+pub struct Linku {
+    words: BTreeMap<String, LinkuWord>,
+}
+
+impl Linku {
+    /// Reads a copy of Linku's words (as served at `WORDS_URL`), or downloads them if there's no
+    /// copy
+    pub fn load(path: Option<&Path>) -> io::Result<Self> {
+        let json = match path {
+            Some(path) => fs::read_to_string(path)?,
+            None => download()?,
+        };
+        let words = serde_json::from_str(&json).map_err(invalid_data)?;
+        Ok(Self { words })
+    }
+}
+
+fn download() -> io::Result<String> {
+    let output = process::Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--location",
+            WORDS_URL,
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "couldn't download {WORDS_URL}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(invalid_data)
+}
+
+#[derive(Serialize)]
+pub struct MissingWord {
+    word: String,
+    category: String,
+}
+
+#[derive(Serialize)]
+pub struct LinkuReport {
+    /// The words in the checked categories without a glyph
+    missing: Vec<MissingWord>,
+    /// The glyphs whose words Linku doesn't have
+    undocumented: Vec<String>,
+}
+
+/// Compares the words of `font` (those of its base blocks and glyph packs) against `linku`,
+/// reporting the words in `categories` without a glyph
+pub fn report(font: &Font, linku: &Linku, categories: &[String]) -> LinkuReport {
+    let glyphs = GlyphQuery::blocks_starting_with(&["base_cor", "base_ext", "pack_"])
+        .select(&font.blocks)
+        .into_iter()
+        .map(|(_, glyph)| glyph.glyph.name.to_string())
+        .collect::<HashSet<_>>();
+    let words = linku
+        .words
+        .values()
+        .map(|word| word.word.as_str())
+        .collect::<HashSet<_>>();
+
+    let missing = linku
+        .words
+        .values()
+        .filter(|word| categories.contains(&word.usage_category) && !glyphs.contains(&word.word))
+        .map(|word| MissingWord {
+            word: word.word.clone(),
+            category: word.usage_category.clone(),
+        })
+        .collect();
+    let mut undocumented = glyphs
+        .iter()
+        .filter(|glyph| !words.contains(glyph.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    undocumented.sort();
+
+    LinkuReport {
+        missing,
+        undocumented,
+    }
+}
+
+impl LinkuReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} words without a glyph, {} glyphs not in Linku",
+                    self.missing.len(),
+                    self.undocumented.len()
+                );
+                for word in &self.missing {
+                    println!("missing       {:<10} {}", word.category, word.word);
+                }
+                for glyph in &self.undocumented {
+                    println!("undocumented  {glyph}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
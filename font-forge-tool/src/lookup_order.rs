@@ -0,0 +1,78 @@
+//! A check that the lookups are applied in an order where every glyph's entries can match: each
+//! glyph an entry takes has to be encoded, or made by a lookup applied before the entry's
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    rules::{self, GlyphSection},
+    Font,
+};
+
+/// Where each lookup that's applied from a contextual subtable (by a `SeqLookup:`) is first
+/// applied that way, by the position of the contextual lookup
+fn contextual_positions(font: &Font) -> HashMap<&str, usize> {
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    let mut current = None;
+    for line in font.contextual.lines() {
+        let quoted = line.split('"').nth(1);
+        if line.starts_with("ContextSub2:") || line.starts_with("ChainSub2:") {
+            current = quoted.and_then(|subtable| font.features.lookup_position(subtable));
+        } else if line.trim_start().starts_with("SeqLookup:") {
+            if let (Some(current), Some(name)) = (current, quoted) {
+                let position = positions.entry(name).or_insert(current);
+                *position = current.min(*position);
+            }
+        }
+    }
+    positions
+}
+
+/// Fails if a lookup depends on one applied after it, or if a glyph's entry takes a glyph that's
+/// only made by lookups applied at or after the entry's own
+pub fn check(font: &Font, sections: &[GlyphSection]) -> Result<(), String> {
+    font.features.check_order()?;
+
+    let contextual = contextual_positions(font);
+    let rules = rules::rules(font, sections);
+
+    // where each glyph is first made, if it isn't encoded
+    let encoded = sections
+        .iter()
+        .filter(|glyph| glyph.encoded)
+        .map(|glyph| glyph.name.as_str())
+        .collect::<HashSet<_>>();
+    let mut made: HashMap<&str, (usize, &str)> = HashMap::new();
+    for rule in &rules {
+        let position = contextual
+            .get(rule.lookup)
+            .map_or(rule.position, |&applied| applied.min(rule.position));
+        // a glyph that's kept (like `elikiTok` in `elikiTok combCartExtTok`) isn't made
+        for output in rule
+            .outputs
+            .iter()
+            .filter(|glyph| !rule.inputs.contains(glyph))
+        {
+            let first = made.entry(output).or_insert((position, rule.lookup));
+            if position < first.0 {
+                *first = (position, rule.lookup);
+            }
+        }
+    }
+
+    for rule in &rules {
+        for input in &rule.inputs {
+            if encoded.contains(input) {
+                continue;
+            }
+            if let Some(&(position, lookup)) = made.get(input) {
+                if position >= rule.position {
+                    return Err(format!(
+                        "an entry in {} takes {input}, which isn't made until {lookup}",
+                        rule.lookup
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
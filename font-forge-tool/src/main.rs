@@ -1,22 +1,464 @@
+use block_kind::BlockKind;
+use cache::GlyphCache;
+use clap::Parser;
+use cli::{Cli, Command, Target};
+use contextual::{ContextRule, ContextualLookupBuilder};
+use em::Metrics;
+use features::*;
 use ffir::*;
-use glyph_blocks::{*, ctrl::*, base::*, lower::*, outer::*, inner::*};
+use fontforge::FontFormat;
+use gasp::GaspRange;
+use glyph_blocks::{base::*, ctrl::*, inner::*, lower::*, outer::*, synth::*, *};
 use itertools::Itertools;
-use std::{collections::HashSet, fs::File, io::Write};
+use manifest::Manifest;
+use name_table::NameTable;
+use plugins::{Letterspacing, Plugin, PluginFactory, RaiseDescenders};
+use profile::{Capability, Outlines, Profile};
+use query::GlyphQuery;
+use selectors::{SelectorKind, SELECTORS};
+use sources::GlyphBlockSpec;
+use spacing::Spacing;
+use spellings::Spelling;
+use spline::Direction;
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+use svg_table::SvgStyle;
+use widths::SideBearings;
+use writer::SfdWriter;
 
+mod ascii;
+mod block_kind;
+mod browse;
+mod cache;
+mod carets;
+mod cheat_sheet;
+mod cli;
+mod color;
+mod combos;
+mod compose;
+mod conformance;
+mod containers;
+mod containment;
+mod contextual;
+mod corners;
+mod coverage;
+mod credits;
+mod data_crate;
+mod directional;
+mod dotted_circle;
+mod em;
+mod fallback;
+mod features;
+mod ff_script;
 mod ffir;
+mod fontforge;
+mod gasp;
+mod gdef;
+#[cfg(test)]
+mod glyph_assert;
 mod glyph_blocks;
+mod glyph_list;
+mod grid;
+mod half_width;
+mod hinting;
+mod interior;
+mod linku;
+mod lookup_order;
+mod manifest;
+mod metrics;
+mod mirror;
+mod name_table;
+mod names;
+mod numbers;
+mod optical;
+mod os2;
+mod outlines;
+mod packs;
+mod patch;
+mod plugins;
+mod preview;
+mod profile;
+mod profiles;
+mod proof;
+mod query;
+mod raster;
+mod reachability;
+mod rules;
+mod selectors;
+mod sfd_check;
+mod shaping;
+mod shared_contours;
+mod similarity;
+mod simplify;
+mod slots;
+mod snapshot;
+mod sources;
+mod spacing;
+mod spellings;
+mod spline;
+mod split;
+mod stack3;
+mod stroke;
+mod subset;
+mod svg;
+mod svg_table;
+mod underline;
+mod vertical;
+mod visual_diff;
+mod watch;
+mod web;
+mod widths;
+mod writer;
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-enum NasinNanpaVariation {
-    Main,
-    Ucsur,
+/// The plugins `--plugin` can run, by name (see `plugins`)
+const PLUGINS: [(&str, PluginFactory); 2] = [
+    ("letterspacing", |percent| {
+        Box::new(Letterspacing { percent })
+    }),
+    ("raise-descenders", |units| {
+        Box::new(RaiseDescenders { units })
+    }),
+];
+
+/// Which blocks go in the font. Filters match a block's name (e.g. `base_alt`)
+/// or any `_`-separated part of it (e.g. `alt` for every alt block, `pack` for every glyph pack).
+#[derive(Default)]
+struct BlockFilter {
+    /// If not empty, only blocks matching one of these are included
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl BlockFilter {
+    fn matches(filter: &str, name: &str) -> bool {
+        name == filter || name.split('_').any(|part| part == filter)
+    }
+
+    fn includes(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|f| Self::matches(f, name)))
+            && !self.exclude.iter().any(|f| Self::matches(f, name))
+    }
+}
+
+/// Everything besides the variation that goes into generating an `.sfd`
+#[derive(Default)]
+struct GenOptions {
+    /// The blocks from glyph packs and SVG directories, merged
+    sources: Vec<GlyphBlockSpec>,
+    blocks: BlockFilter,
+    /// If set, only the sitelen pona for these words (with their alternates and combinations) are included
+    words: Option<HashSet<String>>,
+    /// The words that can start a long glyph (those in `data/containers.txt` if `None`)
+    containers: Option<Vec<String>>,
+    /// The names that are spelled out in a cartouche when they're typed as `[Name]`
+    names: Vec<String>,
+    /// The latin spellings to give glyphs besides (or instead of) the ones they're generated with
+    spellings: Vec<Spelling>,
+    /// The capabilities left out of every profile
+    without: Vec<Capability>,
+    /// Whether to add vertical metrics and the rotated forms used in vertical text
+    vertical: bool,
+    /// The `ModificationTime`, in seconds since the UNIX epoch (the current time if `None`)
+    timestamp: Option<u64>,
+    /// Which way the outlines go (the holes go the other way)
+    direction: Direction,
+    /// The radius every corner is rounded to, in the units the glyphs are drawn in (the corners
+    /// are left as they were drawn if `None`)
+    corner_radius: Option<f64>,
+    /// Whether to move the points that are a unit or two off the guides the sitelen pona are
+    /// drawn on onto them
+    snap_to_grid: bool,
+    /// How far (in the units the glyphs are drawn in) simplifying the outlines can move them (they
+    /// aren't simplified if `None`)
+    simplify: Option<f64>,
+    /// The em the glyphs are scaled to
+    metrics: Metrics,
+    /// How the space between sitelen pona words is set
+    spacing: Spacing,
+    /// What's run on the glyphs once they're built, in order
+    plugins: Vec<Box<dyn Plugin>>,
+    /// The blocks whose widths are worked out from their ink, and the side bearings they're given
+    side_bearings: Vec<SideBearings>,
+    /// The blocks padded differently than they ask to (a block's name, or a `_`-separated part of
+    /// it), and how (the last that matches a block)
+    padding: Vec<(String, Padding)>,
+    /// How TrueType rasterizers render the glyphs at each size (the recommended ranges, if empty)
+    gasp: Vec<GaspRange>,
+    /// What's added to the font's names (like `-base`, for a font of one of its parts)
+    name_suffix: &'static str,
+    /// Whether to move the contours glyphs share into components they reference
+    extract_refs: bool,
+    /// Whether to list the blocks on stderr as they're written
+    progress: bool,
+    /// Whether to generate and check everything without writing any files
+    dry_run: bool,
+    /// Whether glyphs that can't be reached (and dead lookups) fail the build
+    strict: bool,
+}
+
+/// An `InvalidData` error saying `e`, for the errors of generating and reading the font
+fn invalid_data(e: impl ToString) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// The name of the `.sfd` file generated for `profile`
+fn sfd_filename(profile: Profile) -> String {
+    format!("nasin-nanpa-{VERSION}{}.sfd", profile.suffix)
 }
 
-fn gen_nasin_nanpa(variation: NasinNanpaVariation) -> std::io::Result<()> {
-    let mut ff_pos: usize = 0;
+/// Generates the `.sfd` for `profile` into a file named after it, in the current directory,
+/// along with its JSON manifest if `manifest` is set
+fn gen_nasin_nanpa(
+    profile: Profile,
+    options: &GenOptions,
+    manifest: bool,
+    cache: Option<&mut GlyphCache>,
+) -> std::io::Result<Font> {
+    let filename = sfd_filename(profile);
+    let _span = tracing::info_span!("sfd", file = %filename).entered();
+    let font = gen_font(profile, options)?;
+    check_font(&font, options)?;
+    let written = if options.dry_run {
+        font.write_sfd(std::io::sink(), options, cache)
+    } else {
+        font.write_sfd(File::create(&filename)?, options, cache)
+    };
+    written.map_err(|e| std::io::Error::new(e.kind(), format!("{filename}: {e}")))?;
+    tracing::info!(
+        glyphs = font.glyph_count,
+        dry_run = options.dry_run,
+        "generated"
+    );
+
+    if manifest && !options.dry_run {
+        let file = BufWriter::new(File::create(Path::new(&filename).with_extension("json"))?);
+        serde_json::to_writer_pretty(file, &Manifest::new(&font))?;
+    }
+    Ok(font)
+}
+
+/// Checks that the lookups of `font` are applied in order, and that its glyphs and lookups can be
+/// reached (failing on the ones that can't only if `options` are strict)
+fn check_font(font: &Font, options: &GenOptions) -> std::io::Result<()> {
+    let sections = rules::sections(font);
+    lookup_order::check(font, &sections).map_err(invalid_data)?;
+    reachability::report(font, &sections).gate(options.strict)
+}
+
+/// Writes the `.sfd` of `font` in memory
+fn gen_sfd(font: &Font, options: &GenOptions) -> std::io::Result<String> {
+    let mut buf = vec![];
+    font.write_sfd(&mut buf, options, None)?;
+    String::from_utf8(buf).map_err(invalid_data)
+}
 
-    let mut ctrl_block = GlyphBlock::new_from_enc_glyphs(
-        &mut ff_pos,
+/// Generates the `.sfd` for `profile` in memory, checked the way `gen_nasin_nanpa` checks the ones
+/// it writes
+fn gen_checked_sfd(profile: Profile, options: &GenOptions) -> std::io::Result<String> {
+    let font = gen_font(profile, options)?;
+    check_font(&font, options)?;
+    gen_sfd(&font, options)
+}
+
+/// A generated font: its glyph blocks (with resolved references) and the features they take part in
+struct Font {
+    profile: Profile,
+    blocks: Vec<GlyphBlock>,
+    features: FeatureRegistry,
+    /// The contextual subtables, which are written in the header
+    contextual: String,
+    glyph_count: usize,
+    /// Whether the glyphs have vertical metrics
+    vertical: bool,
+    metrics: Metrics,
+}
+
+impl Font {
+    /// Every glyph with a codepoint, with its full name
+    fn encoded_glyphs(&self) -> impl Iterator<Item = (usize, GlyphName)> + '_ {
+        self.blocks.iter().flat_map(|block| {
+            block
+                .full_names()
+                .filter_map(|(name, glyph)| match glyph.encoding.enc_pos {
+                    EncPos::Pos(codepoint) => Some((codepoint, name)),
+                    EncPos::None => None,
+                })
+        })
+    }
+
+    /// The glyph with the full name `name` (like `tokiTok`), if the font has it
+    pub fn glyph(&self, name: &str) -> Option<&GlyphFull> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.full_names())
+            .find(|(full_name, _)| full_name.as_str() == name)
+            .map(|(_, glyph)| glyph)
+    }
+
+    /// The glyph encoded at `codepoint`, with its full name
+    pub fn codepoint(&self, codepoint: usize) -> Option<(GlyphName, &GlyphFull)> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.full_names())
+            .find(|(_, glyph)| matches!(glyph.encoding.enc_pos, EncPos::Pos(c) if c == codepoint))
+    }
+
+    /// The names of the lookups that take or make the glyph with the full name `name`, in the
+    /// order they're applied (every glyph's entries are generated to find them, so it's slow to
+    /// call for many glyphs)
+    pub fn lookups_for(&self, name: &str) -> Vec<String> {
+        let sections = rules::sections(self);
+        rules::rules(self, &sections)
+            .into_iter()
+            .filter(|rule| rule.inputs.contains(&name) || rule.outputs.contains(&name))
+            .sorted_by_key(|rule| rule.position)
+            .map(|rule| rule.lookup.to_string())
+            .dedup()
+            .collect()
+    }
+
+    /// Writes the font as an `.sfd`, a block at a time, with the timestamp of `options` (or the
+    /// current time) as its `ModificationTime`, checking its bookkeeping as it goes. The glyphs in
+    /// `cache` that haven't changed are reused.
+    fn write_sfd(
+        &self,
+        out: impl Write,
+        options: &GenOptions,
+        cache: Option<&mut GlyphCache>,
+    ) -> std::io::Result<()> {
+        let lookups = self.features.gen_header();
+        let anchor_classes = self.features.gen_anchor_classes();
+
+        let time = options
+            .timestamp
+            .unwrap_or_else(|| std::time::UNIX_EPOCH.elapsed().unwrap().as_secs());
+
+        let os2 = os2::report(self).gen();
+        let private = hinting::report(self)
+            .map_err(invalid_data)?
+            .gen(&self.metrics);
+        let family = format!("{FAMILY}{}", options.name_suffix);
+        let lang_name = NameTable::nasin_nanpa(&family).gen();
+        let gasp = gasp::gen(&options.gasp);
+        let vmetrics = if self.vertical {
+            "HasVMetrics: 1\n"
+        } else {
+            ""
+        };
+        let contextual = &self.contextual;
+        let ff_pos = self.glyph_count;
+        let [details1, details2, other] =
+            [DETAILS1, DETAILS2, OTHER].map(|header| self.metrics.scale_header(header));
+
+        // FINAL `.sfd` COMPOSITIION
+        let mut sfd = SfdWriter::new(out);
+        if options.progress {
+            sfd = sfd.with_progress(ff_pos);
+        }
+        if let Some(cache) = cache {
+            sfd = sfd.with_cache(cache, &self.features);
+        }
+        sfd.header(&format!(
+            r#"{HEADER}FontName: {family}
+FullName: {family}
+FamilyName: {family}
+{STYLE}Version: {VERSION}
+{details1}ModificationTime: {time}{details2}{os2}{vmetrics}{lookups}DEI: 91125
+{contextual}{lang_name}{gasp}{other}{private}{anchor_classes}BeginChars: {ff_pos} {ff_pos}"#
+        ))?;
+        for block in &self.blocks {
+            sfd.block(block, self.profile, &self.features)?;
+        }
+        sfd.finish()
+    }
+}
+
+/// Logs the slots and codepoints `block` was given
+fn trace_block(block: &GlyphBlock) {
+    let slots = block
+        .glyphs
+        .first()
+        .zip(block.glyphs.last())
+        .map(|(first, last)| format!("{}..={}", first.encoding.ff_pos, last.encoding.ff_pos));
+    let codepoints = block
+        .glyphs
+        .iter()
+        .filter_map(|glyph| match glyph.encoding.enc_pos {
+            EncPos::Pos(codepoint) => Some(codepoint),
+            EncPos::None => None,
+        })
+        .minmax()
+        .into_option()
+        .map(|(first, last)| format!("U+{first:04X}..=U+{last:04X}"));
+    tracing::debug!(
+        block = %block.name,
+        glyphs = block.glyphs.len(),
+        slots = slots.as_deref().unwrap_or("none"),
+        codepoints = codepoints.as_deref().unwrap_or("none"),
+        "assigned slots"
+    );
+}
+
+/// Generates the glyph blocks and features of `profile`
+fn gen_font(profile: Profile, options: &GenOptions) -> std::io::Result<Font> {
+    let profile = options
+        .without
+        .iter()
+        .fold(profile, |profile, capability| profile.without(*capability));
+    let mut features = FeatureRegistry::nasin_nanpa();
+    let spelled_names = if profile.has(Capability::LatinLigatures) {
+        options.names.as_slice()
+    } else {
+        &[]
+    };
+
+    let mut blocks = control_blocks(options)?;
+    blocks.extend(text_blocks(profile, options, spelled_names)?);
+    let base = base_blocks(profile, options, &mut features)?;
+    let combinations = combination_blocks(&base, &mut features)?;
+    blocks.extend([base.cor, base.ext]);
+    blocks.extend(base.packs);
+    blocks.extend([base.alt, base.arrow, base.ss03]);
+    blocks.extend(combinations);
+    blocks.extend(other_blocks(profile, options)?);
+
+    let (mut blocks, excluded) = select_blocks(blocks, options);
+    process_outlines(&mut blocks, profile, options, &mut features)?;
+    declare_features(
+        &mut blocks,
+        &excluded,
+        profile,
+        options,
+        spelled_names,
+        &mut features,
+    )?;
+    let contextual = gen_contextual(&blocks, &excluded, &mut features);
+    let glyph_count = allocate_slots(&mut blocks)?;
+
+    Ok(Font {
+        profile,
+        blocks,
+        features,
+        contextual,
+        glyph_count,
+        vertical: options.vertical,
+        metrics: options.metrics,
+    })
+}
+
+/// The blocks of the control characters: the latin ones, the sitelen pona ones, and the glyphs that
+/// start the long glyphs of `options`' containers
+fn control_blocks(options: &GenOptions) -> std::io::Result<Vec<GlyphBlock>> {
+    let (bottom_ticks, bottom_tick_ligs): (Vec<_>, Vec<_>) =
+        cart_tick_glyphs(TickSide::Bottom).into_iter().unzip();
+    let (top_ticks, top_tick_ligs): (Vec<_>, Vec<_>) =
+        cart_tick_glyphs(TickSide::Top).into_iter().unzip();
+
+    let ctrl_block = GlyphBlock::new_from_enc_glyphs(
         vec![
             GlyphEnc::new_from_parts(EncPos::Pos(0x0000), "NUL", 0, Rep::default()),
             GlyphEnc::new_from_parts(EncPos::Pos(0x200B), "ZWSP", 0, Rep::default()),
@@ -30,314 +472,77 @@ fn gen_nasin_nanpa(variation: NasinNanpaVariation) -> std::io::Result<()> {
             GlyphEnc::new_from_parts(EncPos::Pos(0x2197), "arrowNE", 0, Rep::default()),
             GlyphEnc::new_from_parts(EncPos::Pos(0x2198), "arrowSE", 0, Rep::default()),
             GlyphEnc::new_from_parts(EncPos::Pos(0x2199), "arrowSW", 0, Rep::default()),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt1TickTok",
-                0,
-                Rep::new(
-                    r#"
--500 -100 m 0
- -472 -100 -450 -122 -450 -150 c 2
- -450 -250 l 2
- -450 -278 -472 -300 -500 -300 c 0
- -528 -300 -550 -278 -550 -250 c 2
- -550 -150 l 2
- -550 -122 -528 -100 -500 -100 c 0"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt2TickTok",
-                0,
-                Rep::new(
-                    r#"
--400 -100 m 0
- -372 -100 -350 -122 -350 -150 c 2
- -350 -250 l 2
- -350 -278 -372 -300 -400 -300 c 0
- -428 -300 -450 -278 -450 -250 c 2
- -450 -150 l 2
- -450 -122 -428 -100 -400 -100 c 0
--600 -100 m 0
- -572 -100 -550 -122 -550 -150 c 2
- -550 -250 l 2
- -550 -278 -572 -300 -600 -300 c 0
- -628 -300 -650 -278 -650 -250 c 2
- -650 -150 l 2
- -650 -122 -628 -100 -600 -100 c 0"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt3TickTok",
-                0,
-                Rep::new(
-                    r#"
--300 -100 m 0
- -272 -100 -250 -122 -250 -150 c 2
- -250 -250 l 2
- -250 -278 -272 -300 -300 -300 c 0
- -328 -300 -350 -278 -350 -250 c 2
- -350 -150 l 2
- -350 -122 -328 -100 -300 -100 c 0
--500 -100 m 0
- -472 -100 -450 -122 -450 -150 c 2
- -450 -250 l 2
- -450 -278 -472 -300 -500 -300 c 0
- -528 -300 -550 -278 -550 -250 c 2
- -550 -150 l 2
- -550 -122 -528 -100 -500 -100 c 0
--700 -100 m 0
- -672 -100 -650 -122 -650 -150 c 2
- -650 -250 l 2
- -650 -278 -672 -300 -700 -300 c 0
- -728 -300 -750 -278 -750 -250 c 2
- -750 -150 l 2
- -750 -122 -728 -100 -700 -100 c 0"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt4TickTok",
-                0,
-                Rep::new(
-                    r#"
--400 -100 m 0
- -372 -100 -350 -122 -350 -150 c 2
- -350 -250 l 2
- -350 -278 -372 -300 -400 -300 c 0
- -428 -300 -450 -278 -450 -250 c 2
- -450 -150 l 2
- -450 -122 -428 -100 -400 -100 c 0
--200 -100 m 0
- -172 -100 -150 -122 -150 -150 c 2
- -150 -250 l 2
- -150 -278 -172 -300 -200 -300 c 0
- -228 -300 -250 -278 -250 -250 c 2
- -250 -150 l 2
- -250 -122 -228 -100 -200 -100 c 0
--600 -100 m 0
- -572 -100 -550 -122 -550 -150 c 2
- -550 -250 l 2
- -550 -278 -572 -300 -600 -300 c 0
- -628 -300 -650 -278 -650 -250 c 2
- -650 -150 l 2
- -650 -122 -628 -100 -600 -100 c 0
--800 -100 m 0
- -772 -100 -750 -122 -750 -150 c 2
- -750 -250 l 2
- -750 -278 -772 -300 -800 -300 c 0
- -828 -300 -850 -278 -850 -250 c 2
- -850 -150 l 2
- -850 -122 -828 -100 -800 -100 c 0"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE00), "VAR01", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE01), "VAR02", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE02), "VAR03", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE03), "VAR04", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE04), "VAR05", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE05), "VAR06", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE06), "VAR07", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE07), "VAR08", 0, Rep::default()),
-            GlyphEnc::new_from_parts(EncPos::Pos(0xFE08), "VAR09", 0, Rep::default()),
+        ]
+        .into_iter()
+        .chain(bottom_ticks)
+        .chain(
+            SELECTORS
+                .of_kind(SelectorKind::Variation)
+                .filter_map(|selector| {
+                    let codepoint = selector.codepoint?;
+                    Some(GlyphEnc::new_from_parts(
+                        EncPos::Pos(codepoint),
+                        selector.name,
+                        0,
+                        Rep::default(),
+                    ))
+                }),
+        )
+        .chain(vec![
             GlyphEnc::new_from_parts(EncPos::Pos(0xE01EF), "VAR256", 0, Rep::default()),
             GlyphEnc::new_from_parts(
                 EncPos::None,
                 "combCartExtHalfTok",
                 0,
-                Rep::new(
-                    r#"
--550 -150 m 4
- -550 -122 -528 -100 -500 -100 c 6
- 0 -100 l 2
- 28 -100 50 -122 50 -150 c 0
- 50 -178 28 -200 0 -200 c 2
- -500 -200 l 6
- -528 -200 -550 -178 -550 -150 c 4
--550 950 m 4
- -550 978 -528 1000 -500 1000 c 6
- 0 1000 l 2
- 28 1000 50 978 50 950 c 0
- 50 922 28 900 0 900 c 2
- -500 900 l 6
- -528 900 -550 922 -550 950 c 4"#,
-                    vec![],
-                ),
+                Rep::new(ext_bars(&CART_BARS, EXT_HALF_LENGTH).gen(), vec![]),
             ),
             GlyphEnc::new_from_parts(
                 EncPos::None,
                 "combLongGlyphExtHalfTok",
                 0,
-                Rep::new(
-                    r#"
--550 -150 m 4
- -550 -122 -528 -100 -500 -100 c 6
- 0 -100 l 2
- 28 -100 50 -122 50 -150 c 0
- 50 -178 28 -200 0 -200 c 2
- -500 -200 l 6
- -528 -200 -550 -178 -550 -150 c 4"#,
-                    vec![],
-                ),
-            ),
-
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt5TickTok",
-                0,
-                Rep::new(
-                    r#"
--500 1100 m 4
- -472 1100 -450 1078 -450 1050 c 6
- -450 950 l 6
- -450 922 -472 900 -500 900 c 4
- -528 900 -550 922 -550 950 c 6
- -550 1050 l 6
- -550 1078 -528 1100 -500 1100 c 4"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt6TickTok",
-                0,
-                Rep::new(
-                    r#"
--400 1100 m 4
- -372 1100 -350 1078 -350 1050 c 6
- -350 950 l 6
- -350 922 -372 900 -400 900 c 4
- -428 900 -450 922 -450 950 c 6
- -450 1050 l 6
- -450 1078 -428 1100 -400 1100 c 4
--600 1100 m 4
- -572 1100 -550 1078 -550 1050 c 6
- -550 950 l 6
- -550 922 -572 900 -600 900 c 4
- -628 900 -650 922 -650 950 c 6
- -650 1050 l 6
- -650 1078 -628 1100 -600 1100 c 4"#,
-                    vec![],
-                ),
+                Rep::new(ext_bars(&LONG_GLYPH_BARS, EXT_HALF_LENGTH).gen(), vec![]),
             ),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt7TickTok",
-                0,
-                Rep::new(
-                    r#"
--300 1100 m 4
- -272 1100 -250 1078 -250 1050 c 6
- -250 950 l 6
- -250 922 -272 900 -300 900 c 4
- -328 900 -350 922 -350 950 c 6
- -350 1050 l 6
- -350 1078 -328 1100 -300 1100 c 4
--500 1100 m 4
- -472 1100 -450 1078 -450 1050 c 6
- -450 950 l 6
- -450 922 -472 900 -500 900 c 4
- -528 900 -550 922 -550 950 c 6
- -550 1050 l 6
- -550 1078 -528 1100 -500 1100 c 4
--700 1100 m 4
- -672 1100 -650 1078 -650 1050 c 6
- -650 950 l 6
- -650 922 -672 900 -700 900 c 4
- -728 900 -750 922 -750 950 c 6
- -750 1050 l 6
- -750 1078 -728 1100 -700 1100 c 4"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(
-                EncPos::None,
-                "combCartExt8TickTok",
-                0,
-                Rep::new(
-                    r#"
--400 1100 m 4
- -372 1100 -350 1078 -350 1050 c 6
- -350 950 l 6
- -350 922 -372 900 -400 900 c 4
- -428 900 -450 922 -450 950 c 6
- -450 1050 l 6
- -450 1078 -428 1100 -400 1100 c 4
--200 1100 m 4
- -172 1100 -150 1078 -150 1050 c 6
- -150 950 l 6
- -150 922 -172 900 -200 900 c 4
- -228 900 -250 922 -250 950 c 6
- -250 1050 l 6
- -250 1078 -228 1100 -200 1100 c 4
--600 1100 m 4
- -572 1100 -550 1078 -550 1050 c 6
- -550 950 l 6
- -550 922 -572 900 -600 900 c 4
- -628 900 -650 922 -650 950 c 6
- -650 1050 l 6
- -650 1078 -628 1100 -600 1100 c 4
--800 1100 m 4
- -772 1100 -750 1078 -750 1050 c 6
- -750 950 l 6
- -750 922 -772 900 -800 900 c 4
- -828 900 -850 922 -850 950 c 6
- -850 1050 l 6
- -850 1078 -828 1100 -800 1100 c 4"#,
-                    vec![],
-                ),
-            ),
-            GlyphEnc::new_from_parts(EncPos::None, "combCartExtNoneTok", 0, Rep::default()),
-        ],
-        LookupsMode::WordLigManual(vec![
-            String::new(),
-            String::new(),
-            "bar".to_string(),
-            "ampersand".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "arrow".to_string(),
-            "combCartExtTok comma".to_string(),
-            "comma comma".to_string(),
-            "comma comma comma".to_string(),
-            "comma comma comma comma".to_string(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            "combCartExtTok quotesingle".to_string(),
-            "quotesingle quotesingle".to_string(),
-            "quotesingle quotesingle quotesingle".to_string(),
-            "quotesingle quotesingle quotesingle quotesingle".to_string(),
-            String::new(),
-        ]),
+        ])
+        .chain(top_ticks)
+        .chain(vec![GlyphEnc::new_from_parts(
+            EncPos::None,
+            "combCartExtNoneTok",
+            0,
+            Rep::default(),
+        )])
+        .collect(),
+        LookupsMode::WordLigManual(
+            vec![
+                String::new(),
+                String::new(),
+                "bar".to_string(),
+                "ampersand".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+                "arrow".to_string(),
+            ]
+            .into_iter()
+            .chain(bottom_tick_ligs)
+            .chain(vec![String::new(); 12])
+            .chain(top_tick_ligs)
+            .chain(vec![String::new()])
+            .collect(),
+        ),
         Cc::Participant,
         "",
         "",
-        "fa6791",
-    );
-    ctrl_block.glyphs[0].cc_subs = Cc::None;
+        BlockKind::Control.color(),
+    )
+    .named("ctrl")
+    .with_glyph("NUL", |glyph| glyph.cc_subs = Cc::None)
+    .map_err(invalid_data)?;
 
-    let mut tok_ctrl_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let tok_ctrl_block = GlyphBlock::new_from_constants(
         TOK_CTRL.as_slice(),
         LookupsMode::WordLigManual(vec![
             "bracketleft".to_string(),
@@ -360,46 +565,86 @@ fn gen_nasin_nanpa(variation: NasinNanpaVariation) -> std::io::Result<()> {
         Cc::None,
         "",
         "Tok",
-        "aaafff",
+        BlockKind::TokControl.color(),
         EncPos::Pos(0xF1990),
         0,
-    );
-    tok_ctrl_block.glyphs[5].cc_subs = Cc::Participant;
-    tok_ctrl_block.glyphs[6].cc_subs = Cc::Participant;
-    tok_ctrl_block.glyphs[12].encoding.enc_pos = EncPos::None;
-    tok_ctrl_block.glyphs[13].encoding.enc_pos = EncPos::None;
-
-    let mut start_long_glyph_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
-        START_LONG_GLYPH.as_slice(),
-        LookupsMode::StartLongGlyph,
+    )
+    .map_err(invalid_data)?
+    .named("tok_ctrl")
+    // the extensions are generated, so they always span the glyphs they extend under
+    .with_glyph("combCartExt", |glyph| {
+        glyph.glyph.rep = Rep::new(ext_bars(&CART_BARS, EXT_LENGTH).gen(), vec![])
+    })
+    .and_then(|block| {
+        block.with_glyph("combLongPiExt", |glyph| {
+            glyph.glyph.rep = Rep::new(ext_bars(&LONG_GLYPH_BARS, EXT_LENGTH).gen(), vec![])
+        })
+    })
+    .and_then(|block| {
+        block.with_glyph("combLongGlyphExt", |glyph| {
+            glyph.glyph.rep = Rep::new(ext_bars(&LONG_GLYPH_BARS, EXT_LENGTH).gen(), vec![])
+        })
+    })
+    .and_then(|block| block.with_glyph("joinStack", |glyph| glyph.cc_subs = Cc::Participant))
+    .and_then(|block| block.with_glyph("joinScale", |glyph| glyph.cc_subs = Cc::Participant))
+    .and_then(|block| {
+        block.with_glyph("startCartAlt", |glyph| {
+            glyph.encoding.enc_pos = EncPos::None
+        })
+    })
+    .and_then(|block| block.with_glyph("endCartAlt", |glyph| glyph.encoding.enc_pos = EncPos::None))
+    .map_err(invalid_data)?;
+
+    let containers = match &options.containers {
+        Some(containers) => containers.clone(),
+        None => containers::default_containers(),
+    };
+    let (start_long_glyphs, directions) = containers::start_long_glyphs(&containers)
+        .map_err(invalid_data)?
+        .into_iter()
+        .unzip();
+    let start_long_glyph_block = GlyphBlock::new_from_basic_glyphs(
+        start_long_glyphs,
+        LookupsMode::StartLongGlyph(directions),
         Cc::None,
         "",
         "_startLongGlyphTok",
-        "aaafff",
+        BlockKind::TokControl.color(),
         EncPos::None,
-        1000,
-    );
-    start_long_glyph_block.glyphs[7].lookups = Lookups::EndLongGlyph;
+    )
+    .named("start_long_glyph");
 
-    let latn_block = if variation == NasinNanpaVariation::Main {
+    Ok(vec![ctrl_block, tok_ctrl_block, start_long_glyph_block])
+}
+
+/// The blocks of the latin letters, the names spelled in cartouches, the glyphs that don't combine,
+/// and the radicals
+fn text_blocks(
+    profile: Profile,
+    options: &GenOptions,
+    spelled_names: &[String],
+) -> std::io::Result<Vec<GlyphBlock>> {
+    let mut latn_block = if profile.has(Capability::Ascii) {
         GlyphBlock::new_from_constants(
-            &mut ff_pos,
             LATN.as_slice(),
             LookupsMode::None,
             Cc::Half,
             "",
             "",
-            "fffaaa",
+            BlockKind::Latin.color(),
             EncPos::Pos(0x0020),
             500,
         )
+        .map_err(invalid_data)?
+        .named("latn")
     } else {
-        GlyphBlock::new_empty(&mut ff_pos, 0, 0)
+        GlyphBlock::new_empty().named("latn")
     };
+    names::use_letters(&mut latn_block);
+
+    let names_block = names::block(spelled_names);
 
-    let mut no_comb_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let no_comb_block = GlyphBlock::new_from_constants(
         TOK_NO_COMB.as_slice(),
         LookupsMode::WordLigManual(vec![
             "period".to_string(),
@@ -416,30 +661,63 @@ fn gen_nasin_nanpa(variation: NasinNanpaVariation) -> std::io::Result<()> {
         Cc::Full,
         "",
         "Tok",
-        "cccfff",
+        BlockKind::NoCombination.color(),
         EncPos::None,
         1000,
-    );
-    no_comb_block.glyphs[0].encoding.enc_pos = EncPos::Pos(0xF199C);
-    no_comb_block.glyphs[1].encoding.enc_pos = EncPos::Pos(0xF199D);
-    no_comb_block.glyphs[4].encoding.enc_pos = EncPos::Pos(0x3000);
+    )
+    .map_err(invalid_data)?
+    .named("no_comb")
+    .with_glyph("middleDot", |glyph| {
+        glyph.encoding.enc_pos = EncPos::Pos(0xF199C)
+    })
+    .and_then(|block| {
+        block.with_glyph("colon", |glyph| {
+            glyph.encoding.enc_pos = EncPos::Pos(0xF199D)
+        })
+    })
+    .and_then(|block| {
+        block.with_glyph("space", |glyph| {
+            glyph.encoding.enc_pos = EncPos::Pos(0x3000);
+            options.spacing.shape_space(glyph);
+        })
+    })
+    .map_err(invalid_data)?;
 
     let radicals_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
         RADICALS.as_slice(),
         LookupsMode::None,
         Cc::Full,
         "",
         "Rad",
-        "7777cc",
+        BlockKind::Radical.color(),
         EncPos::Pos(0xF1C80),
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("radicals");
 
+    Ok(vec![latn_block, names_block, no_comb_block, radicals_block])
+}
+
+/// The blocks of the base glyphs, which the combinations are made of
+struct BaseBlocks {
+    cor: GlyphBlock,
+    ext: GlyphBlock,
+    packs: Vec<GlyphBlock>,
+    alt: GlyphBlock,
+    arrow: GlyphBlock,
+    ss03: GlyphBlock,
+}
+
+/// The base glyphs of the font and of `options`' glyph packs, with their alternates
+fn base_blocks(
+    profile: Profile,
+    options: &GenOptions,
+    features: &mut FeatureRegistry,
+) -> std::io::Result<BaseBlocks> {
     let base_cor_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
         BASE_COR.as_slice(),
-        if variation == NasinNanpaVariation::Main {
+        if profile.has(Capability::LatinLigatures) {
             LookupsMode::WordLigFromLetters
         } else {
             LookupsMode::None
@@ -447,15 +725,16 @@ fn gen_nasin_nanpa(variation: NasinNanpaVariation) -> std::io::Result<()> {
         Cc::Full,
         "",
         "Tok",
-        "bf80ff",
+        BlockKind::Core.color(),
         EncPos::Pos(0xF1900),
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("base_cor");
 
-    let mut base_ext_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let base_ext_block = GlyphBlock::new_from_constants(
         BASE_EXT.as_slice(),
-        if variation == NasinNanpaVariation::Main {
+        if profile.has(Capability::LatinLigatures) {
             LookupsMode::WordLigFromLetters
         } else {
             LookupsMode::None
@@ -463,400 +742,1080 @@ fn gen_nasin_nanpa(variation: NasinNanpaVariation) -> std::io::Result<()> {
         Cc::Full,
         "",
         "Tok",
-        "df80ff",
+        BlockKind::Extended.color(),
         EncPos::Pos(0xF19A0),
         1000,
-    );
-    base_ext_block.glyphs[41].encoding.enc_pos = EncPos::None;
-    base_ext_block.glyphs[42].encoding.enc_pos = EncPos::None;
+    )
+    .map_err(invalid_data)?
+    .named("base_ext")
+    .with_glyph("Pingo", |glyph| glyph.encoding.enc_pos = EncPos::None)
+    .and_then(|block| block.with_glyph("eliki", |glyph| glyph.encoding.enc_pos = EncPos::None))
+    .map_err(invalid_data)?;
+
+    let mut next_enc = base_ext_block
+        .glyphs
+        .iter()
+        .filter_map(|glyph| match glyph.encoding.enc_pos {
+            EncPos::Pos(p) => Some(p + 1),
+            EncPos::None => None,
+        })
+        .max()
+        .unwrap();
+    let pack_blocks = options
+        .sources
+        .iter()
+        .map(|spec| spec.to_block(&mut next_enc, profile))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid_data)?;
 
     let base_alt_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
         BASE_ALT.as_slice(),
         LookupsMode::Alt,
         Cc::Full,
         "",
         "",
-        "ff80e6",
+        BlockKind::Alternate.color(),
         EncPos::None,
         1000,
+    )
+    .map_err(invalid_data)?
+    .named("base_alt");
+
+    let base_arrow_block = directional::block(
+        &[BASE_COR.as_slice(), BASE_EXT.as_slice()],
+        BASE_ALT.as_slice(),
     );
 
-    let outer_cor_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let base_ss03_block = GlyphBlock::new_from_constants(
+        BASE_SS03.as_slice(),
+        LookupsMode::None,
+        Cc::Full,
+        "",
+        "",
+        BlockKind::Simplified.color(),
+        EncPos::None,
+        1000,
+    )
+    .map_err(invalid_data)?
+    .named("base_ss03")
+    .padded(Padding::None);
+    for glyph in &BASE_SS03 {
+        let Some((base, _)) = glyph.name.split_once('_') else {
+            continue;
+        };
+        features.declare(base, SS03_SIMPLIFIED, glyph.name);
+    }
+
+    Ok(BaseBlocks {
+        cor: base_cor_block,
+        ext: base_ext_block,
+        packs: pack_blocks,
+        alt: base_alt_block,
+        arrow: base_arrow_block,
+        ss03: base_ss03_block,
+    })
+}
+
+/// The blocks of the combinations of `base`: scaled (outer and inner) and stacked (lower, upper, and
+/// the 3-stacks)
+fn combination_blocks(
+    base: &BaseBlocks,
+    features: &mut FeatureRegistry,
+) -> std::io::Result<Vec<GlyphBlock>> {
+    let mut outer_cor_block = GlyphBlock::new_from_constants(
         OUTER_COR.as_slice(),
-        LookupsMode::ComboFirst,
+        LookupsMode::ComboFirst(Joiner::Scale),
         Cc::Full,
         "",
         "Tok_joinScaleTok",
-        "ffff",
+        BlockKind::Outer.color(),
         EncPos::None,
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("outer_cor");
 
-    let outer_ext_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut outer_ext_block = GlyphBlock::new_from_constants(
         OUTER_EXT.as_slice(),
-        LookupsMode::ComboFirst,
+        LookupsMode::ComboFirst(Joiner::Scale),
         Cc::Full,
         "",
         "Tok_joinScaleTok",
-        "ffff",
+        BlockKind::Outer.color(),
         EncPos::None,
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("outer_ext");
 
-    let outer_alt_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut outer_alt_block = GlyphBlock::new_from_constants(
         OUTER_ALT.as_slice(),
-        LookupsMode::ComboFirst,
+        LookupsMode::ComboFirst(Joiner::Scale),
         Cc::Full,
         "",
         "_joinScaleTok",
-        "ffff",
+        BlockKind::Outer.color(),
         EncPos::None,
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("outer_alt");
+
+    for block in [
+        &mut outer_cor_block,
+        &mut outer_ext_block,
+        &mut outer_alt_block,
+    ] {
+        interior::derive_scale_anchors(block).map_err(invalid_data)?;
+    }
 
-    let inner_cor_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut inner_cor_block = GlyphBlock::new_from_constants(
         INNER_COR.as_slice(),
-        LookupsMode::ComboLast,
+        LookupsMode::ComboLast(Joiner::Scale),
         Cc::Full,
         "joinScaleTok_",
         "Tok",
-        "80ffff",
+        BlockKind::Inner.color(),
         EncPos::None,
         0,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("inner_cor");
 
-    let inner_ext_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut inner_ext_block = GlyphBlock::new_from_constants(
         INNER_EXT.as_slice(),
-        LookupsMode::ComboLast,
+        LookupsMode::ComboLast(Joiner::Scale),
         Cc::Full,
         "joinScaleTok_",
         "Tok",
-        "80ffff",
+        BlockKind::Inner.color(),
         EncPos::None,
         0,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("inner_ext");
 
-    let inner_alt_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut inner_alt_block = GlyphBlock::new_from_constants(
         INNER_ALT.as_slice(),
-        LookupsMode::ComboLast,
+        LookupsMode::ComboLast(Joiner::Scale),
         Cc::Full,
         "joinScaleTok_",
         "",
-        "80ffff",
+        BlockKind::Inner.color(),
         EncPos::None,
         0,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("inner_alt");
 
-    let lower_cor_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut lower_cor_block = GlyphBlock::new_from_constants(
         LOWER_COR.as_slice(),
-        LookupsMode::ComboFirst,
+        LookupsMode::ComboFirst(Joiner::Stack),
         Cc::Full,
         "",
         "Tok_joinStackTok",
-        "ff00",
+        BlockKind::Lower.color(),
         EncPos::None,
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("lower_cor");
 
-    let lower_ext_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut lower_ext_block = GlyphBlock::new_from_constants(
         LOWER_EXT.as_slice(),
-        LookupsMode::ComboFirst,
+        LookupsMode::ComboFirst(Joiner::Stack),
         Cc::Full,
         "",
         "Tok_joinStackTok",
-        "ff00",
+        BlockKind::Lower.color(),
         EncPos::None,
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("lower_ext");
 
-    let lower_alt_block = GlyphBlock::new_from_constants(
-        &mut ff_pos,
+    let mut lower_alt_block = GlyphBlock::new_from_constants(
         LOWER_ALT.as_slice(),
-        LookupsMode::ComboFirst,
+        LookupsMode::ComboFirst(Joiner::Stack),
         Cc::Full,
         "",
         "_joinStackTok",
-        "ff00",
+        BlockKind::Lower.color(),
         EncPos::None,
         1000,
-    );
+    )
+    .map_err(invalid_data)?
+    .named("lower_alt");
 
-    let upper_cor_block = lower_cor_block.new_from_refs(
-        &mut ff_pos,
-        "S 1 0 0 1 -1000 500 2".to_string(),
-        LookupsMode::ComboLast,
-        Cc::Full,
-        false,
-        "joinStackTok_",
-        "Tok",
-        "80ff80",
-        Some(0),
-        Some(Anchor::new_stack(AnchorType::Mark)),
-    );
+    // base glyphs (and pack glyphs) without hand-drawn combination forms get generated ones
+    let cor_sources = std::iter::once(&base.cor).chain(&base.packs).collect_vec();
+    for (block, sources) in [
+        (&mut lower_cor_block, cor_sources.as_slice()),
+        (&mut lower_ext_block, &[&base.ext]),
+        (&mut lower_alt_block, &[&base.alt, &base.ss03]),
+    ] {
+        combos::fill_missing(
+            block,
+            sources,
+            &combos::LOWER_FROM_BASE,
+            LookupsMode::ComboFirst(Joiner::Stack),
+            None,
+            Anchor::new_stack(AnchorType::Base),
+        );
+    }
+    for (block, sources) in [
+        (&mut inner_cor_block, cor_sources.as_slice()),
+        (&mut inner_ext_block, &[&base.ext]),
+        (&mut inner_alt_block, &[&base.alt, &base.ss03]),
+    ] {
+        combos::fill_missing(
+            block,
+            sources,
+            &combos::INNER_FROM_BASE,
+            LookupsMode::ComboLast(Joiner::Scale),
+            Some(0),
+            Anchor::new_scale(AnchorType::Mark, (-500, 400)),
+        );
+    }
 
-    let upper_ext_block = lower_ext_block.new_from_refs(
-        &mut ff_pos,
-        "S 1 0 0 1 -1000 500 2".to_string(),
-        LookupsMode::ComboLast,
-        Cc::Full,
-        false,
-        "joinStackTok_",
-        "Tok",
-        "80ff80",
-        Some(0),
-        Some(Anchor::new_stack(AnchorType::Mark)),
-    );
+    let upper_cor_block = lower_cor_block
+        .derive(
+            &combos::UPPER_FROM_LOWER,
+            LookupsMode::ComboLast(Joiner::Stack),
+            Cc::Full,
+            false,
+            "joinStackTok_",
+            "Tok",
+            BlockKind::Upper.color(),
+            Some(0),
+            Some(Anchor::new_stack(AnchorType::Mark)),
+        )
+        .named("upper_cor");
 
-    let upper_alt_block = lower_alt_block.new_from_refs(
-        &mut ff_pos,
-        "S 1 0 0 1 -1000 500 2".to_string(),
-        LookupsMode::ComboLast,
-        Cc::Full,
-        false,
-        "joinStackTok_",
-        "",
-        "80ff80",
-        Some(0),
-        Some(Anchor::new_stack(AnchorType::Mark)),
-    );
+    let upper_ext_block = lower_ext_block
+        .derive(
+            &combos::UPPER_FROM_LOWER,
+            LookupsMode::ComboLast(Joiner::Stack),
+            Cc::Full,
+            false,
+            "joinStackTok_",
+            "Tok",
+            BlockKind::Upper.color(),
+            Some(0),
+            Some(Anchor::new_stack(AnchorType::Mark)),
+        )
+        .named("upper_ext");
 
-    let put_in_class = |orig: String| format!("Class: {} {}", orig.len(), orig);
+    let upper_alt_block = lower_alt_block
+        .derive(
+            &combos::UPPER_FROM_LOWER,
+            LookupsMode::ComboLast(Joiner::Stack),
+            Cc::Full,
+            false,
+            "joinStackTok_",
+            "",
+            BlockKind::Upper.color(),
+            Some(0),
+            Some(Anchor::new_stack(AnchorType::Mark)),
+        )
+        .named("upper_alt");
 
-    let space_calt = {
-        let names = vec![&base_cor_block, &base_ext_block, &base_alt_block]
+    let mut stack3_blocks = stack3::blocks(&[&lower_cor_block, &lower_ext_block, &lower_alt_block]);
+    stack3::declare_joiner(features);
+
+    let mut blocks = vec![
+        outer_cor_block,
+        outer_ext_block,
+        outer_alt_block,
+        inner_cor_block,
+        inner_ext_block,
+        inner_alt_block,
+        lower_cor_block,
+        lower_ext_block,
+        lower_alt_block,
+        upper_cor_block,
+        upper_ext_block,
+        upper_alt_block,
+    ];
+    blocks.append(&mut stack3_blocks);
+    Ok(blocks)
+}
+
+/// The blocks generated rather than drawn: the nested container extensions, the letters and
+/// numbers, and the spaces, underline, dotted circle, and fallback
+fn other_blocks(profile: Profile, options: &GenOptions) -> std::io::Result<Vec<GlyphBlock>> {
+    let ctrl_nested_block = containment::nested_ext_block();
+
+    let letters_block = if profile.has(Capability::LatinLigatures) {
+        names::letters_block().map_err(invalid_data)?
+    } else {
+        GlyphBlock::new_empty().named("letters")
+    };
+    let (numbers_block, ordinals_block) = numbers::blocks().map_err(invalid_data)?;
+
+    let mut blocks = vec![
+        ctrl_nested_block,
+        letters_block,
+        numbers_block,
+        ordinals_block,
+    ];
+    blocks.push(half_width::block(&options.metrics).map_err(invalid_data)?);
+    if let Some(spacing_block) = options.spacing.block(profile).map_err(invalid_data)? {
+        blocks.push(spacing_block);
+    }
+    blocks.push(underline::block().map_err(invalid_data)?);
+    blocks.push(dotted_circle::block().map_err(invalid_data)?);
+    blocks.push(fallback::block().map_err(invalid_data)?);
+    Ok(blocks)
+}
+
+/// Leaves out the blocks (and, for a subset, the words) `options` doesn't include, and what only they
+/// need, returning the blocks left and the names of the glyphs left out
+fn select_blocks(
+    blocks: Vec<GlyphBlock>,
+    options: &GenOptions,
+) -> (Vec<GlyphBlock>, HashSet<String>) {
+    let (mut blocks, excluded): (Vec<_>, Vec<_>) = blocks
+        .into_iter()
+        .partition(|block| options.blocks.includes(&block.name));
+    let mut excluded = excluded
+        .iter()
+        .flat_map(|block| block.full_names().map(|(name, _)| name.to_string()))
+        .collect::<HashSet<_>>();
+    if let Some(words) = &options.words {
+        for block in blocks
+            .iter_mut()
+            .filter(|block| subset::is_word_block(&block.name))
+        {
+            excluded.extend(block.retain_glyphs(|name| {
+                subset::word_of(name).is_some_and(|word| words.contains(word))
+            }));
+        }
+        // a subset is as small as it can be, so its blocks aren't lined up
+        for block in &mut blocks {
+            block.padding = Padding::None;
+        }
+    }
+    let left_out = dotted_circle::leave_out(&mut blocks, &excluded);
+    excluded.extend(left_out);
+    fallback::map_unmapped(&mut blocks);
+    selectors::map_variation_sequences(&mut blocks);
+    for block in &mut blocks {
+        if let Some((_, padding)) = options
+            .padding
             .iter()
-            .enumerate()
-            .map(|(i, block)| {
-                block
-                    .glyphs
-                    .iter()
-                    .filter_map(|glyph| {
-                        if glyph.glyph.name.contains("empty") {
-                            None
-                        } else {
-                            Some(format!(
-                                "{}{}",
-                                glyph.glyph.name,
-                                if i != 2 { "Tok" } else { "" }
-                            ))
-                        }
-                    })
-                    .join(" ")
-            })
-            .join(" ");
+            .rev()
+            .find(|(filter, _)| BlockFilter::matches(filter, &block.name))
+        {
+            block.padding = *padding;
+        }
+    }
+    (blocks, excluded)
+}
 
-        let prenames = (1..9)
-            .map(|x| format!("combCartExt{x}TickTok"))
-            .join(" ");
-        let prenames = format!("{prenames} endCartTok endLongGlyphTok endRevLongGlyphTok endCartAltTok teTok toTok middleDotTok colonTok middleDot2Tok middleDot3Tok");
+/// Processes the outlines of `blocks` as `profile` and `options` ask, and sizes and checks the
+/// glyphs
+fn process_outlines(
+    blocks: &mut Vec<GlyphBlock>,
+    profile: Profile,
+    options: &GenOptions,
+    features: &mut FeatureRegistry,
+) -> std::io::Result<()> {
+    if options.snap_to_grid {
+        for glyph in blocks
+            .iter_mut()
+            .filter(|block| grid::is_drawn_on_grid(&block.name))
+            .flat_map(|block| &mut block.glyphs)
+        {
+            glyph.glyph.rep.snap_to_grid().map_err(invalid_data)?;
+        }
+    }
+    if profile.outlines == Outlines::Mirrored {
+        mirror::mirror_directional(blocks).map_err(invalid_data)?;
+    }
+    let corner_radius = match profile.outlines {
+        Outlines::Sharp => Some(0.0),
+        Outlines::Small => None,
+        _ => options.corner_radius,
+    };
+    if let Some(radius) = corner_radius {
+        for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+            glyph
+                .glyph
+                .rep
+                .round_corners(radius)
+                .map_err(invalid_data)?;
+        }
+    }
+    if let Some(max_error) = options.simplify {
+        for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+            glyph.glyph.rep.simplify(max_error).map_err(invalid_data)?;
+        }
+    }
+    if profile.outlines == Outlines::Small {
+        optical::adapt_small(blocks).map_err(invalid_data)?;
+    }
+    if options.direction != Direction::default() {
+        for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+            glyph
+                .glyph
+                .rep
+                .direct(options.direction)
+                .map_err(invalid_data)?;
+        }
+    }
+    plugins::apply_all(&options.plugins, blocks).map_err(invalid_data)?;
+    widths::infer(blocks, &options.side_bearings).map_err(invalid_data)?;
+    if options.vertical {
+        vertical::add_vertical(blocks, features);
+    }
+    if options.metrics.units_per_em != Metrics::DRAWN.units_per_em {
+        options.metrics.scale_blocks(blocks).map_err(invalid_data)?;
+    }
+    if options.extract_refs {
+        shared_contours::extract(blocks).map_err(invalid_data)?;
+    }
+    check_unique_codepoints(blocks).map_err(invalid_data)?;
+    Ok(())
+}
+
+/// Declares what the glyphs of `blocks` substitute, leaving out the glyphs `excluded`, and places
+/// their carets and classes
+fn declare_features(
+    blocks: &mut [GlyphBlock],
+    excluded: &HashSet<String>,
+    profile: Profile,
+    options: &GenOptions,
+    spelled_names: &[String],
+    features: &mut FeatureRegistry,
+) -> std::io::Result<()> {
+    features.leave_out(excluded.clone());
+
+    let blocks_named = |names: &[&str]| {
+        blocks
+            .iter()
+            .filter(|block| names.contains(&block.name.as_str()))
+            .collect_vec()
+    };
+
+    for block in blocks.iter() {
+        block.declare_alternates(features, profile);
+    }
+    names::declare(spelled_names, features).map_err(invalid_data)?;
+    numbers::declare(features, profile);
+    dotted_circle::declare(blocks, features);
+    half_width::declare(blocks, features);
+    underline::declare(blocks, features);
+    options.spacing.declare(blocks, features, profile);
+    spellings::declare(&options.spellings, blocks, excluded, profile, features)
+        .map_err(invalid_data)?;
+    features
+        .add_character_variants(
+            RAND_VARIATIONS,
+            blocks_named(&["base_alt", "base_arrow"])
+                .into_iter()
+                .flat_map(|block| block.glyphs.iter().map(|glyph| glyph.glyph.name.as_ref())),
+        )
+        .map_err(invalid_data)?;
+    carets::place(blocks, features, profile);
+    gdef::assign(blocks, features, profile);
+    Ok(())
+}
 
-        let sp = put_in_class("space".to_string());
-        let tok = put_in_class(format!("{prenames} {names}"));
+/// Generates the contextual lookups' subtables, leaving the glyphs `excluded` out of their classes
+fn gen_contextual(
+    blocks: &[GlyphBlock],
+    excluded: &HashSet<String>,
+    features: &mut FeatureRegistry,
+) -> String {
+    // leaves out any glyphs that were filtered out of the font
+    let put_in_class = |orig: String| {
+        let orig = orig
+            .split(' ')
+            .filter(|name| !excluded.contains(*name))
+            .join(" ");
+        format!("Class: {} {}", orig.len(), orig)
+    };
 
-        let put_in_sub = |c: &str| format!("  {c}{sp}\n  {c}{tok}\n");
-        let subs = format!("{}{}{}", put_in_sub(""), put_in_sub("B"), put_in_sub("F"));
+    let space_calt = {
+        // a space is left out after a word, and after what ends one (the cartouche ticks, the ends
+        // of containers and quotes, and the punctuation)
+        let names = [
+            GlyphQuery::blocks(&["ctrl"]).filter(|glyph| glyph.glyph.name.contains("Tick")),
+            GlyphQuery::blocks(&["tok_ctrl"])
+                .without_tag(TAG_EXTENSION)
+                .without_names_containing(&["start", "join"]),
+            GlyphQuery::blocks(&["no_comb"]).without_names_containing(&["space"]),
+            GlyphQuery::blocks_starting_with(&["base_", "pack_"]),
+        ]
+        .iter()
+        .flat_map(|query| query.names(blocks))
+        .join(" ");
 
-        format!("ContextSub2: class \"'calt' REMOVE SPACE\" 3 3 3 1\n{subs}")
+        let mut builder = ContextualLookupBuilder::new(features, CALT_REMOVE_SPACE, "All_Others");
+        let sp = builder.class("sp", "space");
+        let tok = builder.class("tok", names);
+        builder.rule(ContextRule::new([tok, sp]).apply(1, SS00_SP_TO_ZWSP));
+        builder.build(put_in_class)
     };
 
+    let half_width_calt = half_width::gen_fpst(features, blocks, put_in_class);
+    let underline_ss04 = underline::gen_fpst(features, blocks, put_in_class);
+
     let zwj_calt = {
-        let scale_names = vec![&outer_cor_block, &outer_ext_block, &outer_alt_block]
+        let outer_glyphs =
+            GlyphQuery::blocks(&["outer_cor", "outer_ext", "outer_alt"]).select(blocks);
+        let scale_names = outer_glyphs
             .iter()
-            .enumerate()
-            .map(|(i, &block)| {
-                block
-                    .glyphs
-                    .iter()
-                    .filter_map(|glyph| {
-                        if glyph.glyph.name.contains("empty") {
-                            None
-                        } else {
-                            Some(format!(
-                                "{}{}",
-                                glyph.glyph.name,
-                                if i != 2 { "Tok" } else { "" }
-                            ))
-                        }
-                    })
-                    .join(" ")
+            .map(|(block, glyph)| {
+                format!(
+                    "{}{}",
+                    glyph.glyph.name,
+                    block.suffix.trim_end_matches("_joinScaleTok")
+                )
             })
             .join(" ");
-
-        let scale_glyphs = vec![&outer_cor_block, &outer_ext_block, &outer_alt_block]
+        let scale_glyphs = outer_glyphs
             .iter()
-            .map(|block| {
-                block
-                    .glyphs
-                    .iter()
-                    .filter_map(|glyph| {
-                        if glyph.glyph.name.contains("empty") {
-                            None
-                        } else {
-                            Some(glyph.glyph.name.clone())
-                        }
-                    })
-                    .collect_vec()
-            })
-            .flatten()
+            .map(|(_, glyph)| &glyph.glyph.name)
             .collect::<HashSet<_>>();
 
-        let stack_names = vec![&lower_cor_block, &lower_ext_block, &lower_alt_block]
-            .iter()
-            .enumerate()
-            .map(|(i, block)| {
-                block
-                    .glyphs
-                    .iter()
-                    .filter_map(|glyph| {
-                        if glyph.glyph.name.contains("empty")
-                            || glyph.glyph.name.contains("arrow")
-                            || scale_glyphs.contains(&glyph.glyph.name)
-                        {
-                            None
-                        } else {
-                            Some(format!(
-                                "{}{}",
-                                glyph.glyph.name,
-                                if i != 2 { "Tok" } else { "" }
-                            ))
-                        }
-                    })
-                    .join(" ")
+        let stack_names = GlyphQuery::blocks(&["lower_cor", "lower_ext", "lower_alt"])
+            .without_tag(TAG_DIRECTIONAL)
+            .filter(|glyph| !scale_glyphs.contains(&glyph.glyph.name))
+            .select(blocks)
+            .into_iter()
+            .map(|(block, glyph)| {
+                format!(
+                    "{}{}",
+                    glyph.glyph.name,
+                    block.suffix.trim_end_matches("_joinStackTok")
+                )
             })
             .join(" ");
 
+        let mut builder = ContextualLookupBuilder::new(features, CALT_CHANGE_ZWJ, "other");
+        let zwj = builder.class("zwj", "ZWJ");
+        let scale = builder.class("scale", scale_names);
+        let stack = builder.class("stack", stack_names);
 
-        let zwj = put_in_class("ZWJ".to_string());
-        let scale = put_in_class(scale_names);
-        let stack = put_in_class(stack_names);
-
-        let put_in_sub = |c: &str| format!("  {c}{zwj}\n  {c}{scale}\n  {c}{stack}\n");
-        let subs = format!("{}{}{}", put_in_sub(""), put_in_sub("B"), put_in_sub("F"));
-
-        format!("ContextSub2: class \"'calt' CHANGE ZWJ\" 4 4 4 2\n{subs}")
+        // 3-stacks are matched first, since they start like a two-glyph combination
+        for rule in stack3::zwj_rules(zwj, &[scale, stack]) {
+            builder.rule(rule);
+        }
+        builder
+            .rule(ContextRule::new([scale, zwj]).apply(1, SS01_ZWJ_TO_SCALE))
+            .rule(ContextRule::new([stack, zwj]).apply(1, SS02_ZWJ_TO_STACK));
+        builder.build(put_in_class)
     };
 
-    let mut main_blocks = vec![
-        latn_block,      no_comb_block,   radicals_block,
-        base_cor_block,  base_ext_block,  base_alt_block,
-        outer_cor_block, outer_ext_block, outer_alt_block,
-        inner_cor_block, inner_ext_block, inner_alt_block,
-        lower_cor_block, lower_ext_block, lower_alt_block,
-        upper_cor_block, upper_ext_block, upper_alt_block,
-    ];
-
     let chain_calt = {
-        let put_in_class = |orig: String| format!("Class: {} {}", orig.len(), orig);
-
         let base = {
-            let ctrl_names = ctrl_block
-                .glyphs
-                .iter()
-                .filter_map(|glyph| {
-                    if glyph.glyph.name.contains("Half") || glyph.glyph.name.contains("Tick") {
-                        None
-                    } else {
-                        Some(format!(
-                            "{}{}{}",
-                            ctrl_block.prefix, glyph.glyph.name, ctrl_block.suffix
-                        ))
-                    }
-                })
+            let ctrl_names = GlyphQuery::blocks(&["ctrl"])
+                .without_names_containing(&["Half", "Tick"])
+                .names(blocks)
                 .join(" ");
 
-            let main_names = main_blocks
-                .iter()
-                .map(|block| {
-                    block
-                        .glyphs
-                        .iter()
-                        .map(|glyph| format!("{}{}{}", block.prefix, glyph.glyph.name, block.suffix))
-                        .join(" ")
-                })
-                .join(" ");
+            let main_names = GlyphQuery::all_blocks_except(&[
+                "ctrl",
+                "tok_ctrl",
+                "start_long_glyph",
+                "names",
+                "ctrl_nested",
+                "letters",
+                "ordinals",
+            ])
+            .names(blocks)
+            .join(" ");
 
-            put_in_class(format!(
-                "{} joinStackTok joinScaleTok {}",
-                ctrl_names, main_names
-            ))
+            format!("{} joinStackTok joinScaleTok {}", ctrl_names, main_names)
         };
 
-        let cart = put_in_class(
-            format!("{} {} {}",
-                "combCartExtHalfTok combCartExtNoneTok",
-                (1..=8).map(|x| format!("combCartExt{}TickTok", x)).join(" "),
-                "startCartTok combCartExtTok startCartAltTok"
-            )
-        );
-                // .iter()
-                // .map(|s| format!("{}Tok", s))
+        // only the long glyphs that extend forward are continued by the glyphs after them
+        let longs = GlyphQuery::blocks(&["start_long_glyph"])
+            .filter(|glyph| {
+                !matches!(
+                    glyph.lookups,
+                    Lookups::StartLongGlyph(ContainerDirection::Reverse)
+                )
+            })
+            .names(blocks)
+            .into_iter()
+            .filter(|name| !excluded.contains(name))
+            .collect_vec();
 
-        let cont =  {
-            let longs = start_long_glyph_block
-                .glyphs
-                .iter()
-                .filter_map(|glyph| {
-                    if glyph.glyph.name.eq("laTok") {
-                        None
-                    } else {
-                        Some(format!(
-                            "{}{}{}",
-                            start_long_glyph_block.prefix,
-                            glyph.glyph.name,
-                            start_long_glyph_block.suffix
-                        ))
-                    }
-                })
-                .join(" ");
+        containment::declare_transitions(features, &longs);
+        containment::gen_fpst(features, base, &longs, put_in_class)
+    };
 
-            put_in_class(format!("combLongGlyphExtHalfTok startLongPiTok combLongPiExtTok startLongGlyphTok combLongGlyphExtTok startRevLongGlyphTok {}", longs))
-        };
+    format!("{space_calt}{half_width_calt}{zwj_calt}{chain_calt}{underline_ss04}")
+}
 
-        let put_in_sub = |c: &str| format!("  {c}{base}\n  {c}{cart}\n  {c}{cont}\n");
-        let subs = format!("{}{}{}", put_in_sub(""), put_in_sub("B"), put_in_sub("F"));
-        format!("ChainSub2: class \"'calt' CART AND CONT\" 4 4 4 2\n{subs}")
-    };
+/// Gives the glyphs of `blocks` their slots and resolves their references, returning how many
+/// slots there are
+fn allocate_slots(blocks: &mut Vec<GlyphBlock>) -> std::io::Result<usize> {
+    // the slots are given out once every block is built, so they don't depend on the order they
+    // were built in
+    let ff_pos = slots::allocate(blocks).map_err(invalid_data)?;
+    for block in blocks.iter() {
+        trace_block(block);
+    }
+    let mut names = NameRegistry::default();
+    for block in blocks.iter() {
+        names.register_block(block).map_err(invalid_data)?;
+    }
+    for block in blocks.iter_mut() {
+        block.resolve_refs(&names).map_err(invalid_data)?;
+    }
+    Ok(ff_pos)
+}
 
-    let mut meta_block = vec![ctrl_block, tok_ctrl_block, start_long_glyph_block];
-    meta_block.append(&mut main_blocks);
-    let glyphs_string = format!(
-        "{}",
-        meta_block.iter().map(|block| block.gen(variation)).join("")
-    );
+/// Generates every variation, compiling them and bundling them for the web if asked to, and reusing
+/// the glyphs in `cache` that haven't changed (if there is one)
+fn build(
+    cli: &Cli,
+    options: &GenOptions,
+    mut cache: Option<&mut GlyphCache>,
+) -> std::io::Result<()> {
+    let mut profiles = vec![Profile::MAIN, Profile::UCSUR];
+    if cli.rtl {
+        profiles.push(Profile::RTL);
+    }
+    if cli.sharp {
+        profiles.push(Profile::SHARP);
+    }
+    if cli.small {
+        profiles.push(Profile::SMALL);
+    }
+    let svg_style = load_svg_style(cli)?;
+    for profile in profiles {
+        build_profile(cli, profile, options, &svg_style, cache.as_deref_mut())?;
+    }
+    if cli.target == Target::Web && !options.dry_run {
+        web::write_bundle(&cli.dist)?;
+    }
+    Ok(())
+}
 
-    let time = std::time::UNIX_EPOCH.elapsed().unwrap().as_secs();
+fn load_svg_style(cli: &Cli) -> std::io::Result<SvgStyle> {
+    match &cli.svg_style {
+        Some(path) => SvgStyle::load(path),
+        None => Ok(SvgStyle::default()),
+    }
+}
 
-    let filename = format!(
-        "nasin-nanpa-{VERSION}{}.sfd",
-        if variation == NasinNanpaVariation::Ucsur {
-            "-UCSUR"
-        } else {
-            ""
+/// Generates `profile`, with its color and SVG tables if asked to, and compiles it if asked to
+fn build_profile(
+    cli: &Cli,
+    profile: Profile,
+    options: &GenOptions,
+    svg_style: &SvgStyle,
+    cache: Option<&mut GlyphCache>,
+) -> std::io::Result<()> {
+    let font = gen_nasin_nanpa(profile, options, cli.manifest, cache)?;
+    let color_table = if cli.color {
+        let table = color::table(&font);
+        (!options.dry_run)
+            .then(|| table.write(sfd_filename(profile)))
+            .transpose()?
+    } else {
+        None
+    };
+    let svg_table = if cli.svg {
+        let table = svg_table::table(&font, svg_style).map_err(invalid_data)?;
+        (!options.dry_run)
+            .then(|| table.write(sfd_filename(profile)))
+            .transpose()?
+    } else {
+        None
+    };
+    if cli.compiles() {
+        conformance::report(&font).gate()?;
+        if options.dry_run {
+            return Ok(());
         }
-    );
-    let mut file = File::create(filename)?;
-
-    // FINAL `.sfd` COMPOSITIION
-    writeln!( &mut file,
-r#"{HEADER}Version: {VERSION}
-{DETAILS1}ModificationTime: {time}{DETAILS2}{LOOKUPS}DEI: 91125
-{space_calt}{AFTER_SPACE_CALT}{zwj_calt}{AFTER_ZWJ_CALT}{chain_calt}{AFTER_CHAIN_CALT}{VERSION}{OTHER}BeginChars: {ff_pos} {ff_pos}
-{glyphs_string}EndChars
-EndSplineFont"#
-    )
+        let fonts = fontforge::compile(
+            sfd_filename(profile),
+            &cli.dist,
+            cli.compile_formats(),
+            cli.autohint,
+        )?;
+        if let Some(color_table) = color_table {
+            color::apply(color_table, &fonts)?;
+        }
+        if let Some(svg_table) = svg_table {
+            svg_table::apply(svg_table, &fonts)?;
+        }
+    }
+    Ok(())
 }
 
 fn main() -> std::io::Result<()> {
-    gen_nasin_nanpa(NasinNanpaVariation::Main)?;
-    gen_nasin_nanpa(NasinNanpaVariation::Ucsur)?;
+    let cli = Cli::parse();
+    if cli.trace {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(std::io::stderr)
+            .without_time()
+            .init();
+    }
+    let options = cli.gen_options()?;
+    match &cli.command {
+        None => build(&cli, &options, None)?,
+        Some(Command::Build {
+            profiles,
+            all,
+            config,
+        }) => {
+            let config = profiles::load(config)?;
+            let names = if *all {
+                config.names()
+            } else {
+                profiles.clone()
+            };
+            let named = names
+                .iter()
+                .map(|name| config.resolve(name))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid_data)?;
+            for (a, b) in named.iter().tuple_combinations() {
+                if sfd_filename(a.profile) == sfd_filename(b.profile) {
+                    return Err(invalid_data(format!(
+                        "the profiles {} and {} would both be written to {}",
+                        a.name,
+                        b.name,
+                        sfd_filename(a.profile)
+                    )));
+                }
+            }
+            let svg_style = load_svg_style(&cli)?;
+            for named in named {
+                let options = cli.gen_options()?;
+                let options = GenOptions {
+                    words: named.words,
+                    blocks: BlockFilter {
+                        include: options
+                            .blocks
+                            .include
+                            .into_iter()
+                            .chain(named.include_blocks)
+                            .collect(),
+                        exclude: options
+                            .blocks
+                            .exclude
+                            .into_iter()
+                            .chain(named.exclude_blocks)
+                            .collect(),
+                    },
+                    corner_radius: named.corner_radius.or(options.corner_radius),
+                    ..options
+                };
+                build_profile(&cli, named.profile, &options, &svg_style, None)?;
+            }
+            if cli.target == Target::Web && !cli.dry_run {
+                web::write_bundle(&cli.dist)?;
+            }
+        }
+        Some(Command::Split) => {
+            let svg_style = load_svg_style(&cli)?;
+            for part in &split::PARTS {
+                let options = cli.gen_options()?;
+                let options = GenOptions {
+                    blocks: part.filter(options.blocks),
+                    name_suffix: part.suffix,
+                    ..options
+                };
+                let mut profile = Profile::MAIN;
+                profile.suffix = part.suffix;
+                build_profile(&cli, profile, &options, &svg_style, None)?;
+            }
+        }
+        Some(Command::Watch) => {
+            let mut paths = vec![cli.packs_dir.clone()];
+            paths.extend(cli.packs.iter().cloned());
+            paths.extend(cli.svg_dirs.iter().cloned());
+            let mut cache = GlyphCache::default();
+            watch::watch(&paths, || {
+                build(&cli, &cli.gen_options()?, Some(&mut cache))?;
+                let (generated, reused) = cache.finish_build();
+                println!(
+                    "generated nasin nanpa {VERSION} ({generated} glyphs regenerated, {reused} reused)"
+                );
+                Ok(())
+            })?;
+        }
+        Some(Command::Print { ucsur }) => {
+            let profile = Profile::from_flag(*ucsur);
+            std::io::stdout()
+                .lock()
+                .write_all(gen_checked_sfd(profile, &options)?.as_bytes())?;
+        }
+        Some(Command::Patch {
+            sfd,
+            glyphs,
+            blocks,
+            ucsur,
+            output,
+        }) => {
+            let profile = Profile::from_flag(*ucsur);
+            let font = gen_font(profile, &options)?;
+            let (patched, count) =
+                patch::patch(&std::fs::read_to_string(sfd)?, &font, glyphs, blocks)
+                    .and_then(|(patched, count)| {
+                        sfd_check::check(&patched).map(|()| (patched, count))
+                    })
+                    .map_err(invalid_data)?;
+            let output = output.as_ref().unwrap_or(sfd);
+            if options.dry_run {
+                println!("would patch {count} glyphs into {}", output.display());
+            } else {
+                std::fs::write(output, patched)?;
+                println!("patched {count} glyphs into {}", output.display());
+            }
+        }
+        Some(Command::Browse { ucsur }) => {
+            let profile = Profile::from_flag(*ucsur);
+            browse::browse(&gen_font(profile, &options)?)?;
+        }
+        Some(Command::Png {
+            glyphs,
+            texts,
+            sequences,
+            size,
+            ucsur,
+            out,
+        }) => {
+            let profile = Profile::from_flag(*ucsur);
+            let font = gen_font(profile, &options)?;
+            preview::write_pngs(&font, glyphs, texts, sequences, *size, out)?;
+        }
+        Some(Command::TestShaping { font, corpus }) => {
+            let font = match font {
+                Some(font) => font.clone(),
+                None if options.dry_run => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "test-shaping compiles the generated .sfd, which --dry-run doesn't write; \
+                         pass a compiled font with --font",
+                    ));
+                }
+                None => {
+                    gen_nasin_nanpa(Profile::MAIN, &options, false, None)?;
+                    let sfd = sfd_filename(Profile::MAIN);
+                    fontforge::compile(sfd, &cli.dist, &[FontFormat::Ttf], cli.autohint)?.remove(0)
+                }
+            };
+            let cases = shaping::load_corpus(corpus)?;
+            shaping::run(&font, &cases)?;
+        }
+        Some(Command::Coverage { format }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            coverage::report(&font).print(*format)?;
+        }
+        Some(Command::AuditMetrics { format }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            metrics::report(&font)
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::AuditOs2 { format }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            os2::report(&font).print(*format)?;
+        }
+        Some(Command::AuditHinting { format }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            hinting::report(&font)
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::AuditOutlines { format }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            outlines::report(&font, options.direction)
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::AuditGrid { blocks, format }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            grid::report(&font, blocks)
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::AuditWidths { format }) => {
+            let unchanged = GenOptions {
+                side_bearings: vec![],
+                ..cli.gen_options()?
+            };
+            let mut font = gen_font(Profile::MAIN, &unchanged)?;
+            let scale = font.metrics.scale();
+            widths::report(&mut font.blocks, &options.side_bearings, scale)
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::AuditSimplify {
+            blocks,
+            max_error,
+            format,
+        }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            simplify::report(&font, blocks, max_error * font.metrics.scale())
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::Similar {
+            blocks,
+            tolerance,
+            format,
+        }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            similarity::report(&font, blocks, *tolerance)
+                .map_err(invalid_data)?
+                .print(*format)?;
+        }
+        Some(Command::VisualDiff {
+            old,
+            new,
+            ucsur,
+            size,
+            threshold,
+            out,
+            format,
+        }) => {
+            let old = preview::Previewer::load(old)?;
+            let new = match new {
+                Some(new) => preview::Previewer::load(new)?,
+                None => {
+                    let profile = Profile::from_flag(*ucsur);
+                    preview::Previewer::new(&gen_font(profile, &options)?).map_err(invalid_data)?
+                }
+            };
+            visual_diff::report(&old, &new, *size, *threshold, out)?.print(*format)?;
+        }
+        Some(Command::Conformance { main, format }) => {
+            let profile = Profile::from_flag(!*main);
+            let font = gen_font(profile, &options)?;
+            conformance::report(&font).print(*format)?;
+        }
+        Some(Command::Glyph { name, ucsur }) => {
+            let profile = Profile::from_flag(*ucsur);
+            let font = gen_font(profile, &options)?;
+            let glyph = font.glyph(name).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("there's no glyph {name}"),
+                )
+            })?;
+            println!("width     {}", glyph.glyph.width);
+            if let EncPos::Pos(codepoint) = glyph.encoding.enc_pos {
+                println!("codepoint U+{codepoint:04X}");
+            }
+            for lookup in font.lookups_for(name) {
+                println!("lookup    {lookup}");
+            }
+        }
+        Some(Command::ListGlyphs {
+            blocks,
+            ucsur,
+            format,
+        }) => {
+            let profile = Profile::from_flag(*ucsur);
+            let font = gen_font(profile, &options)?;
+            let manifest = Manifest::new(&font);
+            glyph_list::print(&glyph_list::select(&manifest, blocks), *format)?;
+        }
+        Some(Command::Linku {
+            data,
+            categories,
+            format,
+        }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            let linku = linku::Linku::load(data.as_deref())?;
+            let categories = if categories.is_empty() {
+                linku::DEFAULT_CATEGORIES.map(String::from).to_vec()
+            } else {
+                categories.clone()
+            };
+            linku::report(&font, &linku, &categories).print(*format)?;
+        }
+        Some(Command::Credits { output, provenance }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            let provenances = credits::Provenances::new(&font);
+            std::fs::write(output, provenances.credits())?;
+            let json = serde_json::to_string_pretty(&provenances).map_err(invalid_data)?;
+            std::fs::write(provenance, json)?;
+        }
+        Some(Command::Transliteration { output }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            std::fs::write(output, Manifest::new(&font).transliteration_table())?;
+        }
+        Some(Command::CheatSheet { markdown, html }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            let cheat_sheet = cheat_sheet::CheatSheet::new(&font);
+            std::fs::write(markdown, cheat_sheet.markdown())?;
+            std::fs::write(html, cheat_sheet.html())?;
+        }
+        Some(Command::DataCrate { output }) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            data_crate::write(&Manifest::new(&font), output)?;
+        }
+        Some(Command::Script { ucsur, output }) => {
+            let profile = Profile::from_flag(*ucsur);
+            let sfd = gen_checked_sfd(profile, &options)?;
+            let script = ff_script::script(&sfd).map_err(invalid_data)?;
+            let output = output.clone().unwrap_or_else(|| {
+                format!("{}.py", sfd_filename(profile).trim_end_matches(".sfd")).into()
+            });
+            std::fs::write(output, script)?;
+        }
+        Some(Command::Legend) => {
+            let font = gen_font(Profile::MAIN, &options)?;
+            std::fs::write("legend.html", block_kind::legend(&font))?;
+        }
+        Some(Command::Proof) => {
+            let font = gen_font(Profile::UCSUR, &options)?;
+            std::fs::create_dir_all(&cli.dist)?;
+            std::fs::write(cli.dist.join("nasin-nanpa.css"), web::css())?;
+            std::fs::write(cli.dist.join("proof.html"), proof::proof(&font))?;
+        }
+        Some(Command::Subset {
+            words,
+            corpus,
+            tags,
+            ucsur,
+            output,
+        }) => {
+            let mut words = words.iter().cloned().collect::<HashSet<_>>();
+            if let Some(corpus) = corpus {
+                words.extend(subset::words_in(&std::fs::read_to_string(corpus)?));
+            }
+            for tag in tags {
+                words.extend(subset::words_tagged(tag));
+            }
+            let profile = Profile::from_flag(*ucsur);
+            let options = GenOptions {
+                words: Some(words),
+                ..cli.gen_options()?
+            };
+            let sfd = gen_checked_sfd(profile, &options)?;
+            if !options.dry_run {
+                std::fs::write(output, sfd)?;
+            }
+            if cli.compiles() && !options.dry_run {
+                fontforge::compile(output, &cli.dist, cli.compile_formats(), cli.autohint)?;
+            }
+        }
+        Some(Command::Snapshot { dir, bless }) => {
+            for profile in snapshot::PROFILES {
+                let options = snapshot::options();
+                let font = gen_font(profile, &options)?;
+                let sfd = gen_sfd(&font, &options)?;
+                let path = snapshot::path(dir, profile);
+                if *bless {
+                    snapshot::bless(&path, &sfd)?;
+                } else {
+                    snapshot::check(&path, &sfd)?;
+                }
+            }
+        }
+    }
     Ok(())
 }
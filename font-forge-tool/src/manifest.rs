@@ -0,0 +1,131 @@
+//! A machine-readable manifest of every glyph in a generated font, written next to the `.sfd`
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{ascii, ffir::TAG_PADDING, glyph_blocks::VERSION, EncPos, Font};
+
+#[derive(Serialize)]
+pub struct ManifestGlyph {
+    pub name: String,
+    pub codepoint: Option<String>,
+    pub width: usize,
+    pub block: String,
+    pub color: String,
+    /// The lookups that substitute this glyph, produce it, or use it as a component
+    pub lookups: Vec<String>,
+    /// The ASCII text that ligates into this glyph
+    pub spellings: Vec<String>,
+    /// The glyphs a combination glyph stands for, in the order they're typed (none for the
+    /// others), so text drawn with it can be copied back out
+    pub decomposition: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct Manifest {
+    pub version: &'static str,
+    pub glyphs: Vec<ManifestGlyph>,
+}
+
+impl Manifest {
+    /// Collects the manifest of `font`, leaving out padding glyphs
+    pub fn new(font: &Font) -> Self {
+        let mut lookups: HashMap<String, HashSet<&str>> = HashMap::new();
+        let mut spellings: HashMap<String, Vec<String>> = HashMap::new();
+        let mut glyphs = vec![];
+
+        for block in &font.blocks {
+            for (name, glyph) in block.full_names() {
+                if glyph.glyph.has_tag(TAG_PADDING) {
+                    continue;
+                }
+                let name = name.to_string();
+                let generated = glyph.gen(
+                    &block.prefix,
+                    &block.suffix,
+                    &block.color,
+                    font.profile,
+                    &font.features,
+                );
+                for line in generated.lines() {
+                    let Some((lookup, args)) = font.features.parse_line(line) else {
+                        continue;
+                    };
+                    lookups.entry(name.clone()).or_default().insert(lookup);
+                    for other in args.split_whitespace() {
+                        lookups.entry(other.to_string()).or_default().insert(lookup);
+                    }
+                    if line.starts_with("Ligature2:") {
+                        if let Some(spelling) = ascii::spelling(args) {
+                            spellings.entry(name.clone()).or_default().push(spelling);
+                        }
+                    }
+                }
+
+                let decomposition = glyph
+                    .lookups
+                    .decomposition(&name)
+                    .map(|parts| parts.map(String::from).to_vec())
+                    .unwrap_or_default();
+                glyphs.push(ManifestGlyph {
+                    codepoint: match glyph.encoding.enc_pos {
+                        EncPos::Pos(codepoint) => Some(format!("U+{codepoint:04X}")),
+                        EncPos::None => None,
+                    },
+                    width: glyph.glyph.width,
+                    block: block.name.clone(),
+                    color: block.color.clone(),
+                    lookups: vec![],
+                    spellings: vec![],
+                    decomposition,
+                    name,
+                });
+            }
+        }
+
+        for glyph in &mut glyphs {
+            if let Some(used) = lookups.get(&glyph.name) {
+                glyph.lookups = font
+                    .features
+                    .lookup_names()
+                    .filter(|lookup| used.contains(lookup))
+                    .map(String::from)
+                    .collect();
+            }
+            glyph.spellings = spellings.remove(&glyph.name).unwrap_or_default();
+        }
+
+        Manifest {
+            version: VERSION,
+            glyphs,
+        }
+    }
+
+    /// The table behind `translit::ascii_to_ucsur`: every spelling of an encoded sitelen pona glyph
+    pub fn transliteration_table(&self) -> String {
+        let mut entries = self
+            .glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let codepoint = glyph.codepoint.as_ref()?.strip_prefix("U+")?;
+                let codepoint = u32::from_str_radix(codepoint, 16).ok()?;
+                (codepoint >= 0x80).then_some((codepoint, glyph))
+            })
+            .flat_map(|(codepoint, glyph)| {
+                glyph
+                    .spellings
+                    .iter()
+                    .filter(|spelling| !spelling.contains(' '))
+                    .map(move |spelling| format!("{spelling}\t{codepoint:04X}\n"))
+            })
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries.dedup();
+        format!(
+            "# The ASCII spellings the font ligates, and the codepoints they become.\n\
+             # Generated by `font-forge-tool transliteration`; don't edit by hand.\n{}",
+            entries.concat()
+        )
+    }
+}
@@ -0,0 +1,204 @@
+//! An audit of the glyphs' ink against the em box and their declared widths
+
+use std::{collections::HashMap, io};
+
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, em::Metrics, ffir::*, spline::*, Font};
+
+/// How far past a limit ink can go before it's reported, for rounding in the drawings (on the
+/// drawn em)
+const TOLERANCE: f64 = 1.0;
+
+/// How far round glyphs can dip below the baseline before it counts as going into the descent
+/// (on the drawn em)
+const OVERSHOOT: f64 = 10.0;
+
+/// Latin glyphs that are drawn into the descent on purpose
+const DESCENDERS: [&str; 14] = [
+    "g",
+    "j",
+    "p",
+    "q",
+    "y",
+    "comma",
+    "semicolon",
+    "parenleft",
+    "parenright",
+    "bracketleft",
+    "bracketright",
+    "braceleft",
+    "braceright",
+    "underscore",
+];
+
+/// The parts of the names of the glyphs that draw cartouches, long glyphs and underlines, which
+/// reach past the em box and their width on purpose to join up with their neighbors
+pub const FRAME_PARTS: [&str; 4] = ["Cart", "LongGlyph", "LongPi", "Underline"];
+
+/// The box around a glyph's ink (including its references)
+#[derive(Clone, Copy)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    fn union(self, other: Self) -> Self {
+        Self {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// The box around this one after `transform`
+    fn transformed(self, transform: &Transform) -> Self {
+        let corners = [
+            Point::new(self.min.x, self.min.y),
+            Point::new(self.min.x, self.max.y),
+            Point::new(self.max.x, self.min.y),
+            Point::new(self.max.x, self.max.y),
+        ]
+        .map(|p| transform.apply(p));
+        corners[1..].iter().fold(
+            Self {
+                min: corners[0],
+                max: corners[0],
+            },
+            |bounds, &p| bounds.union(Self { min: p, max: p }),
+        )
+    }
+}
+
+#[derive(Serialize)]
+pub struct Finding {
+    name: String,
+    block: String,
+    width: usize,
+    /// The box around the ink, as `[left, bottom, right, top]`
+    ink: [f64; 4],
+    problem: String,
+}
+
+#[derive(Serialize)]
+pub struct MetricsReport {
+    glyphs: usize,
+    findings: Vec<Finding>,
+}
+
+/// The problems with a glyph's ink, given its declared width
+fn problems(
+    metrics: &Metrics,
+    block: &str,
+    name: &str,
+    width: usize,
+    bounds: Bounds,
+) -> Vec<String> {
+    let Bounds { min, max } = bounds;
+    let tolerance = TOLERANCE * metrics.scale();
+    let mut problems = vec![];
+    if block == "latn" && min.y < -OVERSHOOT * metrics.scale() && !DESCENDERS.contains(&name) {
+        problems.push(format!(
+            "ink reaches {} into the descent",
+            gen_coord(-min.y.round())
+        ));
+    }
+    if FRAME_PARTS.iter().any(|part| name.contains(part)) {
+        return problems;
+    }
+
+    if min.y < -(metrics.descent as f64) - tolerance || max.y > metrics.ascent as f64 + tolerance {
+        problems.push(format!(
+            "ink from {} to {} goes past the em box",
+            gen_coord(min.y.round()),
+            gen_coord(max.y.round()),
+        ));
+    }
+
+    // zero-width glyphs combine with the glyph before them, so they're drawn to the left
+    let (left, right) = match width {
+        0 => (-(metrics.units_per_em as f64), 0.0),
+        width => (0.0, width as f64),
+    };
+    if min.x < left - tolerance || max.x > right + tolerance {
+        problems.push(format!(
+            "ink from {} to {} doesn't fit its width of {width}",
+            gen_coord(min.x.round()),
+            gen_coord(max.x.round()),
+        ));
+    }
+    problems
+}
+
+/// Measures the ink of `rep`, given the ink of the glyphs measured so far (by full name), which
+/// its references should be among
+pub fn ink_bounds(
+    rep: &Rep,
+    measured: &HashMap<String, Option<Bounds>>,
+) -> Result<Option<Bounds>, String> {
+    let mut bounds = SplineSet::parse(rep.spline_set())?
+        .bounds()
+        .map(|(min, max)| Bounds { min, max });
+    for reference in rep.references() {
+        let Some(Some(target)) = measured.get(reference.target()) else {
+            continue;
+        };
+        let shown = target.transformed(&reference.transform());
+        bounds = Some(bounds.map_or(shown, |bounds| bounds.union(shown)));
+    }
+    Ok(bounds)
+}
+
+/// Measures the ink of every glyph in `font` (in order, so references are measured before the
+/// glyphs that use them) and checks it against the em box and each glyph's width
+pub fn report(font: &Font) -> Result<MetricsReport, String> {
+    let mut measured = HashMap::<String, Option<Bounds>>::new();
+    let mut report = MetricsReport {
+        glyphs: 0,
+        findings: vec![],
+    };
+    for block in &font.blocks {
+        for (name, glyph) in block.full_names() {
+            let bounds = ink_bounds(&glyph.glyph.rep, &measured)?;
+            measured.insert(name.to_string(), bounds);
+
+            report.glyphs += 1;
+            let Some(bounds) = bounds else {
+                continue;
+            };
+            let width = glyph.glyph.width;
+            for problem in problems(&font.metrics, &block.name, name.as_str(), width, bounds) {
+                report.findings.push(Finding {
+                    name: name.to_string(),
+                    block: block.name.clone(),
+                    width,
+                    ink: [bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y]
+                        .map(|c| c.round()),
+                    problem,
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+impl MetricsReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!("{} findings in {} glyphs", self.findings.len(), self.glyphs);
+                for finding in &self.findings {
+                    println!(
+                        "{:<12} {}  {}",
+                        finding.block, finding.name, finding.problem
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
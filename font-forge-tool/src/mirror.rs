@@ -0,0 +1,50 @@
+//! The right-to-left variation, for experiments: glyphs with a direction (arrows, `tawa`, and the
+//! pieces of cartouches and long glyphs) are mirrored, so they point and extend the other way.
+//! Cartouches need nothing else: a mirrored start piece closes on the right, where RTL text starts.
+
+use std::collections::HashMap;
+
+use crate::ffir::*;
+
+/// Whether the glyph `full_name` has a direction, and so is mirrored
+fn is_directional(full_name: &str) -> bool {
+    [
+        "arrow",
+        "tawaTok",
+        "Cart",
+        "LongGlyph",
+        "LongPi",
+        "Underline",
+    ]
+    .iter()
+    .any(|part| full_name.contains(part))
+}
+
+/// Mirrors every directional glyph in `blocks`, along with its anchor
+pub fn mirror_directional(blocks: &mut [GlyphBlock]) -> Result<(), String> {
+    let widths = blocks
+        .iter()
+        .flat_map(|block| block.full_names())
+        .filter(|(name, _)| is_directional(name.as_str()))
+        .map(|(name, glyph)| (name.to_string(), glyph.glyph.width))
+        .collect::<HashMap<_, _>>();
+
+    for block in blocks.iter_mut() {
+        let names = block.full_names().map(|(name, _)| name).collect::<Vec<_>>();
+        for (name, glyph) in names.iter().zip(&mut block.glyphs) {
+            if !widths.contains_key(name.as_str()) {
+                continue;
+            }
+            let width = glyph.glyph.width;
+            glyph
+                .glyph
+                .rep
+                .mirror(width, |target| widths.get(target).copied())
+                .map_err(|e| format!("{name}: {e}"))?;
+            if let Some(anchor) = &mut glyph.glyph.anchor {
+                anchor.mirror(width);
+            }
+        }
+    }
+    Ok(())
+}
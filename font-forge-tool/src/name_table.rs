@@ -0,0 +1,115 @@
+//! The `name` table (FontForge's `LangName`): its entries are kept as plain text here, and encoded
+//! the way FontForge writes strings in an `.sfd` (UTF-7) when the header is generated
+
+use crate::{credits::FONT_DESIGNER, glyph_blocks::VERSION};
+
+/// The language the entries are in (English, US)
+const LANGUAGE: u16 = 1033;
+
+/// The `name` table's name IDs that nasin nanpa sets
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NameId {
+    Version = 5,
+    Trademark = 7,
+    Designer = 9,
+    DesignerUrl = 12,
+    License = 13,
+    LicenseUrl = 14,
+    TypographicFamily = 16,
+    TypographicSubfamily = 17,
+}
+
+pub struct NameTable {
+    language: u16,
+    /// The entries, by name ID (an empty one isn't set)
+    entries: Vec<String>,
+}
+
+impl NameTable {
+    pub fn new(language: u16) -> Self {
+        Self {
+            language,
+            entries: vec![],
+        }
+    }
+
+    /// nasin nanpa's entries, for a font of the family `family`
+    pub fn nasin_nanpa(family: &str) -> Self {
+        let mut table = Self::new(LANGUAGE);
+        table
+            .set(NameId::Version, VERSION)
+            .set(NameId::Trademark, format!("{FONT_DESIGNER} 2023"))
+            .set(NameId::Designer, FONT_DESIGNER)
+            .set(NameId::DesignerUrl, "https://etbcor.com/")
+            .set(NameId::License, "MIT License")
+            .set(NameId::LicenseUrl, "https://opensource.org/licenses/MIT")
+            .set(NameId::TypographicFamily, family)
+            .set(NameId::TypographicSubfamily, "Regular");
+        table
+    }
+
+    pub fn set(&mut self, id: NameId, value: impl Into<String>) -> &mut Self {
+        let id = id as usize;
+        if self.entries.len() <= id {
+            self.entries.resize(id + 1, String::new());
+        }
+        self.entries[id] = value.into();
+        self
+    }
+
+    /// Generates the `LangName` line
+    pub fn gen(&self) -> String {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| format!(" \"{}\"", utf7(entry)))
+            .collect::<String>();
+        format!("LangName: {}{entries}\n", self.language)
+    }
+}
+
+/// Whether FontForge writes `c` as it is in a UTF-7 string
+fn is_direct(c: char) -> bool {
+    (' '..'\x7f').contains(&c) && !matches!(c, '"' | '+' | '=' | '\\' | '~')
+}
+
+/// Whether `c` could be read as part of a run of base64 (so a run before it has to end with `-`)
+fn is_base64(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '-')
+}
+
+/// Encodes `s` the way FontForge does: each run of characters that aren't written as they are is
+/// a `+`, then its UTF-16 (padded with zeros to whole groups of three bytes) in base64, then a `-`
+/// if what follows could be mistaken for more of the run
+fn utf7(s: &str) -> String {
+    const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_direct(c) {
+            out.push(c);
+            continue;
+        }
+        let mut run = vec![c];
+        while let Some(c) = chars.next_if(|c| !is_direct(*c)) {
+            run.push(c);
+        }
+        let mut bytes = run
+            .iter()
+            .flat_map(|c| c.encode_utf16(&mut [0; 2]).to_vec())
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<_>>();
+        bytes.resize(bytes.len().div_ceil(3) * 3, 0);
+        out.push('+');
+        for group in bytes.chunks(3) {
+            let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+            for shift in [18, 12, 6, 0] {
+                out.push(BASE64[(bits >> shift) as usize & 0x3f] as char);
+            }
+        }
+        if chars.peek().is_some_and(|c| is_base64(*c)) {
+            out.push('-');
+        }
+    }
+    out
+}
@@ -0,0 +1,209 @@
+//! Names, written the way toki pona writes them: in a cartouche, with a glyph for each syllable
+//! (read for the syllable its word starts with). Which names are spelled out is opt-in (any file
+//! passed with `--names`, in the same format as `data/containers.txt`); typing one as `[Name]`
+//! turns into its cartouche, like `S e k a` and `L i n k u` turn into their logos.
+//!
+//! Any other name can be typed inside a cartouche letter by letter: the letters that don't make a
+//! word turn into letter glyphs there (the glyph of a word that starts with the letter, at half
+//! width), so `[Sonja]` reads as a name.
+
+use std::{io, path::Path};
+
+use itertools::Itertools;
+
+use crate::{
+    block_kind::BlockKind,
+    containers,
+    em::Metrics,
+    features::*,
+    ffir::*,
+    glyph_blocks::{base::*, synth::cart_tick_name},
+    spline::SplineSet,
+};
+
+const VOWELS: [u8; 5] = *b"aeiou";
+
+/// The letters of toki pona
+pub const LETTERS: [u8; 14] = *b"aeijklmnopstuw";
+
+/// How much smaller a letter glyph is than the glyph of its word
+const LETTER_SCALE: f64 = 0.5;
+
+/// Reads a list of names, in the same format as `data/containers.txt`
+pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    containers::load(path)
+}
+
+/// The name of the glyph that `[Name]` ligates to before it's spelled out
+pub fn glyph_name(name: &str) -> String {
+    format!("{name}NameTok")
+}
+
+/// The block of (empty) glyphs that the names ligate to
+pub fn block(names: &[String]) -> GlyphBlock {
+    GlyphBlock::new_from_enc_glyphs(
+        names
+            .iter()
+            .map(|name| GlyphEnc::new_from_parts(EncPos::None, glyph_name(name), 0, Rep::default()))
+            .collect(),
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "",
+        BlockKind::Names.color(),
+    )
+    .named("names")
+    .padded(Padding::None)
+}
+
+/// The name of the glyph `letter` turns into inside a cartouche (e.g. `SLetterTok`)
+pub fn letter_glyph_name(letter: u8) -> String {
+    format!("{}LetterTok", letter.to_ascii_uppercase() as char)
+}
+
+/// The words of the base glyphs, in the order they're picked from to spell with
+fn words() -> Vec<&'static str> {
+    BASE_COR
+        .iter()
+        .chain(BASE_EXT.iter())
+        .map(|glyph| glyph.name)
+        .collect()
+}
+
+/// The word that's spelled with to write `start`: the word itself, or else the first that starts
+/// with it
+fn word_for<'a>(start: &str, words: &[&'a str]) -> Option<&'a str> {
+    words
+        .iter()
+        .find(|word| **word == start)
+        .or_else(|| words.iter().find(|word| word.starts_with(start)))
+        .copied()
+}
+
+/// The block of letter glyphs, each the glyph of its word scaled down to half width, around the
+/// middle of the em. The outlines are copied rather than referenced, so a subset that leaves the
+/// word out keeps its letter.
+pub fn letters_block() -> Result<GlyphBlock, String> {
+    let words = words();
+    let glyphs = LETTERS
+        .iter()
+        .map(|&letter| {
+            let word = word_for(&(letter as char).to_string(), &words)
+                .ok_or_else(|| format!("no word starts with {}", letter as char))?;
+            let drawn = BASE_COR
+                .iter()
+                .chain(BASE_EXT.iter())
+                .find(|glyph| glyph.name == word)
+                .map(GlyphDescriptor::outlines)
+                .transpose()?
+                .unwrap_or_default();
+            // keeps the middle of the em (halfway between the descent and the ascent) in place
+            let middle = (Metrics::DRAWN.ascent as f64 - Metrics::DRAWN.descent as f64) / 2.0;
+            let spline_set = SplineSet::parse(&drawn)?
+                .scaled(LETTER_SCALE)
+                .translated(0.0, middle * (1.0 - LETTER_SCALE));
+            Ok(GlyphEnc::new_from_parts(
+                EncPos::None,
+                (letter.to_ascii_uppercase() as char).to_string(),
+                500,
+                Rep::new(spline_set.gen(), vec![]),
+            ))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(GlyphBlock::new_from_enc_glyphs(
+        glyphs,
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "LetterTok",
+        BlockKind::Names.color(),
+    )
+    .named("letters")
+    .padded(Padding::None))
+}
+
+/// Turns the latin letters of toki pona (in both cases) into their letter glyphs inside cartouches
+pub fn use_letters(latn_block: &mut GlyphBlock) {
+    for glyph in &mut latn_block.glyphs {
+        let name = glyph.glyph.name.as_bytes();
+        if let [letter] = name {
+            if LETTERS.contains(&letter.to_ascii_lowercase()) {
+                glyph.cc_subs = Cc::Letter(letter_glyph_name(*letter));
+            }
+        }
+    }
+}
+
+fn is_vowel(letter: u8) -> bool {
+    VOWELS.contains(&letter)
+}
+
+/// The lengths of the syllables `letters` could start with, longest first: a consonant (if
+/// there's one), a vowel, and an `n` (unless it starts the next syllable), then the same without
+/// the `n`, then just the first letter
+fn syllable_lengths(letters: &[u8]) -> Vec<usize> {
+    let onset = usize::from(!is_vowel(letters[0]));
+    if !letters.get(onset).copied().is_some_and(is_vowel) {
+        return vec![1];
+    }
+    let mut lengths = vec![onset + 1];
+    let coda = onset + 1;
+    if letters.get(coda) == Some(&b'n') && !letters.get(coda + 1).copied().is_some_and(is_vowel) {
+        lengths.insert(0, coda + 1);
+    }
+    if onset == 1 {
+        lengths.push(1);
+    }
+    lengths
+}
+
+/// The glyphs `name` is spelled with (in its cartouche), using the first of `words` that starts
+/// with each syllable. A consonant that's left without a vowel is spelled with a word that starts
+/// with it, followed by a tick, which marks the glyph as read for its first letter only.
+pub fn spell(name: &str, words: &[&str]) -> Result<Vec<String>, String> {
+    let mut chars = name.chars();
+    if !chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        || !chars.all(|c| c.is_ascii_lowercase())
+    {
+        return Err(format!("{name} isn't a capitalized name"));
+    }
+    let letters = name.to_ascii_lowercase();
+    let mut glyphs = vec!["startCartTok".to_string()];
+    let mut rest = letters.as_str();
+    while !rest.is_empty() {
+        let (length, word) = syllable_lengths(rest.as_bytes())
+            .into_iter()
+            .find_map(|length| word_for(&rest[..length], words).map(|word| (length, word)))
+            .ok_or_else(|| format!("no word starts with the {} in {name}", &rest[..1]))?;
+        glyphs.push(format!("{word}Tok"));
+        if length == 1 && !is_vowel(rest.as_bytes()[0]) && word.len() > 1 {
+            glyphs.push(cart_tick_name(1));
+        }
+        rest = &rest[length..];
+    }
+    glyphs.push("endCartTok".to_string());
+    Ok(glyphs)
+}
+
+/// Declares the lookups that turn each of `names`, typed as `[Name]`, into its cartouche
+pub fn declare(names: &[String], features: &mut FeatureRegistry) -> Result<(), String> {
+    if names.is_empty() {
+        return Ok(());
+    }
+    features
+        .add_before(LIGA_SPACE, LIGA_NAMES, LookupType::Ligature, 0)
+        .add_after(LIGA_NAMES, LIGA_SPELL_NAMES, LookupType::Multiple, 0);
+
+    let words = words();
+    for name in names {
+        let glyph = glyph_name(name);
+        let typed = name.chars().map(String::from).join(" ");
+        features.declare(
+            &glyph,
+            LIGA_NAMES,
+            format!("bracketleft {typed} bracketright"),
+        );
+        features.declare(&glyph, LIGA_SPELL_NAMES, spell(name, &words)?.join(" "));
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Numbers, in the system of nasin nanpa pona (`ale` 100, `mute` 20, `luka` 5, `tu` 2, `wan` 1,
+//! added up from the biggest). With `ss05`, a number's words (like `luka luka tu wan`) turn into
+//! a glyph that writes it with digits, and `nanpa` before a number (in words, or in ASCII digits)
+//! makes it an ordinal.
+
+use crate::{
+    block_kind::BlockKind, features::*, ffir::*, glyph_blocks::ctrl::LATN, profile::Capability,
+    spline::SplineSet, Profile,
+};
+
+/// The number words, biggest first
+const NUMBER_WORDS: [(&str, usize); 5] = [
+    ("ale", 100),
+    ("mute", 20),
+    ("luka", 5),
+    ("tu", 2),
+    ("wan", 1),
+];
+
+/// The names of the latin digits, from zero
+const DIGITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// The biggest number with a glyph (the biggest that's written with two digits)
+pub const MAX: usize = 99;
+
+/// The full name of the glyph that writes `n` with digits
+pub fn number_glyph_name(n: usize) -> String {
+    format!("number{n}Tok")
+}
+
+/// The full name of the glyph that `nanpa` and `n` ligate to before they're split again
+fn ordinal_glyph_name(n: usize) -> String {
+    format!("nanpaTok_number{n}Tok")
+}
+
+/// The words `n` is written with, biggest first (e.g. `luka luka tu wan` for 13)
+pub fn words(mut n: usize) -> Vec<&'static str> {
+    let mut words = vec![];
+    for (word, value) in NUMBER_WORDS {
+        while n >= value {
+            words.push(word);
+            n -= value;
+        }
+    }
+    words
+}
+
+/// The glyph that writes `n` with the latin digits, centered in the em. The outlines are copied
+/// rather than referenced, since the UCSUR variation doesn't have the latin glyphs.
+fn number_glyph(n: usize) -> Result<GlyphEnc, String> {
+    let digits = n.to_string();
+    let left = (1000 - 500 * digits.len()) as f64 / 2.0;
+    let mut contours = vec![];
+    for (i, digit) in digits.bytes().enumerate() {
+        let name = DIGITS[(digit - b'0') as usize];
+        let drawn = LATN
+            .iter()
+            .find(|glyph| glyph.name == name)
+            .ok_or_else(|| format!("there's no {name} to write {n} with"))?;
+        let spline_set =
+            SplineSet::parse(&drawn.outlines()?)?.translated(left + 500.0 * i as f64, 0.0);
+        contours.extend(spline_set.contours);
+    }
+    Ok(GlyphEnc::new_from_parts(
+        EncPos::None,
+        n.to_string(),
+        1000,
+        Rep::new(SplineSet::new(contours).gen(), vec![]),
+    ))
+}
+
+/// The block of glyphs that write numbers with digits, and the block of (empty) glyphs that
+/// ordinals ligate to
+pub fn blocks() -> Result<(GlyphBlock, GlyphBlock), String> {
+    let numbers = (1..=MAX).map(number_glyph).collect::<Result<_, _>>()?;
+    let numbers_block = GlyphBlock::new_from_enc_glyphs(
+        numbers,
+        LookupsMode::None,
+        Cc::Full,
+        "number",
+        "Tok",
+        BlockKind::Numbers.color(),
+    )
+    .named("numbers")
+    .padded(Padding::None);
+
+    let ordinals = (1..=MAX)
+        .map(|n| GlyphEnc::new_from_parts(EncPos::None, n.to_string(), 0, Rep::default()))
+        .collect();
+    let ordinals_block = GlyphBlock::new_from_enc_glyphs(
+        ordinals,
+        LookupsMode::None,
+        Cc::None,
+        "nanpaTok_number",
+        "Tok",
+        BlockKind::Numbers.color(),
+    )
+    .named("ordinals")
+    .padded(Padding::None);
+
+    Ok((numbers_block, ordinals_block))
+}
+
+/// The ways a sequence of glyphs can be typed: one after the other (in UCSUR), or with spaces
+/// between them (in ASCII, where they've become ZWSPs by now)
+fn spaced(glyphs: &[String]) -> [String; 2] {
+    [glyphs.join(" "), glyphs.join(" ZWSP ")]
+}
+
+/// Declares the `ss05` ligatures from each number's words to its glyph, and from `nanpa` and a
+/// number to its ordinal (which is split back into `nanpa` and the number's glyph)
+pub fn declare(features: &mut FeatureRegistry, profile: Profile) {
+    for n in 1..=MAX {
+        let number = number_glyph_name(n);
+        let ordinal = ordinal_glyph_name(n);
+        let words = words(n)
+            .iter()
+            .map(|word| format!("{word}Tok"))
+            .collect::<Vec<_>>();
+
+        // a number that's a single word is already written with that word's glyph
+        if words.len() > 1 {
+            for typed in spaced(&words) {
+                features.declare(&number, SS05_NUMBERS, typed);
+            }
+        }
+
+        let nanpa_words = [vec!["nanpaTok".to_string()], words].concat();
+        for typed in spaced(&nanpa_words) {
+            features.declare(&ordinal, SS05_NUMBERS, typed);
+        }
+        if profile.has(Capability::LatinLigatures) {
+            let digits = n
+                .to_string()
+                .bytes()
+                .map(|digit| DIGITS[(digit - b'0') as usize])
+                .collect::<Vec<_>>();
+            let digits = digits.join(" ");
+            features.declare(&ordinal, SS05_NUMBERS, format!("nanpaTok {digits}"));
+            features.declare(&ordinal, SS05_NUMBERS, format!("nanpaTok space {digits}"));
+        }
+        features.declare(&ordinal, SS05_ORDINALS, format!("nanpaTok {number}"));
+    }
+}
@@ -0,0 +1,24 @@
+//! The small optical size, for UI text at 12–16px, where the regular outlines fill in: the
+//! details are simplified (the rounded corners and terminals are made sharp, which takes fewer
+//! points and lands on the pixel grid better), and the strokes are made thicker by growing the
+//! outlines outwards, while the holes are left as they were drawn so the counters stay open
+
+use crate::ffir::GlyphBlock;
+
+/// How far the outlines are grown outwards (so the strokes get twice this much thicker), in the
+/// units the glyphs are drawn in
+const GROWTH: f64 = 15.0;
+
+/// Adapts every glyph in `blocks` to small sizes
+pub fn adapt_small(blocks: &mut [GlyphBlock]) -> Result<(), String> {
+    for block in blocks.iter_mut() {
+        let names = block.full_names().map(|(name, _)| name).collect::<Vec<_>>();
+        for (name, glyph) in names.iter().zip(&mut block.glyphs) {
+            let rep = &mut glyph.glyph.rep;
+            rep.round_corners(0.0)
+                .and_then(|()| rep.grow(GROWTH))
+                .map_err(|e| format!("{name}: {e}"))?;
+        }
+    }
+    Ok(())
+}
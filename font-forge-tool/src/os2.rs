@@ -0,0 +1,235 @@
+//! The OS/2 table's Unicode ranges and code pages, worked out from the codepoints the font maps
+//! (rather than written down once and left to go stale)
+
+use std::{collections::BTreeSet, io, ops::RangeInclusive};
+
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, Font};
+
+/// The `ulUnicodeRange` bits (from the OpenType spec) of the blocks a font like this one might
+/// have, with their blocks
+const UNICODE_RANGES: &[(usize, &str, &[RangeInclusive<usize>])] = &[
+    (0, "Basic Latin", &[0x0000..=0x007F]),
+    (1, "Latin-1 Supplement", &[0x0080..=0x00FF]),
+    (2, "Latin Extended-A", &[0x0100..=0x017F]),
+    (3, "Latin Extended-B", &[0x0180..=0x024F]),
+    (
+        6,
+        "Combining Diacritical Marks",
+        &[0x0300..=0x036F, 0x1DC0..=0x1DFF],
+    ),
+    (
+        31,
+        "General Punctuation",
+        &[0x2000..=0x206F, 0x2E00..=0x2E7F],
+    ),
+    (33, "Currency Symbols", &[0x20A0..=0x20CF]),
+    (35, "Letterlike Symbols", &[0x2100..=0x214F]),
+    (36, "Number Forms", &[0x2150..=0x218F]),
+    (
+        37,
+        "Arrows",
+        &[
+            0x2190..=0x21FF,
+            0x27F0..=0x27FF,
+            0x2900..=0x297F,
+            0x2B00..=0x2BFF,
+        ],
+    ),
+    (
+        38,
+        "Mathematical Operators",
+        &[
+            0x2200..=0x22FF,
+            0x27C0..=0x27EF,
+            0x2980..=0x29FF,
+            0x2A00..=0x2AFF,
+        ],
+    ),
+    (39, "Miscellaneous Technical", &[0x2300..=0x23FF]),
+    (43, "Box Drawing", &[0x2500..=0x257F]),
+    (45, "Geometric Shapes", &[0x25A0..=0x25FF]),
+    (46, "Miscellaneous Symbols", &[0x2600..=0x26FF]),
+    (47, "Dingbats", &[0x2700..=0x27BF]),
+    (48, "CJK Symbols And Punctuation", &[0x3000..=0x303F]),
+    (57, "Non-Plane 0", &[0x10000..=0x10FFFF]),
+    (60, "Private Use Area (plane 0)", &[0xE000..=0xF8FF]),
+    (
+        65,
+        "CJK Compatibility Forms",
+        &[0xFE10..=0xFE1F, 0xFE30..=0xFE4F],
+    ),
+    (68, "Halfwidth And Fullwidth Forms", &[0xFF00..=0xFFEF]),
+    (69, "Specials", &[0xFFF0..=0xFFFF]),
+    (
+        90,
+        "Private Use (plane 15)",
+        &[0xF0000..=0xFFFFD, 0x100000..=0x10FFFD],
+    ),
+    (
+        91,
+        "Variation Selectors",
+        &[0xFE00..=0xFE0F, 0xE0100..=0xE01EF],
+    ),
+    (92, "Tags", &[0xE0000..=0xE007F]),
+];
+
+/// The `ulCodePageRange` bits of the code pages a font like this one might support, each with a
+/// letter of it that's in no smaller one: a font with all of ASCII and that letter supports it
+const CODE_PAGES: &[(usize, &str, usize)] = &[
+    (0, "1252 Latin 1", 0x00DE),
+    (1, "1250 Latin 2", 0x0150),
+    (2, "1251 Cyrillic", 0x0416),
+    (3, "1253 Greek", 0x03A3),
+    (4, "1254 Turkish", 0x011E),
+    (7, "1257 Baltic", 0x0172),
+];
+
+/// A font that supports no code page is marked as supporting Latin 1 anyway, since some
+/// applications (like Microsoft Word) hide fonts with none
+const FALLBACK_CODE_PAGE: usize = 0;
+
+#[derive(Serialize)]
+pub struct Bit {
+    bit: usize,
+    name: &'static str,
+    /// How many of the font's codepoints it's set for
+    codepoints: usize,
+}
+
+#[derive(Serialize)]
+pub struct CodePage {
+    bit: usize,
+    name: &'static str,
+    /// Whether it's only set as `FALLBACK_CODE_PAGE`
+    fallback: bool,
+}
+
+#[derive(Serialize)]
+pub struct Os2Report {
+    unicode_ranges: Vec<Bit>,
+    code_pages: Vec<CodePage>,
+    /// Codepoints the font maps that are in none of the ranges above
+    unranged: Vec<String>,
+}
+
+/// Every codepoint `font` maps to a glyph (variation sequences aside)
+fn codepoints(font: &Font) -> BTreeSet<usize> {
+    font.encoded_glyphs()
+        .map(|(codepoint, _)| codepoint)
+        .chain(
+            font.blocks
+                .iter()
+                .flat_map(|block| &block.glyphs)
+                .flat_map(|glyph| glyph.encoding.alt_unis.iter().copied()),
+        )
+        .collect()
+}
+
+/// Works out the OS/2 bits of `font` from its codepoints
+pub fn report(font: &Font) -> Os2Report {
+    let codepoints = codepoints(font);
+    let unicode_ranges = UNICODE_RANGES
+        .iter()
+        .map(|&(bit, name, ranges)| Bit {
+            bit,
+            name,
+            codepoints: codepoints
+                .iter()
+                .filter(|codepoint| ranges.iter().any(|range| range.contains(codepoint)))
+                .count(),
+        })
+        .filter(|bit| bit.codepoints > 0)
+        .collect();
+
+    let has_ascii = (0x20..=0x7E).all(|codepoint| codepoints.contains(&codepoint));
+    let mut code_pages = CODE_PAGES
+        .iter()
+        .filter(|&&(_, _, letter)| has_ascii && codepoints.contains(&letter))
+        .map(|&(bit, name, _)| CodePage {
+            bit,
+            name,
+            fallback: false,
+        })
+        .collect::<Vec<_>>();
+    if code_pages.is_empty() {
+        let &(bit, name, _) = CODE_PAGES
+            .iter()
+            .find(|(bit, ..)| *bit == FALLBACK_CODE_PAGE)
+            .unwrap();
+        code_pages.push(CodePage {
+            bit,
+            name,
+            fallback: true,
+        });
+    }
+
+    let unranged = codepoints
+        .iter()
+        .filter(|codepoint| {
+            !UNICODE_RANGES
+                .iter()
+                .any(|(_, _, ranges)| ranges.iter().any(|range| range.contains(codepoint)))
+        })
+        .map(|codepoint| format!("U+{codepoint:04X}"))
+        .collect();
+
+    Os2Report {
+        unicode_ranges,
+        code_pages,
+        unranged,
+    }
+}
+
+/// `bits` set in `N` words, as FontForge writes them (most significant word last)
+fn gen_bits<const N: usize>(bits: impl Iterator<Item = usize>) -> String {
+    let mut words = [0u32; N];
+    for bit in bits {
+        words[bit / 32] |= 1 << (bit % 32);
+    }
+    words.map(|word| format!("{word:08x}")).join(".")
+}
+
+impl Os2Report {
+    /// The `OS2CodePages` and `OS2UnicodeRanges` lines of the `.sfd` header
+    pub fn gen(&self) -> String {
+        format!(
+            "OS2CodePages: {}\nOS2UnicodeRanges: {}\n",
+            gen_bits::<2>(self.code_pages.iter().map(|code_page| code_page.bit)),
+            gen_bits::<4>(self.unicode_ranges.iter().map(|range| range.bit)),
+        )
+    }
+
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                for bit in &self.unicode_ranges {
+                    println!(
+                        "unicode range  {:>3}  {} ({} codepoints)",
+                        bit.bit, bit.name, bit.codepoints
+                    );
+                }
+                for code_page in &self.code_pages {
+                    let fallback = if code_page.fallback {
+                        " (as a fallback)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "code page      {:>3}  {}{fallback}",
+                        code_page.bit, code_page.name
+                    );
+                }
+                for codepoint in &self.unranged {
+                    println!("unranged       {codepoint}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
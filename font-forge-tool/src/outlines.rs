@@ -0,0 +1,108 @@
+//! A validation of the hand-written outlines: contours that cross themselves (which some
+//! rasterizers get wrong after FontForge removes overlaps) and contours that go the wrong way, which
+//! fills their holes
+
+use std::io;
+
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, spline::*, Font};
+
+#[derive(Serialize)]
+pub struct Finding {
+    name: String,
+    block: String,
+    /// The index of the contour in the glyph's spline set
+    contour: usize,
+    problem: String,
+}
+
+#[derive(Serialize)]
+pub struct OutlineReport {
+    contours: usize,
+    findings: Vec<Finding>,
+}
+
+/// The problems with a contour at `depth` (see `SplineSet::depths`), whose outlines should go
+/// `direction` and holes the other way
+fn problems(contour: &Contour, depth: usize, direction: Direction) -> Vec<String> {
+    let mut problems = contour
+        .self_intersections()
+        .iter()
+        .map(|p| {
+            format!(
+                "crosses itself at {} {}",
+                gen_coord(p.x.round()),
+                gen_coord(p.y.round())
+            )
+        })
+        .collect::<Vec<_>>();
+    if contour.is_degenerate() {
+        problems.push("encloses no area".to_string());
+        return problems;
+    }
+    let clockwise = contour.signed_area() < 0.0;
+    let outline = depth.is_multiple_of(2);
+    if clockwise != (outline == (direction == Direction::Clockwise)) {
+        let kind = if outline { "outline" } else { "hole" };
+        let way = if clockwise {
+            "clockwise"
+        } else {
+            "counter-clockwise"
+        };
+        problems.push(format!("{kind} goes {way}"));
+    }
+    problems
+}
+
+/// Checks the contours of every glyph in `font` with a spline set of its own, whose outlines
+/// should go `direction`
+pub fn report(font: &Font, direction: Direction) -> Result<OutlineReport, String> {
+    let mut report = OutlineReport {
+        contours: 0,
+        findings: vec![],
+    };
+    for block in &font.blocks {
+        for (name, glyph) in block.full_names() {
+            let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())?;
+            let depths = spline_set.depths();
+            for (i, contour) in spline_set.contours.iter().enumerate() {
+                report.contours += 1;
+                for problem in problems(contour, depths[i], direction) {
+                    report.findings.push(Finding {
+                        name: name.to_string(),
+                        block: block.name.clone(),
+                        contour: i,
+                        problem,
+                    });
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+impl OutlineReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} findings in {} contours",
+                    self.findings.len(),
+                    self.contours
+                );
+                for finding in &self.findings {
+                    println!(
+                        "{:<12} {} (contour {})  {}",
+                        finding.block, finding.name, finding.contour, finding.problem
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//! Glyph packs are TOML files that add glyphs (usually proposed UCSUR nimi sin) to the
+//! extension area without touching the constants. A pack looks like this:
+//!
+//! ```toml
+//! name = "nimi-sin-2024"
+//! # optional; defaults to the first codepoint after the previous pack (or the extension block)
+//! start = 0xF19D0
+//! # optional; who drew the glyphs (if not the font's designer), and what they're based on
+//! designer = "jan Sin"
+//! source = "sitelen seli kiwen"
+//!
+//! [[glyph]]
+//! name = "kiki"
+//! # optional; defaults to the next codepoint
+//! codepoint = 0xF19D4
+//! # optional; defaults to 1000
+//! width = 1000
+//! # optional; makes spline_set centerlines, drawn with a round pen this wide
+//! stroke_width = 80
+//! # optional; override the pack's designer and source
+//! designer = "jan Ante"
+//! spline_set = """
+//! 500 900 m 0
+//!  ...
+//! """
+//!
+//! [[glyph]]
+//! name = "kulupu-suli"
+//! # optional; glyphs referenced instead of (or besides) drawing a spline_set (see `compose`)
+//! compose = [
+//!     "ref(ijo, scale(0.5) translate(0, 0))",
+//!     "ref(ijo, scale(0.5) translate(500, 0))",
+//!     "ref(ijo, scale(0.5) translate(250, 400))",
+//! ]
+//! ```
+
+use std::{fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::invalid_data;
+use crate::sources::{GlyphBlockSpec, GlyphSource, SourceGlyph};
+
+/// A glyph pack, as read from its manifest
+#[derive(Deserialize, Clone)]
+pub struct GlyphPack {
+    pub name: String,
+    pub start: Option<usize>,
+    /// The designer of the glyphs that don't name their own
+    pub designer: Option<String>,
+    /// What the glyphs that don't name their own source are based on
+    pub source: Option<String>,
+    #[serde(rename = "glyph", default)]
+    pub glyphs: Vec<SourceGlyph>,
+}
+
+impl GlyphPack {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| invalid_data(format!("{}: {e}", path.display())))
+    }
+
+    /// Loads every `.toml` pack in `dir` (sorted by file name), or nothing if `dir` doesn't exist
+    pub fn discover(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+        paths.sort();
+        paths.iter().map(Self::load).collect()
+    }
+}
+
+impl GlyphSource for GlyphPack {
+    fn name(&self) -> String {
+        format!("the pack {}", self.name)
+    }
+
+    fn blocks(&self) -> Vec<GlyphBlockSpec> {
+        vec![GlyphBlockSpec {
+            name: self.name.clone(),
+            start: self.start,
+            glyphs: self
+                .glyphs
+                .iter()
+                .map(|glyph| SourceGlyph {
+                    designer: glyph.designer.clone().or_else(|| self.designer.clone()),
+                    source: glyph.source.clone().or_else(|| self.source.clone()),
+                    ..glyph.clone()
+                })
+                .collect(),
+        }]
+    }
+}
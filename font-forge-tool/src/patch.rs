@@ -0,0 +1,134 @@
+//! Regenerating some glyphs of an `.sfd` written earlier, and splicing them into it in place of the
+//! old ones, for iterating on an outline without writing the whole font again. The rest of the file
+//! (the header, with its lookups, and every other glyph) is kept as it is, so the glyphs have to be
+//! in the slots they were in when it was written, and reference glyphs that are still where they
+//! were.
+
+use std::{collections::HashMap, ops::Range};
+
+use crate::{BlockFilter, Font};
+
+/// A glyph's section of an `.sfd`
+struct Section {
+    name: String,
+    /// The lines it spans, from its `StartChar` to its `EndChar`
+    lines: Range<usize>,
+}
+
+/// The glyph sections of the lines of an `.sfd`, in order
+fn sections(lines: &[&str]) -> Result<Vec<Section>, String> {
+    let mut sections = vec![];
+    let mut start = None;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(name) = line.strip_prefix("StartChar: ") {
+            start = Some((name.trim().to_string(), i));
+        } else if *line == "EndChar" {
+            let (name, start) = start
+                .take()
+                .ok_or_else(|| format!("line {} ends a glyph that wasn't started", i + 1))?;
+            sections.push(Section {
+                name,
+                lines: start..i + 1,
+            });
+        }
+    }
+    Ok(sections)
+}
+
+/// The value of the first line of `section` that starts with `key`
+fn field<'a>(section: &'a str, key: &str) -> Option<&'a str> {
+    section.lines().find_map(|line| line.strip_prefix(key))
+}
+
+/// Replaces the sections of the glyphs of `font` named in `glyphs` (by full name, like `tokiTok`),
+/// and of every glyph of the blocks matching `blocks`, in the `.sfd` `sfd`. Returns the patched
+/// `.sfd`, and how many glyphs were replaced.
+pub fn patch(
+    sfd: &str,
+    font: &Font,
+    glyphs: &[String],
+    blocks: &[String],
+) -> Result<(String, usize), String> {
+    let lines = sfd.lines().collect::<Vec<_>>();
+    let existing = sections(&lines)?;
+    let by_name = existing
+        .iter()
+        .map(|section| (section.name.as_str(), section))
+        .collect::<HashMap<_, _>>();
+
+    let mut slots = HashMap::new();
+    let mut patches = HashMap::new();
+    for block in &font.blocks {
+        let whole = blocks
+            .iter()
+            .any(|filter| BlockFilter::matches(filter, &block.name));
+        for (name, glyph) in block.full_names() {
+            slots.insert(glyph.encoding.ff_pos, name.to_string());
+            if whole || glyphs.iter().any(|glyph| *glyph == name.as_str()) {
+                let section = glyph.gen(
+                    &block.prefix,
+                    &block.suffix,
+                    &block.color,
+                    font.profile,
+                    &font.features,
+                );
+                patches.insert(name.to_string(), section);
+            }
+        }
+    }
+    if let Some(missing) = glyphs.iter().find(|glyph| !patches.contains_key(*glyph)) {
+        return Err(format!("the font has no glyph called {missing}"));
+    }
+    if patches.is_empty() {
+        return Err("there are no glyphs to patch".to_string());
+    }
+
+    let mut replaced = HashMap::new();
+    for (name, section) in &patches {
+        let Some(old) = by_name.get(name.as_str()) else {
+            return Err(format!(
+                "{name} isn't in the .sfd; generate it again in full"
+            ));
+        };
+        let old_section = lines[old.lines.clone()].join("\n");
+        if field(section, "Encoding: ") != field(&old_section, "Encoding: ") {
+            return Err(format!(
+                "{name} has moved since the .sfd was generated; generate it again in full"
+            ));
+        }
+        for refer in section
+            .lines()
+            .filter_map(|line| line.strip_prefix("Refer: "))
+        {
+            let slot = refer
+                .split_whitespace()
+                .next()
+                .and_then(|slot| slot.parse::<usize>().ok())
+                .ok_or_else(|| format!("{name} has a reference without a slot"))?;
+            let then = existing.get(slot).map(|section| section.name.as_str());
+            if then != slots.get(&slot).map(String::as_str) {
+                return Err(format!(
+                    "{name} references slot {slot}, which has another glyph in the .sfd; generate it again in full"
+                ));
+            }
+        }
+        replaced.insert(old.lines.start, (old.lines.end, section.trim_matches('\n')));
+    }
+
+    let mut out = String::new();
+    let mut i = 0;
+    while i < lines.len() {
+        match replaced.get(&i) {
+            Some(&(end, section)) => {
+                out.push_str(section);
+                i = end;
+            }
+            None => {
+                out.push_str(lines[i]);
+                i += 1;
+            }
+        }
+        out.push('\n');
+    }
+    Ok((out, patches.len()))
+}
@@ -0,0 +1,102 @@
+//! Plugins: transformations of the glyphs run after they're all built (and their outlines adapted
+//! to the profile), before they're scaled to the em and written, for trying things out without
+//! changing how the blocks are made. They're registered in `PLUGINS`, and picked with `--plugin`.
+
+use crate::{
+    ffir::{GlyphBlock, Rep, TAG_PADDING},
+    metrics::FRAME_PARTS,
+    spline::SplineSet,
+};
+
+/// A transformation of every block's glyphs
+pub trait Plugin {
+    /// What it's called in errors
+    fn name(&self) -> &'static str;
+
+    fn apply(&self, blocks: &mut [GlyphBlock]) -> Result<(), String>;
+}
+
+/// Makes a plugin from the number it's given (like the `2` of `letterspacing=2`)
+pub type PluginFactory = fn(f64) -> Box<dyn Plugin>;
+
+/// Makes the plugin `spec` names (`NAME=NUMBER`) from the ones in `registry`
+pub fn parse(spec: &str, registry: &[(&str, PluginFactory)]) -> Result<Box<dyn Plugin>, String> {
+    let (name, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("the plugin `{spec}` isn't written as NAME=NUMBER"))?;
+    let value = value
+        .parse::<f64>()
+        .map_err(|e| format!("the plugin `{spec}` is given `{value}`: {e}"))?;
+    let (_, factory) = registry
+        .iter()
+        .find(|(registered, _)| *registered == name)
+        .ok_or_else(|| {
+            let names = registry.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+            format!("there's no plugin `{name}` (there's {})", names.join(", "))
+        })?;
+    Ok(factory(value))
+}
+
+/// Runs every plugin in `plugins` on `blocks`, in order
+pub fn apply_all(plugins: &[Box<dyn Plugin>], blocks: &mut [GlyphBlock]) -> Result<(), String> {
+    for plugin in plugins {
+        plugin
+            .apply(blocks)
+            .map_err(|e| format!("plugin {}: {e}", plugin.name()))?;
+    }
+    Ok(())
+}
+
+/// Adds `percent` of each glyph's width after it (like CSS's `letter-spacing`), so the glyphs are
+/// set further apart. Glyphs with no width (the combining ones) are left as they are.
+pub struct Letterspacing {
+    pub percent: f64,
+}
+
+impl Plugin for Letterspacing {
+    fn name(&self) -> &'static str {
+        "letterspacing"
+    }
+
+    fn apply(&self, blocks: &mut [GlyphBlock]) -> Result<(), String> {
+        if self.percent <= -100.0 {
+            return Err(format!("{}% would leave no width", self.percent));
+        }
+        for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+            let width = glyph.glyph.width as f64;
+            glyph.glyph.width = (width * (1.0 + self.percent / 100.0)).round() as usize;
+        }
+        Ok(())
+    }
+}
+
+/// Moves the outlines of every glyph whose ink goes below the baseline up by `units`. The glyphs
+/// that draw containers (which reach down on purpose) are left where they are, and so are
+/// references, which follow the glyphs they reference.
+pub struct RaiseDescenders {
+    pub units: f64,
+}
+
+impl Plugin for RaiseDescenders {
+    fn name(&self) -> &'static str {
+        "raise-descenders"
+    }
+
+    fn apply(&self, blocks: &mut [GlyphBlock]) -> Result<(), String> {
+        for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+            let glyph = &mut glyph.glyph;
+            if glyph.has_tag(TAG_PADDING)
+                || FRAME_PARTS.iter().any(|part| glyph.name.contains(part))
+            {
+                continue;
+            }
+            let spline_set = SplineSet::parse(glyph.rep.spline_set())
+                .map_err(|e| format!("{}: {e}", glyph.name))?;
+            if spline_set.bounds().is_some_and(|(min, _)| min.y < 0.0) {
+                let references = glyph.rep.references().to_vec();
+                glyph.rep = Rep::new(spline_set.translated(0.0, self.units).gen(), references);
+            }
+        }
+        Ok(())
+    }
+}
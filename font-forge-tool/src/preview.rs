@@ -0,0 +1,333 @@
+//! Previews of glyphs, and of lines of them, drawn straight from the generated outlines (for the
+//! README, chat, and comparing builds) without compiling the font
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use font_forge_tool::translit;
+use itertools::Itertools;
+
+use crate::{
+    fallback::NOTDEF,
+    ffir::{EncPos, TAG_PADDING},
+    invalid_data,
+    raster::{self, Bitmap},
+    spline::{Point, SplineSet},
+    Font,
+};
+
+/// How much room is left around a preview, as a fraction of the em
+const MARGIN: f64 = 0.125;
+
+/// The glyphs of a font, as they're previewed
+pub struct Previewer {
+    outlines: HashMap<String, SplineSet>,
+    widths: HashMap<String, usize>,
+    /// The glyph each codepoint is mapped to
+    cmap: HashMap<usize, String>,
+    /// The em, from its descent to its ascent
+    em: (f64, f64),
+}
+
+impl Previewer {
+    pub fn new(font: &Font) -> Result<Self, String> {
+        let mut widths = HashMap::new();
+        let mut cmap = HashMap::new();
+        for block in &font.blocks {
+            for (name, glyph) in block.full_names() {
+                if glyph.glyph.has_tag(TAG_PADDING) {
+                    continue;
+                }
+                widths.insert(name.to_string(), glyph.glyph.width);
+                let codepoint = match glyph.encoding.enc_pos {
+                    EncPos::Pos(codepoint) => Some(codepoint),
+                    EncPos::None => None,
+                };
+                for codepoint in codepoint.iter().chain(&glyph.encoding.alt_unis) {
+                    cmap.insert(*codepoint, name.to_string());
+                }
+            }
+        }
+        Ok(Self {
+            outlines: raster::outlines(font)?,
+            widths,
+            cmap,
+            em: (-(font.metrics.descent as f64), font.metrics.ascent as f64),
+        })
+    }
+
+    /// Reads the glyphs of a `.sfd` file (like an earlier release's), in their foreground layer
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_sfd(&fs::read_to_string(path)?)
+            .map_err(|e| invalid_data(format!("{}: {e}", path.display())))
+    }
+
+    fn from_sfd(sfd: &str) -> Result<Self, String> {
+        /// A glyph as it's written, before its references are resolved
+        #[derive(Default)]
+        struct Char<'a> {
+            name: &'a str,
+            width: usize,
+            spline_set: String,
+            /// The slots of the glyphs it references, with their matrices
+            references: Vec<(usize, [f64; 6])>,
+            /// Whether it's anything more than a slot (which padding, left out as it's never
+            /// shown, isn't)
+            shown: bool,
+        }
+
+        let mut em = (0.0, 0.0);
+        let mut chars = HashMap::<usize, Char>::new();
+        let mut cmap = HashMap::new();
+        let (mut current, mut slot) = (None::<Char>, 0);
+        let (mut fore, mut in_spline_set) = (true, false);
+        for line in sfd.lines() {
+            let err = || format!("couldn't parse the line `{line}`");
+            let (key, value) = line.split_once(": ").unwrap_or((line, ""));
+            if let Some(char) = &mut current {
+                if in_spline_set {
+                    if line == "EndSplineSet" {
+                        in_spline_set = false;
+                    } else if fore {
+                        // only the point's flags are kept, not its hint mask
+                        let (point, flags) = line.trim().rsplit_once(' ').ok_or_else(err)?;
+                        let end = flags.find(|c: char| !c.is_ascii_digit());
+                        let flags = &flags[..end.unwrap_or(flags.len())];
+                        char.spline_set.push_str(&format!("{point} {flags}\n"));
+                    }
+                    continue;
+                }
+                if !matches!(
+                    (key, value),
+                    ("Encoding" | "LayerCount" | "Colour" | "EndChar", _) | ("Width", "0")
+                ) {
+                    char.shown = true;
+                }
+                match key {
+                    "Encoding" => {
+                        let numbers = value
+                            .split_whitespace()
+                            .map(|n| n.parse::<isize>().map_err(|_| err()))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let [_, codepoint, gid] = numbers[..] else {
+                            return Err(err());
+                        };
+                        slot = gid as usize;
+                        if codepoint >= 0 {
+                            char.shown = true;
+                            cmap.insert(codepoint as usize, char.name.to_string());
+                        }
+                    }
+                    "AltUni2" => {
+                        for alt in value.split_whitespace() {
+                            if let [codepoint, "ffffffff", _] = alt.split('.').collect_vec()[..] {
+                                let codepoint =
+                                    usize::from_str_radix(codepoint, 16).map_err(|_| err())?;
+                                cmap.insert(codepoint, char.name.to_string());
+                            }
+                        }
+                    }
+                    "Width" => char.width = value.parse().map_err(|_| err())?,
+                    "Refer" => {
+                        let tokens = value.split_whitespace().collect_vec();
+                        let (Some(target), Some(matrix)) = (tokens.first(), tokens.get(3..9))
+                        else {
+                            return Err(err());
+                        };
+                        let mut m = [0.0; 6];
+                        for (m, token) in m.iter_mut().zip(matrix) {
+                            *m = token.parse().map_err(|_| err())?;
+                        }
+                        char.references
+                            .push((target.parse().map_err(|_| err())?, m));
+                    }
+                    "Fore" => fore = true,
+                    "Back" => fore = false,
+                    "SplineSet" => in_spline_set = true,
+                    "EndChar" => {
+                        let char = current.take().unwrap();
+                        if char.shown {
+                            chars.insert(slot, char);
+                        }
+                    }
+                    _ => {}
+                }
+            } else {
+                match key {
+                    "Ascent" => em.1 = value.parse().map_err(|_| err())?,
+                    "Descent" => em.0 = -value.parse::<f64>().map_err(|_| err())?,
+                    "StartChar" => {
+                        current = Some(Char {
+                            name: value,
+                            ..Default::default()
+                        });
+                        fore = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        /// The outlines of the glyph in `slot`, with its references' put where they're shown
+        fn resolve(
+            slot: usize,
+            chars: &HashMap<usize, Char>,
+            outlines: &mut HashMap<usize, SplineSet>,
+        ) -> Result<SplineSet, String> {
+            if let Some(outline) = outlines.get(&slot) {
+                return Ok(outline.clone());
+            }
+            let char = &chars[&slot];
+            let mut outline =
+                SplineSet::parse(&char.spline_set).map_err(|e| format!("{}: {e}", char.name))?;
+            for &(target, [a, b, c, d, e, f]) in &char.references {
+                if !chars.contains_key(&target) {
+                    return Err(format!("{} references a glyph that isn't there", char.name));
+                }
+                let shown = resolve(target, chars, outlines)?
+                    .mapped(|p| Point::new(a * p.x + c * p.y + e, b * p.x + d * p.y + f));
+                outline.contours.extend(shown.contours);
+            }
+            outlines.insert(slot, outline.clone());
+            Ok(outline)
+        }
+
+        let mut by_slot = HashMap::new();
+        for &slot in chars.keys() {
+            resolve(slot, &chars, &mut by_slot)?;
+        }
+        Ok(Self {
+            outlines: chars
+                .iter()
+                .map(|(slot, char)| (char.name.to_string(), by_slot.remove(slot).unwrap()))
+                .collect(),
+            widths: chars
+                .values()
+                .map(|char| (char.name.to_string(), char.width))
+                .collect(),
+            cmap,
+            em,
+        })
+    }
+
+    /// The names of the glyphs
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.widths.keys().map(String::as_str)
+    }
+
+    /// The outlines of the glyph `name`
+    pub fn outline(&self, name: &str) -> Option<&SplineSet> {
+        self.outlines.get(name)
+    }
+
+    /// The width of the glyph `name`
+    pub fn width(&self, name: &str) -> Option<usize> {
+        self.widths.get(name).copied()
+    }
+
+    /// The glyph names of the glyphs in `names` (separated by spaces)
+    pub fn glyphs(&self, names: &str) -> Result<Vec<String>, String> {
+        names
+            .split_whitespace()
+            .map(|name| {
+                if self.widths.contains_key(name) {
+                    Ok(name.to_string())
+                } else {
+                    Err(format!("there's no glyph called {name}"))
+                }
+            })
+            .collect()
+    }
+
+    /// The glyphs of `text` (toki pona in ASCII or UCSUR), a character at a time from the cmap
+    /// (so without the font's lookups: joined glyphs are shown with their joiners, and
+    /// cartouches without their extensions), with `.notdef` for the characters it doesn't have
+    pub fn text_glyphs(&self, text: &str) -> Vec<String> {
+        translit::ascii_to_ucsur(text)
+            .chars()
+            .map(|c| {
+                self.cmap
+                    .get(&(c as usize))
+                    .cloned()
+                    .unwrap_or_else(|| NOTDEF.to_string())
+            })
+            .collect()
+    }
+
+    /// The box a glyph `width` wide with the outlines `outline` is shown in: the em and its
+    /// width, and any ink past them (zero-width glyphs are shown as wide as the em is tall)
+    pub fn frame(&self, outline: &SplineSet, width: usize) -> (Point, Point) {
+        let (descent, ascent) = self.em;
+        let (mut min, mut max) = (Point::new(0.0, descent), Point::new(width as f64, ascent));
+        if let Some((ink_min, ink_max)) = outline.bounds() {
+            min = Point::new(min.x.min(ink_min.x), min.y.min(ink_min.y));
+            max = Point::new(max.x.max(ink_max.x), max.y.max(ink_max.y));
+        }
+        if max.x - min.x < 1.0 {
+            max.x = min.x + (ascent - descent);
+        }
+        (min, max)
+    }
+
+    /// `glyphs` set one after another, drawn with the em `size` pixels tall
+    pub fn line(&self, glyphs: &[String], size: usize) -> Bitmap {
+        let mut line = SplineSet::default();
+        let mut x = 0.0;
+        for glyph in glyphs {
+            if let Some(outline) = self.outlines.get(glyph) {
+                line.contours.extend(outline.translated(x, 0.0).contours);
+            }
+            x += self.widths.get(glyph).copied().unwrap_or_default() as f64;
+        }
+        self.draw(&line, self.frame(&line, x.round() as usize), size)
+    }
+
+    /// `outline` drawn in the box from `min` to `max` (with a margin around it), with the em
+    /// `size` pixels tall
+    pub fn draw(&self, outline: &SplineSet, (min, max): (Point, Point), size: usize) -> Bitmap {
+        let (descent, ascent) = self.em;
+        let margin = (ascent - descent) * MARGIN;
+        let (min, max) = (
+            Point::new(min.x - margin, min.y - margin),
+            Point::new(max.x + margin, max.y + margin),
+        );
+        let scale = size as f64 / (ascent - descent);
+        raster::rasterize(
+            outline,
+            (min, max),
+            ((max.x - min.x) * scale).ceil() as usize,
+            ((max.y - min.y) * scale).ceil() as usize,
+        )
+    }
+}
+
+/// Writes `glyphs` (each to `<glyph>.png`), then `texts` and `sequences` of glyph names as lines
+/// (to `sample-<n>.png`, counting from 1), into `out`
+pub fn write_pngs(
+    font: &Font,
+    glyphs: &[String],
+    texts: &[String],
+    sequences: &[String],
+    size: usize,
+    out: &Path,
+) -> io::Result<()> {
+    let previewer = Previewer::new(font).map_err(invalid_data)?;
+    fs::create_dir_all(out)?;
+    for name in glyphs {
+        let glyph = previewer.glyphs(name).map_err(invalid_data)?;
+        let path = out.join(format!("{name}.png"));
+        previewer.line(&glyph, size).write_png(&path)?;
+        println!("wrote {}", path.display());
+    }
+    let lines = texts
+        .iter()
+        .map(|text| Ok(previewer.text_glyphs(text)))
+        .chain(sequences.iter().map(|names| previewer.glyphs(names)));
+    for (n, line) in lines.enumerate() {
+        let path = out.join(format!("sample-{}.png", n + 1));
+        previewer
+            .line(&line.map_err(invalid_data)?, size)
+            .write_png(&path)?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Build profiles: what a build of the font does for text typed in ASCII (and for picking
+//! alternates), as capabilities that can each be left out, and how its outlines are adapted. The
+//! main and UCSUR variations are the same font with different capabilities.
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Something a build can do, which a profile can leave out
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Capability {
+    /// The glyphs of the ASCII characters (the latin letters, digits, and punctuation), which the
+    /// latin ligatures and digit selectors need
+    Ascii,
+    /// Words (and the ASCII spellings of the control glyphs) typed in latin letters turning into
+    /// sitelen pona
+    LatinLigatures,
+    /// Alternates picked by typing a digit after their glyph, besides by a variation selector
+    DigitSelectors,
+    /// `'rand'` picking among a glyph's alternates at random
+    Rand,
+}
+
+/// How the outlines of a build are changed from how they're drawn
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outlines {
+    Drawn,
+    /// With directional glyphs mirrored, for right-to-left experiments
+    Mirrored,
+    /// With sharp corners and square terminals instead of rounded ones
+    Sharp,
+    /// For UI text at small sizes (see `optical`)
+    Small,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Profile {
+    /// What's added to the names of its files (like `-UCSUR`)
+    pub suffix: &'static str,
+    ascii: bool,
+    latin_ligatures: bool,
+    digit_selectors: bool,
+    rand: bool,
+    pub outlines: Outlines,
+}
+
+impl Profile {
+    /// Typed in ASCII, with the latin glyphs
+    pub const MAIN: Self = Self {
+        suffix: "",
+        ascii: true,
+        latin_ligatures: true,
+        digit_selectors: true,
+        rand: true,
+        outlines: Outlines::Drawn,
+    };
+    /// Typed in UCSUR, without anything from ASCII
+    pub const UCSUR: Self = Self {
+        suffix: "-UCSUR",
+        ascii: false,
+        latin_ligatures: false,
+        digit_selectors: false,
+        ..Self::MAIN
+    };
+    /// Like `UCSUR`, but with directional glyphs mirrored
+    pub const RTL: Self = Self {
+        suffix: "-RTL",
+        outlines: Outlines::Mirrored,
+        ..Self::UCSUR
+    };
+    /// Like `UCSUR`, but sharp
+    pub const SHARP: Self = Self {
+        suffix: "-Sharp",
+        outlines: Outlines::Sharp,
+        ..Self::UCSUR
+    };
+    /// Like `UCSUR`, but for small sizes
+    pub const SMALL: Self = Self {
+        suffix: "-Small",
+        outlines: Outlines::Small,
+        ..Self::UCSUR
+    };
+
+    /// `UCSUR` if `ucsur` (as the subcommands' `--ucsur` flag), `MAIN` otherwise
+    pub fn from_flag(ucsur: bool) -> Self {
+        if ucsur {
+            Self::UCSUR
+        } else {
+            Self::MAIN
+        }
+    }
+
+    /// Whether the build has `capability` (and the capabilities it needs)
+    pub fn has(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Ascii => self.ascii,
+            Capability::LatinLigatures => self.ascii && self.latin_ligatures,
+            Capability::DigitSelectors => self.ascii && self.digit_selectors,
+            Capability::Rand => self.rand,
+        }
+    }
+
+    /// This profile, with or without `capability`
+    pub fn with(mut self, capability: Capability, has: bool) -> Self {
+        match capability {
+            Capability::Ascii => self.ascii = has,
+            Capability::LatinLigatures => self.latin_ligatures = has,
+            Capability::DigitSelectors => self.digit_selectors = has,
+            Capability::Rand => self.rand = has,
+        }
+        self
+    }
+
+    /// This profile, leaving out `capability`
+    pub fn without(self, capability: Capability) -> Self {
+        self.with(capability, false)
+    }
+}
@@ -0,0 +1,156 @@
+//! Named builds, from a file like `profiles.toml`: a build profile (see `profile`) and the settings
+//! it's generated with, each inherited from another named build unless it sets them itself
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    invalid_data,
+    profile::{Capability, Outlines, Profile},
+    subset,
+};
+
+/// A named build, as it's written in the file
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ProfileSpec {
+    /// The build it inherits what it doesn't set from (the main variation if none)
+    inherits: Option<String>,
+    /// What's added to the names of its files (`-NAME` if not set; it isn't inherited)
+    suffix: Option<String>,
+    ascii: Option<bool>,
+    latin_ligatures: Option<bool>,
+    digit_selectors: Option<bool>,
+    rand: Option<bool>,
+    outlines: Option<Outlines>,
+    /// If either of these is set, only the sitelen pona of these words (and of the words with
+    /// these tags) are included
+    words: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+    include_blocks: Option<Vec<String>>,
+    exclude_blocks: Option<Vec<String>>,
+    corner_radius: Option<f64>,
+}
+
+impl ProfileSpec {
+    /// This build's settings, with the ones it doesn't set taken from `parent`
+    fn or(self, parent: ProfileSpec) -> ProfileSpec {
+        ProfileSpec {
+            inherits: parent.inherits,
+            suffix: self.suffix,
+            ascii: self.ascii.or(parent.ascii),
+            latin_ligatures: self.latin_ligatures.or(parent.latin_ligatures),
+            digit_selectors: self.digit_selectors.or(parent.digit_selectors),
+            rand: self.rand.or(parent.rand),
+            outlines: self.outlines.or(parent.outlines),
+            words: self.words.or(parent.words),
+            tags: self.tags.or(parent.tags),
+            include_blocks: self.include_blocks.or(parent.include_blocks),
+            exclude_blocks: self.exclude_blocks.or(parent.exclude_blocks),
+            corner_radius: self.corner_radius.or(parent.corner_radius),
+        }
+    }
+}
+
+/// A named build, with everything it inherits
+pub struct NamedProfile {
+    pub name: String,
+    pub profile: Profile,
+    /// If set, only the sitelen pona for these words are included
+    pub words: Option<HashSet<String>>,
+    pub include_blocks: Vec<String>,
+    pub exclude_blocks: Vec<String>,
+    pub corner_radius: Option<f64>,
+}
+
+/// Every named build in a file, by name
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct Profiles(BTreeMap<String, ProfileSpec>);
+
+/// Reads the named builds in `path`
+pub fn load(path: impl AsRef<Path>) -> io::Result<Profiles> {
+    let path = path.as_ref();
+    toml::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| invalid_data(format!("{}: {e}", path.display())))
+}
+
+impl Profiles {
+    /// The names of every build, in alphabetical order
+    pub fn names(&self) -> Vec<String> {
+        self.0.keys().cloned().collect()
+    }
+
+    /// The build called `name`, with what it inherits
+    pub fn resolve(&self, name: &str) -> Result<NamedProfile, String> {
+        let mut spec = self
+            .0
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("there's no profile called {name}"))?;
+        let mut chain = vec![name];
+        while let Some(parent) = spec.inherits.clone() {
+            let (parent, parent_spec) = self.0.get_key_value(&parent).ok_or_else(|| {
+                format!(
+                    "{} inherits from {parent}, which isn't a profile",
+                    chain.last().unwrap()
+                )
+            })?;
+            if chain.contains(&parent.as_str()) {
+                return Err(format!(
+                    "the profile {name} inherits from itself ({} -> {parent})",
+                    chain.join(" -> ")
+                ));
+            }
+            chain.push(parent);
+            spec = spec.or(parent_spec.clone());
+        }
+
+        let mut profile = Profile::MAIN;
+        for (capability, has) in [
+            (Capability::Ascii, spec.ascii),
+            (Capability::LatinLigatures, spec.latin_ligatures),
+            (Capability::DigitSelectors, spec.digit_selectors),
+            (Capability::Rand, spec.rand),
+        ] {
+            if let Some(has) = has {
+                profile = profile.with(capability, has);
+            }
+        }
+        profile.outlines = spec.outlines.unwrap_or(profile.outlines);
+        // a build's profile is kept for as long as the program runs
+        profile.suffix = Box::leak(
+            spec.suffix
+                .unwrap_or_else(|| format!("-{name}"))
+                .into_boxed_str(),
+        );
+
+        let words = match (spec.words, spec.tags) {
+            (None, None) => None,
+            (words, tags) => {
+                let mut words = words
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect::<HashSet<_>>();
+                for tag in tags.unwrap_or_default() {
+                    words.extend(subset::words_tagged(&tag));
+                }
+                Some(words)
+            }
+        };
+
+        Ok(NamedProfile {
+            name: name.to_string(),
+            profile,
+            words,
+            include_blocks: spec.include_blocks.unwrap_or_default(),
+            exclude_blocks: spec.exclude_blocks.unwrap_or_default(),
+            corner_radius: spec.corner_radius,
+        })
+    }
+}
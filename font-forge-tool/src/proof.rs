@@ -0,0 +1,199 @@
+//! The proof sheet: an HTML page that exercises every glyph, alternate, combination, and cartouche length,
+//! so visual regressions can be spotted between releases
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{ffir::TAG_DIRECTIONAL, glyph_blocks::VERSION, query::GlyphQuery, Font};
+
+const STACKING_JOINER: char = '\u{F1995}';
+const SCALING_JOINER: char = '\u{F1996}';
+const START_CART: char = '\u{F1990}';
+const END_CART: char = '\u{F1991}';
+const LONGEST_CARTOUCHE: usize = 12;
+
+/// The variation selector a `_VARnn` suffix stands for
+fn variation_selector(n: u32) -> Option<char> {
+    match n {
+        1..=16 => char::from_u32(0xFE00 + n - 1),
+        17..=256 => char::from_u32(0xE0100 + n - 17),
+        _ => None,
+    }
+}
+
+/// Maps glyph names to the UCSUR text that displays them
+struct Texts {
+    codepoints: HashMap<String, char>,
+}
+
+impl Texts {
+    fn new(font: &Font) -> Self {
+        let codepoints = font
+            .encoded_glyphs()
+            .filter_map(|(codepoint, name)| {
+                Some((name.to_string(), char::from_u32(codepoint as u32)?))
+            })
+            .collect();
+        Self { codepoints }
+    }
+
+    /// The text for a glyph, which is either encoded itself or an alternate of an encoded glyph,
+    /// picked by a variation selector (`jakiTok_VAR01`) or another encoded glyph (`niTok_arrowN`)
+    fn get(&self, name: &str) -> Option<String> {
+        if let Some(c) = self.codepoints.get(name) {
+            return Some(c.to_string());
+        }
+        if let Some((base, var)) = name.rsplit_once("_VAR") {
+            return Some(format!(
+                "{}{}",
+                self.get(base)?,
+                variation_selector(var.parse().ok()?)?
+            ));
+        }
+        let (base, selector) = name.rsplit_once('_')?;
+        Some(format!(
+            "{}{}",
+            self.get(base)?,
+            self.codepoints.get(selector)?
+        ))
+    }
+}
+
+/// The full names of the glyphs in the blocks whose names start with `prefix` (e.g. `outer_`)
+fn block_glyphs(font: &Font, prefix: &str) -> impl Iterator<Item = String> {
+    GlyphQuery::blocks_starting_with(&[prefix])
+        .names(&font.blocks)
+        .into_iter()
+}
+
+fn section(title: &str, body: String) -> String {
+    format!("  <h2>{title}</h2>\n{body}")
+}
+
+/// A row of glyphs, each one labelled with its names on hover
+fn glyph_row(glyphs: impl Iterator<Item = (String, String)>) -> String {
+    let cells = glyphs
+        .map(|(label, text)| format!("<span title=\"{label}\">{text}</span>"))
+        .join(" ");
+    format!("  <p class=\"nasin-nanpa-UCSUR\">{cells}</p>\n")
+}
+
+/// Every pair of a glyph from the `first` blocks and one from the `second` blocks, joined with `joiner`.
+/// The combination glyphs are named after their parts, so `joined` strips the joiner back off.
+fn combinations(
+    font: &Font,
+    texts: &Texts,
+    first: &str,
+    second: &str,
+    joiner: char,
+    joined: &str,
+) -> String {
+    let firsts = block_glyphs(font, first)
+        .filter_map(|name| name.strip_suffix(&format!("_{joined}")).map(String::from))
+        .collect_vec();
+    let seconds = block_glyphs(font, second)
+        .filter_map(|name| name.strip_prefix(&format!("{joined}_")).map(String::from))
+        .collect_vec();
+    firsts
+        .iter()
+        .filter_map(|a| Some((a, texts.get(a)?)))
+        .map(|(a, a_text)| {
+            glyph_row(seconds.iter().filter_map(|b| {
+                Some((
+                    format!("{a} {b}"),
+                    format!("{a_text}{joiner}{}", texts.get(b)?),
+                ))
+            }))
+        })
+        .collect()
+}
+
+/// Generates the proof sheet for `font` (which should be the UCSUR variation)
+pub fn proof(font: &Font) -> String {
+    let texts = Texts::new(font);
+
+    let mut encoded = font
+        .encoded_glyphs()
+        .filter(|(codepoint, _)| *codepoint >= 0xF1900)
+        .collect_vec();
+    encoded.sort_by_key(|(codepoint, _)| *codepoint);
+    let every_glyph = glyph_row(
+        encoded
+            .iter()
+            .filter_map(|(_, name)| Some((name.to_string(), texts.get(name.as_str())?))),
+    );
+
+    let alternates = glyph_row(
+        block_glyphs(font, "base_alt").filter_map(|name| Some((name.clone(), texts.get(&name)?))),
+    );
+
+    let directional = glyph_row(
+        GlyphQuery::blocks_starting_with(&["base_"])
+            .with_tag(TAG_DIRECTIONAL)
+            .names(&font.blocks)
+            .into_iter()
+            .filter_map(|name| Some((name.clone(), texts.get(&name)?))),
+    );
+
+    let words = encoded
+        .iter()
+        .filter(|(codepoint, _)| *codepoint < 0xF1989)
+        .filter_map(|(_, name)| texts.get(name.as_str()))
+        .collect_vec();
+    let cartouches = glyph_row((1..=LONGEST_CARTOUCHE).map(|len| {
+        let inside = words.iter().cycle().take(len).join("");
+        (
+            format!("{len} glyphs"),
+            format!("{START_CART}{inside}{END_CART}"),
+        )
+    }));
+
+    let sections = [
+        section("every glyph", every_glyph),
+        section("alternates", alternates),
+        section("directional glyphs", directional),
+        section("cartouches", cartouches),
+        section(
+            "stacking",
+            combinations(
+                font,
+                &texts,
+                "lower_",
+                "upper_",
+                STACKING_JOINER,
+                "joinStackTok",
+            ),
+        ),
+        section(
+            "scaling",
+            combinations(
+                font,
+                &texts,
+                "outer_",
+                "inner_",
+                SCALING_JOINER,
+                "joinScaleTok",
+            ),
+        ),
+    ]
+    .concat();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="tok">
+<head>
+  <meta charset="utf-8">
+  <title>nasin nanpa {VERSION} proof</title>
+  <link rel="stylesheet" href="nasin-nanpa.css">
+  <style>
+    p.nasin-nanpa-UCSUR {{ font-size: 2em; line-height: 1.5; }}
+  </style>
+</head>
+<body>
+  <h1>nasin nanpa {VERSION} proof</h1>
+{sections}</body>
+</html>
+"#
+    )
+}
@@ -0,0 +1,231 @@
+//! Selecting glyphs by the blocks they're in and their names, for the classes of the contextual
+//! subtables
+
+use crate::ffir::{GlyphBlock, GlyphFull, TAG_PADDING};
+
+/// Which blocks a query looks in
+enum BlockSelection {
+    Named(Vec<String>),
+    StartingWith(Vec<String>),
+    AllExcept(Vec<String>),
+}
+
+type Predicate<'p> = Box<dyn Fn(&GlyphFull) -> bool + 'p>;
+
+/// A selection of glyphs: those in some blocks, minus the ones whose names contain any of some
+/// parts, whose tags don't match, or that any of some predicates reject. Padding is always left
+/// out.
+pub struct GlyphQuery<'p> {
+    blocks: BlockSelection,
+    excluded_parts: Vec<String>,
+    required_tags: Vec<&'static str>,
+    excluded_tags: Vec<&'static str>,
+    predicates: Vec<Predicate<'p>>,
+}
+
+impl<'p> GlyphQuery<'p> {
+    fn new(blocks: BlockSelection) -> Self {
+        Self {
+            blocks,
+            excluded_parts: vec![],
+            required_tags: vec![],
+            excluded_tags: vec![],
+            predicates: vec![],
+        }
+    }
+
+    /// The glyphs in the blocks named `names`
+    pub fn blocks(names: &[&str]) -> Self {
+        Self::new(BlockSelection::Named(to_strings(names)))
+    }
+
+    /// The glyphs in the blocks whose names start with any of `prefixes`
+    pub fn blocks_starting_with(prefixes: &[&str]) -> Self {
+        Self::new(BlockSelection::StartingWith(to_strings(prefixes)))
+    }
+
+    /// The glyphs in every block except the ones named `names`
+    pub fn all_blocks_except(names: &[&str]) -> Self {
+        Self::new(BlockSelection::AllExcept(to_strings(names)))
+    }
+
+    /// Leaves out the glyphs whose names contain any of `parts`
+    pub fn without_names_containing(mut self, parts: &[&str]) -> Self {
+        self.excluded_parts.extend(to_strings(parts));
+        self
+    }
+
+    /// Leaves out the glyphs without the tag `tag`
+    pub fn with_tag(mut self, tag: &'static str) -> Self {
+        self.required_tags.push(tag);
+        self
+    }
+
+    /// Leaves out the glyphs with the tag `tag`
+    pub fn without_tag(mut self, tag: &'static str) -> Self {
+        self.excluded_tags.push(tag);
+        self
+    }
+
+    /// Leaves out the glyphs that `predicate` rejects
+    pub fn filter(mut self, predicate: impl Fn(&GlyphFull) -> bool + 'p) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    pub fn includes_block(&self, block: &GlyphBlock) -> bool {
+        let name = block.name.as_str();
+        match &self.blocks {
+            BlockSelection::Named(names) => names.iter().any(|n| n == name),
+            BlockSelection::StartingWith(prefixes) => prefixes
+                .iter()
+                .any(|prefix| name.starts_with(prefix.as_str())),
+            BlockSelection::AllExcept(names) => !names.iter().any(|n| n == name),
+        }
+    }
+
+    /// Whether `glyph` (in a block the query looks in) is selected
+    pub fn matches(&self, glyph: &GlyphFull) -> bool {
+        let name = glyph.glyph.name.as_ref();
+        !glyph.glyph.has_tag(TAG_PADDING)
+            && self
+                .required_tags
+                .iter()
+                .all(|tag| glyph.glyph.has_tag(tag))
+            && !self
+                .excluded_tags
+                .iter()
+                .any(|tag| glyph.glyph.has_tag(tag))
+            && !self
+                .excluded_parts
+                .iter()
+                .any(|part| name.contains(part.as_str()))
+            && self.predicates.iter().all(|keep| keep(glyph))
+    }
+
+    /// The selected glyphs of `blocks`, each with its block, in order
+    pub fn select<'b>(&self, blocks: &'b [GlyphBlock]) -> Vec<(&'b GlyphBlock, &'b GlyphFull)> {
+        blocks
+            .iter()
+            .filter(|block| self.includes_block(block))
+            .flat_map(|block| {
+                block
+                    .glyphs
+                    .iter()
+                    .filter(|glyph| self.matches(glyph))
+                    .map(move |glyph| (block, glyph))
+            })
+            .collect()
+    }
+
+    /// The names of the selected glyphs of `blocks` (with their blocks' prefixes and suffixes)
+    pub fn names(&self, blocks: &[GlyphBlock]) -> Vec<String> {
+        self.select(blocks)
+            .into_iter()
+            .map(|(block, glyph)| format!("{}{}{}", block.prefix, glyph.glyph.name, block.suffix))
+            .collect()
+    }
+}
+
+fn to_strings(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffir::{renumber, Cc, EncPos, GlyphBasic, LookupsMode, Rep};
+
+    /// A block called `name` of the glyphs `glyphs`, each with its tags
+    fn block(name: &str, glyphs: &[(&'static str, &[&'static str])]) -> GlyphBlock {
+        let glyphs = glyphs
+            .iter()
+            .map(|(name, tags)| {
+                let mut glyph = GlyphBasic::new(*name, 1000, Rep::default(), None);
+                glyph.tags = tags.to_vec();
+                glyph
+            })
+            .collect();
+        GlyphBlock::new_from_basic_glyphs(
+            glyphs,
+            LookupsMode::None,
+            Cc::None,
+            "",
+            "Tok",
+            "",
+            EncPos::None,
+        )
+        .named(name)
+    }
+
+    /// A few glyphs in three blocks, padded like the font's
+    fn blocks() -> Vec<GlyphBlock> {
+        let mut blocks = vec![
+            block(
+                "base_cor",
+                &[("akesi", &["animal"]), ("kala", &["animal"]), ("toki", &[])],
+            ),
+            block("base_alt", &[("akesiAlt", &["animal"]), ("tokiAlt", &[])]),
+            block("ctrl", &[("startCart", &[])]),
+        ];
+        renumber(&mut blocks);
+        blocks
+    }
+
+    #[test]
+    fn padding_is_left_out() {
+        let blocks = blocks();
+        let query = GlyphQuery::all_blocks_except(&[]);
+        assert_eq!(blocks[0].glyphs.len(), 16);
+        assert!(!query.matches(&blocks[0].glyphs[3]));
+        assert_eq!(query.select(&blocks).len(), 6);
+    }
+
+    #[test]
+    fn blocks_are_selected_by_name() {
+        let blocks = blocks();
+        assert_eq!(
+            GlyphQuery::blocks_starting_with(&["base"]).names(&blocks),
+            [
+                "akesiTok",
+                "kalaTok",
+                "tokiTok",
+                "akesiAltTok",
+                "tokiAltTok"
+            ]
+        );
+        assert_eq!(
+            GlyphQuery::all_blocks_except(&["base_cor", "base_alt"]).names(&blocks),
+            ["startCartTok"]
+        );
+    }
+
+    #[test]
+    fn tags_filter_glyphs() {
+        let blocks = blocks();
+        assert_eq!(
+            GlyphQuery::blocks(&["base_cor", "base_alt"])
+                .with_tag("animal")
+                .names(&blocks),
+            ["akesiTok", "kalaTok", "akesiAltTok"]
+        );
+        assert_eq!(
+            GlyphQuery::blocks(&["base_cor"])
+                .without_tag("animal")
+                .names(&blocks),
+            ["tokiTok"]
+        );
+    }
+
+    #[test]
+    fn parts_and_predicates_filter_glyphs() {
+        let blocks = blocks();
+        assert_eq!(
+            GlyphQuery::blocks(&["base_cor", "base_alt"])
+                .without_names_containing(&["Alt"])
+                .filter(|glyph| glyph.glyph.name != "kala")
+                .names(&blocks),
+            ["akesiTok", "tokiTok"]
+        );
+    }
+}
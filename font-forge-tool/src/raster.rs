@@ -0,0 +1,144 @@
+//! Filling glyph outlines (with their references) into bitmaps of how much of each pixel is
+//! inked, for previewing glyphs without FontForge
+
+use std::{collections::HashMap, fs::File, io, path::Path};
+
+use itertools::Itertools;
+
+use crate::{
+    spline::{spans_at, Point, SplineSet},
+    Font,
+};
+
+/// How many rows each row of pixels is sampled along
+const SAMPLES: usize = 4;
+
+/// Every glyph's outlines in `font`, with the outlines of the glyphs it references put where
+/// they're shown (the glyphs are in order, so references come before the glyphs that use them)
+pub fn outlines(font: &Font) -> Result<HashMap<String, SplineSet>, String> {
+    let mut outlines = HashMap::<String, SplineSet>::new();
+    for block in &font.blocks {
+        for (name, glyph) in block.full_names() {
+            let rep = &glyph.glyph.rep;
+            let mut spline_set = SplineSet::parse(rep.spline_set())?;
+            for reference in rep.references() {
+                let Some(target) = outlines.get(reference.target()) else {
+                    continue;
+                };
+                let transform = reference.transform();
+                let shown = target.mapped(|p| transform.apply(p));
+                spline_set.contours.extend(shown.contours);
+            }
+            outlines.insert(name.to_string(), spline_set);
+        }
+    }
+    Ok(outlines)
+}
+
+/// How much of each pixel (from 0 to 1) is inked, row by row from the top
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub coverage: Vec<f64>,
+}
+
+impl Bitmap {
+    pub fn get(&self, x: usize, y: usize) -> f64 {
+        self.coverage[y * self.width + x]
+    }
+
+    /// Writes the bitmap as a grayscale PNG, black on white
+    pub fn write_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut encoder =
+            png::Encoder::new(File::create(path)?, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let pixels = self
+            .coverage
+            .iter()
+            .map(|coverage| 255 - (coverage.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect::<Vec<_>>();
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(io::Error::other)
+    }
+}
+
+/// Fills `spline_set` (by the nonzero rule) into a bitmap `width` by `height` pixels, with the
+/// box from `min` to `max` scaled to fit in the middle of it
+pub fn rasterize(
+    spline_set: &SplineSet,
+    (min, max): (Point, Point),
+    width: usize,
+    height: usize,
+) -> Bitmap {
+    let scale = (width as f64 / (max.x - min.x)).min(height as f64 / (max.y - min.y));
+    let (dx, dy) = (
+        (width as f64 - (max.x - min.x) * scale) / 2.0,
+        (height as f64 - (max.y - min.y) * scale) / 2.0,
+    );
+    // the polygons in pixels, with y going down
+    let polygons = spline_set
+        .polygons()
+        .into_iter()
+        .map(|polygon| {
+            polygon
+                .into_iter()
+                .map(|p| {
+                    Point::new(
+                        (p.x - min.x) * scale + dx,
+                        height as f64 - ((p.y - min.y) * scale + dy),
+                    )
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let mut coverage = vec![0.0; width * height];
+    for row in 0..height {
+        for sample in 0..SAMPLES {
+            let y = row as f64 + (sample as f64 + 0.5) / SAMPLES as f64;
+            for (start, end) in spans_at(&polygons, y) {
+                let (start, end) = (start.max(0.0), end.min(width as f64));
+                if start >= end {
+                    continue;
+                }
+                for x in start.floor() as usize..(end.ceil() as usize).min(width) {
+                    let inked = end.min(x as f64 + 1.0) - start.max(x as f64);
+                    coverage[row * width + x] += inked / SAMPLES as f64;
+                }
+            }
+        }
+    }
+    Bitmap {
+        width,
+        height,
+        coverage,
+    }
+}
+
+/// `bitmap` drawn with braille characters, each showing 2 by 4 pixels (a dot for each one that's
+/// at least half inked)
+pub fn braille(bitmap: &Bitmap) -> Vec<String> {
+    // the bit of each dot, by its row and then its column
+    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+    (0..bitmap.height.div_ceil(4))
+        .map(|row| {
+            (0..bitmap.width.div_ceil(2))
+                .map(|column| {
+                    let mut bits = 0;
+                    for (dy, dots) in DOTS.iter().enumerate() {
+                        for (dx, dot) in dots.iter().enumerate() {
+                            let (x, y) = (column * 2 + dx, row * 4 + dy);
+                            if x < bitmap.width && y < bitmap.height && bitmap.get(x, y) >= 0.5 {
+                                bits |= dot;
+                            }
+                        }
+                    }
+                    char::from_u32(0x2800 + bits).unwrap()
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,143 @@
+//! An analysis of which glyphs can't be reached (typed, made by a lookup from glyphs that can be,
+//! or referenced by one that can be), and which lookups only have entries for missing glyphs. The
+//! glyphs in `data/latin-only.txt` are only typed with the latin ligatures, so a variation without
+//! them is expected not to reach them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+use crate::{
+    invalid_data,
+    profile::Capability,
+    rules::{self, GlyphSection},
+    sfd_filename, Font,
+};
+
+const LATIN_ONLY: &str = include_str!("../data/latin-only.txt");
+
+/// The glyphs in `data/latin-only.txt`
+fn latin_only() -> HashSet<&'static str> {
+    LATIN_ONLY
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+pub struct ReachabilityReport {
+    /// The `.sfd` the font is written to
+    pub font: String,
+    /// The glyphs that can't be reached
+    pub unreachable: Vec<String>,
+    /// The lookups whose every entry mentions a glyph that isn't in the font
+    pub dead_lookups: Vec<String>,
+}
+
+pub fn report(font: &Font, sections: &[GlyphSection]) -> ReachabilityReport {
+    let rules = rules::rules(font, sections);
+    let names = sections
+        .iter()
+        .map(|glyph| glyph.name.as_str())
+        .collect::<HashSet<_>>();
+    let references = sections
+        .iter()
+        .map(|glyph| (glyph.name.as_str(), &glyph.references))
+        .collect::<HashMap<_, _>>();
+
+    let mut reachable = HashSet::new();
+    let mut queue = sections
+        .iter()
+        .filter(|glyph| glyph.encoded)
+        .map(|glyph| glyph.name.as_str())
+        .collect::<Vec<_>>();
+    loop {
+        while let Some(name) = queue.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            if let Some(references) = references.get(name) {
+                queue.extend(references.iter().map(String::as_str));
+            }
+        }
+        // a lookup makes its outputs once all of its inputs can be reached
+        queue.extend(
+            rules
+                .iter()
+                .filter(|rule| rule.inputs.iter().all(|input| reachable.contains(input)))
+                .flat_map(|rule| rule.outputs.iter().copied())
+                .filter(|output| !reachable.contains(output)),
+        );
+        if queue.is_empty() {
+            break;
+        }
+    }
+
+    // without the latin ligatures, the glyphs only typed with them (and the combinations made with
+    // them) can't be reached
+    let expected = if font.profile.has(Capability::LatinLigatures) {
+        HashSet::new()
+    } else {
+        latin_only()
+    };
+    let unreachable = sections
+        .iter()
+        .map(|glyph| glyph.name.clone())
+        .filter(|name| !reachable.contains(name.as_str()))
+        .filter(|name| !name.split('_').any(|part| expected.contains(part)))
+        .collect();
+
+    let mut entries: HashMap<&str, (usize, usize)> = HashMap::new();
+    for rule in &rules {
+        let (total, missing) = entries.entry(rule.lookup).or_default();
+        *total += 1;
+        let mentions = rule.inputs.iter().chain(&rule.outputs);
+        if mentions.into_iter().any(|glyph| !names.contains(glyph)) {
+            *missing += 1;
+        }
+    }
+    let dead_lookups = font
+        .features
+        .lookup_names()
+        .filter(|lookup| matches!(entries.get(lookup), Some((total, missing)) if total == missing))
+        .map(String::from)
+        .collect();
+
+    ReachabilityReport {
+        font: sfd_filename(font.profile),
+        unreachable,
+        dead_lookups,
+    }
+}
+
+impl ReachabilityReport {
+    /// Warns about the glyphs that can't be reached and the dead lookups, or fails if `strict`
+    pub fn gate(&self, strict: bool) -> io::Result<()> {
+        if self.unreachable.is_empty() && self.dead_lookups.is_empty() {
+            return Ok(());
+        }
+        let level = if strict { "error" } else { "warning" };
+        for glyph in &self.unreachable {
+            eprintln!(
+                "{level}: {}: {glyph} can't be typed or made by any lookup",
+                self.font
+            );
+        }
+        for lookup in &self.dead_lookups {
+            eprintln!(
+                "{level}: {}: every entry in {lookup} mentions a glyph that isn't in the font",
+                self.font
+            );
+        }
+        if !strict {
+            return Ok(());
+        }
+        Err(invalid_data(format!(
+            "{}: {} unreachable glyphs and {} dead lookups",
+            self.font,
+            self.unreachable.len(),
+            self.dead_lookups.len()
+        )))
+    }
+}
@@ -0,0 +1,58 @@
+//! The entries each glyph of a generated font has in the lookups, as `Rule`s, for the checks of
+//! how the glyphs are reached
+
+use crate::{fallback, features::Rule, ffir::TAG_PADDING, Font};
+
+/// A glyph of a font (but not padding), with its generated section
+pub struct GlyphSection {
+    pub name: String,
+    pub section: String,
+    /// Whether it has a codepoint, so it can be typed (or is `.notdef`, which is drawn for the
+    /// characters that don't)
+    pub encoded: bool,
+    /// The glyphs it references
+    pub references: Vec<String>,
+}
+
+/// Generates the section of every glyph in `font`
+pub fn sections(font: &Font) -> Vec<GlyphSection> {
+    font.blocks
+        .iter()
+        .flat_map(|block| {
+            block
+                .full_names()
+                .filter(|(_, glyph)| !glyph.glyph.has_tag(TAG_PADDING))
+                .map(|(name, glyph)| GlyphSection {
+                    name: name.to_string(),
+                    section: glyph.gen(
+                        &block.prefix,
+                        &block.suffix,
+                        &block.color,
+                        font.profile,
+                        &font.features,
+                    ),
+                    encoded: glyph.encoding.is_mapped() || name.as_str() == fallback::NOTDEF,
+                    references: glyph
+                        .glyph
+                        .rep
+                        .references()
+                        .iter()
+                        .map(|reference| reference.target().to_string())
+                        .collect(),
+                })
+        })
+        .collect()
+}
+
+/// The entries of every glyph in `sections`
+pub fn rules<'a>(font: &'a Font, sections: &'a [GlyphSection]) -> Vec<Rule<'a>> {
+    sections
+        .iter()
+        .flat_map(|glyph| {
+            glyph
+                .section
+                .lines()
+                .filter_map(|line| font.features.parse_rule(&glyph.name, line))
+        })
+        .collect()
+}
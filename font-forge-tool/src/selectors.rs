@@ -0,0 +1,167 @@
+//! The selectors that pick a numbered alternate after a glyph: the variation selectors, the digits
+//! (typed out in ASCII, in the main variation), and the arrows (for glyphs that point).
+
+use std::collections::HashMap;
+
+use crate::{
+    ffir::{EncPos, GlyphBlock, Lookups, VariationSequence},
+    profile::Capability,
+    Profile,
+};
+
+/// What kind of character a selector is
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SelectorKind {
+    Variation,
+    Digit,
+    Arrow,
+}
+
+/// A selector glyph, and the index of the alternate it picks
+pub struct Selector {
+    pub name: &'static str,
+    pub kind: SelectorKind,
+    pub index: usize,
+    /// The codepoint, for selectors that are their own glyph (and not a letter's)
+    pub codepoint: Option<usize>,
+}
+
+impl Selector {
+    const fn variation(name: &'static str, index: usize) -> Self {
+        Self {
+            name,
+            kind: SelectorKind::Variation,
+            index,
+            codepoint: Some(0xFE00 + index - 1),
+        }
+    }
+
+    const fn digit(name: &'static str, index: usize) -> Self {
+        Self {
+            name,
+            kind: SelectorKind::Digit,
+            index,
+            codepoint: None,
+        }
+    }
+
+    const fn arrow(name: &'static str, index: usize) -> Self {
+        Self {
+            name,
+            kind: SelectorKind::Arrow,
+            index,
+            codepoint: None,
+        }
+    }
+}
+
+/// Every selector, and the alternate each one picks
+pub struct SelectorTable {
+    selectors: &'static [Selector],
+}
+
+/// The selectors of nasin nanpa
+pub const SELECTORS: SelectorTable = SelectorTable {
+    selectors: &[
+        Selector::variation("VAR01", 1),
+        Selector::variation("VAR02", 2),
+        Selector::variation("VAR03", 3),
+        Selector::variation("VAR04", 4),
+        Selector::variation("VAR05", 5),
+        Selector::variation("VAR06", 6),
+        Selector::variation("VAR07", 7),
+        Selector::variation("VAR08", 8),
+        Selector::variation("VAR09", 9),
+        Selector::digit("one", 1),
+        Selector::digit("two", 2),
+        Selector::digit("three", 3),
+        Selector::digit("four", 4),
+        Selector::digit("five", 5),
+        Selector::digit("six", 6),
+        Selector::digit("seven", 7),
+        Selector::digit("eight", 8),
+        Selector::digit("nine", 9),
+        Selector::arrow("arrowW", 1),
+        Selector::arrow("arrowN", 2),
+        Selector::arrow("arrowE", 3),
+        Selector::arrow("arrowS", 4),
+        Selector::arrow("arrowNW", 5),
+        Selector::arrow("arrowNE", 6),
+        Selector::arrow("arrowSE", 7),
+        Selector::arrow("arrowSW", 8),
+    ],
+};
+
+impl SelectorTable {
+    pub fn get(&self, name: &str) -> Option<&'static Selector> {
+        self.selectors.iter().find(|selector| selector.name == name)
+    }
+
+    /// The selectors of a kind, in order
+    pub fn of_kind(&self, kind: SelectorKind) -> impl Iterator<Item = &'static Selector> {
+        self.selectors
+            .iter()
+            .filter(move |selector| selector.kind == kind)
+    }
+
+    /// The selectors that pick the same alternate as `name` in `profile`: a variation selector
+    /// can also be typed as its digit (where there's ASCII input), and an arrow only as itself.
+    /// Names that aren't selectors pick only themselves.
+    pub fn pickers<'a>(&self, name: &'a str, profile: Profile) -> Vec<&'a str> {
+        let Some(selector) = self.get(name) else {
+            return vec![name];
+        };
+        if selector.kind == SelectorKind::Arrow {
+            return vec![selector.name];
+        }
+        let kinds = if profile.has(Capability::DigitSelectors) {
+            &[SelectorKind::Variation, SelectorKind::Digit][..]
+        } else {
+            &[SelectorKind::Variation]
+        };
+        kinds
+            .iter()
+            .flat_map(|&kind| self.of_kind(kind))
+            .filter(|other| other.index == selector.index)
+            .map(|other| other.name)
+            .collect()
+    }
+}
+
+/// Maps each alternate that a variation selector picks (like `jakiTok_VAR01`) from the sequence
+/// of its glyph's codepoint and the selector's, so renderers that look variation sequences up in
+/// the cmap (rather than shaping them) find it too
+pub fn map_variation_sequences(blocks: &mut [GlyphBlock]) {
+    let codepoints = blocks
+        .iter()
+        .flat_map(|block| block.full_names())
+        .filter_map(|(name, glyph)| match glyph.encoding.enc_pos {
+            EncPos::Pos(codepoint) => Some((name.to_string(), codepoint)),
+            EncPos::None => None,
+        })
+        .collect::<HashMap<_, _>>();
+    for block in blocks {
+        let names = block
+            .full_names()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        for (name, glyph) in names.iter().zip(&mut block.glyphs) {
+            if !matches!(glyph.lookups, Lookups::Alt) {
+                continue;
+            }
+            let Some((base, sel)) = name.split_once('_') else {
+                continue;
+            };
+            let selector = SELECTORS
+                .get(sel)
+                .filter(|selector| selector.kind == SelectorKind::Variation)
+                .and_then(|selector| selector.codepoint);
+            if let (Some(&base), Some(selector)) = (codepoints.get(base), selector) {
+                glyph
+                    .encoding
+                    .variation_sequences
+                    .push(VariationSequence { base, selector });
+            }
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! A check of the bookkeeping in an `.sfd`, as it's written or read back: the numbers FontForge
+//! reads before what they count or point to (the glyphs of `BeginChars`, each glyph's index, the
+//! classes and rules of the contextual subtables and the lengths of their classes, the glyphs
+//! references point to, and the subtables entries go in) have to match what the file holds
+
+use std::collections::{HashMap, HashSet};
+
+/// The keys of the glyph lines that name the subtable they're an entry of
+const ENTRY_KEYS: [&str; 6] = [
+    "Ligature2",
+    "MultipleSubs2",
+    "Substitution2",
+    "AlternateSubs2",
+    "Position2",
+    "PairPos2",
+];
+
+/// The numbers in `rest`, failing on anything else
+fn numbers(line: &str, rest: &str) -> Result<Vec<i64>, String> {
+    rest.split_whitespace()
+        .map(|n| n.parse().map_err(|_| format!("couldn't parse `{line}`")))
+        .collect()
+}
+
+/// The strings quoted in `line`
+fn quoted(line: &str) -> impl Iterator<Item = &str> {
+    line.split('"').skip(1).step_by(2)
+}
+
+/// A rule of a contextual subtable: the counts it starts with, and what follows them
+#[derive(Default)]
+struct Rule {
+    counts: Vec<i64>,
+    /// The classes of the input, backtrack, and lookahead, in the order of the counts
+    lists: [Vec<i64>; 3],
+    seq_count: i64,
+    /// Where each lookup is applied in the input
+    seq_positions: Vec<i64>,
+}
+
+/// A contextual subtable, as far as it's counted
+struct Fpst<'a> {
+    subtable: &'a str,
+    /// The counts of the classes (of the input, backtrack, and lookahead), and of the rules
+    counts: Vec<i64>,
+    /// How many classes are listed for each part, besides the first (every other glyph)
+    classes: [i64; 3],
+    class_names: [Option<usize>; 3],
+    rules: Vec<Rule>,
+    lookups: Vec<&'a str>,
+}
+
+impl Fpst<'_> {
+    fn check(&self, lookups: &HashSet<&str>, errors: &mut Vec<String>) {
+        let subtable = self.subtable;
+        if self.counts.len() != 4 {
+            errors.push(format!("{subtable} doesn't start with 4 counts"));
+            return;
+        }
+        for (part, name) in ["input", "backtrack", "lookahead"].iter().enumerate() {
+            let classes = self.classes[part] + 1;
+            if self.counts[part] != classes {
+                errors.push(format!(
+                    "{subtable} counts {} {name} classes, but has {classes}",
+                    self.counts[part]
+                ));
+            }
+            if let Some(names) = self.class_names[part] {
+                if names as i64 != classes {
+                    errors.push(format!(
+                        "{subtable} names {names} {name} classes, but has {classes}"
+                    ));
+                }
+            }
+        }
+        if self.counts[3] != self.rules.len() as i64 {
+            errors.push(format!(
+                "{subtable} counts {} rules, but has {}",
+                self.counts[3],
+                self.rules.len()
+            ));
+        }
+        for (i, rule) in self.rules.iter().enumerate() {
+            for part in 0..3 {
+                let list = &rule.lists[part];
+                if rule.counts.get(part) != Some(&(list.len() as i64)) {
+                    errors.push(format!(
+                        "{subtable} rule {i} counts {:?} classes, but lists {list:?}",
+                        rule.counts
+                    ));
+                }
+                if let Some(class) = list
+                    .iter()
+                    .find(|class| **class < 0 || **class > self.classes[part])
+                {
+                    errors.push(format!(
+                        "{subtable} rule {i} lists class {class}, which it lacks"
+                    ));
+                }
+            }
+            if rule.seq_count != rule.seq_positions.len() as i64 {
+                errors.push(format!(
+                    "{subtable} rule {i} counts {} lookups, but applies {}",
+                    rule.seq_count,
+                    rule.seq_positions.len()
+                ));
+            }
+            if let Some(position) = rule
+                .seq_positions
+                .iter()
+                .find(|position| **position < 0 || **position >= rule.lists[0].len() as i64)
+            {
+                errors.push(format!(
+                    "{subtable} rule {i} applies a lookup at {position}, past its input"
+                ));
+            }
+        }
+        for lookup in &self.lookups {
+            if !lookups.contains(lookup) {
+                errors.push(format!("{subtable} applies {lookup}, which isn't declared"));
+            }
+        }
+    }
+}
+
+/// Reads the contextual subtable `subtable` from `lines` (after its first line, which has
+/// `counts`), up to its `EndFPST`
+fn read_fpst<'a>(
+    subtable: &'a str,
+    counts: Vec<i64>,
+    lines: &mut impl Iterator<Item = &'a str>,
+    errors: &mut Vec<String>,
+) -> Result<Fpst<'a>, String> {
+    let mut fpst = Fpst {
+        subtable,
+        counts,
+        classes: [0; 3],
+        class_names: [None; 3],
+        rules: vec![],
+        lookups: vec![],
+    };
+    for line in lines.by_ref() {
+        let trimmed = line.trim();
+        let Some((key, rest)) = trimmed.split_once(':') else {
+            if trimmed == "EndFPST" {
+                return Ok(fpst);
+            }
+            // the counts that start a rule, then the count of the lookups it applies
+            let counts = numbers(line, trimmed)?;
+            match (counts.len(), fpst.rules.last_mut()) {
+                (1, Some(rule)) => rule.seq_count = counts[0],
+                _ => fpst.rules.push(Rule {
+                    counts,
+                    ..Default::default()
+                }),
+            }
+            continue;
+        };
+        let part = |prefix: &str| ["", "B", "F"].iter().position(|p| *p == prefix);
+        match key {
+            "Class" | "BClass" | "FClass" => {
+                let part = part(&key[..key.len() - 5]).unwrap();
+                fpst.classes[part] += 1;
+                let rest = rest.trim_start();
+                let (length, members) = rest.split_once(' ').unwrap_or((rest, ""));
+                if length.parse() != Ok(members.len()) {
+                    errors.push(format!(
+                        "{subtable} has a class {length} long, but {} long: {members}",
+                        members.len()
+                    ));
+                }
+            }
+            "ClassNames" | "BClassNames" | "FClassNames" => {
+                let part = part(&key[..key.len() - 10]).unwrap();
+                fpst.class_names[part] = Some(quoted(rest).count());
+            }
+            "ClsList" | "BClsList" | "FClsList" => {
+                let part = part(&key[..key.len() - 7]).unwrap();
+                let rule = fpst
+                    .rules
+                    .last_mut()
+                    .ok_or(format!("{subtable} lists classes before its first rule"))?;
+                rule.lists[part] = numbers(line, rest)?;
+            }
+            "SeqLookup" => {
+                let rule = fpst
+                    .rules
+                    .last_mut()
+                    .ok_or(format!("{subtable} applies a lookup before its first rule"))?;
+                let position = rest.split_whitespace().next().unwrap_or_default();
+                rule.seq_positions.extend(numbers(line, position)?);
+                fpst.lookups.extend(quoted(rest));
+            }
+            _ => {}
+        }
+    }
+    Err(format!("{subtable} has no EndFPST"))
+}
+
+/// A glyph, as far as it's counted
+struct Glyph {
+    name: String,
+    /// The codepoint (-1 for none) and the index
+    encoding: Option<(i64, i64)>,
+    /// The index and codepoint of each glyph referenced
+    references: Vec<(i64, i64)>,
+}
+
+/// A check of an `.sfd` fed to it a section at a time, as it's written. A section has to end at
+/// the end of a line, and a contextual subtable has to be in the same section as the lookups it
+/// applies (they're all in the header).
+#[derive(Default)]
+pub struct Checker {
+    errors: Vec<String>,
+    lookups: HashSet<String>,
+    subtables: HashSet<String>,
+    begin_chars: Option<Vec<i64>>,
+    glyphs: Vec<Glyph>,
+    /// The glyph each entry is of (none for an anchor class), and the subtable it's in
+    entries: Vec<(String, String)>,
+}
+
+impl Checker {
+    /// Checks the next section of the `.sfd`, failing only on what keeps it from being read
+    pub fn feed(&mut self, section: &str) -> Result<(), String> {
+        let mut fpsts = vec![];
+        let mut lines = section.lines();
+        while let Some(line) = lines.next() {
+            let Some((key, rest)) = line.split_once(':') else {
+                continue;
+            };
+            match key {
+                "Lookup" => {
+                    let mut names = quoted(rest).map(str::to_string);
+                    self.lookups.extend(names.next());
+                    self.subtables.extend(names);
+                }
+                "ContextSub2" | "ChainSub2" | "ContextPos2" | "ChainPos2" => {
+                    let subtable = quoted(rest).next().unwrap_or_default();
+                    let counts = numbers(line, rest.rsplit('"').next().unwrap_or_default())?;
+                    fpsts.push(read_fpst(subtable, counts, &mut lines, &mut self.errors)?);
+                }
+                "AnchorClass2" => {
+                    self.entries.extend(
+                        quoted(rest)
+                            .skip(1)
+                            .step_by(2)
+                            .map(|subtable| (String::new(), subtable.to_string())),
+                    );
+                }
+                "BeginChars" => self.begin_chars = Some(numbers(line, rest)?),
+                "StartChar" => self.glyphs.push(Glyph {
+                    name: rest.trim().to_string(),
+                    encoding: None,
+                    references: vec![],
+                }),
+                _ => {
+                    let Some(glyph) = self.glyphs.last_mut() else {
+                        continue;
+                    };
+                    match key {
+                        "Encoding" => {
+                            let encoding = numbers(line, rest)?;
+                            match encoding[..] {
+                                [_, codepoint, index] => glyph.encoding = Some((codepoint, index)),
+                                _ => self
+                                    .errors
+                                    .push(format!("{}: couldn't parse `{line}`", glyph.name)),
+                            }
+                        }
+                        "Refer" => {
+                            let mut fields = rest.split_whitespace();
+                            let (Some(index), Some(codepoint)) = (fields.next(), fields.next())
+                            else {
+                                return Err(format!("couldn't parse `{line}`"));
+                            };
+                            let reference = numbers(line, &format!("{index} {codepoint}"))?;
+                            glyph.references.push((reference[0], reference[1]));
+                        }
+                        "LCarets2" => {
+                            let carets = numbers(line, rest)?;
+                            if carets.first() != Some(&(carets.len() as i64 - 1)) {
+                                self.errors.push(format!(
+                                    "{}: {line} doesn't count its carets",
+                                    glyph.name
+                                ));
+                            }
+                        }
+                        _ if ENTRY_KEYS.contains(&key) => {
+                            self.entries.extend(
+                                quoted(rest)
+                                    .next()
+                                    .map(|subtable| (glyph.name.clone(), subtable.to_string())),
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let lookups: HashSet<&str> = self.lookups.iter().map(String::as_str).collect();
+        for fpst in &fpsts {
+            fpst.check(&lookups, &mut self.errors);
+        }
+        Ok(())
+    }
+
+    /// Fails with everything fed to the check whose count or index doesn't match what it counts
+    /// or points to
+    pub fn finish(mut self) -> Result<(), String> {
+        let errors = &mut self.errors;
+        for (glyph, subtable) in &self.entries {
+            if !self.subtables.contains(subtable) {
+                errors.push(format!(
+                    "{glyph} has an entry in {subtable}, which isn't declared"
+                ));
+            }
+        }
+        let glyphs = &self.glyphs;
+        match self.begin_chars.as_deref() {
+            Some(&[slots, count]) => {
+                if count != glyphs.len() as i64 {
+                    errors.push(format!(
+                        "BeginChars counts {count} glyphs, but there's {}",
+                        glyphs.len()
+                    ));
+                }
+                if slots < count {
+                    errors.push(format!("BeginChars has {slots} slots for {count} glyphs"));
+                }
+            }
+            _ => errors.push("there's no BeginChars with 2 counts".to_string()),
+        }
+        let mut codepoints = HashMap::new();
+        for (i, glyph) in glyphs.iter().enumerate() {
+            match glyph.encoding {
+                Some((codepoint, index)) => {
+                    if index != i as i64 {
+                        errors.push(format!(
+                            "{} is glyph {i}, but is indexed {index}",
+                            glyph.name
+                        ));
+                    }
+                    codepoints.insert(i as i64, codepoint);
+                }
+                None => errors.push(format!("{} has no Encoding", glyph.name)),
+            }
+        }
+        for glyph in glyphs {
+            for (index, codepoint) in &glyph.references {
+                match codepoints.get(index) {
+                    Some(actual) if actual == codepoint => {}
+                    Some(actual) => errors.push(format!(
+                        "{} references glyph {index} as {codepoint}, but it's {actual}",
+                        glyph.name
+                    )),
+                    None => errors.push(format!(
+                        "{} references glyph {index}, which there isn't",
+                        glyph.name
+                    )),
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "the .sfd's counts don't match what it holds:\n{}",
+                errors.join("\n")
+            ))
+        }
+    }
+}
+
+/// Fails with everything in `sfd` whose count or index doesn't match what it counts or points to
+pub fn check(sfd: &str) -> Result<(), String> {
+    let mut checker = Checker::default();
+    checker.feed(sfd)?;
+    checker.finish()
+}
@@ -0,0 +1,106 @@
+//! Shaping tests: a corpus of strings is run through rustybuzz with the compiled font,
+//! and the resulting glyph sequences are compared against the expected ones.
+//!
+//! Each non-empty corpus line that doesn't start with `#` looks like `input => glyph glyph ...`,
+//! where the input may contain `\u{XXXX}` escapes (e.g. `\u{200D}` for a ZWJ).
+
+use std::{fs, io, path::Path};
+
+use rustybuzz::{ttf_parser::GlyphId, Face, UnicodeBuffer};
+
+use crate::invalid_data;
+
+/// A string and the glyph sequence it should shape into
+pub struct ShapingCase {
+    pub line: usize,
+    pub input: String,
+    pub expected: Vec<String>,
+}
+
+/// Replaces `\u{XXXX}` escapes with the characters they stand for
+fn unescape(s: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(idx) = rest.find("\\u{") {
+        out.push_str(&rest[..idx]);
+        let (hex, after) = rest[idx + 3..].split_once('}')?;
+        out.push(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?);
+        rest = after;
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+pub fn load_corpus(path: impl AsRef<Path>) -> io::Result<Vec<ShapingCase>> {
+    let path = path.as_ref();
+    fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(idx, line)| {
+            let error = |msg: &str| invalid_data(format!("{}:{}: {msg}", path.display(), idx + 1));
+            let (input, expected) = line
+                .split_once(" => ")
+                .ok_or_else(|| error("expected `input => glyphs`"))?;
+            Ok(ShapingCase {
+                line: idx + 1,
+                input: unescape(input).ok_or_else(|| error("invalid \\u{...} escape"))?,
+                expected: expected.split_whitespace().map(String::from).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Shapes `text` with `face`, returning the names of the resulting glyphs
+fn shape(face: &Face, text: &str) -> Vec<String> {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    let glyphs = rustybuzz::shape(face, &[], buffer);
+    glyphs
+        .glyph_infos()
+        .iter()
+        .map(|info| {
+            let id = GlyphId(info.glyph_id as u16);
+            face.glyph_name(id)
+                .map(String::from)
+                .unwrap_or_else(|| format!("gid{}", id.0))
+        })
+        .collect()
+}
+
+/// Runs every case against the font at `font`, printing the failures
+pub fn run(font: impl AsRef<Path>, cases: &[ShapingCase]) -> io::Result<()> {
+    let font = font.as_ref();
+    let data = fs::read(font)?;
+    let face = Face::from_slice(&data, 0).ok_or_else(|| {
+        invalid_data(format!(
+            "{} isn't a font rustybuzz can read",
+            font.display()
+        ))
+    })?;
+
+    let mut failures = 0;
+    for case in cases {
+        let actual = shape(&face, &case.input);
+        if actual != case.expected {
+            failures += 1;
+            println!(
+                "line {}: {:?}\n  expected: {}\n    actual: {}",
+                case.line,
+                case.input,
+                case.expected.join(" "),
+                actual.join(" ")
+            );
+        }
+    }
+    println!(
+        "{} of {} shaping cases passed",
+        cases.len() - failures,
+        cases.len()
+    );
+
+    if failures > 0 {
+        return Err(io::Error::other(format!("{failures} shaping cases failed")));
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Moving the contours that glyphs share (like the circle of `ijo`, or the rays of the emitters)
+//! into component glyphs, which the glyphs reference instead, so the `.sfd` is smaller and the
+//! contours only have to be edited in one place
+
+use std::collections::HashMap;
+
+use crate::{
+    block_kind::BlockKind,
+    ffir::{
+        Cc, EncPos, GlyphBasic, GlyphBlock, GlyphName, LookupsMode, Padding, Ref, Rep, Transform,
+        TAG_PADDING,
+    },
+    spline::SplineSet,
+};
+
+/// How many segments a contour needs for a reference to it to be shorter than the contour
+const MIN_SEGMENTS: usize = 4;
+
+/// How many times a contour has to appear to be moved into a component
+const MIN_USES: usize = 2;
+
+/// The name of the `n`th component
+fn component_name(n: usize) -> String {
+    format!("sharedContour{n}")
+}
+
+/// A contour, moved so it starts at the origin, as generated (which is also how it's compared)
+fn key(spline_set: &SplineSet, i: usize) -> (String, f64, f64) {
+    let contour = &spline_set.contours[i];
+    let (x, y) = (contour.start.x, contour.start.y);
+    let moved = SplineSet::new(vec![contour.clone()]).translated(-x, -y);
+    (moved.gen(), x, y)
+}
+
+/// Moves every contour that appears at least `MIN_USES` times across `blocks` into a component
+/// glyph, and references it where it was. The components are put in a block of their own, right
+/// after the first block, whose glyphs are left alone so the font starts with the same glyph
+pub fn extract(blocks: &mut Vec<GlyphBlock>) -> Result<(), String> {
+    let mut uses: HashMap<String, usize> = HashMap::new();
+    for block in blocks.iter().skip(1) {
+        for glyph in &block.glyphs {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())?;
+            for (i, contour) in spline_set.contours.iter().enumerate() {
+                if contour.segments.len() >= MIN_SEGMENTS {
+                    *uses.entry(key(&spline_set, i).0).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let mut components: Vec<String> = vec![];
+    let mut names: HashMap<String, String> = HashMap::new();
+    for block in blocks.iter_mut().skip(1) {
+        for glyph in &mut block.glyphs {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())?;
+            let mut kept = vec![];
+            let mut references = glyph.glyph.rep.references().to_vec();
+            for (i, contour) in spline_set.contours.iter().enumerate() {
+                let (key, x, y) = key(&spline_set, i);
+                if uses.get(&key).is_none_or(|&uses| uses < MIN_USES) {
+                    kept.push(contour.clone());
+                    continue;
+                }
+                let name = names.entry(key.clone()).or_insert_with(|| {
+                    components.push(key);
+                    component_name(components.len())
+                });
+                references.push(Ref::new(
+                    GlyphName::new("", name, ""),
+                    Transform::translation(x, y),
+                ));
+            }
+            if kept.len() < spline_set.contours.len() {
+                glyph.glyph.rep = Rep::new(SplineSet::new(kept).gen(), references);
+            }
+        }
+    }
+
+    if components.is_empty() {
+        return Ok(());
+    }
+    let glyphs = components
+        .into_iter()
+        .enumerate()
+        .map(|(i, spline_set)| {
+            GlyphBasic::new(component_name(i + 1), 0, Rep::new(spline_set, vec![]), None)
+        })
+        .collect();
+    let block = GlyphBlock::new_from_basic_glyphs(
+        glyphs,
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "",
+        BlockKind::SharedContours.color(),
+        EncPos::None,
+    )
+    .named("shared_contours")
+    .padded(Padding::None);
+    blocks.insert(1, block);
+    Ok(())
+}
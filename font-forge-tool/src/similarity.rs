@@ -0,0 +1,211 @@
+//! A search for glyphs drawn with the same (or nearly the same) outlines, wherever they are in the
+//! glyph, so the duplicates can be turned into references
+
+use std::{collections::HashMap, io};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, ffir::TAG_PADDING, spline::*, BlockFilter, Font};
+
+/// How far apart the rows the outlines are compared along are, in font units
+const ROW_STEP: f64 = 5.0;
+
+/// A glyph's outlines, moved so the box around their ink starts at the origin
+struct Outline {
+    name: String,
+    /// The outlines as text that doesn't depend on where the contours start or the order
+    /// they're in, with the coordinates rounded
+    canonical: String,
+    /// The inked spans along every row
+    rows: Vec<Vec<(f64, f64)>>,
+    area: f64,
+}
+
+impl Outline {
+    fn new(name: String, spline_set: &SplineSet) -> Option<Self> {
+        let (min, max) = spline_set.bounds()?;
+        let spline_set = spline_set.translated(-min.x, -min.y);
+        let rows = (0..=((max.y - min.y) / ROW_STEP) as usize)
+            .map(|i| spline_set.spans_at((i as f64 + 0.5) * ROW_STEP))
+            .collect_vec();
+        let area = rows.iter().map(|spans| spans_length(spans)).sum::<f64>() * ROW_STEP;
+        Some(Self {
+            name,
+            canonical: canonical(&spline_set),
+            rows,
+            area,
+        })
+    }
+
+    /// How much of the ink of the two outlines isn't shared, out of all of it (0 for the same
+    /// shape, and 1 for shapes that don't overlap)
+    fn distance(&self, other: &Outline) -> f64 {
+        let (mut shared, mut total) = (0.0, 0.0);
+        for i in 0..self.rows.len().max(other.rows.len()) {
+            let a = self.rows.get(i).map_or(&[][..], Vec::as_slice);
+            let b = other.rows.get(i).map_or(&[][..], Vec::as_slice);
+            let overlap = overlap(a, b);
+            shared += overlap;
+            total += spans_length(a) + spans_length(b) - overlap;
+        }
+        if total == 0.0 {
+            0.0
+        } else {
+            1.0 - shared / total
+        }
+    }
+}
+
+fn spans_length(spans: &[(f64, f64)]) -> f64 {
+    spans.iter().map(|(start, end)| end - start).sum()
+}
+
+/// How long the parts of two rows of (sorted) spans that are both inked are
+fn overlap(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    let (mut i, mut j, mut length) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        length += (end - start).max(0.0);
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    length
+}
+
+/// Each contour's segments (with the point they start from), sorted, and the contours sorted
+fn canonical(spline_set: &SplineSet) -> String {
+    let point = |p: Point| format!("{} {}", p.x.round() + 0.0, p.y.round() + 0.0);
+    spline_set
+        .contours
+        .iter()
+        .map(|contour| {
+            let mut from = contour.start;
+            contour
+                .segments
+                .iter()
+                .map(|(segment, _)| {
+                    let (line, to) = match segment {
+                        Segment::Line { to } => (format!("{} l {}", point(from), point(*to)), *to),
+                        Segment::Curve { c1, c2, to } => (
+                            format!(
+                                "{} c {} {} {}",
+                                point(from),
+                                point(*c1),
+                                point(*c2),
+                                point(*to)
+                            ),
+                            *to,
+                        ),
+                    };
+                    from = to;
+                    line
+                })
+                .sorted()
+                .join(", ")
+        })
+        .sorted()
+        .join("; ")
+}
+
+#[derive(Serialize)]
+pub struct SimilarPair {
+    a: String,
+    b: String,
+    distance: f64,
+}
+
+#[derive(Serialize)]
+pub struct SimilarityReport {
+    /// The glyphs with the same outlines, in groups
+    identical: Vec<Vec<String>>,
+    /// The pairs of glyphs whose outlines are nearly the same, from the closest
+    similar: Vec<SimilarPair>,
+}
+
+/// Compares the outlines of every glyph in `font` (in blocks matching any of `blocks`, or in
+/// every block if there are none), finding those that are the same and those that are at most
+/// `tolerance` apart (see `Outline::distance`)
+pub fn report(font: &Font, blocks: &[String], tolerance: f64) -> Result<SimilarityReport, String> {
+    let mut outlines = vec![];
+    for block in &font.blocks {
+        if !blocks.is_empty() && !blocks.iter().any(|b| BlockFilter::matches(b, &block.name)) {
+            continue;
+        }
+        for (name, glyph) in block.full_names() {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())?;
+            outlines.extend(Outline::new(name.to_string(), &spline_set));
+        }
+    }
+
+    let mut groups: HashMap<&str, Vec<String>> = HashMap::new();
+    for outline in &outlines {
+        groups
+            .entry(&outline.canonical)
+            .or_default()
+            .push(outline.name.clone());
+    }
+    let identical = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .sorted()
+        .collect();
+
+    // the shared ink is at most the smaller area, so outlines whose areas are too far apart can't
+    // be close, and the rest are next to each other once they're sorted by area
+    outlines.sort_by(|a, b| a.area.total_cmp(&b.area));
+    let mut similar = vec![];
+    for (i, a) in outlines.iter().enumerate() {
+        for b in &outlines[i + 1..] {
+            if 1.0 - a.area / b.area > tolerance {
+                break;
+            }
+            if a.canonical == b.canonical {
+                continue;
+            }
+            let distance = a.distance(b);
+            if distance <= tolerance {
+                similar.push(SimilarPair {
+                    a: a.name.clone(),
+                    b: b.name.clone(),
+                    distance,
+                });
+            }
+        }
+    }
+    similar.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+    Ok(SimilarityReport { identical, similar })
+}
+
+impl SimilarityReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} groups of identical outlines, {} pairs of nearly identical ones",
+                    self.identical.len(),
+                    self.similar.len()
+                );
+                for group in &self.identical {
+                    println!("identical  {}", group.join(", "));
+                }
+                for pair in &self.similar {
+                    println!("{:.4}     {}, {}", pair.distance, pair.a, pair.b);
+                }
+            }
+        }
+        Ok(())
+    }
+}
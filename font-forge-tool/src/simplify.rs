@@ -0,0 +1,406 @@
+//! Simplifying outlines drawn with more points than they need: lines of no length and points
+//! along a straight line are taken out, curves that are nearly straight are made lines, and runs of
+//! curves between extremes are replaced with as few curves as follow them to within an error, the
+//! Ramer–Douglas–Peucker way (a run that one curve can't follow closely enough is split at the
+//! point furthest from that curve, and each side is tried again)
+
+use std::io;
+
+use serde::Serialize;
+
+use crate::{
+    cli::ReportFormat,
+    ffir::TAG_PADDING,
+    spline::{Contour, Point, Segment, SplineSet},
+    stroke::{add, cross, dot, length, scale, sub, Piece, EPSILON},
+    BlockFilter, Font,
+};
+
+/// How far (in radians) a contour can turn where two curves meet for them to be merged (the drawn
+/// points are whole units, so their tangents are a little off)
+const SMOOTH_ANGLE: f64 = 0.05;
+
+/// How many points along each curve are measured
+const SAMPLES: usize = 16;
+
+/// How many times the points measured are moved to the closest ones on a fitted curve, and the
+/// curve fitted again
+const REFINEMENTS: usize = 4;
+
+/// The point `t` along the curve from `p[0]` to `p[3]` (from 0 at its start to 1 at its end)
+fn at(p: [Point; 4], t: f64) -> Point {
+    let u = 1.0 - t;
+    let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+    p.iter()
+        .zip(weights)
+        .fold(Point::new(0.0, 0.0), |sum, (p, w)| add(sum, scale(*p, w)))
+}
+
+/// The direction and speed of the curve at `t`, and how that's changing
+fn derivatives(p: [Point; 4], t: f64) -> (Point, Point) {
+    let u = 1.0 - t;
+    let d = [sub(p[1], p[0]), sub(p[2], p[1]), sub(p[3], p[2])];
+    let first = add(
+        add(scale(d[0], 3.0 * u * u), scale(d[1], 6.0 * u * t)),
+        scale(d[2], 3.0 * t * t),
+    );
+    let second = add(
+        scale(sub(d[1], d[0]), 6.0 * u),
+        scale(sub(d[2], d[1]), 6.0 * t),
+    );
+    (first, second)
+}
+
+/// The points of `piece`, as a curve (a line's control points are at its ends)
+fn control_points(piece: &Piece) -> [Point; 4] {
+    match *piece {
+        Piece::Line(a, b) => [a, a, b, b],
+        Piece::Curve(p0, p1, p2, p3) => [p0, p1, p2, p3],
+    }
+}
+
+/// How far `p` is from the line from `a` to `b`
+fn distance_to_line(p: Point, a: Point, b: Point) -> f64 {
+    let ab = sub(b, a);
+    let t = if length(ab) < EPSILON {
+        0.0
+    } else {
+        (dot(sub(p, a), ab) / dot(ab, ab)).clamp(0.0, 1.0)
+    };
+    length(sub(p, add(a, scale(ab, t))))
+}
+
+/// How far `p` is from the polyline through `points`
+fn distance_to_polyline(p: Point, points: &[Point]) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| distance_to_line(p, pair[0], pair[1]))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// The points `SAMPLES` apart along each of `run`, starting with its start
+fn samples(run: &[Piece]) -> Vec<Point> {
+    std::iter::once(run[0].start())
+        .chain(run.iter().flat_map(|piece| {
+            let p = control_points(piece);
+            (1..=SAMPLES).map(move |i| at(p, i as f64 / SAMPLES as f64))
+        }))
+        .collect()
+}
+
+/// The lengths of the handles (from the ends, going `t0` and back from `t3`) of the curve from
+/// `p0` to `p3` that comes closest to `points` at `params`, by least squares. The curve is made a
+/// third of its chord long at each end if there's no good fit.
+fn fit_handles(
+    points: &[Point],
+    params: &[f64],
+    p0: Point,
+    p3: Point,
+    t0: Point,
+    t3: Point,
+) -> (f64, f64) {
+    let (mut c, mut x) = ([[0.0; 2]; 2], [0.0; 2]);
+    for (&point, &t) in points.iter().zip(params) {
+        let u = 1.0 - t;
+        let a = [scale(t0, 3.0 * u * u * t), scale(t3, -3.0 * u * t * t)];
+        let rest = sub(point, at([p0, p0, p3, p3], t));
+        c[0][0] += dot(a[0], a[0]);
+        c[0][1] += dot(a[0], a[1]);
+        c[1][1] += dot(a[1], a[1]);
+        x[0] += dot(a[0], rest);
+        x[1] += dot(a[1], rest);
+    }
+    let det = c[0][0] * c[1][1] - c[0][1] * c[0][1];
+    let chord = length(sub(p3, p0));
+    if det.abs() > 1e-9 {
+        let alpha = (x[0] * c[1][1] - x[1] * c[0][1]) / det;
+        let beta = (c[0][0] * x[1] - c[0][1] * x[0]) / det;
+        if alpha > EPSILON && beta > EPSILON {
+            return (alpha, beta);
+        }
+    }
+    (chord / 3.0, chord / 3.0)
+}
+
+/// One curve (with its control points rounded to whole units) that follows `run` from its start to its
+/// end, how far it strays from `run` at most, and the joint between the pieces of `run` (by the
+/// index of the piece it ends) that's furthest from it
+fn fit(run: &[Piece]) -> (Piece, f64, usize) {
+    let (p0, p3) = (run[0].start(), run[run.len() - 1].end());
+    let (t0, t3) = (run[0].start_tangent(), run[run.len() - 1].end_tangent());
+    let points = samples(run);
+
+    // the points are first taken to be as far along the curve as they are along the run
+    let mut params = vec![0.0];
+    for pair in points.windows(2) {
+        params.push(params[params.len() - 1] + length(sub(pair[1], pair[0])));
+    }
+    let total = params[params.len() - 1].max(EPSILON);
+    params.iter_mut().for_each(|t| *t /= total);
+
+    let mut curve = [p0, p0, p3, p3];
+    for refinement in 0..=REFINEMENTS {
+        let (alpha, beta) = fit_handles(&points, &params, p0, p3, t0, t3);
+        curve = [p0, add(p0, scale(t0, alpha)), sub(p3, scale(t3, beta)), p3];
+        if refinement == REFINEMENTS {
+            break;
+        }
+        for (t, &point) in params.iter_mut().zip(&points) {
+            let (first, second) = derivatives(curve, *t);
+            let off = sub(at(curve, *t), point);
+            let slope = dot(first, first) + dot(off, second);
+            if slope.abs() > 1e-9 {
+                *t = (*t - dot(off, first) / slope).clamp(0.0, 1.0);
+            }
+        }
+    }
+    let rounded = [
+        p0,
+        Point::new(curve[1].x.round(), curve[1].y.round()),
+        Point::new(curve[2].x.round(), curve[2].y.round()),
+        p3,
+    ];
+    let piece = Piece::Curve(rounded[0], rounded[1], rounded[2], rounded[3]);
+
+    let steps = 2 * SAMPLES * run.len();
+    let fitted = (0..=steps)
+        .map(|i| at(rounded, i as f64 / steps as f64))
+        .collect::<Vec<_>>();
+    let error = points
+        .iter()
+        .map(|&p| distance_to_polyline(p, &fitted))
+        .chain(fitted.iter().map(|&p| distance_to_polyline(p, &points)))
+        .fold(0.0, f64::max);
+    let worst = (0..run.len() - 1)
+        .max_by(|&a, &b| {
+            let distance = |i: usize| distance_to_polyline(run[i].end(), &fitted);
+            distance(a).total_cmp(&distance(b))
+        })
+        .unwrap_or(0);
+    (piece, error, worst)
+}
+
+/// The pieces (with the flags of the points they end at) that follow the run of smoothly joined
+/// curves `run` to within `max_error`
+fn simplify_curves(run: &[(Piece, u8)], max_error: f64) -> Vec<(Piece, u8)> {
+    if run.len() < 2 {
+        return run.to_vec();
+    }
+    let pieces = run.iter().map(|(piece, _)| *piece).collect::<Vec<_>>();
+    let (piece, error, worst) = fit(&pieces);
+    if error <= max_error {
+        return vec![(piece, run[run.len() - 1].1)];
+    }
+    let mut simplified = simplify_curves(&run[..=worst], max_error);
+    simplified.extend(simplify_curves(&run[worst + 1..], max_error));
+    simplified
+}
+
+/// The lines (with the flags of the points they end at) that follow the run of lines `run` to
+/// within `max_error`
+fn simplify_lines(run: &[(Piece, u8)], max_error: f64) -> Vec<(Piece, u8)> {
+    if run.len() < 2 {
+        return run.to_vec();
+    }
+    let (start, end) = (run[0].0.start(), run[run.len() - 1].0.end());
+    let (worst, error) = run[..run.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, (piece, _))| (i, distance_to_line(piece.end(), start, end)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0));
+    if error <= max_error && length(sub(end, start)) > EPSILON {
+        return vec![(Piece::Line(start, end), run[run.len() - 1].1)];
+    }
+    let mut simplified = simplify_lines(&run[..=worst], max_error);
+    simplified.extend(simplify_lines(&run[worst + 1..], max_error));
+    simplified
+}
+
+/// Whether the pieces `a` and `b` can be simplified together, where `a` ends and `b` starts.
+/// Curves can be if they meet smoothly, but not at an extreme (where they go straight across or
+/// straight up), since rasterizers and hinting expect a point there.
+fn joinable(a: &Piece, b: &Piece) -> bool {
+    match (a, b) {
+        (Piece::Line(..), Piece::Line(..)) => true,
+        (Piece::Curve(..), Piece::Curve(..)) => {
+            let (t_in, t_out) = (a.end_tangent(), b.start_tangent());
+            let extreme = t_in.x.abs().min(t_in.y.abs()) < SMOOTH_ANGLE.sin();
+            !extreme && cross(t_in, t_out).atan2(dot(t_in, t_out)).abs() < SMOOTH_ANGLE
+        }
+        _ => false,
+    }
+}
+
+/// `contour`, simplified to within `max_error` (its start is kept where it is)
+fn simplify_contour(contour: &Contour, max_error: f64) -> Contour {
+    let mut flagged = vec![];
+    let mut from = contour.start;
+    for &(segment, flags) in &contour.segments {
+        let piece = match segment {
+            Segment::Line { to } => Piece::Line(from, to),
+            // nearly straight curves are made lines
+            Segment::Curve { c1, c2, to }
+                if distance_to_line(c1, from, to).max(distance_to_line(c2, from, to))
+                    <= max_error =>
+            {
+                Piece::Line(from, to)
+            }
+            Segment::Curve { c1, c2, to } => Piece::Curve(from, c1, c2, to),
+        };
+        from = piece.end();
+        if matches!(piece, Piece::Curve(..)) || length(sub(piece.end(), piece.start())) > EPSILON {
+            flagged.push((piece, flags));
+        }
+    }
+    if flagged.is_empty() {
+        return contour.clone();
+    }
+
+    let mut simplified = vec![];
+    let mut start = 0;
+    for i in 0..flagged.len() {
+        if i + 1 < flagged.len() && joinable(&flagged[i].0, &flagged[i + 1].0) {
+            continue;
+        }
+        let run = &flagged[start..=i];
+        simplified.extend(match run[0].0 {
+            Piece::Line(..) => simplify_lines(run, max_error),
+            Piece::Curve(..) => simplify_curves(run, max_error),
+        });
+        start = i + 1;
+    }
+
+    let mut simple = Contour::new(contour.start, contour.start_flags);
+    for (piece, flags) in simplified {
+        simple = match piece {
+            Piece::Line(_, b) => simple.line_to(b, flags),
+            Piece::Curve(_, p1, p2, p3) => simple.curve_to(p1, p2, p3, flags),
+        };
+    }
+    simple
+}
+
+/// The number of points on the contours of `spline_set`, not counting the control points (or the
+/// last point of each contour, which is where it starts)
+pub fn points(spline_set: &SplineSet) -> usize {
+    spline_set
+        .contours
+        .iter()
+        .map(|contour| contour.segments.len())
+        .sum()
+}
+
+/// `spline_set`, with as few points as follow its outlines to within `max_error`. A contour that's
+/// made to cross itself or go the other way is left as it was drawn, and so is the whole spline set
+/// if its contours end up inside different ones.
+pub fn simplify(spline_set: &SplineSet, max_error: f64) -> SplineSet {
+    let simplified = SplineSet::new(
+        spline_set
+            .contours
+            .iter()
+            .map(|original| {
+                let simple = simplify_contour(original, max_error);
+                if simple.segments.len() >= original.segments.len()
+                    || simple.self_intersections().len() > original.self_intersections().len()
+                    || simple.signed_area().signum() != original.signed_area().signum()
+                {
+                    original.clone()
+                } else {
+                    simple
+                }
+            })
+            .collect(),
+    );
+    if simplified == *spline_set || simplified.depths() == spline_set.depths() {
+        simplified
+    } else {
+        spline_set.clone()
+    }
+}
+
+#[derive(Serialize)]
+pub struct Reduction {
+    name: String,
+    block: String,
+    /// The points on the glyph's outlines as drawn, and once they're simplified
+    before: usize,
+    after: usize,
+}
+
+#[derive(Serialize)]
+pub struct SimplifyReport {
+    /// How far the outlines can stray, in font units
+    max_error: f64,
+    /// The points on every glyph's outlines as drawn, and once they're simplified
+    before: usize,
+    after: usize,
+    /// The glyphs that lose points, those that lose the most first
+    reductions: Vec<Reduction>,
+}
+
+/// Simplifies the outlines of every glyph in `font` (in blocks matching any of `blocks`, or in
+/// every block if there are none) to within `max_error` of them, counting the points taken out
+pub fn report(font: &Font, blocks: &[String], max_error: f64) -> Result<SimplifyReport, String> {
+    let mut report = SimplifyReport {
+        max_error,
+        before: 0,
+        after: 0,
+        reductions: vec![],
+    };
+    for block in &font.blocks {
+        if !blocks.is_empty() && !blocks.iter().any(|b| BlockFilter::matches(b, &block.name)) {
+            continue;
+        }
+        for (name, glyph) in block.full_names() {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            let spline_set = SplineSet::parse(glyph.glyph.rep.spline_set())
+                .map_err(|e| format!("{name}: {e}"))?;
+            let before = points(&spline_set);
+            let after = points(&simplify(&spline_set, max_error));
+            report.before += before;
+            report.after += after;
+            if after < before {
+                report.reductions.push(Reduction {
+                    name: name.to_string(),
+                    block: block.name.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+    report
+        .reductions
+        .sort_by_key(|reduction| std::cmp::Reverse(reduction.before - reduction.after));
+    Ok(report)
+}
+
+impl SimplifyReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} of {} points taken out of {} glyphs, to within {} units",
+                    self.before - self.after,
+                    self.before,
+                    self.reductions.len(),
+                    self.max_error
+                );
+                for reduction in &self.reductions {
+                    println!(
+                        "{:<12} {}  {} -> {}",
+                        reduction.block, reduction.name, reduction.before, reduction.after
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! Giving out the glyphs' slots once every block is built, in an order that doesn't depend on the
+//! order the blocks were built in: the blocks by name, and the glyphs of each block by name, except
+//! that a glyph always comes after the glyphs it references (and a block after the blocks it
+//! references). Moving a block's construction around then leaves the slots (and the `.sfd`) as they
+//! were. The glyphs in `PINNED` are the exception: they come first, and their blocks before the
+//! rest, so `.notdef` is glyph 0 (as OpenType requires).
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    fallback::NOTDEF,
+    ffir::{renumber, GlyphBlock, TAG_PADDING},
+};
+
+/// The glyphs given the first slots, in this order (`.notdef` is in the fallback block, so that
+/// block comes first, and the block of the other two right after it)
+const PINNED: [&str; 3] = [NOTDEF, "NUL", "ZWSP"];
+
+/// Where `name` goes among the pinned glyphs, or after them if it isn't one
+fn pin(name: &str) -> usize {
+    PINNED
+        .iter()
+        .position(|pinned| *pinned == name)
+        .unwrap_or(PINNED.len())
+}
+
+/// The order of the items with `keys`, smallest key first, except that each comes after the items
+/// (by index) it `needs`. Items that need each other are left out.
+fn order<K: Ord + Clone>(keys: &[K], needs: &[HashSet<usize>]) -> Vec<usize> {
+    let mut waiting = needs.iter().map(HashSet::len).collect::<Vec<_>>();
+    let mut needed_by = vec![vec![]; keys.len()];
+    for (i, needs) in needs.iter().enumerate() {
+        for &need in needs {
+            needed_by[need].push(i);
+        }
+    }
+    let mut ready = (0..keys.len())
+        .filter(|&i| waiting[i] == 0)
+        .map(|i| (keys[i].clone(), i))
+        .collect::<BTreeSet<_>>();
+    let mut order = vec![];
+    while let Some((_, i)) = ready.pop_first() {
+        order.push(i);
+        for &after in &needed_by[i] {
+            waiting[after] -= 1;
+            if waiting[after] == 0 {
+                ready.insert((keys[after].clone(), after));
+            }
+        }
+    }
+    order
+}
+
+/// Takes the items of `items` in `order`
+fn reordered<T>(items: Vec<T>, order: &[usize]) -> Vec<T> {
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+    order.iter().map(|&i| items[i].take().unwrap()).collect()
+}
+
+/// Puts the blocks and their glyphs in order (see the module), then gives them consecutive slots
+/// with `renumber`, returning the number of slots used
+pub fn allocate(blocks: &mut Vec<GlyphBlock>) -> Result<usize, String> {
+    for block in blocks.iter_mut() {
+        block
+            .glyphs
+            .retain(|glyph| !glyph.glyph.has_tag(TAG_PADDING));
+    }
+    let mut owners = HashMap::new();
+    for (b, block) in blocks.iter().enumerate() {
+        for (g, (name, _)) in block.full_names().enumerate() {
+            owners.insert(name.to_string(), (b, g));
+        }
+    }
+
+    let mut block_needs = vec![HashSet::new(); blocks.len()];
+    for (b, block) in blocks.iter_mut().enumerate() {
+        let names = block
+            .full_names()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        let mut glyph_needs = vec![HashSet::new(); names.len()];
+        for (g, glyph) in block.glyphs.iter().enumerate() {
+            for reference in glyph.glyph.rep.references() {
+                match owners.get(reference.target()) {
+                    Some(&(target, target_glyph)) if target == b => {
+                        glyph_needs[g].insert(target_glyph);
+                    }
+                    Some(&(target, _)) => {
+                        block_needs[b].insert(target);
+                    }
+                    // resolving the references reports it
+                    None => {}
+                }
+            }
+        }
+        let keys = names
+            .iter()
+            .map(|name| (pin(name), name.clone()))
+            .collect::<Vec<_>>();
+        let glyph_order = order(&keys, &glyph_needs);
+        if glyph_order.len() < names.len() {
+            return Err(format!("glyphs of {} reference each other", block.name));
+        }
+        block.glyphs = reordered(std::mem::take(&mut block.glyphs), &glyph_order);
+    }
+
+    let keys = blocks
+        .iter()
+        .map(|block| {
+            let pinned = block
+                .full_names()
+                .map(|(name, _)| pin(name.as_str()))
+                .min()
+                .unwrap_or(PINNED.len());
+            (pinned, block.name.clone())
+        })
+        .collect::<Vec<_>>();
+    let block_order = order(&keys, &block_needs);
+    if block_order.len() < keys.len() {
+        return Err("some blocks reference each other".to_string());
+    }
+    *blocks = reordered(std::mem::take(blocks), &block_order);
+    let ff_pos = renumber(blocks);
+    if let Some((_, notdef)) = blocks
+        .iter()
+        .flat_map(|block| block.full_names())
+        .find(|(name, _)| name.as_str() == NOTDEF)
+    {
+        assert_eq!(notdef.encoding.ff_pos, 0, "{NOTDEF} has to be glyph 0");
+    }
+    Ok(ff_pos)
+}
@@ -0,0 +1,118 @@
+//! Golden-file snapshots of the generated `.sfd` files.
+//! The `ModificationTime` line changes on every run, so it's normalized before comparing or blessing.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{profile::Profile, sfd_filename, GenOptions};
+
+const MODIFICATION_TIME: &str = "ModificationTime: ";
+
+/// The variations that are snapshotted
+pub const PROFILES: [Profile; 2] = [Profile::MAIN, Profile::UCSUR];
+
+/// The options the snapshots are generated with. Packs and SVGs are left out, so the snapshots
+/// don't depend on what's in `packs/`.
+pub fn options() -> GenOptions {
+    GenOptions {
+        timestamp: Some(0),
+        ..Default::default()
+    }
+}
+
+/// The golden file of `profile` in `dir`
+pub fn path(dir: &Path, profile: Profile) -> PathBuf {
+    dir.join(sfd_filename(profile))
+}
+
+/// Replaces the timestamp in the `ModificationTime` line with `0`
+fn normalize(sfd: &str) -> String {
+    sfd.split_inclusive('\n')
+        .map(|line| {
+            if line.starts_with(MODIFICATION_TIME) {
+                format!("{MODIFICATION_TIME}0\n")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Writes `sfd` as the new golden file at `path`
+pub fn bless(path: &Path, sfd: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, normalize(sfd))?;
+    println!("blessed {}", path.display());
+    Ok(())
+}
+
+/// Compares `sfd` against the golden file at `path`, reporting the first line that differs
+pub fn check(path: &Path, sfd: &str) -> io::Result<()> {
+    let golden = fs::read_to_string(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "couldn't read {} ({e}); run with --bless to create it",
+                path.display()
+            ),
+        )
+    })?;
+    let actual = normalize(sfd);
+    if golden == actual {
+        println!("{} matches", path.display());
+        return Ok(());
+    }
+
+    let mut golden_lines = golden.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (golden_lines.next(), actual_lines.next()) {
+            (Some(g), Some(a)) if g == a => line += 1,
+            (g, a) => {
+                println!(
+                    "{} differs at line {line}\n  golden: {}\n  actual: {}",
+                    path.display(),
+                    g.unwrap_or("<end of file>"),
+                    a.unwrap_or("<end of file>"),
+                );
+                break;
+            }
+        }
+    }
+    Err(io::Error::other(format!(
+        "{} doesn't match the generated output; run with --bless if the change is intended",
+        path.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gen_font, gen_sfd};
+
+    /// Generates `profile` and compares it against its golden file in `snapshots/`
+    fn matches_snapshot(profile: Profile) {
+        let options = options();
+        let font = gen_font(profile, &options).unwrap();
+        let sfd = gen_sfd(&font, &options).unwrap();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+        if let Err(e) = check(&path(&dir, profile), &sfd) {
+            panic!("{e}");
+        }
+    }
+
+    #[test]
+    fn main_matches_snapshot() {
+        matches_snapshot(Profile::MAIN);
+    }
+
+    #[test]
+    fn ucsur_matches_snapshot() {
+        matches_snapshot(Profile::UCSUR);
+    }
+}
@@ -0,0 +1,187 @@
+//! Where glyphs come from besides the hand-written blocks: glyph packs (see `packs`) and
+//! directories of SVGs (see `svg`). Each source gives a list of blocks, and the blocks of every
+//! source are merged before the font is generated, so that two sources (or a source and the
+//! built-in glyphs) can't add the same glyph twice.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use serde::Deserialize;
+
+use crate::{
+    block_kind::BlockKind,
+    compose::Component,
+    glyph_blocks::base::{BASE_COR, BASE_EXT},
+    profile::Capability,
+    Cc, EncPos, GlyphBasic, GlyphBlock, LookupsMode, Profile, Provenance, Rep, Stroke,
+};
+
+/// A single glyph from a source
+#[derive(Deserialize, Clone, Default)]
+pub struct SourceGlyph {
+    pub name: String,
+    pub codepoint: Option<usize>,
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub spline_set: String,
+    /// The glyphs this one references, besides its own outlines, as written in a pack (see
+    /// `compose`)
+    #[serde(default)]
+    pub compose: Vec<String>,
+    /// If set, `spline_set` holds centerlines, drawn with a round pen this wide
+    pub stroke_width: Option<f64>,
+    /// Who drew the glyph, if it isn't the font's designer
+    pub designer: Option<String>,
+    /// What the glyph is based on
+    pub source: Option<String>,
+}
+
+/// A block of glyphs from a source, before it's given its place in the font
+#[derive(Clone)]
+pub struct GlyphBlockSpec {
+    /// The block's name (without the `pack_` it's given in the font)
+    pub name: String,
+    /// The first codepoint (by default, the one after the previous block's, or the extension block's)
+    pub start: Option<usize>,
+    pub glyphs: Vec<SourceGlyph>,
+}
+
+/// Something that provides glyphs
+pub trait GlyphSource {
+    /// What the source is called in errors
+    fn name(&self) -> String;
+
+    fn blocks(&self) -> Vec<GlyphBlockSpec>;
+}
+
+/// The hand-written base glyphs, which every other source is checked against
+pub struct BuiltIn;
+
+impl GlyphSource for BuiltIn {
+    fn name(&self) -> String {
+        "the built-in glyphs".to_string()
+    }
+
+    /// Just the names of the glyphs (their codepoints are checked once the font is generated)
+    fn blocks(&self) -> Vec<GlyphBlockSpec> {
+        [
+            ("base_cor", BASE_COR.as_slice()),
+            ("base_ext", BASE_EXT.as_slice()),
+        ]
+        .into_iter()
+        .map(|(name, constants)| GlyphBlockSpec {
+            name: name.to_string(),
+            start: None,
+            glyphs: constants
+                .iter()
+                .map(|glyph| SourceGlyph {
+                    name: glyph.name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        })
+        .collect()
+    }
+}
+
+/// Merges the blocks of `sources` (in order), making sure that no glyph is added by two of them
+/// (or is already built in), and that no codepoint is given to two glyphs
+pub fn merge(sources: &[Box<dyn GlyphSource>]) -> Result<Vec<GlyphBlockSpec>, String> {
+    let mut names = HashMap::new();
+    let mut codepoints = HashMap::new();
+    for glyph in BuiltIn.blocks().iter().flat_map(|block| &block.glyphs) {
+        names.insert(glyph.name.clone(), BuiltIn.name());
+    }
+
+    let mut blocks = vec![];
+    for source in sources {
+        let source_name = source.name();
+        for block in source.blocks() {
+            for glyph in &block.glyphs {
+                if let Some(other) = names.insert(glyph.name.clone(), source_name.clone()) {
+                    return Err(format!(
+                        "{} is in both {other} and {source_name}",
+                        glyph.name
+                    ));
+                }
+                if let Some(codepoint) = glyph.codepoint {
+                    if let Some((other, other_source)) =
+                        codepoints.insert(codepoint, (glyph.name.clone(), source_name.clone()))
+                    {
+                        return Err(format!(
+                            "{} (in {source_name}) and {other} (in {other_source}) are both at U+{codepoint:04X}",
+                            glyph.name
+                        ));
+                    }
+                }
+            }
+            blocks.push(block);
+        }
+    }
+    Ok(blocks)
+}
+
+impl GlyphBlockSpec {
+    /// Turns this spec into a block of base glyphs, assigning codepoints starting at `next_enc`
+    /// (or at `start`), and moving `next_enc` past the last one used
+    pub fn to_block(&self, next_enc: &mut usize, profile: Profile) -> Result<GlyphBlock, String> {
+        if let Some(start) = self.start {
+            *next_enc = start;
+        }
+
+        let glyphs = self
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                let spline_set = match glyph.stroke_width {
+                    Some(width) => Stroke {
+                        centerlines: &glyph.spline_set,
+                        width,
+                    }
+                    .expand()
+                    .map_err(|e| format!("{}: {e}", glyph.name))?,
+                    None => format!("\n{}", glyph.spline_set.trim()),
+                };
+                let references = glyph
+                    .compose
+                    .iter()
+                    .map(|component| Component::parse(component).map(|c| c.to_ref()))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| format!("{}: {e}", glyph.name))?;
+                let mut basic = GlyphBasic::new(
+                    glyph.name.clone(),
+                    glyph.width.unwrap_or(1000),
+                    Rep::new(spline_set, references),
+                    None,
+                );
+                basic.provenance = Provenance {
+                    designer: glyph.designer.clone().map(Cow::Owned),
+                    source: glyph.source.clone().map(Cow::Owned),
+                };
+                Ok(basic)
+            })
+            .collect::<Result<_, String>>()?;
+
+        let mut block = GlyphBlock::new_from_basic_glyphs(
+            glyphs,
+            if profile.has(Capability::LatinLigatures) {
+                LookupsMode::WordLigFromLetters
+            } else {
+                LookupsMode::None
+            },
+            Cc::Full,
+            "",
+            "Tok",
+            BlockKind::Pack.color(),
+            EncPos::None,
+        )
+        .named(format!("pack_{}", self.name));
+
+        for (glyph, source_glyph) in block.glyphs.iter_mut().zip(&self.glyphs) {
+            let codepoint = source_glyph.codepoint.unwrap_or(*next_enc);
+            glyph.encoding.enc_pos = EncPos::Pos(codepoint);
+            *next_enc = codepoint + 1;
+        }
+
+        Ok(block)
+    }
+}
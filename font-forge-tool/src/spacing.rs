@@ -0,0 +1,157 @@
+//! The space between sitelen pona words: the sitelen pona space (U+3000, which two latin spaces
+//! make), how wide a gap it leaves, and whether that gap shows a dot to separate the words.
+//!
+//! A gap is a glyph as wide as a sitelen pona (or half of one, for a narrow gap), so cartouches and
+//! long glyphs extend over it like they do over any other glyph. A wide gap is that glyph followed
+//! by a half-width space, since there's no extension one and a half glyphs long.
+
+use clap::ValueEnum;
+use itertools::Itertools;
+
+use crate::{
+    block_kind::BlockKind,
+    features::*,
+    ffir::*,
+    profile::{Capability, Profile},
+};
+
+/// The sitelen pona space
+const SPACE: &str = "space";
+/// The space with a dot in it, which two latin spaces make instead when there's a separator
+const SEPARATOR: &str = "wordSeparator";
+/// The half-width space that widens a wide gap
+const HALF_SPACE: &str = "halfSpace";
+
+/// The latin sequences that make the sitelen pona space
+const LIGATURES: [&str; 3] = ["space space", "z z space", "z z"];
+
+/// How wide the gap between sitelen pona words is
+#[derive(Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Gap {
+    /// Half a sitelen pona wide
+    Narrow,
+    /// A sitelen pona wide
+    #[default]
+    Regular,
+    /// One and a half sitelen pona wide
+    Wide,
+}
+
+impl Gap {
+    /// How wide the gap is, in the units the glyphs are drawn in
+    pub fn width(self) -> usize {
+        match self {
+            Gap::Narrow => 500,
+            Gap::Regular => 1000,
+            Gap::Wide => 1500,
+        }
+    }
+
+    /// How wide the glyph that starts the gap is (the rest of a wide gap is a half-width space)
+    fn head_width(self) -> usize {
+        self.width().min(1000)
+    }
+
+    /// The extensions the glyph that starts the gap gets in a container
+    fn cc(self) -> Cc {
+        match self {
+            Gap::Narrow => Cc::Half,
+            Gap::Regular | Gap::Wide => Cc::Full,
+        }
+    }
+}
+
+/// How the space between sitelen pona words is set
+#[derive(Clone, Copy, Default)]
+pub struct Spacing {
+    pub gap: Gap,
+    /// Whether two latin spaces make a gap with a dot in it, instead of an empty one
+    pub separator: bool,
+}
+
+impl Spacing {
+    /// Sizes the sitelen pona space for the gap
+    pub fn shape_space(&self, space: &mut GlyphFull) {
+        space.glyph.width = self.gap.head_width();
+        space.cc_subs = self.gap.cc();
+    }
+
+    /// The block of the separator and the half-width space, when they're needed (the separator is
+    /// only made by latin spaces, so it's left out of a `profile` without the latin ligatures)
+    pub fn block(&self, profile: Profile) -> Result<Option<GlyphBlock>, String> {
+        let separator = self.separator && profile.has(Capability::LatinLigatures);
+        let mut glyphs = vec![];
+        if separator {
+            let dot = Stroke {
+                centerlines: &format!("{} 400 m 1\n", self.gap.width() / 2),
+                width: 100.0,
+            };
+            glyphs.push(GlyphBasic::new(
+                SEPARATOR,
+                self.gap.head_width(),
+                Rep::new(dot.expand()?, vec![]),
+                None,
+            ));
+        }
+        if self.gap == Gap::Wide {
+            glyphs.push(GlyphBasic::new(
+                HALF_SPACE,
+                Gap::Narrow.width(),
+                Rep::new("", vec![]),
+                None,
+            ));
+        }
+        if glyphs.is_empty() {
+            return Ok(None);
+        }
+        let block = GlyphBlock::new_from_basic_glyphs(
+            glyphs,
+            LookupsMode::None,
+            Cc::Half,
+            "",
+            "Tok",
+            BlockKind::NoCombination.color(),
+            EncPos::None,
+        )
+        .named("spacing")
+        .padded(Padding::None);
+        let block = match separator {
+            true => block.with_glyph(SEPARATOR, |glyph| glyph.cc_subs = self.gap.cc())?,
+            false => block,
+        };
+        Ok(Some(block))
+    }
+
+    /// Declares the latin sequences that make the gap, and the lookup that widens a wide one
+    pub fn declare(&self, blocks: &[GlyphBlock], features: &mut FeatureRegistry, profile: Profile) {
+        let has = |name: &str| {
+            blocks
+                .iter()
+                .flat_map(|block| block.full_names())
+                .any(|(full_name, _)| full_name.as_str() == format!("{name}Tok"))
+        };
+        let head = if self.separator && has(SEPARATOR) {
+            SEPARATOR
+        } else {
+            SPACE
+        };
+        if !has(head) {
+            return;
+        }
+        if profile.has(Capability::LatinLigatures) {
+            for ligature in LIGATURES {
+                features.declare(&format!("{head}Tok"), LIGA_SPACE, ligature);
+            }
+        }
+        if has(HALF_SPACE) {
+            features.add_after(LIGA_SPACE, LIGA_WIDE_SPACE, LookupType::Multiple, 0);
+            for start in [SPACE, head].into_iter().filter(|start| has(start)).dedup() {
+                features.declare(
+                    &format!("{start}Tok"),
+                    LIGA_WIDE_SPACE,
+                    format!("{start}Tok {HALF_SPACE}Tok"),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Latin spellings of glyphs besides the ones they're generated with, from any file passed with
+//! `--spellings` (like `data/spellings-example.txt`), for the ASCII conventions some communities
+//! type with instead (like `(( ))` for a cartouche)
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::Path,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    features::*,
+    ffir::*,
+    invalid_data,
+    profile::{Capability, Profile},
+};
+
+/// A spelling of a glyph, as the text that's typed for it
+#[derive(Clone)]
+pub struct Spelling {
+    glyph: String,
+    text: String,
+    /// Whether it replaces the spellings the glyph is generated with, rather than adding to them
+    replaces: bool,
+}
+
+fn parse(list: &str) -> Result<Vec<Spelling>, String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let err = || format!("couldn't parse the spelling `{line}`");
+            let (glyph, rest) = line.split_once(char::is_whitespace).ok_or_else(err)?;
+            let rest = rest.trim_start();
+            let replaces = match rest.chars().next() {
+                Some('+') => false,
+                Some('=') => true,
+                _ => return Err(err()),
+            };
+            let text = rest[1..].trim();
+            if text.is_empty() {
+                return Err(err());
+            }
+            Ok(Spelling {
+                glyph: glyph.to_string(),
+                text: text.to_string(),
+                replaces,
+            })
+        })
+        .collect()
+}
+
+/// Reads a list of spellings, in the format of `data/spellings-example.txt`
+pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<Spelling>> {
+    parse(&fs::read_to_string(path)?).map_err(invalid_data)
+}
+
+/// Declares each of `spellings` as a ligature of the glyphs of the characters it's typed with
+/// (in builds with latin ligatures), leaving out the generated
+/// spellings of the glyphs it replaces; spellings of glyphs in `excluded` are skipped
+pub fn declare(
+    spellings: &[Spelling],
+    blocks: &[GlyphBlock],
+    excluded: &HashSet<String>,
+    profile: Profile,
+    features: &mut FeatureRegistry,
+) -> Result<(), String> {
+    if !profile.has(Capability::LatinLigatures) {
+        return Ok(());
+    }
+    let mut names = HashSet::new();
+    let mut glyph_of = HashMap::new();
+    for block in blocks {
+        for (name, glyph) in block.full_names() {
+            if glyph.glyph.has_tag(TAG_PADDING) {
+                continue;
+            }
+            if let EncPos::Pos(codepoint) = glyph.encoding.enc_pos {
+                if let Some(c) = char::from_u32(codepoint as u32) {
+                    glyph_of.insert(c, name.to_string());
+                }
+            }
+            names.insert(name.to_string());
+        }
+    }
+
+    for spelling in spellings {
+        if excluded.contains(&spelling.glyph) {
+            continue;
+        }
+        if !names.contains(&spelling.glyph) {
+            return Err(format!(
+                "there's no glyph called {} to spell",
+                spelling.glyph
+            ));
+        }
+        let typed = spelling
+            .text
+            .chars()
+            .map(|c| {
+                glyph_of.get(&c).ok_or_else(|| {
+                    format!(
+                        "the spelling `{}` of {} has `{c}`, which has no glyph",
+                        spelling.text, spelling.glyph
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .join(" ");
+        if spelling.replaces {
+            features.respell(&spelling.glyph);
+        }
+        features.declare(&spelling.glyph, LIGA_WORD, typed);
+    }
+    Ok(())
+}
@@ -0,0 +1,431 @@
+use itertools::Itertools;
+
+/// A point in font units
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    fn gen(&self) -> String {
+        format!("{} {}", gen_coord(self.x), gen_coord(self.y))
+    }
+}
+
+/// Formats a coordinate the way FontForge does (integers without a decimal point)
+pub fn gen_coord(c: f64) -> String {
+    if c.fract() == 0.0 {
+        // `+ 0.0` turns `-0` into `0`
+        format!("{}", c + 0.0)
+    } else {
+        let s = format!("{c:.5}");
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// How many lines each curve is split into when measuring outlines
+const FLATTEN_STEPS: usize = 16;
+
+/// A segment of a contour, ending at `to`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Segment {
+    Line { to: Point },
+    Curve { c1: Point, c2: Point, to: Point },
+}
+
+/// A closed contour, consisting of a start point and the segments that follow it.
+/// Each point carries FontForge's point flags (0 = curve, 1 = corner, 2 = tangent, plus higher bits).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Contour {
+    pub start: Point,
+    pub start_flags: u8,
+    pub segments: Vec<(Segment, u8)>,
+}
+
+impl Contour {
+    pub fn new(start: Point, start_flags: u8) -> Self {
+        Self {
+            start,
+            start_flags,
+            segments: vec![],
+        }
+    }
+
+    pub fn line_to(mut self, to: Point, flags: u8) -> Self {
+        self.segments.push((Segment::Line { to }, flags));
+        self
+    }
+
+    pub fn curve_to(mut self, c1: Point, c2: Point, to: Point, flags: u8) -> Self {
+        self.segments.push((Segment::Curve { c1, c2, to }, flags));
+        self
+    }
+
+    /// The same contour with `f` applied to every point (including control points)
+    fn map_points(&self, f: impl Fn(Point) -> Point) -> Self {
+        Self {
+            start: f(self.start),
+            start_flags: self.start_flags,
+            segments: self
+                .segments
+                .iter()
+                .map(|(segment, flags)| {
+                    let segment = match segment {
+                        Segment::Line { to } => Segment::Line { to: f(*to) },
+                        Segment::Curve { c1, c2, to } => Segment::Curve {
+                            c1: f(*c1),
+                            c2: f(*c2),
+                            to: f(*to),
+                        },
+                    };
+                    (segment, *flags)
+                })
+                .collect(),
+        }
+    }
+
+    /// The same contour going the other way (which flips its fill under the nonzero rule)
+    pub fn reversed(&self) -> Self {
+        let mut points = vec![(self.start, self.start_flags)];
+        points.extend(self.segments.iter().map(|(segment, flags)| match segment {
+            Segment::Line { to } | Segment::Curve { to, .. } => (*to, *flags),
+        }));
+
+        let (end, end_flags) = points[points.len() - 1];
+        let mut contour = Contour::new(end, swap_cp_flags(end_flags));
+        for (i, (segment, _)) in self.segments.iter().enumerate().rev() {
+            let (to, flags) = points[i];
+            let flags = swap_cp_flags(flags);
+            contour = match segment {
+                Segment::Line { .. } => contour.line_to(to, flags),
+                Segment::Curve { c1, c2, .. } => contour.curve_to(*c2, *c1, to, flags),
+            };
+        }
+        contour
+    }
+
+    /// The same contour, mirrored horizontally within `width`. The points are also reversed,
+    /// so the contour keeps its direction (and so its fill).
+    pub fn mirrored(&self, width: f64) -> Self {
+        self.map_points(|p| Point::new(width - p.x, p.y)).reversed()
+    }
+
+    /// The contour as a closed polygon, with each curve split into `steps` lines
+    pub fn flatten(&self, steps: usize) -> Vec<Point> {
+        let mut points = vec![self.start];
+        let mut from = self.start;
+        for (segment, _) in &self.segments {
+            match segment {
+                Segment::Line { to } => points.push(*to),
+                Segment::Curve { c1, c2, to } => points.extend((1..=steps).map(|i| {
+                    let t = i as f64 / steps as f64;
+                    let u = 1.0 - t;
+                    let bezier = |a: f64, b: f64, c: f64, d: f64| {
+                        u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+                    };
+                    Point::new(
+                        bezier(from.x, c1.x, c2.x, to.x),
+                        bezier(from.y, c1.y, c2.y, to.y),
+                    )
+                })),
+            }
+            from = match segment {
+                Segment::Line { to } | Segment::Curve { to, .. } => *to,
+            };
+        }
+        points
+    }
+
+    /// The area inside the contour, positive if it goes counter-clockwise and negative if it goes
+    /// clockwise
+    pub fn signed_area(&self) -> f64 {
+        let polygon = self.flatten(FLATTEN_STEPS);
+        polygon
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<f64>()
+            / 2.0
+    }
+
+    /// The leftmost point of the contour (the lowest, if there's a tie), which doesn't depend on
+    /// where the contour starts or which way it goes
+    fn leftmost(&self) -> Point {
+        self.flatten(FLATTEN_STEPS)
+            .into_iter()
+            .min_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)))
+            .unwrap_or(self.start)
+    }
+
+    /// Whether the contour encloses (next to) no area, like a stray point, so it has no direction
+    pub fn is_degenerate(&self) -> bool {
+        self.signed_area().abs() < 1.0
+    }
+
+    /// Whether `p` is inside the contour (using the even-odd rule)
+    pub fn contains(&self, p: Point) -> bool {
+        let polygon = self.flatten(FLATTEN_STEPS);
+        polygon
+            .iter()
+            .circular_tuple_windows()
+            .filter(|(a, b)| {
+                (a.y <= p.y) != (b.y <= p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            })
+            .count()
+            % 2
+            == 1
+    }
+
+    /// The points where the contour crosses itself
+    pub fn self_intersections(&self) -> Vec<Point> {
+        let polygon = self.flatten(FLATTEN_STEPS);
+        let edges = polygon
+            .iter()
+            .copied()
+            .circular_tuple_windows()
+            .filter(|(a, b)| a != b)
+            .collect_vec();
+        let mut crossings = vec![];
+        for (i, j) in (0..edges.len()).tuple_combinations() {
+            // neighboring edges share a point, so they're skipped (including the last and first)
+            if j == i + 1 || (i == 0 && j == edges.len() - 1) {
+                continue;
+            }
+            let ((a, b), (c, d)) = (edges[i], edges[j]);
+            if let Some(p) = crossing(a, b, c, d) {
+                crossings.push(p);
+            }
+        }
+        crossings
+    }
+
+    fn gen(&self) -> String {
+        let start = format!("{} m {}", self.start.gen(), self.start_flags);
+        let segments = self.segments.iter().map(|(segment, flags)| match segment {
+            Segment::Line { to } => format!(" {} l {flags}", to.gen()),
+            Segment::Curve { c1, c2, to } => {
+                format!(" {} {} {} c {flags}", c1.gen(), c2.gen(), to.gen())
+            }
+        });
+        std::iter::once(start).chain(segments).join("\n")
+    }
+}
+
+/// Where the line from `a` to `b` crosses the one from `c` to `d`, if they cross (and don't just
+/// touch at an end)
+fn crossing(a: Point, b: Point, c: Point, d: Point) -> Option<Point> {
+    let cross =
+        |o: Point, p: Point, q: Point| (p.x - o.x) * (q.y - o.y) - (p.y - o.y) * (q.x - o.x);
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        let t = d1 / (d1 - d2);
+        Some(Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y)))
+    } else {
+        None
+    }
+}
+
+/// Swaps the flags that say whether a point's next and previous control points are the defaults,
+/// since reversing a contour swaps which is which
+fn swap_cp_flags(flags: u8) -> u8 {
+    const NEXT_CP_DEF: u8 = 1 << 3;
+    const PREV_CP_DEF: u8 = 1 << 4;
+    let swapped = ((flags & NEXT_CP_DEF) << 1) | ((flags & PREV_CP_DEF) >> 1);
+    (flags & !(NEXT_CP_DEF | PREV_CP_DEF)) | swapped
+}
+
+/// Which way the outlines of a spline set go (with the holes going the other way)
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Direction {
+    /// As FontForge's "Correct Direction" leaves them, and as the glyphs are drawn
+    #[default]
+    Clockwise,
+    /// The PostScript convention
+    CounterClockwise,
+}
+
+/// The ranges of x that `polygons` ink along the horizontal line at `y` (using the nonzero rule)
+pub fn spans_at(polygons: &[Vec<Point>], y: f64) -> Vec<(f64, f64)> {
+    let mut crossings = vec![];
+    for polygon in polygons {
+        for (a, b) in polygon.iter().circular_tuple_windows() {
+            if (a.y <= y) != (b.y <= y) {
+                let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+                crossings.push((x, if b.y > a.y { 1 } else { -1 }));
+            }
+        }
+    }
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut spans = vec![];
+    let mut winding = 0;
+    let mut start = 0.0;
+    for (x, dir) in crossings {
+        if winding == 0 {
+            start = x;
+        }
+        winding += dir;
+        if winding == 0 {
+            spans.push((start, x));
+        }
+    }
+    spans
+}
+
+/// A typed spline set, which generates the body of a `SplineSet` section
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct SplineSet {
+    pub contours: Vec<Contour>,
+}
+
+impl SplineSet {
+    pub fn new(contours: Vec<Contour>) -> Self {
+        Self { contours }
+    }
+
+    /// Parses the body of a `SplineSet` section (like the hand-written constants)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut contours: Vec<Contour> = vec![];
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let err = || format!("couldn't parse the spline line `{line}`");
+            let tokens = line.split_whitespace().collect_vec();
+            let [coords @ .., kind, flags] = tokens.as_slice() else {
+                return Err(err());
+            };
+            let coords = coords
+                .iter()
+                .map(|c| c.parse::<f64>().map_err(|_| err()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let flags = flags.parse::<u8>().map_err(|_| err())?;
+            let point = |i: usize| Point::new(coords[2 * i], coords[2 * i + 1]);
+
+            match (*kind, coords.len()) {
+                ("m", 2) => contours.push(Contour::new(point(0), flags)),
+                ("l", 2) | ("c", 6) => {
+                    let contour = contours.pop().ok_or_else(err)?;
+                    contours.push(if *kind == "l" {
+                        contour.line_to(point(0), flags)
+                    } else {
+                        contour.curve_to(point(0), point(1), point(2), flags)
+                    });
+                }
+                _ => return Err(err()),
+            }
+        }
+        Ok(Self { contours })
+    }
+
+    /// The lowest and highest corners of the box around the ink, or `None` if there's none
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let points = self
+            .contours
+            .iter()
+            .flat_map(|c| c.flatten(FLATTEN_STEPS))
+            .collect_vec();
+        let (min_x, max_x) = points
+            .iter()
+            .map(|p| p.x)
+            .minmax_by(f64::total_cmp)
+            .into_option()?;
+        let (min_y, max_y) = points
+            .iter()
+            .map(|p| p.y)
+            .minmax_by(f64::total_cmp)
+            .into_option()?;
+        Some((Point::new(min_x, min_y), Point::new(max_x, max_y)))
+    }
+
+    /// The ranges of x that are inked along the horizontal line at `y` (using the nonzero rule)
+    pub fn spans_at(&self, y: f64) -> Vec<(f64, f64)> {
+        spans_at(&self.polygons(), y)
+    }
+
+    /// The contours, flattened into polygons
+    pub fn polygons(&self) -> Vec<Vec<Point>> {
+        self.contours
+            .iter()
+            .map(|c| c.flatten(FLATTEN_STEPS))
+            .collect()
+    }
+
+    /// How many of the other contours each contour is inside. Contours at an even depth are
+    /// outlines, and those at an odd depth are holes.
+    pub fn depths(&self) -> Vec<usize> {
+        self.contours
+            .iter()
+            .enumerate()
+            .map(|(i, contour)| {
+                self.contours
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && other.contains(contour.leftmost()))
+                    .count()
+            })
+            .collect()
+    }
+
+    /// The same spline set with its outlines going `direction` and its holes going the other way
+    pub fn directed(&self, direction: Direction) -> Self {
+        let contours = self
+            .contours
+            .iter()
+            .zip(self.depths())
+            .map(|(contour, depth)| {
+                let clockwise = contour.signed_area() < 0.0;
+                let outline = depth.is_multiple_of(2);
+                if contour.is_degenerate()
+                    || clockwise == (outline == (direction == Direction::Clockwise))
+                {
+                    contour.clone()
+                } else {
+                    contour.reversed()
+                }
+            })
+            .collect();
+        Self::new(contours)
+    }
+
+    /// The same spline set, scaled by `s` around the origin
+    pub fn scaled(&self, s: f64) -> Self {
+        Self::new(
+            self.contours
+                .iter()
+                .map(|c| c.map_points(|p| Point::new(p.x * s, p.y * s)))
+                .collect(),
+        )
+    }
+
+    /// The same spline set with `f` applied to every point (including control points)
+    pub fn mapped(&self, f: impl Fn(Point) -> Point) -> Self {
+        Self::new(self.contours.iter().map(|c| c.map_points(&f)).collect())
+    }
+
+    /// The same spline set, moved by `dx` across and `dy` up
+    pub fn translated(&self, dx: f64, dy: f64) -> Self {
+        Self::new(
+            self.contours
+                .iter()
+                .map(|c| c.map_points(|p| Point::new(p.x + dx, p.y + dy)))
+                .collect(),
+        )
+    }
+
+    /// The same spline set, mirrored horizontally within `width`
+    pub fn mirrored(&self, width: f64) -> Self {
+        Self::new(self.contours.iter().map(|c| c.mirrored(width)).collect())
+    }
+
+    /// Generates the spline set in the same form as the hand-written constants (with a leading newline)
+    pub fn gen(&self) -> String {
+        self.contours
+            .iter()
+            .map(|contour| format!("\n{}", contour.gen()))
+            .join("")
+    }
+}
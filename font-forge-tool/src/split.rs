@@ -0,0 +1,45 @@
+//! The font split into its parts (the base glyphs, the extension, and the alternates), each
+//! generated as a font of its own, so a glyph that shapes wrong can be narrowed down to the part
+//! it's from. Each part's font has the blocks of that part, and the blocks of no part (the control
+//! characters, latin, numbers, and so on) that shaping relies on.
+
+use crate::BlockFilter;
+
+/// A part of the font
+pub struct Part {
+    /// What's added to the font's name and file names (like `-base`)
+    pub suffix: &'static str,
+    /// The blocks in the part (block names, or `_`-separated parts of them)
+    blocks: &'static [&'static str],
+}
+
+/// The parts, which no block is in more than one of. The arrows reference the base glyphs, and
+/// the alternates reference the stylistic set `ss03`, so they go with them.
+pub const PARTS: [Part; 3] = [
+    Part {
+        suffix: "-base",
+        blocks: &["cor", "arrow"],
+    },
+    Part {
+        suffix: "-ext",
+        blocks: &["ext"],
+    },
+    Part {
+        suffix: "-alt",
+        blocks: &["alt", "ss03"],
+    },
+];
+
+impl Part {
+    /// `filter`, also leaving out the blocks of every other part
+    pub fn filter(&self, filter: BlockFilter) -> BlockFilter {
+        let others = PARTS
+            .iter()
+            .filter(|part| part.suffix != self.suffix)
+            .flat_map(|part| part.blocks.iter().map(|block| block.to_string()));
+        BlockFilter {
+            exclude: filter.exclude.into_iter().chain(others).collect(),
+            ..filter
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Stacks of three glyphs (`a ZWJ b ZWJ c`), each squashed into a third of the em. The glyphs are
+//! references to the lower (stacking) forms, so any glyph that can stack can be in a 3-stack.
+//!
+//! Both ZWJs become `joinStack3Tok`. Then, like two-glyph stacks, each glyph followed by the joiner
+//! becomes its bottom form (respawning the joiner), and each joiner followed by a glyph becomes the
+//! glyph's middle or top form: `a_js3 js3 b_js3 js3 c` → `a_js3 js3_b_js3 js3_c`.
+
+use itertools::Itertools;
+
+use crate::{block_kind::BlockKind, contextual::*, features::*, ffir::*};
+
+/// The joiner between the glyphs of a 3-stack
+pub const JOINER: &str = "joinStack3Tok";
+
+/// The part of a 3-stack a block is for, from the bottom up
+#[derive(Clone, Copy)]
+enum Part {
+    Lower,
+    Middle,
+    Upper,
+}
+
+impl Part {
+    const ALL: [Self; 3] = [Self::Lower, Self::Middle, Self::Upper];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Part::Lower => "lower3",
+            Part::Middle => "middle3",
+            Part::Upper => "upper3",
+        }
+    }
+
+    /// The transformation from a lower form (in the bottom half of the em) to this part
+    fn transform(&self) -> Transform {
+        // the lower forms go from -100 to 400, and each part gets a third of -100 to 900
+        let scale = 2.0 / 3.0;
+        let third = 1000.0 / 3.0;
+        let (x, y) = match self {
+            Part::Lower => (0.0, -100.0 * (1.0 - scale)),
+            Part::Middle => (-1000.0, -100.0 * (1.0 - scale) + third),
+            Part::Upper => (-1000.0, -100.0 * (1.0 - scale) + 2.0 * third),
+        };
+        Transform::scale_xy(1.0, scale).translate(x, y)
+    }
+}
+
+/// The block with the joiner, plus the blocks of each part made from each of `lower_blocks`
+pub fn blocks(lower_blocks: &[&GlyphBlock]) -> Vec<GlyphBlock> {
+    let ctrl_block = GlyphBlock::new_from_basic_glyphs(
+        vec![GlyphBasic::new(JOINER, 0, Rep::default(), None)],
+        LookupsMode::None,
+        Cc::Participant,
+        "",
+        "",
+        BlockKind::TokControl.color(),
+        EncPos::None,
+    )
+    .named("stack3_ctrl")
+    .padded(Padding::None);
+
+    let mut blocks = vec![ctrl_block];
+    for lower in lower_blocks {
+        let kind = lower.name.trim_start_matches("lower_");
+        let suffix = lower.suffix.replace("joinStackTok", JOINER);
+        for part in Part::ALL {
+            let (lookups, prefix, suffix, width, color) = match part {
+                Part::Lower => (
+                    LookupsMode::ComboFirst(Joiner::Stack3),
+                    String::new(),
+                    suffix.clone(),
+                    None,
+                    BlockKind::Lower.color(),
+                ),
+                Part::Middle => (
+                    LookupsMode::ComboLast(Joiner::Stack3),
+                    format!("{JOINER}_"),
+                    suffix.clone(),
+                    Some(0),
+                    BlockKind::Upper.color(),
+                ),
+                Part::Upper => (
+                    LookupsMode::ComboLast(Joiner::Stack3),
+                    format!("{JOINER}_"),
+                    lower.suffix.trim_end_matches("_joinStackTok").to_string(),
+                    Some(0),
+                    BlockKind::Upper.color(),
+                ),
+            };
+            let mut block = lower
+                .derive(
+                    &part.transform(),
+                    lookups,
+                    Cc::Full,
+                    false,
+                    prefix,
+                    suffix,
+                    color,
+                    width,
+                    None,
+                )
+                .named(format!("{}_{kind}", part.name()));
+            // the parts are placed by their references, not the stacking anchors
+            for glyph in &mut block.glyphs {
+                glyph.glyph.anchor = None;
+            }
+            blocks.push(block);
+        }
+    }
+    blocks
+}
+
+/// Declares the substitution of the ZWJs in a 3-stack with the joiner
+pub fn declare_joiner(features: &mut FeatureRegistry) {
+    features.declare("ZWJ", SS02_ZWJ_TO_STACK3, JOINER);
+}
+
+/// The `'calt' CHANGE ZWJ` rules that turn both ZWJs of a 3-stack into the joiner, given the
+/// classes of the ZWJ and of the glyphs that can stack. These go before the two-glyph rules.
+pub fn zwj_rules(zwj: ClassId, stackable: &[ClassId]) -> Vec<ContextRule> {
+    (0..3)
+        .map(|_| stackable.iter())
+        .multi_cartesian_product()
+        .map(|glyphs| {
+            ContextRule::new([*glyphs[0], zwj, *glyphs[1], zwj, *glyphs[2]])
+                .apply(1, SS02_ZWJ_TO_STACK3)
+                .apply(3, SS02_ZWJ_TO_STACK3)
+        })
+        .collect()
+}
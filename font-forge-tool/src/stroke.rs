@@ -0,0 +1,385 @@
+//! Expanding centerlines into the outlines of a round pen drawn along them: each side is offset by
+//! half the pen's width (with curves offset the Tiller-Hanson way), the outsides of corners are
+//! rounded, the insides are cut where the sides cross, and open paths get round caps
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::spline::{Contour, Point, Segment, SplineSet};
+
+/// How far (in radians) a path can turn where two segments meet and still be treated as smooth
+const SMOOTH_ANGLE: f64 = 0.01;
+
+/// How close two points have to be to be treated as the same, in font units
+pub const EPSILON: f64 = 0.01;
+
+pub fn add(a: Point, b: Point) -> Point {
+    Point::new(a.x + b.x, a.y + b.y)
+}
+
+pub fn sub(a: Point, b: Point) -> Point {
+    Point::new(a.x - b.x, a.y - b.y)
+}
+
+pub fn scale(a: Point, s: f64) -> Point {
+    Point::new(a.x * s, a.y * s)
+}
+
+pub fn cross(a: Point, b: Point) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+pub fn dot(a: Point, b: Point) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+pub fn length(a: Point) -> f64 {
+    a.x.hypot(a.y)
+}
+
+/// `a` scaled to a length of 1, or the zero vector if it has no length
+pub fn unit(a: Point) -> Point {
+    let length = length(a);
+    if length < EPSILON {
+        Point::new(0.0, 0.0)
+    } else {
+        scale(a, 1.0 / length)
+    }
+}
+
+/// The unit vector a quarter turn counter-clockwise from the direction `t`
+fn left_of(t: Point) -> Point {
+    Point::new(-t.y, t.x)
+}
+
+/// Where the line through `a` going `da` crosses the line through `b` going `db`
+pub fn intersection(a: Point, da: Point, b: Point, db: Point) -> Option<Point> {
+    let denominator = cross(da, db);
+    if denominator.abs() < 1e-9 {
+        return None;
+    }
+    Some(add(a, scale(da, cross(sub(b, a), db) / denominator)))
+}
+
+/// A piece of a path
+#[derive(Clone, Copy)]
+pub enum Piece {
+    Line(Point, Point),
+    Curve(Point, Point, Point, Point),
+}
+
+impl Piece {
+    pub fn start(&self) -> Point {
+        match *self {
+            Piece::Line(a, _) | Piece::Curve(a, ..) => a,
+        }
+    }
+
+    pub fn end(&self) -> Point {
+        match *self {
+            Piece::Line(_, b) | Piece::Curve(.., b) => b,
+        }
+    }
+
+    /// The direction the piece leaves its start in
+    pub fn start_tangent(&self) -> Point {
+        match *self {
+            Piece::Line(a, b) => unit(sub(b, a)),
+            Piece::Curve(p0, p1, p2, p3) => [p1, p2, p3]
+                .into_iter()
+                .map(|p| unit(sub(p, p0)))
+                .find(|t| length(*t) > 0.0)
+                .unwrap_or(Point::new(0.0, 0.0)),
+        }
+    }
+
+    /// The direction the piece arrives at its end in
+    pub fn end_tangent(&self) -> Point {
+        match *self {
+            Piece::Line(a, b) => unit(sub(b, a)),
+            Piece::Curve(p0, p1, p2, p3) => [p2, p1, p0]
+                .into_iter()
+                .map(|p| unit(sub(p3, p)))
+                .find(|t| length(*t) > 0.0)
+                .unwrap_or(Point::new(0.0, 0.0)),
+        }
+    }
+
+    pub fn reversed(&self) -> Self {
+        match *self {
+            Piece::Line(a, b) => Piece::Line(b, a),
+            Piece::Curve(p0, p1, p2, p3) => Piece::Curve(p3, p2, p1, p0),
+        }
+    }
+
+    /// The piece moved to start at `p` (with the control point next to it moved along)
+    fn with_start(&self, p: Point) -> Self {
+        match *self {
+            Piece::Line(_, b) => Piece::Line(p, b),
+            Piece::Curve(p0, p1, p2, p3) => Piece::Curve(p, add(p1, sub(p, p0)), p2, p3),
+        }
+    }
+
+    /// The piece moved to end at `p` (with the control point next to it moved along)
+    fn with_end(&self, p: Point) -> Self {
+        match *self {
+            Piece::Line(a, _) => Piece::Line(a, p),
+            Piece::Curve(p0, p1, p2, p3) => Piece::Curve(p0, p1, add(p2, sub(p, p3)), p),
+        }
+    }
+
+    /// The piece moved `d` to its left (or to its right, if `d` is negative)
+    fn offset(&self, d: f64) -> Self {
+        match *self {
+            Piece::Line(a, b) => {
+                let n = scale(left_of(self.start_tangent()), d);
+                Piece::Line(add(a, n), add(b, n))
+            }
+            Piece::Curve(p0, p1, p2, p3) => {
+                let (t0, t3) = (self.start_tangent(), self.end_tangent());
+                let q0 = add(p0, scale(left_of(t0), d));
+                let q3 = add(p3, scale(left_of(t3), d));
+                // the control points go where the offset legs of the control polygon cross, unless
+                // that's far off (when the legs fold back on each other)
+                let middle = unit(sub(p2, p1));
+                let on_middle = add(p1, scale(left_of(middle), d));
+                let near = |q: Option<Point>, p: Point, fallback: Point| match q {
+                    Some(q) if length(middle) > 0.0 && length(sub(q, p)) < 3.0 * d.abs() => q,
+                    _ => fallback,
+                };
+                let q1 = near(
+                    intersection(q0, t0, on_middle, middle),
+                    p1,
+                    add(p1, scale(left_of(t0), d)),
+                );
+                let q2 = near(
+                    intersection(on_middle, middle, q3, t3),
+                    p2,
+                    add(p2, scale(left_of(t3), d)),
+                );
+                Piece::Curve(q0, q1, q2, q3)
+            }
+        }
+    }
+
+    /// The pieces before and after `t` (from 0 at the start to 1 at the end)
+    pub fn split(&self, t: f64) -> (Self, Self) {
+        let between = |a: Point, b: Point| add(a, scale(sub(b, a), t));
+        match *self {
+            Piece::Line(a, b) => {
+                let m = between(a, b);
+                (Piece::Line(a, m), Piece::Line(m, b))
+            }
+            Piece::Curve(p0, p1, p2, p3) => {
+                let (a, b, c) = (between(p0, p1), between(p1, p2), between(p2, p3));
+                let (d, e) = (between(a, b), between(b, c));
+                let m = between(d, e);
+                (Piece::Curve(p0, a, d, m), Piece::Curve(m, e, c, p3))
+            }
+        }
+    }
+
+    /// The piece split into halves, which are offset more closely than the whole
+    fn halves(&self) -> Vec<Self> {
+        match *self {
+            Piece::Line(..) => vec![*self],
+            Piece::Curve(..) => {
+                let (a, b) = self.split(0.5);
+                vec![a, b]
+            }
+        }
+    }
+}
+
+/// The arc of a circle around `center` with radius `r`, from the angle `from` turning `sweep`
+/// (counter-clockwise if it's positive), as curves of at most a quarter turn each
+fn arc(center: Point, r: f64, from: f64, sweep: f64) -> Vec<Piece> {
+    let count = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / count as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan() * r;
+    (0..count)
+        .map(|i| {
+            let (a, b) = (from + step * i as f64, from + step * (i + 1) as f64);
+            let p0 = add(center, Point::new(r * a.cos(), r * a.sin()));
+            let p3 = add(center, Point::new(r * b.cos(), r * b.sin()));
+            let p1 = add(p0, scale(Point::new(-a.sin(), a.cos()), k));
+            let p2 = sub(p3, scale(Point::new(-b.sin(), b.cos()), k));
+            Piece::Curve(p0, p1, p2, p3)
+        })
+        .collect()
+}
+
+fn angle_of(v: Point) -> f64 {
+    v.y.atan2(v.x)
+}
+
+/// One side of `path`, `d` to its left: the offset pieces, with the outsides of its corners rounded
+/// and the insides cut where the pieces cross
+fn side(path: &[Piece], d: f64, closed: bool) -> Vec<Piece> {
+    let mut offset = path.iter().map(|piece| piece.offset(d)).collect::<Vec<_>>();
+    let mut joins = vec![vec![]; path.len()];
+    for i in 0..path.len() {
+        let j = if i + 1 < path.len() {
+            i + 1
+        } else if closed {
+            0
+        } else {
+            break;
+        };
+        let (t_in, t_out) = (path[i].end_tangent(), path[j].start_tangent());
+        let turn = cross(t_in, t_out).atan2(dot(t_in, t_out));
+        if turn.abs() < SMOOTH_ANGLE {
+            continue;
+        }
+        let vertex = path[i].end();
+        if turn * d < 0.0 {
+            let from = angle_of(sub(offset[i].end(), vertex));
+            joins[i] = arc(vertex, d.abs(), from, turn);
+        } else if let Some(p) = intersection(offset[i].end(), t_in, offset[j].start(), t_out) {
+            offset[i] = offset[i].with_end(p);
+            offset[j] = offset[j].with_start(p);
+        }
+    }
+    offset
+        .into_iter()
+        .zip(joins)
+        .flat_map(|(piece, join)| std::iter::once(piece).chain(join))
+        .collect()
+}
+
+/// Joins `pieces` into a contour, with lines across any gaps between them
+pub fn contour(pieces: &[Piece]) -> Contour {
+    let start = pieces[0].start();
+    let mut contour = Contour::new(start, 1);
+    let mut at = start;
+    for piece in pieces {
+        if length(sub(piece.start(), at)) > EPSILON {
+            contour = contour.line_to(piece.start(), 1);
+        }
+        contour = match *piece {
+            Piece::Line(_, b) => contour.line_to(b, 1),
+            Piece::Curve(_, p1, p2, p3) => contour.curve_to(p1, p2, p3, 0),
+        };
+        at = piece.end();
+    }
+    if length(sub(start, at)) > EPSILON {
+        contour = contour.line_to(start, 1);
+    }
+    contour
+}
+
+/// The pieces `contour` is made of, including the line back to its start (if it doesn't end there)
+pub fn pieces(contour: &Contour) -> Vec<Piece> {
+    let mut from = contour.start;
+    let mut pieces = contour
+        .segments
+        .iter()
+        .map(|(segment, _)| {
+            let piece = match *segment {
+                Segment::Line { to } => Piece::Line(from, to),
+                Segment::Curve { c1, c2, to } => Piece::Curve(from, c1, c2, to),
+            };
+            from = piece.end();
+            piece
+        })
+        .collect::<Vec<_>>();
+    if length(sub(contour.start, from)) > EPSILON {
+        pieces.push(Piece::Line(from, contour.start));
+    }
+    pieces.retain(|piece| {
+        matches!(piece, Piece::Curve(..)) || length(sub(piece.end(), piece.start())) > EPSILON
+    });
+    pieces
+}
+
+/// The outlines of a round pen `width` wide drawn along `centerline`, which is closed if it ends
+/// where it starts
+fn expand_path(centerline: &Contour, width: f64) -> Vec<Contour> {
+    let h = width / 2.0;
+    let mut path = vec![];
+    let mut from = centerline.start;
+    for (segment, _) in &centerline.segments {
+        let piece = match *segment {
+            Segment::Line { to } => Piece::Line(from, to),
+            Segment::Curve { c1, c2, to } => Piece::Curve(from, c1, c2, to),
+        };
+        from = piece.end();
+        if length(sub(piece.end(), piece.start())) > EPSILON || matches!(piece, Piece::Curve(..)) {
+            path.extend(piece.halves());
+        }
+    }
+
+    // a single point is a dot
+    if path.is_empty() {
+        return vec![contour(&arc(centerline.start, h, 0.0, 2.0 * PI))];
+    }
+
+    let closed = path.len() > 1 && length(sub(from, centerline.start)) < EPSILON;
+    let reversed =
+        |pieces: Vec<Piece>| pieces.iter().rev().map(Piece::reversed).collect::<Vec<_>>();
+    if closed {
+        return vec![
+            contour(&side(&path, h, true)),
+            contour(&reversed(side(&path, -h, true))),
+        ];
+    }
+
+    let left = side(&path, h, false);
+    let right = reversed(side(&path, -h, false));
+    let (first, last) = (path[0], path[path.len() - 1]);
+    let end_cap = arc(last.end(), h, angle_of(left_of(last.end_tangent())), -PI);
+    let start_cap = arc(
+        first.start(),
+        h,
+        angle_of(scale(left_of(first.start_tangent()), -1.0)),
+        -PI,
+    );
+    let pieces = [left, end_cap, right, start_cap].concat();
+    vec![contour(&pieces)]
+}
+
+/// The outlines of a round pen `width` wide drawn along each contour of `centerlines` (those that
+/// end where they start are closed), with the points rounded to whole units
+pub fn expand(centerlines: &SplineSet, width: f64) -> SplineSet {
+    SplineSet::new(
+        centerlines
+            .contours
+            .iter()
+            .flat_map(|centerline| expand_path(centerline, width))
+            .collect(),
+    )
+    .mapped(|p| Point::new(p.x.round(), p.y.round()))
+}
+
+/// `spline_set` with its outlines grown outwards by `d` (with the outsides of their corners
+/// rounded) and its holes left as they are, so the strokes get thicker without the counters
+/// filling in, with the points rounded to whole units. An outline that would cross itself (where
+/// it curves inwards more tightly than `d`) is left as it is too.
+pub fn grow(spline_set: &SplineSet, d: f64) -> SplineSet {
+    SplineSet::new(
+        spline_set
+            .contours
+            .iter()
+            .zip(spline_set.depths())
+            .map(|(original, depth)| {
+                let path = pieces(original)
+                    .iter()
+                    .flat_map(Piece::halves)
+                    .collect::<Vec<_>>();
+                if depth % 2 == 1 || path.is_empty() {
+                    return original.clone();
+                }
+                // the inside of an outline that goes clockwise is on its right
+                let d = if original.signed_area() < 0.0 { d } else { -d };
+                let grown = SplineSet::new(vec![contour(&side(&path, d, true))])
+                    .mapped(|p| Point::new(p.x.round(), p.y.round()))
+                    .contours
+                    .remove(0);
+                if grown.self_intersections().len() > original.self_intersections().len() {
+                    original.clone()
+                } else {
+                    grown
+                }
+            })
+            .collect(),
+    )
+}
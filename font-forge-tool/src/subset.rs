@@ -0,0 +1,79 @@
+//! Subsetting: keeping only the sitelen pona (with their alternates and combinations) that some
+//! text uses, for embedding a minimal font in PDFs or e-readers
+
+use std::collections::{HashMap, HashSet};
+
+use font_forge_tool::translit;
+
+use crate::glyph_blocks::base::{BASE_COR, BASE_EXT};
+
+/// The blocks a subset keeps whole, since their glyphs aren't for any one word
+const SHARED_BLOCKS: [&str; 14] = [
+    "ctrl",
+    "tok_ctrl",
+    "latn",
+    "names",
+    "letters",
+    "numbers",
+    "no_comb",
+    "half_width",
+    "spacing",
+    "underline",
+    "radicals",
+    "ctrl_nested",
+    "stack3_ctrl",
+    "fallback",
+];
+
+/// Whether the block named `block` has a glyph per word, which a subset can leave out
+pub fn is_word_block(block: &str) -> bool {
+    !SHARED_BLOCKS.contains(&block)
+}
+
+/// The word a glyph in a word block is for (e.g. `jaki` for `jakiTok_VAR01_joinScaleTok`),
+/// or `None` for padding
+pub fn word_of(full_name: &str) -> Option<&str> {
+    let name = full_name
+        .trim_start_matches("joinScaleTok_")
+        .trim_start_matches("joinStackTok_")
+        .trim_start_matches("joinStack3Tok_");
+    name.split_once("Tok").map(|(word, _)| word)
+}
+
+/// Every word in `text`, which may be toki pona in ASCII, UCSUR, or a mix of both
+pub fn words_in(text: &str) -> HashSet<String> {
+    let ucsur = translit::table()
+        .into_iter()
+        .filter(|(spelling, _)| spelling.chars().all(|c| c.is_ascii_lowercase()))
+        .map(|(spelling, c)| (c, spelling))
+        .collect::<HashMap<_, _>>();
+
+    let mut words = HashSet::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphabetic() {
+            word.push(c.to_ascii_lowercase());
+            continue;
+        }
+        if !word.is_empty() {
+            words.insert(std::mem::take(&mut word));
+        }
+        if let Some(spelling) = ucsur.get(&c) {
+            words.insert(spelling.to_string());
+        }
+    }
+    if !word.is_empty() {
+        words.insert(word);
+    }
+    words
+}
+
+/// Every word whose glyph has the tag `tag` (like `animal`)
+pub fn words_tagged(tag: &str) -> HashSet<String> {
+    BASE_COR
+        .iter()
+        .chain(BASE_EXT.iter())
+        .filter(|glyph| glyph.tags.contains(&tag))
+        .map(|glyph| glyph.name.to_string())
+        .collect()
+}
@@ -0,0 +1,367 @@
+//! Glyphs drawn in SVG: a directory of `{word}.svg` files, one glyph each, added like a glyph pack
+//! named after the directory. The `viewBox` is the glyph's em (its top is the top of the em, and
+//! its height is scaled to 1000 units), and its width is the glyph's width. Only the `d` of each
+//! `<path>` is read, so strokes, fills and transforms have to be turned into outlines first.
+
+use std::{fs, io, path::Path};
+
+use crate::{
+    em::Metrics,
+    invalid_data,
+    sources::{GlyphBlockSpec, GlyphSource, SourceGlyph},
+    spline::{Contour, Point, SplineSet},
+};
+
+/// FontForge's point flags for the end of a curve and of a line
+const CURVE: u8 = 0;
+const CORNER: u8 = 1;
+
+/// A directory of SVG glyphs
+pub struct SvgDirectory {
+    pub name: String,
+    pub glyphs: Vec<SourceGlyph>,
+}
+
+impl SvgDirectory {
+    /// Reads every `.svg` in `dir` (sorted by file name)
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "svg"));
+        paths.sort();
+
+        let glyphs = paths
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                parse_glyph(&name, &fs::read_to_string(path)?)
+                    .map_err(|e| invalid_data(format!("{}: {e}", path.display())))
+            })
+            .collect::<io::Result<_>>()?;
+
+        let name = dir
+            .canonicalize()?
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self { name, glyphs })
+    }
+}
+
+impl GlyphSource for SvgDirectory {
+    fn name(&self) -> String {
+        format!("the SVG directory {}", self.name)
+    }
+
+    fn blocks(&self) -> Vec<GlyphBlockSpec> {
+        vec![GlyphBlockSpec {
+            name: self.name.clone(),
+            start: None,
+            glyphs: self.glyphs.clone(),
+        }]
+    }
+}
+
+/// The value of the attribute `name` in `tag` (the text between `<` and `>`)
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(i) = rest.find(name) {
+        let before = rest[..i].chars().next_back();
+        let after = rest[i + name.len()..].trim_start();
+        rest = &rest[i + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// The text of each `<name ...>` tag in `svg`
+fn tags<'a>(svg: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> {
+    svg.split('<').filter_map(move |tag| {
+        let tag = tag.split('>').next()?;
+        tag.strip_prefix(name)
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map(|_| tag)
+    })
+}
+
+/// Reads the glyph `name` from the text of its SVG
+pub fn parse_glyph(name: &str, svg: &str) -> Result<SourceGlyph, String> {
+    let view_box = tags(svg, "svg")
+        .next()
+        .and_then(|tag| attribute(tag, "viewBox"))
+        .ok_or("there's no viewBox")?;
+    let view_box = view_box
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("couldn't parse the viewBox `{view_box}`"))?;
+    let [min_x, min_y, width, height] = view_box[..] else {
+        return Err("the viewBox isn't four numbers".to_string());
+    };
+
+    let scale = Metrics::DRAWN.units_per_em as f64 / height;
+    let ascent = Metrics::DRAWN.ascent as f64;
+    let to_font = |p: Point| Point::new((p.x - min_x) * scale, ascent - (p.y - min_y) * scale);
+
+    let mut contours = vec![];
+    for tag in tags(svg, "path") {
+        if let Some(d) = attribute(tag, "d") {
+            contours.extend(parse_path(d, &to_font)?.contours);
+        }
+    }
+    Ok(SourceGlyph {
+        name: name.to_string(),
+        codepoint: None,
+        width: Some((width * scale).round() as usize),
+        spline_set: SplineSet::new(contours).gen(),
+        ..Default::default()
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Token {
+    Command(char),
+    Number(f64),
+}
+
+/// Splits path data into commands and numbers (which can run together, like `M1-2.5.5`)
+fn tokenize(d: &str) -> Result<Vec<Token>, String> {
+    let bytes = d.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() || c == b',' {
+            i += 1;
+        } else if c.is_ascii_alphabetic() {
+            tokens.push(Token::Command(c as char));
+            i += 1;
+        } else {
+            let start = i;
+            if matches!(bytes[i], b'+' | b'-') {
+                i += 1;
+            }
+            let mut seen_dot = false;
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || (bytes[i] == b'.' && !seen_dot))
+            {
+                seen_dot |= bytes[i] == b'.';
+                i += 1;
+            }
+            if i < bytes.len() && matches!(bytes[i], b'e' | b'E') {
+                i += 1;
+                if i < bytes.len() && matches!(bytes[i], b'+' | b'-') {
+                    i += 1;
+                }
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let number = &d[start..i];
+            tokens.push(Token::Number(number.parse().map_err(|_| {
+                format!(
+                    "couldn't parse `{}` in the path data",
+                    d[start..].chars().next().unwrap_or_default()
+                )
+            })?));
+        }
+    }
+    Ok(tokens)
+}
+
+/// The contour being drawn, and where the pen is
+struct Pen<'a> {
+    to_font: &'a dyn Fn(Point) -> Point,
+    contours: Vec<Contour>,
+    contour: Option<Contour>,
+    start: Point,
+    current: Point,
+    /// The last control point of the last segment, if it was a cubic (`C`/`S`) or a quadratic
+    /// (`Q`/`T`) curve, to reflect for smooth curves
+    last_cubic: Option<Point>,
+    last_quad: Option<Point>,
+}
+
+impl Pen<'_> {
+    fn move_to(&mut self, to: Point) {
+        self.close();
+        self.contour = Some(Contour::new((self.to_font)(to), CORNER));
+        self.start = to;
+        self.current = to;
+    }
+
+    /// The contour being drawn (a new one at the pen, after a `Z` that isn't followed by a move)
+    fn contour(&mut self) -> Contour {
+        let current = (self.to_font)(self.current);
+        self.contour
+            .take()
+            .unwrap_or_else(|| Contour::new(current, CORNER))
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let contour = self.contour().line_to((self.to_font)(to), CORNER);
+        self.contour = Some(contour);
+        self.current = to;
+    }
+
+    fn curve_to(&mut self, c1: Point, c2: Point, to: Point) {
+        let [c1, c2, to_font] = [c1, c2, to].map(self.to_font);
+        let contour = self.contour().curve_to(c1, c2, to_font, CURVE);
+        self.contour = Some(contour);
+        self.current = to;
+    }
+
+    /// Closes the contour with a line back to its start (if it doesn't end there already)
+    fn close(&mut self) {
+        if self.contour.is_some() && self.current != self.start {
+            self.line_to(self.start);
+        }
+        if let Some(mut contour) = self.contour.take() {
+            if let Some((_, flags)) = contour.segments.last() {
+                contour.start_flags = *flags;
+            }
+            if !contour.segments.is_empty() {
+                self.contours.push(contour);
+            }
+        }
+        self.current = self.start;
+    }
+}
+
+/// Parses the `d` of an SVG path, drawing each point at `to_font(p)`. Every subpath is closed.
+pub fn parse_path(d: &str, to_font: &dyn Fn(Point) -> Point) -> Result<SplineSet, String> {
+    let tokens = tokenize(d)?;
+    let mut pen = Pen {
+        to_font,
+        contours: vec![],
+        contour: None,
+        start: Point::new(0.0, 0.0),
+        current: Point::new(0.0, 0.0),
+        last_cubic: None,
+        last_quad: None,
+    };
+
+    let mut i = 0;
+    let mut command = None;
+    while i < tokens.len() {
+        let c = match tokens[i] {
+            Token::Command(c) => {
+                i += 1;
+                c
+            }
+            // numbers after the first pair of a move are lines
+            Token::Number(_) => match command {
+                Some('M') => 'L',
+                Some('m') => 'l',
+                Some(c) => c,
+                None => return Err("the path data doesn't start with a command".to_string()),
+            },
+        };
+        command = Some(c);
+
+        let mut numbers = |n: usize| -> Result<Vec<f64>, String> {
+            let numbers = tokens
+                .get(i..i + n)
+                .unwrap_or_default()
+                .iter()
+                .map_while(|token| match token {
+                    Token::Number(n) => Some(*n),
+                    Token::Command(_) => None,
+                })
+                .collect::<Vec<_>>();
+            if numbers.len() < n {
+                return Err(format!("`{c}` needs {n} numbers"));
+            }
+            i += n;
+            Ok(numbers)
+        };
+        let origin = if c.is_ascii_lowercase() {
+            pen.current
+        } else {
+            Point::new(0.0, 0.0)
+        };
+        let point = |x: f64, y: f64| Point::new(origin.x + x, origin.y + y);
+        let reflect = |control: Option<Point>, around: Point| {
+            control.map_or(around, |p| {
+                Point::new(2.0 * around.x - p.x, 2.0 * around.y - p.y)
+            })
+        };
+
+        let (mut last_cubic, mut last_quad) = (None, None);
+        match c.to_ascii_uppercase() {
+            'M' => {
+                let n = numbers(2)?;
+                pen.move_to(point(n[0], n[1]));
+            }
+            'L' => {
+                let n = numbers(2)?;
+                pen.line_to(point(n[0], n[1]));
+            }
+            'H' => {
+                let n = numbers(1)?;
+                pen.line_to(Point::new(origin.x + n[0], pen.current.y));
+            }
+            'V' => {
+                let n = numbers(1)?;
+                pen.line_to(Point::new(pen.current.x, origin.y + n[0]));
+            }
+            'C' => {
+                let n = numbers(6)?;
+                let c2 = point(n[2], n[3]);
+                pen.curve_to(point(n[0], n[1]), c2, point(n[4], n[5]));
+                last_cubic = Some(c2);
+            }
+            'S' => {
+                let n = numbers(4)?;
+                let c1 = reflect(pen.last_cubic, pen.current);
+                let c2 = point(n[0], n[1]);
+                pen.curve_to(c1, c2, point(n[2], n[3]));
+                last_cubic = Some(c2);
+            }
+            'Q' | 'T' => {
+                let (q, to) = if c.eq_ignore_ascii_case(&'Q') {
+                    let n = numbers(4)?;
+                    (point(n[0], n[1]), point(n[2], n[3]))
+                } else {
+                    let n = numbers(2)?;
+                    (reflect(pen.last_quad, pen.current), point(n[0], n[1]))
+                };
+                // the same curve as a cubic one
+                let from = pen.current;
+                let c1 = Point::new(
+                    from.x + 2.0 / 3.0 * (q.x - from.x),
+                    from.y + 2.0 / 3.0 * (q.y - from.y),
+                );
+                let c2 = Point::new(
+                    to.x + 2.0 / 3.0 * (q.x - to.x),
+                    to.y + 2.0 / 3.0 * (q.y - to.y),
+                );
+                pen.curve_to(c1, c2, to);
+                last_quad = Some(q);
+            }
+            'Z' => pen.close(),
+            'A' => {
+                return Err("arcs (`A`) aren't supported; turn them into curves first".to_string())
+            }
+            _ => return Err(format!("`{c}` isn't a path command")),
+        }
+        pen.last_cubic = last_cubic;
+        pen.last_quad = last_quad;
+    }
+    pen.close();
+    Ok(SplineSet::new(pen.contours))
+}
@@ -0,0 +1,190 @@
+//! An SVG-in-OpenType build, for platforms that draw the `SVG ` table: each glyph is drawn as an
+//! SVG document generated from its outlines (with its references filled in), styled by an
+//! `SvgStyle`. FontForge can't write the table, so the documents are written to a `.svg.json`
+//! next to the `.sfd`, and added to compiled fonts with fontTools.
+//!
+//! A style looks like this (every field is optional):
+//!
+//! ```toml
+//! fill = "#202020"
+//! # containers (the outer glyphs, cartouches and long glyphs), and the glyphs inside them
+//! container_fill = "#1f5fbf"
+//! inner_fill = "#d0402a"
+//! stroke = "#ffffff"
+//! stroke_width = 10
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color::{palette_entry, PaletteEntry},
+    invalid_data,
+    spline::{gen_coord, Point, Segment, SplineSet},
+    Font,
+};
+
+/// How the glyphs are drawn
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SvgStyle {
+    pub fill: String,
+    pub container_fill: Option<String>,
+    pub inner_fill: Option<String>,
+    pub stroke: Option<String>,
+    pub stroke_width: f64,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            fill: "#000000".to_string(),
+            container_fill: None,
+            inner_fill: None,
+            stroke: None,
+            stroke_width: 0.0,
+        }
+    }
+}
+
+impl SvgStyle {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| invalid_data(format!("{}: {e}", path.display())))
+    }
+
+    /// The attributes of the path that draws the glyph `name` of the block `block`
+    fn attributes(&self, block: &str, name: &str) -> String {
+        let fill = match palette_entry(block, name) {
+            Some(PaletteEntry::Container) => self.container_fill.as_ref(),
+            Some(PaletteEntry::Inner) => self.inner_fill.as_ref(),
+            None => None,
+        }
+        .unwrap_or(&self.fill);
+        let mut attributes = format!(r#"fill="{fill}""#);
+        if let Some(stroke) = &self.stroke {
+            attributes += &format!(
+                r#" stroke="{stroke}" stroke-width="{}""#,
+                gen_coord(self.stroke_width)
+            );
+        }
+        attributes
+    }
+}
+
+/// The path data that draws `spline_set`, with y going down (as in the `SVG ` table, where the
+/// baseline is at 0)
+fn path_data(spline_set: &SplineSet) -> String {
+    let point = |p: Point| format!("{} {}", gen_coord(p.x), gen_coord(-p.y));
+    let mut d = vec![];
+    for contour in &spline_set.contours {
+        d.push(format!("M{}", point(contour.start)));
+        for (segment, _) in &contour.segments {
+            d.push(match segment {
+                Segment::Line { to } => format!("L{}", point(*to)),
+                Segment::Curve { c1, c2, to } => {
+                    format!("C{} {} {}", point(*c1), point(*c2), point(*to))
+                }
+            });
+        }
+        d.push("Z".to_string());
+    }
+    d.join("")
+}
+
+/// The SVG document of each glyph that draws something, by name. Each document's glyph has the
+/// id `glyph{gid}`, since the glyph ID is only known once the font is compiled.
+#[derive(Serialize)]
+pub struct SvgTable {
+    documents: BTreeMap<String, String>,
+}
+
+/// Draws every glyph of `font` in `style` (in order, so references are drawn before the glyphs
+/// that use them)
+pub fn table(font: &Font, style: &SvgStyle) -> Result<SvgTable, String> {
+    let mut drawn = HashMap::<String, SplineSet>::new();
+    let mut documents = BTreeMap::new();
+    for block in &font.blocks {
+        for (name, glyph) in block.full_names() {
+            let rep = &glyph.glyph.rep;
+            let mut spline_set = SplineSet::parse(rep.spline_set())?;
+            for reference in rep.references() {
+                let Some(target) = drawn.get(reference.target()) else {
+                    continue;
+                };
+                let transform = reference.transform();
+                spline_set
+                    .contours
+                    .extend(target.mapped(|p| transform.apply(p)).contours);
+            }
+
+            let name = name.to_string();
+            if !spline_set.contours.is_empty() {
+                documents.insert(
+                    name.clone(),
+                    format!(
+                        r#"<svg xmlns="http://www.w3.org/2000/svg"><path id="glyph{{gid}}" {} d="{}"/></svg>"#,
+                        style.attributes(&block.name, &name),
+                        path_data(&spline_set),
+                    ),
+                );
+            }
+            drawn.insert(name, spline_set);
+        }
+    }
+    Ok(SvgTable { documents })
+}
+
+impl SvgTable {
+    /// Writes the table next to the `.sfd` at `sfd`, returning where it went
+    pub fn write(&self, sfd: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let path = sfd.as_ref().with_extension("svg.json");
+        serde_json::to_writer_pretty(BufWriter::new(File::create(&path)?), self)?;
+        Ok(path)
+    }
+}
+
+/// The fontTools script that adds the table (from the JSON in its first argument) to the fonts in
+/// the rest
+const APPLY: &str = r#"
+import json, sys
+from fontTools.ttLib import TTFont, newTable
+documents = json.load(open(sys.argv[1]))["documents"]
+for path in sys.argv[2:]:
+    font = TTFont(path)
+    order = set(font.getGlyphOrder())
+    doc_list = []
+    for glyph, document in documents.items():
+        if glyph in order:
+            gid = font.getGlyphID(glyph)
+            doc_list.append((document.replace("{gid}", str(gid)), gid, gid))
+    table = newTable("SVG ")
+    table.docList = sorted(doc_list, key=lambda doc: doc[1])
+    font["SVG "] = table
+    font.save(path)
+"#;
+
+/// Adds the `SVG ` table in `table` (as written by `SvgTable::write`) to each of the compiled
+/// `fonts`
+pub fn apply(table: impl AsRef<Path>, fonts: &[PathBuf]) -> io::Result<()> {
+    let status = process::Command::new("python3")
+        .args(["-c", APPLY])
+        .arg(table.as_ref())
+        .args(fonts)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("couldn't run python3: {e}")))?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "couldn't add the SVG table (is fontTools installed?) ({status})"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Transliteration from the ASCII the font ligates (e.g. `toki pona`, `[ijo]`) to UCSUR text.
+//! The rules come from `data/transliteration.txt`, which is generated from the font's own lookups.
+
+const TABLE: &str = include_str!("../data/transliteration.txt");
+
+/// Every ASCII spelling and the character it becomes, longest spellings first
+pub fn table() -> Vec<(&'static str, char)> {
+    let mut table = TABLE
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (spelling, codepoint) = line.split_once('\t')?;
+            let c = char::from_u32(u32::from_str_radix(codepoint, 16).ok()?)?;
+            Some((spelling, c))
+        })
+        .collect::<Vec<_>>();
+    table.sort_by_key(|(spelling, _)| std::cmp::Reverse(spelling.len()));
+    table
+}
+
+/// Converts ASCII text to UCSUR the way the font's ligatures do.
+/// The longest matching spelling wins, spaces between two sitelen pona are dropped
+/// (the font renders them with zero width), and anything else is kept as-is.
+pub fn ascii_to_ucsur(text: &str) -> String {
+    let table = table();
+    let mut out = String::new();
+    let mut rest = text;
+    // whether the last thing written was a sitelen pona, followed by the spaces since then
+    let mut pending_spaces: Option<String> = None;
+
+    while let Some(c) = rest.chars().next() {
+        if c == ' ' {
+            match &mut pending_spaces {
+                Some(spaces) => spaces.push(c),
+                None => out.push(c),
+            }
+            rest = &rest[1..];
+            continue;
+        }
+
+        match table
+            .iter()
+            .find(|(spelling, _)| rest.starts_with(spelling))
+        {
+            Some((spelling, ucsur)) => {
+                pending_spaces = Some(String::new());
+                out.push(*ucsur);
+                rest = &rest[spelling.len()..];
+            }
+            None => {
+                if let Some(spaces) = pending_spaces.take() {
+                    out.push_str(&spaces);
+                }
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if let Some(spaces) = pending_spaces {
+        out.push_str(&spaces);
+    }
+    out
+}
@@ -0,0 +1,135 @@
+//! `'ss04'`: a `pi` phrase bracketed by an underline instead of the long `pi` running under it.
+//! `pi` keeps its usual form, with a hook after it that the underline starts from, and the
+//! phrase's long glyph extensions (and its end) are swapped for the underline's, one after another.
+//!
+//! Like the containment model (see `containment`), the state is carried by the glyph before: an
+//! extension is the underline's if the one before the glyph it's under is. So a cartouche in the
+//! phrase, or a combination (whose upper glyph draws its own extension), ends the underline there,
+//! and the rest of the phrase goes back to the long `pi`.
+
+use crate::{
+    block_kind::BlockKind,
+    contextual::{ContextRule, ContextualLookupBuilder},
+    features::*,
+    ffir::*,
+    glyph_blocks::{base::BASE_COR, synth::*},
+    spline::SplineSet,
+};
+
+/// The long `pi` that's underlined instead
+const LONG_PI: &str = "piTok_startLongGlyphTok";
+
+/// Each glyph of the long `pi` and the underline glyph it's swapped for
+const SWAPS: [(&str, &str); 4] = [
+    (LONG_PI, "piTok_startUnderlineTok"),
+    ("combLongGlyphExtTok", "combUnderlineExtTok"),
+    ("combLongGlyphExtHalfTok", "combUnderlineExtHalfTok"),
+    ("endLongGlyphTok", "endUnderlineTok"),
+];
+
+/// The block of the underline glyphs: `pi` with the hook the underline starts from, the
+/// extensions (like the long glyph's), and the end with its own hook
+pub fn block() -> Result<GlyphBlock, String> {
+    let pi = BASE_COR
+        .iter()
+        .find(|glyph| glyph.name == "pi")
+        .ok_or("there's no pi to underline from")?;
+    let mut start = SplineSet::parse(&pi.outlines()?)?;
+    start.contours.extend(underline_hook(EXT_LENGTH).contours);
+    let glyphs = vec![
+        GlyphBasic::new(SWAPS[0].1, 1000, Rep::new(start.gen(), vec![]), None),
+        GlyphBasic::new(
+            SWAPS[1].1,
+            0,
+            Rep::new(ext_bars(&UNDERLINE_BARS, EXT_LENGTH).gen(), vec![]),
+            None,
+        ),
+        GlyphBasic::new(
+            SWAPS[2].1,
+            0,
+            Rep::new(ext_bars(&UNDERLINE_BARS, EXT_HALF_LENGTH).gen(), vec![]),
+            None,
+        ),
+        GlyphBasic::new(
+            SWAPS[3].1,
+            0,
+            Rep::new(underline_hook(0.0).gen(), vec![]),
+            None,
+        ),
+    ];
+    Ok(GlyphBlock::new_from_basic_glyphs(
+        glyphs,
+        LookupsMode::None,
+        Cc::None,
+        "",
+        "",
+        BlockKind::TokControl.color(),
+        EncPos::None,
+    )
+    .named("underline")
+    .padded(Padding::None))
+}
+
+/// Whether `pi` can start a long glyph, and the underline is in `blocks`
+fn generated(blocks: &[GlyphBlock]) -> bool {
+    let has = |name: &str| {
+        blocks
+            .iter()
+            .flat_map(|block| block.full_names())
+            .any(|(full_name, _)| full_name.as_str() == name)
+    };
+    has(LONG_PI) && has(SWAPS[0].1)
+}
+
+/// Declares `'ss04'` and the swaps it makes, if the underline is generated
+pub fn declare(blocks: &[GlyphBlock], features: &mut FeatureRegistry) {
+    if !generated(blocks) {
+        return;
+    }
+    // marks aren't skipped, since the extensions it swaps are marks themselves
+    features
+        .add_after(
+            LIGA_CC_CLEANUP,
+            SS04_PI_UNDERLINE,
+            LookupType::ChainContext,
+            0,
+        )
+        .add_after(SS04_PI_UNDERLINE, SS00_TO_UNDERLINE, LookupType::Single, 0)
+        .depends_on(SS04_PI_UNDERLINE, &[LIGA_CC_CLEANUP]);
+    for (long, underline) in SWAPS {
+        features.declare(long, SS00_TO_UNDERLINE, underline);
+    }
+}
+
+/// Generates the `'ss04' PI UNDERLINE` subtable, if the underline is generated
+pub fn gen_fpst(
+    features: &FeatureRegistry,
+    blocks: &[GlyphBlock],
+    put_in_class: impl Fn(String) -> String,
+) -> String {
+    if !generated(blocks) {
+        return String::new();
+    }
+    let mut builder = ContextualLookupBuilder::new(features, SS04_PI_UNDERLINE, "other");
+    let other = builder.others();
+    let start = builder.class("start", LONG_PI);
+    let ext = builder.class("ext", format!("{} {}", SWAPS[1].0, SWAPS[2].0));
+    let end = builder.class("end", SWAPS[3].0);
+    let underlined = builder.class(
+        "underlined",
+        format!("{} {} {}", SWAPS[0].1, SWAPS[1].1, SWAPS[2].1),
+    );
+    builder
+        .rule(ContextRule::new([start]).apply(0, SS00_TO_UNDERLINE))
+        .rule(
+            ContextRule::new([ext])
+                .backtrack([underlined, other])
+                .apply(0, SS00_TO_UNDERLINE),
+        )
+        .rule(
+            ContextRule::new([end])
+                .backtrack([underlined])
+                .apply(0, SS00_TO_UNDERLINE),
+        );
+    builder.build(put_in_class)
+}
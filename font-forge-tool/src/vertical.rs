@@ -0,0 +1,104 @@
+//! Vertical writing: vertical advances for every glyph, plus rotated forms of the pieces that run
+//! along the line (cartouches, long glyphs, and quotes), which `vert` and `vrt2` substitute in
+
+use crate::{block_kind::BlockKind, features::*, ffir::*, glyph_blocks::synth::*};
+
+/// The font's `Ascent`, where vertical layout puts the top of each glyph
+const ASCENT: isize = 900;
+
+/// The glyphs that are rotated in vertical text, by full name
+const ROTATED: [&str; 23] = [
+    "startCartTok",
+    "endCartTok",
+    "combCartExtTok",
+    "combCartExtHalfTok",
+    "startCartAltTok",
+    "endCartAltTok",
+    "startLongPiTok",
+    "combLongPiExtTok",
+    "startLongGlyphTok",
+    "endLongGlyphTok",
+    "startRevLongGlyphTok",
+    "endRevLongGlyphTok",
+    "combLongGlyphExtTok",
+    "combLongGlyphExtHalfTok",
+    "combCartInContExtTok",
+    "combCartInContExtHalfTok",
+    "combCartInContExtNoneTok",
+    "combContInCartExtTok",
+    "combContInCartExtHalfTok",
+    "combContInCartExtNoneTok",
+    "teTok",
+    "toTok",
+    "colonTok",
+];
+
+/// The name of the rotated form of `full_name`
+fn rotated_name(full_name: &str) -> String {
+    format!("{full_name}_vert")
+}
+
+/// Gives every glyph in `blocks` a vertical advance (sitelen pona are square, and combining pieces
+/// take no space), then appends a block of rotated forms and declares the lookups that use them
+pub fn add_vertical(blocks: &mut Vec<GlyphBlock>, features: &mut FeatureRegistry) {
+    for glyph in blocks.iter_mut().flat_map(|block| &mut block.glyphs) {
+        let width = glyph.glyph.width;
+        glyph.glyph.vwidth = Some(if width == 0 { 0 } else { 1000 });
+    }
+
+    features
+        .add_after(LIGA_CC_CLEANUP, VERT_VERTICAL, LookupType::Single, 0)
+        .add_after(VERT_VERTICAL, VRT2_VERTICAL, LookupType::Single, 0);
+
+    let ticks = (1..=2 * CART_TICK_COUNT)
+        .map(cart_tick_name)
+        .collect::<Vec<_>>();
+    let mut rotated = vec![];
+    for block in blocks.iter() {
+        for (name, glyph) in block.full_names() {
+            if !ROTATED.contains(&name.as_str()) && !ticks.iter().any(|tick| tick == name.as_str())
+            {
+                continue;
+            }
+            rotated.push(rotate(name.as_str(), glyph.glyph.width));
+            for subtable in [VERT_VERTICAL, VRT2_VERTICAL] {
+                features.declare(name.as_str(), subtable, rotated_name(name.as_str()));
+            }
+        }
+    }
+
+    blocks.push(
+        GlyphBlock::new_from_basic_glyphs(
+            rotated,
+            LookupsMode::None,
+            Cc::None,
+            "",
+            "",
+            BlockKind::Vertical.color(),
+            EncPos::None,
+        )
+        .named("vert"),
+    );
+}
+
+/// A glyph that references `full_name` turned 90° clockwise, so the line runs down instead of right.
+/// The rotated glyph advances down by the original's width, and its ascender lines up with the baseline's.
+fn rotate(full_name: &str, width: usize) -> GlyphBasic {
+    let rotated_width = if width == 0 { 0 } else { 1000 };
+    // moves the middle of the em (halfway between the descent and the ascent) to the middle of the glyph
+    let dx = rotated_width as isize / 2 - (ASCENT - 500);
+    let mut glyph = GlyphBasic::new(
+        rotated_name(full_name),
+        rotated_width,
+        Rep::new(
+            "",
+            vec![Ref::new(
+                GlyphName::new("", full_name, ""),
+                Transform::rotation(0.0, -1.0).translate(dx as f64, ASCENT as f64),
+            )],
+        ),
+        None,
+    );
+    glyph.vwidth = Some(width);
+    glyph
+}
@@ -0,0 +1,179 @@
+//! Comparing two builds of the font glyph by glyph, both drawn in the same box, to catch changes
+//! to how glyphs look (like after a refactor that shouldn't have changed any)
+
+use std::{fs, io, path::Path};
+
+use serde::Serialize;
+
+use crate::{cli::ReportFormat, preview::Previewer, raster::Bitmap, spline::Point};
+
+/// How many pixels are left between the drawings of a side-by-side image
+const GAP: usize = 4;
+
+#[derive(Serialize)]
+pub struct GlyphDiff {
+    name: String,
+    /// How much of the ink of either build differs, in percent
+    difference: f64,
+    /// The side-by-side image written for it, if it's past the threshold
+    image: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct VisualDiffReport {
+    /// The glyphs in both builds that are drawn differently, from the most different
+    changed: Vec<GlyphDiff>,
+    /// How many glyphs in both builds are drawn the same
+    unchanged: usize,
+    /// The glyphs only in the new build
+    added: Vec<String>,
+    /// The glyphs only in the old build
+    removed: Vec<String>,
+}
+
+/// How much of the ink of `old` or `new` (drawn the same size) differs, in percent
+fn difference(old: &Bitmap, new: &Bitmap) -> f64 {
+    let (mut differs, mut ink) = (0.0, 0.0);
+    for (a, b) in old.coverage.iter().zip(&new.coverage) {
+        differs += (a - b).abs();
+        ink += a.max(*b);
+    }
+    if ink == 0.0 {
+        0.0
+    } else {
+        differs / ink * 100.0
+    }
+}
+
+/// `old`, `new`, and where they differ, side by side
+fn side_by_side(old: &Bitmap, new: &Bitmap) -> Bitmap {
+    let differs = Bitmap {
+        coverage: old
+            .coverage
+            .iter()
+            .zip(&new.coverage)
+            .map(|(a, b)| (a - b).abs())
+            .collect(),
+        ..*old
+    };
+    let panels = [old, new, &differs];
+    let width = old.width * 3 + GAP * 2;
+    let mut coverage = Vec::with_capacity(width * old.height);
+    for y in 0..old.height {
+        for (i, panel) in panels.iter().enumerate() {
+            if i > 0 {
+                coverage.extend([0.0; GAP]);
+            }
+            coverage.extend(&panel.coverage[y * panel.width..(y + 1) * panel.width]);
+        }
+    }
+    Bitmap {
+        width,
+        height: old.height,
+        coverage,
+    }
+}
+
+/// Draws every glyph of `old` and `new` with the em `size` pixels tall, and compares them,
+/// writing a side-by-side image (the old, the new, and where they differ) into `out` for each
+/// that differs by more than `threshold` percent
+pub fn report(
+    old: &Previewer,
+    new: &Previewer,
+    size: usize,
+    threshold: f64,
+    out: &Path,
+) -> io::Result<VisualDiffReport> {
+    let mut changed = vec![];
+    let mut unchanged = 0;
+    let mut added = vec![];
+    let mut removed = old
+        .names()
+        .filter(|name| new.width(name).is_none())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    removed.sort();
+
+    let mut names = new.names().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let Some(old_width) = old.width(name) else {
+            added.push(name.to_string());
+            continue;
+        };
+        let (old_outline, new_outline) = (
+            old.outline(name).cloned().unwrap_or_default(),
+            new.outline(name).cloned().unwrap_or_default(),
+        );
+        // both are drawn in a box around both, so that they line up
+        let (old_min, old_max) = old.frame(&old_outline, old_width);
+        let (new_min, new_max) = new.frame(&new_outline, new.width(name).unwrap_or_default());
+        let frame = (
+            Point::new(old_min.x.min(new_min.x), old_min.y.min(new_min.y)),
+            Point::new(old_max.x.max(new_max.x), old_max.y.max(new_max.y)),
+        );
+        let old_bitmap = new.draw(&old_outline, frame, size);
+        let new_bitmap = new.draw(&new_outline, frame, size);
+
+        let difference = difference(&old_bitmap, &new_bitmap);
+        if difference == 0.0 {
+            unchanged += 1;
+            continue;
+        }
+        let image = if difference > threshold {
+            fs::create_dir_all(out)?;
+            let path = out.join(format!("{name}.png"));
+            side_by_side(&old_bitmap, &new_bitmap).write_png(&path)?;
+            Some(path.display().to_string())
+        } else {
+            None
+        };
+        changed.push(GlyphDiff {
+            name: name.to_string(),
+            difference,
+            image,
+        });
+    }
+    changed.sort_by(|a, b| b.difference.total_cmp(&a.difference));
+
+    Ok(VisualDiffReport {
+        changed,
+        unchanged,
+        added,
+        removed,
+    })
+}
+
+impl VisualDiffReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} glyphs changed, {} unchanged, {} added, {} removed",
+                    self.changed.len(),
+                    self.unchanged,
+                    self.added.len(),
+                    self.removed.len()
+                );
+                for glyph in &self.changed {
+                    let image = glyph
+                        .image
+                        .as_ref()
+                        .map_or(String::new(), |image| format!("  ({image})"));
+                    println!("{:>7.2}%  {}{image}", glyph.difference, glyph.name);
+                }
+                for name in &self.added {
+                    println!("added     {name}");
+                }
+                for name in &self.removed {
+                    println!("removed   {name}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//! JavaScript bindings (the `wasm` feature), so web input methods and the website can convert text
+//! and look up glyphs with the font's own rules. Built with `wasm-pack build --features wasm`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::translit;
+
+/// Converts ASCII text to UCSUR the way the font's ligatures do
+#[wasm_bindgen(js_name = asciiToUcsur)]
+pub fn ascii_to_ucsur(text: &str) -> String {
+    translit::ascii_to_ucsur(text)
+}
+
+/// The name of the glyph mapped from `codepoint`
+#[wasm_bindgen(js_name = glyphName)]
+pub fn glyph_name(codepoint: u32) -> Option<String> {
+    let glyph = nasin_nanpa_data::by_codepoint(char::from_u32(codepoint)?)?;
+    Some(glyph.name.to_string())
+}
+
+/// The codepoint the glyph called `name` is mapped from
+#[wasm_bindgen(js_name = glyphCodepoint)]
+pub fn glyph_codepoint(name: &str) -> Option<u32> {
+    nasin_nanpa_data::by_name(name)?.codepoint.map(u32::from)
+}
+
+/// The ASCII text the font turns into the glyph called `name`
+#[wasm_bindgen(js_name = glyphSpellings)]
+pub fn glyph_spellings(name: &str) -> Vec<String> {
+    nasin_nanpa_data::by_name(name)
+        .map(|glyph| glyph.spellings.iter().map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// The name of the glyph the font turns `spelling` into
+#[wasm_bindgen(js_name = spellingGlyph)]
+pub fn spelling_glyph(spelling: &str) -> Option<String> {
+    let glyph = nasin_nanpa_data::by_spelling(spelling)?;
+    Some(glyph.name.to_string())
+}
+
+/// The version of the font the glyphs are from
+#[wasm_bindgen]
+pub fn version() -> String {
+    nasin_nanpa_data::VERSION.to_string()
+}
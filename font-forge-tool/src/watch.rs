@@ -0,0 +1,33 @@
+//! Regenerating the font whenever its glyph packs change
+
+use std::{io, path::PathBuf, sync::mpsc, time::Duration};
+
+use notify::{RecursiveMode, Watcher};
+
+/// How long to wait for more changes before regenerating, since editors often write files in several steps
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Calls `build` once, and then again after every change to `paths` (which may not all exist)
+pub fn watch(paths: &[PathBuf], mut build: impl FnMut() -> io::Result<()>) -> io::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+    for path in paths.iter().filter(|path| path.exists()) {
+        watcher
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(io::Error::other)?;
+        println!("watching {}", path.display());
+    }
+
+    loop {
+        if let Err(e) = build() {
+            println!("error: {e}");
+        }
+
+        // wait for a change, and then for things to settle down
+        match rx.recv() {
+            Ok(event) => event.map_err(io::Error::other)?,
+            Err(_) => return Ok(()),
+        };
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+    }
+}
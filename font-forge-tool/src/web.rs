@@ -0,0 +1,106 @@
+//! The web bundle: an `@font-face` stylesheet and a specimen page for the compiled WOFF2 fonts
+
+use std::{fs, io, path::Path};
+
+use font_forge_tool::translit::ascii_to_ucsur;
+
+use crate::{glyph_blocks::VERSION, profile::Profile, sfd_filename};
+
+/// The text shown in the specimen, typed the way the main variation expects it
+const SAMPLES: [(&str, &str); 6] = [
+    ("words", "toki pona li pona"),
+    ("cartouches", "jan [sona ona nasin ale] li toki"),
+    ("stacking", "lili&mute tenpo&suno"),
+    ("scaling", "jan&sona tomo&telo"),
+    ("long glyphs", "lon(kasi) pi(telo_)"),
+    ("punctuation", "mi moku. sina: pona"),
+];
+
+fn family(profile: Profile) -> String {
+    format!("nasin-nanpa{}", profile.suffix)
+}
+
+fn woff2_filename(profile: Profile) -> String {
+    let sfd = sfd_filename(profile);
+    format!("{}.woff2", sfd.trim_end_matches(".sfd"))
+}
+
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// An `@font-face` rule (and a matching class) for each variation
+pub fn css() -> String {
+    [Profile::MAIN, Profile::UCSUR]
+        .map(|profile| {
+            format!(
+                r#"@font-face {{
+  font-family: "{family}";
+  src: url("{woff2}") format("woff2");
+  font-display: swap;
+}}
+
+.{family} {{
+  font-family: "{family}";
+}}
+"#,
+                family = family(profile),
+                woff2 = woff2_filename(profile),
+            )
+        })
+        .join("\n")
+}
+
+/// A page showing each sample in both variations
+pub fn specimen() -> String {
+    let rows = SAMPLES
+        .iter()
+        .map(|(label, text)| {
+            format!(
+                r#"    <tr>
+      <th>{label}</th>
+      <td><code>{ascii}</code></td>
+      <td class="{main}">{ascii}</td>
+      <td class="{ucsur}">{ucsur_text}</td>
+    </tr>
+"#,
+                ascii = escape_html(text),
+                ucsur_text = escape_html(&ascii_to_ucsur(text)),
+                main = family(Profile::MAIN),
+                ucsur = family(Profile::UCSUR),
+            )
+        })
+        .collect::<String>();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="tok">
+<head>
+  <meta charset="utf-8">
+  <title>nasin nanpa {VERSION}</title>
+  <link rel="stylesheet" href="nasin-nanpa.css">
+  <style>
+    td.nasin-nanpa, td.nasin-nanpa-UCSUR {{ font-size: 2.5em; }}
+    th, td {{ padding: 0.25em 1em; text-align: left; }}
+  </style>
+</head>
+<body>
+  <h1>nasin nanpa {VERSION}</h1>
+  <table>
+    <tr><th></th><th>typed</th><th>nasin-nanpa</th><th>nasin-nanpa-UCSUR</th></tr>
+{rows}  </table>
+</body>
+</html>
+"#
+    )
+}
+
+/// Writes the stylesheet and the specimen next to the WOFF2 fonts in `dist`
+pub fn write_bundle(dist: impl AsRef<Path>) -> io::Result<()> {
+    let dist = dist.as_ref();
+    fs::create_dir_all(dist)?;
+    fs::write(dist.join("nasin-nanpa.css"), css())?;
+    fs::write(dist.join("index.html"), specimen())
+}
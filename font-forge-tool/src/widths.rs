@@ -0,0 +1,232 @@
+//! Widths worked out from the ink instead of written down: the glyphs of a block picked with
+//! `--side-bearings` are moved so their ink starts at the left side bearing, and given the width
+//! that leaves the right one after it. `audit-widths` reports how those widths would differ from
+//! the ones the glyphs are drawn with.
+
+use std::{collections::HashMap, io};
+
+use serde::Serialize;
+
+use crate::{
+    cli::ReportFormat,
+    ffir::{GlyphBlock, TAG_PADDING},
+    metrics::{ink_bounds, Bounds, FRAME_PARTS},
+    BlockFilter,
+};
+
+/// The side bearings the glyphs are drawn with (on the drawn em), which the audit assumes for
+/// every block when none are given
+const DEFAULT_BEARING: f64 = 50.0;
+
+/// The space left on either side of the ink of a block's glyphs
+#[derive(Clone)]
+pub struct SideBearings {
+    /// The blocks this is for (a block's name, or a `_`-separated part of it, like `base`)
+    pub block: String,
+    pub left: f64,
+    pub right: f64,
+}
+
+impl SideBearings {
+    /// Parses `BLOCK=LEFT,RIGHT` (or `BLOCK=BEARING`, for the same on both sides)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (block, bearings) = spec.split_once('=').ok_or_else(|| {
+            format!("the side bearings `{spec}` aren't written as BLOCK=LEFT,RIGHT")
+        })?;
+        let bearings = bearings
+            .split(',')
+            .map(|bearing| bearing.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("the side bearings `{spec}` have {e}"))?;
+        let (left, right) = match bearings[..] {
+            [bearing] => (bearing, bearing),
+            [left, right] => (left, right),
+            _ => {
+                return Err(format!(
+                    "the side bearings `{spec}` aren't one or two numbers"
+                ))
+            }
+        };
+        Ok(Self {
+            block: block.to_string(),
+            left,
+            right,
+        })
+    }
+
+    /// The side bearings in `policy` for the block named `block` (the last that matches)
+    fn find<'a>(policy: &'a [Self], block: &str) -> Option<&'a Self> {
+        policy
+            .iter()
+            .rev()
+            .find(|bearings| BlockFilter::matches(&bearings.block, block))
+    }
+}
+
+/// A glyph whose width can be worked out from its ink: its ink, and the side bearings of its block
+struct Inferred {
+    bounds: Bounds,
+    left: f64,
+    right: f64,
+}
+
+impl Inferred {
+    fn width(&self) -> usize {
+        (self.bounds.max.x - self.bounds.min.x + self.left + self.right)
+            .round()
+            .max(0.0) as usize
+    }
+}
+
+/// Measures every glyph of `blocks` in order, calling `found` with each glyph in a block with side
+/// bearings in `policy` that's drawn with ink and a width. `found` can move the glyph, as long as
+/// it gives back where its ink is then, so the glyphs that reference it are measured where it is.
+fn each_inferred(
+    blocks: &mut [GlyphBlock],
+    policy: &[SideBearings],
+    mut found: impl FnMut(&str, &mut GlyphBlock, usize, Inferred) -> Result<Bounds, String>,
+) -> Result<(), String> {
+    let mut measured = HashMap::<String, Option<Bounds>>::new();
+    for block in blocks.iter_mut() {
+        let bearings = SideBearings::find(policy, &block.name).cloned();
+        let names = block
+            .full_names()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        for (i, name) in names.into_iter().enumerate() {
+            let glyph = &block.glyphs[i].glyph;
+            let mut bounds =
+                ink_bounds(&glyph.rep, &measured).map_err(|e| format!("{name}: {e}"))?;
+            let inferable = glyph.width > 0
+                && !glyph.has_tag(TAG_PADDING)
+                && !FRAME_PARTS.iter().any(|part| name.contains(part));
+            if let (Some(bearings), Some(ink), true) = (&bearings, bounds, inferable) {
+                let inferred = Inferred {
+                    bounds: ink,
+                    left: bearings.left,
+                    right: bearings.right,
+                };
+                bounds = Some(found(&name, block, i, inferred)?);
+            }
+            measured.insert(name, bounds);
+        }
+    }
+    Ok(())
+}
+
+/// Moves the ink of every glyph in a block with side bearings in `policy` to start at its left
+/// side bearing, and sets its width to leave its right one after it
+pub fn infer(blocks: &mut [GlyphBlock], policy: &[SideBearings]) -> Result<(), String> {
+    if policy.is_empty() {
+        return Ok(());
+    }
+    each_inferred(blocks, policy, |name, block, i, inferred| {
+        let glyph = &mut block.glyphs[i].glyph;
+        let dx = inferred.left - inferred.bounds.min.x;
+        glyph
+            .rep
+            .translate(dx, 0.0)
+            .map_err(|e| format!("{name}: {e}"))?;
+        glyph.width = inferred.width();
+        let Bounds { mut min, mut max } = inferred.bounds;
+        min.x += dx;
+        max.x += dx;
+        Ok(Bounds { min, max })
+    })
+}
+
+#[derive(Serialize)]
+pub struct WidthChange {
+    name: String,
+    block: String,
+    width: usize,
+    inferred: usize,
+    /// The ink's distance from each side of the glyph as it's drawn
+    bearings: [f64; 2],
+}
+
+#[derive(Serialize)]
+pub struct WidthReport {
+    /// How many glyphs had their width worked out
+    glyphs: usize,
+    changes: Vec<WidthChange>,
+}
+
+/// Works out the width of every glyph in a block with side bearings in `policy` (or in every
+/// block, with the side bearings the glyphs are drawn with, if there are none), without changing
+/// them, and lists those that differ from the glyphs' own. The side bearings are on the drawn em,
+/// and the glyphs are scaled from it by `scale`.
+pub fn report(
+    blocks: &mut [GlyphBlock],
+    policy: &[SideBearings],
+    scale: f64,
+) -> Result<WidthReport, String> {
+    let policy = match policy {
+        [] => blocks
+            .iter()
+            .map(|block| SideBearings {
+                block: block.name.clone(),
+                left: DEFAULT_BEARING,
+                right: DEFAULT_BEARING,
+            })
+            .collect(),
+        policy => policy.to_vec(),
+    }
+    .into_iter()
+    .map(|bearings| SideBearings {
+        left: bearings.left * scale,
+        right: bearings.right * scale,
+        ..bearings
+    })
+    .collect::<Vec<_>>();
+    let mut report = WidthReport {
+        glyphs: 0,
+        changes: vec![],
+    };
+    each_inferred(blocks, &policy, |name, block, i, inferred| {
+        report.glyphs += 1;
+        let width = block.glyphs[i].glyph.width;
+        if inferred.width() != width {
+            let Bounds { min, max } = inferred.bounds;
+            report.changes.push(WidthChange {
+                name: name.to_string(),
+                block: block.name.clone(),
+                width,
+                inferred: inferred.width(),
+                bearings: [min.x.round(), (width as f64 - max.x).round()],
+            });
+        }
+        Ok(inferred.bounds)
+    })?;
+    Ok(report)
+}
+
+impl WidthReport {
+    pub fn print(&self, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(io::stdout().lock(), self)?;
+                println!();
+            }
+            ReportFormat::Table => {
+                println!(
+                    "{} of {} glyphs would change width",
+                    self.changes.len(),
+                    self.glyphs
+                );
+                for change in &self.changes {
+                    println!(
+                        "{:<12} {:<32} {:>5} -> {:<5} (drawn with bearings {} and {})",
+                        change.block,
+                        change.name,
+                        change.width,
+                        change.inferred,
+                        change.bearings[0],
+                        change.bearings[1],
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+//! Writing an `.sfd` as it's generated: the header first, then each block's glyphs (written into
+//! buffers in parallel, then out in order), through a buffer. Each section is checked (see
+//! `sfd_check`) as it's written, so the file doesn't have to be read back.
+
+use std::{
+    fmt,
+    io::{self, BufWriter, Write},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    cache::{self, GlyphCache},
+    features::FeatureRegistry,
+    ffir::GlyphBlock,
+    invalid_data,
+    sfd_check::Checker,
+    Profile,
+};
+
+/// How many glyphs each parallel task writes into its own buffer
+const GLYPHS_PER_TASK: usize = 64;
+
+/// How wide the progress bar is, in characters
+const BAR_WIDTH: usize = 30;
+
+/// How far the glyphs have gotten, shown on stderr
+struct Progress {
+    total: usize,
+    written: usize,
+}
+
+impl Progress {
+    /// Shows that `block` was just written
+    fn show(&self, block: &str) {
+        let filled = BAR_WIDTH * self.written / self.total.max(1);
+        eprintln!(
+            "[{}{}] {:>5}/{} {block}",
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            self.written,
+            self.total,
+        );
+    }
+}
+
+/// The glyphs of earlier builds, and the fingerprint of this build's features
+struct Cached<'c> {
+    cache: &'c mut GlyphCache,
+    fingerprint: u64,
+}
+
+pub struct SfdWriter<'c, W: Write> {
+    out: BufWriter<W>,
+    progress: Option<Progress>,
+    cached: Option<Cached<'c>>,
+    checker: Checker,
+}
+
+impl<'c, W: Write> SfdWriter<'c, W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out: BufWriter::new(out),
+            progress: None,
+            cached: None,
+            checker: Checker::default(),
+        }
+    }
+
+    /// Lists each block on stderr as it's written, with a bar for how many of `total` glyphs are
+    /// done
+    pub fn with_progress(mut self, total: usize) -> Self {
+        self.progress = Some(Progress { total, written: 0 });
+        self
+    }
+
+    /// Reuses the glyphs in `cache` whose inputs haven't changed (given the `features` they're
+    /// written with), and adds the rest
+    pub fn with_cache(mut self, cache: &'c mut GlyphCache, features: &FeatureRegistry) -> Self {
+        self.cached = Some(Cached {
+            cache,
+            fingerprint: features.fingerprint(),
+        });
+        self
+    }
+
+    /// Writes everything before the glyphs (up to and including `BeginChars`)
+    pub fn header(&mut self, header: &str) -> io::Result<()> {
+        self.checker.feed(header).map_err(invalid_data)?;
+        writeln!(self.out, "{header}")
+    }
+
+    /// Generates (or takes from the cache) and writes the glyphs of `block`
+    pub fn block(
+        &mut self,
+        block: &GlyphBlock,
+        profile: Profile,
+        features: &FeatureRegistry,
+    ) -> io::Result<()> {
+        let cached = self
+            .cached
+            .as_ref()
+            .map(|cached| (&*cached.cache, cached.fingerprint));
+        let chunks = block
+            .glyphs
+            .par_chunks(GLYPHS_PER_TASK)
+            .map(|glyphs| {
+                let mut s = String::new();
+                let mut records = vec![];
+                for glyph in glyphs {
+                    let Some((cache, fingerprint)) = cached else {
+                        glyph.write(
+                            &mut s,
+                            &block.prefix,
+                            &block.suffix,
+                            &block.color,
+                            profile,
+                            features,
+                        )?;
+                        continue;
+                    };
+                    let key = cache::key(fingerprint, profile, block, glyph, features);
+                    match cache.get(key) {
+                        Some(section) => {
+                            s.push_str(section);
+                            records.push((key, None));
+                        }
+                        None => {
+                            let section = glyph.gen(
+                                &block.prefix,
+                                &block.suffix,
+                                &block.color,
+                                profile,
+                                features,
+                            );
+                            s.push_str(&section);
+                            records.push((key, Some(section)));
+                        }
+                    }
+                }
+                Ok((s, records))
+            })
+            .collect::<Result<Vec<_>, fmt::Error>>()
+            .map_err(io::Error::other)?;
+        for (chunk, records) in chunks {
+            self.checker.feed(&chunk).map_err(invalid_data)?;
+            self.out.write_all(chunk.as_bytes())?;
+            if let Some(cached) = &mut self.cached {
+                for (key, generated) in records {
+                    cached.cache.record(key, generated);
+                }
+            }
+        }
+        if let Some(progress) = &mut self.progress {
+            progress.written += block.glyphs.len();
+            progress.show(&block.name);
+        }
+        Ok(())
+    }
+
+    /// Ends the glyphs and the font, and flushes the buffer, failing if the counts and indices of
+    /// what was written don't match what it holds
+    pub fn finish(mut self) -> io::Result<()> {
+        writeln!(self.out, "EndChars\nEndSplineFont")?;
+        self.out.flush()?;
+        self.checker.finish().map_err(invalid_data)
+    }
+}
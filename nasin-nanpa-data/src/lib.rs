@@ -0,0 +1,294 @@
+//! The codepoints, glyph names and ASCII spellings of nasin nanpa 5.0.0-beta.2.
+//! Generated by `font-forge-tool data-crate`; don't edit by hand.
+
+/// A glyph of the font
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Glyph {
+    /// The glyph's name in the font (like `tokiTok`)
+    pub name: &'static str,
+    /// The character the glyph is mapped from, if it's mapped from one past ASCII
+    pub codepoint: Option<char>,
+    /// The ASCII text the font turns into the glyph (like `toki`)
+    pub spellings: &'static [&'static str],
+}
+
+/// The version of the font the tables are from
+pub const VERSION: &str = "5.0.0-beta.2";
+
+/// Every glyph with a codepoint past ASCII or an ASCII spelling, in the font's order
+pub static GLYPHS: [Glyph; 259] = [
+    Glyph { name: "ZWSP", codepoint: Some('\u{200B}'), spellings: &[] },
+    Glyph { name: "VAR01", codepoint: Some('\u{FE00}'), spellings: &[] },
+    Glyph { name: "VAR02", codepoint: Some('\u{FE01}'), spellings: &[] },
+    Glyph { name: "VAR03", codepoint: Some('\u{FE02}'), spellings: &[] },
+    Glyph { name: "VAR04", codepoint: Some('\u{FE03}'), spellings: &[] },
+    Glyph { name: "VAR05", codepoint: Some('\u{FE04}'), spellings: &[] },
+    Glyph { name: "VAR06", codepoint: Some('\u{FE05}'), spellings: &[] },
+    Glyph { name: "VAR07", codepoint: Some('\u{FE06}'), spellings: &[] },
+    Glyph { name: "VAR08", codepoint: Some('\u{FE07}'), spellings: &[] },
+    Glyph { name: "VAR09", codepoint: Some('\u{FE08}'), spellings: &[] },
+    Glyph { name: "VAR256", codepoint: Some('\u{E01EF}'), spellings: &[] },
+    Glyph { name: "ZWJ", codepoint: Some('\u{200D}'), spellings: &["&"] },
+    Glyph { name: "ZWNJ", codepoint: Some('\u{200C}'), spellings: &["|"] },
+    Glyph { name: "arrowE", codepoint: Some('\u{2192}'), spellings: &[">"] },
+    Glyph { name: "arrowN", codepoint: Some('\u{2191}'), spellings: &["^"] },
+    Glyph { name: "arrowNE", codepoint: Some('\u{2197}'), spellings: &["^>", ">^"] },
+    Glyph { name: "arrowNW", codepoint: Some('\u{2196}'), spellings: &["^<", "<^"] },
+    Glyph { name: "arrowS", codepoint: Some('\u{2193}'), spellings: &["v"] },
+    Glyph { name: "arrowSE", codepoint: Some('\u{2198}'), spellings: &["v>", ">v"] },
+    Glyph { name: "arrowSW", codepoint: Some('\u{2199}'), spellings: &["v<", "<v"] },
+    Glyph { name: "arrowW", codepoint: Some('\u{2190}'), spellings: &["<"] },
+    Glyph { name: "combCartExt2TickTok", codepoint: None, spellings: &[",,"] },
+    Glyph { name: "combCartExt3TickTok", codepoint: None, spellings: &[",,,"] },
+    Glyph { name: "combCartExt4TickTok", codepoint: None, spellings: &[",,,,"] },
+    Glyph { name: "combCartExt6TickTok", codepoint: None, spellings: &["''"] },
+    Glyph { name: "combCartExt7TickTok", codepoint: None, spellings: &["'''"] },
+    Glyph { name: "combCartExt8TickTok", codepoint: None, spellings: &["''''"] },
+    Glyph { name: "aTok", codepoint: Some('\u{F1900}'), spellings: &["a"] },
+    Glyph { name: "akesiTok", codepoint: Some('\u{F1901}'), spellings: &["akesi"] },
+    Glyph { name: "alaTok", codepoint: Some('\u{F1902}'), spellings: &["ala"] },
+    Glyph { name: "alasaTok", codepoint: Some('\u{F1903}'), spellings: &["alasa"] },
+    Glyph { name: "aleTok", codepoint: Some('\u{F1904}'), spellings: &["ale", "ali"] },
+    Glyph { name: "anpaTok", codepoint: Some('\u{F1905}'), spellings: &["anpa"] },
+    Glyph { name: "anteTok", codepoint: Some('\u{F1906}'), spellings: &["ante"] },
+    Glyph { name: "anuTok", codepoint: Some('\u{F1907}'), spellings: &["anu"] },
+    Glyph { name: "awenTok", codepoint: Some('\u{F1908}'), spellings: &["awen"] },
+    Glyph { name: "eTok", codepoint: Some('\u{F1909}'), spellings: &["e"] },
+    Glyph { name: "enTok", codepoint: Some('\u{F190A}'), spellings: &["en"] },
+    Glyph { name: "epikuTok", codepoint: Some('\u{F1983}'), spellings: &["epiku"] },
+    Glyph { name: "esunTok", codepoint: Some('\u{F190B}'), spellings: &["esun"] },
+    Glyph { name: "ijoTok", codepoint: Some('\u{F190C}'), spellings: &["ijo"] },
+    Glyph { name: "ikeTok", codepoint: Some('\u{F190D}'), spellings: &["ike"] },
+    Glyph { name: "iloTok", codepoint: Some('\u{F190E}'), spellings: &["ilo"] },
+    Glyph { name: "insaTok", codepoint: Some('\u{F190F}'), spellings: &["insa"] },
+    Glyph { name: "jakiTok", codepoint: Some('\u{F1910}'), spellings: &["jaki"] },
+    Glyph { name: "janTok", codepoint: Some('\u{F1911}'), spellings: &["jan"] },
+    Glyph { name: "jasimaTok", codepoint: Some('\u{F197F}'), spellings: &["jasima"] },
+    Glyph { name: "jeloTok", codepoint: Some('\u{F1912}'), spellings: &["jelo"] },
+    Glyph { name: "joTok", codepoint: Some('\u{F1913}'), spellings: &["jo"] },
+    Glyph { name: "kalaTok", codepoint: Some('\u{F1914}'), spellings: &["kala"] },
+    Glyph { name: "kalamaTok", codepoint: Some('\u{F1915}'), spellings: &["kalama"] },
+    Glyph { name: "kamaTok", codepoint: Some('\u{F1916}'), spellings: &["kama"] },
+    Glyph { name: "kasiTok", codepoint: Some('\u{F1917}'), spellings: &["kasi"] },
+    Glyph { name: "kenTok", codepoint: Some('\u{F1918}'), spellings: &["ken"] },
+    Glyph { name: "kepekenTok", codepoint: Some('\u{F1919}'), spellings: &["kepeken"] },
+    Glyph { name: "kijetesantakaluTok", codepoint: Some('\u{F1980}'), spellings: &["kijetesantakalu"] },
+    Glyph { name: "kiliTok", codepoint: Some('\u{F191A}'), spellings: &["kili"] },
+    Glyph { name: "kinTok", codepoint: Some('\u{F1979}'), spellings: &["kin"] },
+    Glyph { name: "kipisiTok", codepoint: Some('\u{F197B}'), spellings: &["kipisi"] },
+    Glyph { name: "kiwenTok", codepoint: Some('\u{F191B}'), spellings: &["kiwen"] },
+    Glyph { name: "koTok", codepoint: Some('\u{F191C}'), spellings: &["ko"] },
+    Glyph { name: "kokosilaTok", codepoint: Some('\u{F1984}'), spellings: &["kokosila"] },
+    Glyph { name: "konTok", codepoint: Some('\u{F191D}'), spellings: &["kon"] },
+    Glyph { name: "kuTok", codepoint: Some('\u{F1988}'), spellings: &["ku"] },
+    Glyph { name: "kuleTok", codepoint: Some('\u{F191E}'), spellings: &["kule"] },
+    Glyph { name: "kulupuTok", codepoint: Some('\u{F191F}'), spellings: &["kulupu"] },
+    Glyph { name: "kuteTok", codepoint: Some('\u{F1920}'), spellings: &["kute"] },
+    Glyph { name: "laTok", codepoint: Some('\u{F1921}'), spellings: &["la"] },
+    Glyph { name: "lanpanTok", codepoint: Some('\u{F1985}'), spellings: &["lanpan"] },
+    Glyph { name: "lapeTok", codepoint: Some('\u{F1922}'), spellings: &["lape"] },
+    Glyph { name: "lasoTok", codepoint: Some('\u{F1923}'), spellings: &["laso"] },
+    Glyph { name: "lawaTok", codepoint: Some('\u{F1924}'), spellings: &["lawa"] },
+    Glyph { name: "lekoTok", codepoint: Some('\u{F197C}'), spellings: &["leko"] },
+    Glyph { name: "lenTok", codepoint: Some('\u{F1925}'), spellings: &["len"] },
+    Glyph { name: "leteTok", codepoint: Some('\u{F1926}'), spellings: &["lete"] },
+    Glyph { name: "liTok", codepoint: Some('\u{F1927}'), spellings: &["li"] },
+    Glyph { name: "liliTok", codepoint: Some('\u{F1928}'), spellings: &["lili"] },
+    Glyph { name: "linjaTok", codepoint: Some('\u{F1929}'), spellings: &["linja"] },
+    Glyph { name: "lipuTok", codepoint: Some('\u{F192A}'), spellings: &["lipu"] },
+    Glyph { name: "lojeTok", codepoint: Some('\u{F192B}'), spellings: &["loje"] },
+    Glyph { name: "lonTok", codepoint: Some('\u{F192C}'), spellings: &["lon"] },
+    Glyph { name: "lukaTok", codepoint: Some('\u{F192D}'), spellings: &["luka"] },
+    Glyph { name: "lukinTok", codepoint: Some('\u{F192E}'), spellings: &["lukin"] },
+    Glyph { name: "lupaTok", codepoint: Some('\u{F192F}'), spellings: &["lupa"] },
+    Glyph { name: "maTok", codepoint: Some('\u{F1930}'), spellings: &["ma"] },
+    Glyph { name: "mamaTok", codepoint: Some('\u{F1931}'), spellings: &["mama"] },
+    Glyph { name: "maniTok", codepoint: Some('\u{F1932}'), spellings: &["mani"] },
+    Glyph { name: "meliTok", codepoint: Some('\u{F1933}'), spellings: &["meli"] },
+    Glyph { name: "mesoTok", codepoint: Some('\u{F1982}'), spellings: &["meso"] },
+    Glyph { name: "miTok", codepoint: Some('\u{F1934}'), spellings: &["mi"] },
+    Glyph { name: "mijeTok", codepoint: Some('\u{F1935}'), spellings: &["mije"] },
+    Glyph { name: "misikekeTok", codepoint: Some('\u{F1987}'), spellings: &["misikeke"] },
+    Glyph { name: "mokuTok", codepoint: Some('\u{F1936}'), spellings: &["moku"] },
+    Glyph { name: "moliTok", codepoint: Some('\u{F1937}'), spellings: &["moli"] },
+    Glyph { name: "monsiTok", codepoint: Some('\u{F1938}'), spellings: &["monsi"] },
+    Glyph { name: "monsutaTok", codepoint: Some('\u{F197D}'), spellings: &["monsuta"] },
+    Glyph { name: "muTok", codepoint: Some('\u{F1939}'), spellings: &["mu"] },
+    Glyph { name: "munTok", codepoint: Some('\u{F193A}'), spellings: &["mun"] },
+    Glyph { name: "musiTok", codepoint: Some('\u{F193B}'), spellings: &["musi"] },
+    Glyph { name: "muteTok", codepoint: Some('\u{F193C}'), spellings: &["mute"] },
+    Glyph { name: "nTok", codepoint: Some('\u{F1986}'), spellings: &["n"] },
+    Glyph { name: "namakoTok", codepoint: Some('\u{F1978}'), spellings: &["namako"] },
+    Glyph { name: "nanpaTok", codepoint: Some('\u{F193D}'), spellings: &["nanpa"] },
+    Glyph { name: "nasaTok", codepoint: Some('\u{F193E}'), spellings: &["nasa"] },
+    Glyph { name: "nasinTok", codepoint: Some('\u{F193F}'), spellings: &["nasin"] },
+    Glyph { name: "nenaTok", codepoint: Some('\u{F1940}'), spellings: &["nena"] },
+    Glyph { name: "niTok", codepoint: Some('\u{F1941}'), spellings: &["ni"] },
+    Glyph { name: "nimiTok", codepoint: Some('\u{F1942}'), spellings: &["nimi"] },
+    Glyph { name: "nokaTok", codepoint: Some('\u{F1943}'), spellings: &["noka"] },
+    Glyph { name: "oTok", codepoint: Some('\u{F1944}'), spellings: &["o"] },
+    Glyph { name: "okoTok", codepoint: Some('\u{F197A}'), spellings: &["oko"] },
+    Glyph { name: "olinTok", codepoint: Some('\u{F1945}'), spellings: &["olin"] },
+    Glyph { name: "onaTok", codepoint: Some('\u{F1946}'), spellings: &["ona"] },
+    Glyph { name: "openTok", codepoint: Some('\u{F1947}'), spellings: &["open"] },
+    Glyph { name: "pakalaTok", codepoint: Some('\u{F1948}'), spellings: &["pakala"] },
+    Glyph { name: "paliTok", codepoint: Some('\u{F1949}'), spellings: &["pali"] },
+    Glyph { name: "palisaTok", codepoint: Some('\u{F194A}'), spellings: &["palisa"] },
+    Glyph { name: "panTok", codepoint: Some('\u{F194B}'), spellings: &["pan"] },
+    Glyph { name: "panaTok", codepoint: Some('\u{F194C}'), spellings: &["pana"] },
+    Glyph { name: "piTok", codepoint: Some('\u{F194D}'), spellings: &["pi"] },
+    Glyph { name: "pilinTok", codepoint: Some('\u{F194E}'), spellings: &["pilin"] },
+    Glyph { name: "pimejaTok", codepoint: Some('\u{F194F}'), spellings: &["pimeja"] },
+    Glyph { name: "piniTok", codepoint: Some('\u{F1950}'), spellings: &["pini"] },
+    Glyph { name: "pipiTok", codepoint: Some('\u{F1951}'), spellings: &["pipi"] },
+    Glyph { name: "pokaTok", codepoint: Some('\u{F1952}'), spellings: &["poka"] },
+    Glyph { name: "pokiTok", codepoint: Some('\u{F1953}'), spellings: &["poki"] },
+    Glyph { name: "ponaTok", codepoint: Some('\u{F1954}'), spellings: &["pona"] },
+    Glyph { name: "puTok", codepoint: Some('\u{F1955}'), spellings: &["pu"] },
+    Glyph { name: "samaTok", codepoint: Some('\u{F1956}'), spellings: &["sama"] },
+    Glyph { name: "seliTok", codepoint: Some('\u{F1957}'), spellings: &["seli"] },
+    Glyph { name: "seloTok", codepoint: Some('\u{F1958}'), spellings: &["selo"] },
+    Glyph { name: "semeTok", codepoint: Some('\u{F1959}'), spellings: &["seme"] },
+    Glyph { name: "sewiTok", codepoint: Some('\u{F195A}'), spellings: &["sewi"] },
+    Glyph { name: "sijeloTok", codepoint: Some('\u{F195B}'), spellings: &["sijelo"] },
+    Glyph { name: "sikeTok", codepoint: Some('\u{F195C}'), spellings: &["sike"] },
+    Glyph { name: "sinTok", codepoint: Some('\u{F195D}'), spellings: &["sin"] },
+    Glyph { name: "sinaTok", codepoint: Some('\u{F195E}'), spellings: &["sina"] },
+    Glyph { name: "sinpinTok", codepoint: Some('\u{F195F}'), spellings: &["sinpin"] },
+    Glyph { name: "sitelenTok", codepoint: Some('\u{F1960}'), spellings: &["sitelen"] },
+    Glyph { name: "sokoTok", codepoint: Some('\u{F1981}'), spellings: &["soko"] },
+    Glyph { name: "sonaTok", codepoint: Some('\u{F1961}'), spellings: &["sona"] },
+    Glyph { name: "soweliTok", codepoint: Some('\u{F1962}'), spellings: &["soweli"] },
+    Glyph { name: "suliTok", codepoint: Some('\u{F1963}'), spellings: &["suli"] },
+    Glyph { name: "sunoTok", codepoint: Some('\u{F1964}'), spellings: &["suno"] },
+    Glyph { name: "supaTok", codepoint: Some('\u{F1965}'), spellings: &["supa"] },
+    Glyph { name: "suwiTok", codepoint: Some('\u{F1966}'), spellings: &["suwi"] },
+    Glyph { name: "tanTok", codepoint: Some('\u{F1967}'), spellings: &["tan"] },
+    Glyph { name: "tasoTok", codepoint: Some('\u{F1968}'), spellings: &["taso"] },
+    Glyph { name: "tawaTok", codepoint: Some('\u{F1969}'), spellings: &["tawa"] },
+    Glyph { name: "teloTok", codepoint: Some('\u{F196A}'), spellings: &["telo"] },
+    Glyph { name: "tenpoTok", codepoint: Some('\u{F196B}'), spellings: &["tenpo"] },
+    Glyph { name: "tokiTok", codepoint: Some('\u{F196C}'), spellings: &["toki"] },
+    Glyph { name: "tomoTok", codepoint: Some('\u{F196D}'), spellings: &["tomo"] },
+    Glyph { name: "tonsiTok", codepoint: Some('\u{F197E}'), spellings: &["tonsi"] },
+    Glyph { name: "tuTok", codepoint: Some('\u{F196E}'), spellings: &["tu"] },
+    Glyph { name: "unpaTok", codepoint: Some('\u{F196F}'), spellings: &["unpa"] },
+    Glyph { name: "utaTok", codepoint: Some('\u{F1970}'), spellings: &["uta"] },
+    Glyph { name: "utalaTok", codepoint: Some('\u{F1971}'), spellings: &["utala"] },
+    Glyph { name: "waloTok", codepoint: Some('\u{F1972}'), spellings: &["walo"] },
+    Glyph { name: "wanTok", codepoint: Some('\u{F1973}'), spellings: &["wan"] },
+    Glyph { name: "wasoTok", codepoint: Some('\u{F1974}'), spellings: &["waso"] },
+    Glyph { name: "wawaTok", codepoint: Some('\u{F1975}'), spellings: &["wawa"] },
+    Glyph { name: "wekaTok", codepoint: Some('\u{F1976}'), spellings: &["weka"] },
+    Glyph { name: "wileTok", codepoint: Some('\u{F1977}'), spellings: &["wile"] },
+    Glyph { name: "PingoTok", codepoint: None, spellings: &["Pingo"] },
+    Glyph { name: "apejaTok", codepoint: Some('\u{F19A1}'), spellings: &["apeja"] },
+    Glyph { name: "elikiTok", codepoint: None, spellings: &["eliki"] },
+    Glyph { name: "isipinTok", codepoint: Some('\u{F19A7}'), spellings: &["isipin"] },
+    Glyph { name: "jamiTok", codepoint: Some('\u{F19A8}'), spellings: &["jami"] },
+    Glyph { name: "jonkeTok", codepoint: Some('\u{F19A9}'), spellings: &["jonke"] },
+    Glyph { name: "kamalawalaTok", codepoint: Some('\u{F19AA}'), spellings: &["kamalawala"] },
+    Glyph { name: "kapesiTok", codepoint: Some('\u{F19AB}'), spellings: &["kapesi"] },
+    Glyph { name: "kikiTok", codepoint: Some('\u{F19A5}'), spellings: &["kiki"] },
+    Glyph { name: "konweTok", codepoint: Some('\u{F19AC}'), spellings: &["konwe"] },
+    Glyph { name: "kulijoTok", codepoint: Some('\u{F19AD}'), spellings: &["kulijo"] },
+    Glyph { name: "linluwiTok", codepoint: Some('\u{F19A4}'), spellings: &["linluwi"] },
+    Glyph { name: "majunaTok", codepoint: Some('\u{F19A2}'), spellings: &["majuna"] },
+    Glyph { name: "melomeTok", codepoint: Some('\u{F19AE}'), spellings: &["melome"] },
+    Glyph { name: "mijomiTok", codepoint: Some('\u{F19AF}'), spellings: &["mijomi"] },
+    Glyph { name: "misaTok", codepoint: Some('\u{F19B0}'), spellings: &["misa"] },
+    Glyph { name: "mulapisuTok", codepoint: Some('\u{F19B1}'), spellings: &["mulapisu"] },
+    Glyph { name: "nimisinTok", codepoint: Some('\u{F19B2}'), spellings: &["nimisin"] },
+    Glyph { name: "njaTok", codepoint: Some('\u{F19B3}'), spellings: &["nja"] },
+    Glyph { name: "ojutaTok", codepoint: Some('\u{F19B4}'), spellings: &["ojuta"] },
+    Glyph { name: "okeTok", codepoint: Some('\u{F19B5}'), spellings: &["oke"] },
+    Glyph { name: "omekapoTok", codepoint: Some('\u{F19B6}'), spellings: &["omekapo"] },
+    Glyph { name: "oweTok", codepoint: Some('\u{F19B7}'), spellings: &["owe"] },
+    Glyph { name: "pakeTok", codepoint: Some('\u{F19A0}'), spellings: &["pake"] },
+    Glyph { name: "pakolaTok", codepoint: Some('\u{F19B8}'), spellings: &["pakola"] },
+    Glyph { name: "penpoTok", codepoint: Some('\u{F19B9}'), spellings: &["penpo"] },
+    Glyph { name: "pikaTok", codepoint: Some('\u{F19BA}'), spellings: &["pika"] },
+    Glyph { name: "poTok", codepoint: Some('\u{F19BB}'), spellings: &["po"] },
+    Glyph { name: "poweTok", codepoint: Some('\u{F19A3}'), spellings: &["powe"] },
+    Glyph { name: "puwaTok", codepoint: Some('\u{F19BC}'), spellings: &["puwa"] },
+    Glyph { name: "sanTok", codepoint: Some('\u{F19BD}'), spellings: &["san"] },
+    Glyph { name: "sotoTok", codepoint: Some('\u{F19BE}'), spellings: &["soto"] },
+    Glyph { name: "suTok", codepoint: Some('\u{F19A6}'), spellings: &["su"] },
+    Glyph { name: "sutopatikunaTok", codepoint: Some('\u{F19C0}'), spellings: &["sutopatikuna"] },
+    Glyph { name: "takiTok", codepoint: Some('\u{F19C1}'), spellings: &["taki"] },
+    Glyph { name: "tejeTok", codepoint: Some('\u{F19BF}'), spellings: &["teje"] },
+    Glyph { name: "unuTok", codepoint: Some('\u{F19C2}'), spellings: &["unu"] },
+    Glyph { name: "usawiTok", codepoint: Some('\u{F19C3}'), spellings: &["usawi"] },
+    Glyph { name: "waTok", codepoint: Some('\u{F19C4}'), spellings: &["wa"] },
+    Glyph { name: "wasoweliTok", codepoint: Some('\u{F19C5}'), spellings: &["wasoweli"] },
+    Glyph { name: "wekamaTok", codepoint: Some('\u{F19C6}'), spellings: &["wekama"] },
+    Glyph { name: "wuwojitiTok", codepoint: Some('\u{F19C7}'), spellings: &["wuwojiti"] },
+    Glyph { name: "yupekosiTok", codepoint: Some('\u{F19C8}'), spellings: &["yupekosi"] },
+    Glyph { name: "LinkuTok", codepoint: None, spellings: &["Linku"] },
+    Glyph { name: "SekaTok", codepoint: None, spellings: &["Seka"] },
+    Glyph { name: "colonTok", codepoint: Some('\u{F199D}'), spellings: &[":"] },
+    Glyph { name: "itanTok", codepoint: None, spellings: &["itan"] },
+    Glyph { name: "lepekaTok", codepoint: None, spellings: &["lepeka"] },
+    Glyph { name: "lipamankaTok", codepoint: None, spellings: &["lipamanka"] },
+    Glyph { name: "middleDotTok", codepoint: Some('\u{F199C}'), spellings: &["."] },
+    Glyph { name: "spaceTok", codepoint: Some('\u{3000}'), spellings: &["  ", "zz ", "zz"] },
+    Glyph { name: "arrowRad", codepoint: Some('\u{F1C80}'), spellings: &[] },
+    Glyph { name: "arrowheadLDRad", codepoint: Some('\u{F1C82}'), spellings: &[] },
+    Glyph { name: "arrowheadRad", codepoint: Some('\u{F1C81}'), spellings: &[] },
+    Glyph { name: "arrowheadURRad", codepoint: Some('\u{F1C83}'), spellings: &[] },
+    Glyph { name: "boxOrCrosshairRad", codepoint: Some('\u{F1C8E}'), spellings: &[] },
+    Glyph { name: "circleRad", codepoint: Some('\u{F1C88}'), spellings: &[] },
+    Glyph { name: "closedBoxRad", codepoint: Some('\u{F1C84}'), spellings: &[] },
+    Glyph { name: "closedTriangleRad", codepoint: Some('\u{F1C97}'), spellings: &[] },
+    Glyph { name: "commaRad", codepoint: Some('\u{F1C9C}'), spellings: &[] },
+    Glyph { name: "crossRad", codepoint: Some('\u{F1C8A}'), spellings: &[] },
+    Glyph { name: "dotRad", codepoint: Some('\u{F1C8B}'), spellings: &[] },
+    Glyph { name: "doubleDotRad", codepoint: Some('\u{F1C8C}'), spellings: &[] },
+    Glyph { name: "emittersRad", codepoint: Some('\u{F1C8D}'), spellings: &[] },
+    Glyph { name: "exclamationLineRad", codepoint: Some('\u{F1C9B}'), spellings: &[] },
+    Glyph { name: "frowningMouthRad", codepoint: Some('\u{F1C93}'), spellings: &[] },
+    Glyph { name: "hammerRad", codepoint: Some('\u{F1C8F}'), spellings: &[] },
+    Glyph { name: "handRad", codepoint: Some('\u{F1C90}'), spellings: &[] },
+    Glyph { name: "heartRad", codepoint: Some('\u{F1C92}'), spellings: &[] },
+    Glyph { name: "horizontalLineRad", codepoint: Some('\u{F1C9A}'), spellings: &[] },
+    Glyph { name: "leftOpenBoxRad", codepoint: Some('\u{F1C86}'), spellings: &[] },
+    Glyph { name: "openBoxRad", codepoint: Some('\u{F1C85}'), spellings: &[] },
+    Glyph { name: "openMouthRad", codepoint: Some('\u{F1C95}'), spellings: &[] },
+    Glyph { name: "openTriangleRad", codepoint: Some('\u{F1C96}'), spellings: &[] },
+    Glyph { name: "pointingHandRad", codepoint: Some('\u{F1C91}'), spellings: &[] },
+    Glyph { name: "rightOpenBoxRad", codepoint: Some('\u{F1C87}'), spellings: &[] },
+    Glyph { name: "semicircleRad", codepoint: Some('\u{F1C89}'), spellings: &[] },
+    Glyph { name: "smilingMouthRad", codepoint: Some('\u{F1C94}'), spellings: &[] },
+    Glyph { name: "verticalLineRad", codepoint: Some('\u{F1C99}'), spellings: &[] },
+    Glyph { name: "wavyLineRad", codepoint: Some('\u{F1C98}'), spellings: &[] },
+    Glyph { name: "combCartExtTok", codepoint: Some('\u{F1992}'), spellings: &["="] },
+    Glyph { name: "combLongGlyphExtTok", codepoint: Some('\u{F1999}'), spellings: &["_"] },
+    Glyph { name: "combLongPiExtTok", codepoint: Some('\u{F1994}'), spellings: &[] },
+    Glyph { name: "endCartTok", codepoint: Some('\u{F1991}'), spellings: &["]"] },
+    Glyph { name: "endLongGlyphTok", codepoint: Some('\u{F1998}'), spellings: &[")"] },
+    Glyph { name: "endRevLongGlyphTok", codepoint: Some('\u{F199B}'), spellings: &["}"] },
+    Glyph { name: "joinScaleTok", codepoint: Some('\u{F1996}'), spellings: &["+"] },
+    Glyph { name: "joinStackTok", codepoint: Some('\u{F1995}'), spellings: &["-"] },
+    Glyph { name: "startCartTok", codepoint: Some('\u{F1990}'), spellings: &["["] },
+    Glyph { name: "startLongGlyphTok", codepoint: Some('\u{F1997}'), spellings: &["("] },
+    Glyph { name: "startLongPiTok", codepoint: Some('\u{F1993}'), spellings: &[] },
+    Glyph { name: "startRevLongGlyphTok", codepoint: Some('\u{F199A}'), spellings: &["{"] },
+    Glyph { name: "teTok", codepoint: Some('\u{F199E}'), spellings: &["te"] },
+    Glyph { name: "toTok", codepoint: Some('\u{F199F}'), spellings: &["to"] },
+    Glyph { name: "dottedCircle", codepoint: Some('\u{25CC}'), spellings: &[] },
+];
+
+/// The glyph called `name`
+pub fn by_name(name: &str) -> Option<&'static Glyph> {
+    GLYPHS.iter().find(|glyph| glyph.name == name)
+}
+
+/// The glyph mapped from `c`
+pub fn by_codepoint(c: char) -> Option<&'static Glyph> {
+    GLYPHS.iter().find(|glyph| glyph.codepoint == Some(c))
+}
+
+/// The glyph the font turns `spelling` into
+pub fn by_spelling(spelling: &str) -> Option<&'static Glyph> {
+    GLYPHS.iter().find(|glyph| glyph.spellings.contains(&spelling))
+}